@@ -0,0 +1,73 @@
+// Compare the time taken to analyze a project of independent contracts one
+// after the other, and with `Session::check_project_parallel`:
+//
+//     cargo run --release --example check_project_parallel [contracts]
+//
+// The contracts are analyzed from the same copy of the interpreter in both
+// cases, so the difference is the parallelism.
+
+use clarity_repl::clarity::types::QualifiedContractIdentifier;
+use clarity_repl::repl::settings::InitialContract;
+use clarity_repl::repl::{Session, SessionSettings};
+use std::time::Instant;
+
+// A contract with enough functions for its analysis to dominate the time
+// spent spawning the threads.
+fn contract_source(index: usize) -> String {
+    let mut source = format!("(define-data-var total-{} uint u0)\n", index);
+    for function in 0..200 {
+        source.push_str(&format!(
+            "(define-public (add-{0} (amount uint))
+    (let ((total (var-get total-{1})))
+        (asserts! (> amount u{0}) (err u1))
+        (ok (var-set total-{1} (+ total (* amount u{0}))))))\n",
+            function, index
+        ));
+    }
+    source
+}
+
+fn main() {
+    let count: usize = std::env::args()
+        .nth(1)
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(16);
+    let session = Session::new(SessionSettings::default());
+    let deployer = session.get_tx_sender();
+    let contracts: Vec<InitialContract> = (0..count)
+        .map(|index| InitialContract {
+            code: contract_source(index),
+            name: Some(format!("contract-{}", index)),
+            path: format!("contracts/contract-{}.clar", index),
+            deployer: Some(deployer.clone()),
+        })
+        .collect();
+
+    let start = Instant::now();
+    let mut interpreter = session.interpreter.clone();
+    for contract in contracts.iter() {
+        let contract_identifier = QualifiedContractIdentifier::parse(&format!(
+            "{}.{}",
+            deployer,
+            contract.name.as_ref().unwrap()
+        ))
+        .unwrap();
+        let ast = interpreter
+            .build_ast(contract_identifier.clone(), contract.code.clone())
+            .unwrap();
+        interpreter.check_ast(contract.code.clone(), contract_identifier, ast);
+    }
+    let sequential = start.elapsed();
+
+    let start = Instant::now();
+    session.check_project_parallel(&contracts).unwrap();
+    let parallel = start.elapsed();
+
+    println!("contracts:  {}", count);
+    println!("sequential: {:?}", sequential);
+    println!("parallel:   {:?}", parallel);
+    println!(
+        "speedup:    {:.2}x",
+        sequential.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
@@ -0,0 +1,264 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor, TypedVar};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::representations::SymbolicExpressionType::*;
+use crate::clarity::types::{CharType, SequenceData, Value};
+use crate::clarity::ClarityName;
+use std::collections::{HashMap, HashSet};
+
+// Reports calls to `fold` whose function appends to its accumulator, when
+// the folded sequence can hold more elements than the accumulator list. Each
+// iteration appends an element, so the list overflows at runtime once the
+// sequence is long enough.
+pub struct AppendOverflowChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    data_vars: HashMap<&'a ClarityName, &'a SymbolicExpression>,
+    arguments: HashMap<&'a ClarityName, &'a SymbolicExpression>,
+    // The accumulator of each function, with its maximum length
+    accumulators: HashMap<&'a ClarityName, (&'a ClarityName, u32)>,
+    // The names which each function appends to
+    appended: HashSet<(&'a ClarityName, &'a ClarityName)>,
+    current_function: Option<&'a ClarityName>,
+    // Each fold with its function, the maximum length of the folded sequence
+    // and the length of the initial value
+    folds: Vec<(&'a SymbolicExpression, &'a ClarityName, u32, u32)>,
+}
+
+impl<'a> AppendOverflowChecker<'a> {
+    fn new() -> AppendOverflowChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            data_vars: HashMap::new(),
+            arguments: HashMap::new(),
+            accumulators: HashMap::new(),
+            appended: HashSet::new(),
+            current_function: None,
+            folds: Vec::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        // Functions can be folded before they are defined
+        for (expr, func, length, initial_length) in self.folds.iter() {
+            let (accumulator, max_len) = match self.accumulators.get(func) {
+                Some(accumulator) => accumulator,
+                None => continue,
+            };
+            let total = length + initial_length;
+            if total > *max_len && self.appended.contains(&(*func, *accumulator)) {
+                self.diagnostics.push(Diagnostic {
+                    level: Level::Warning,
+                    code: Some("append-overflow".to_string()),
+                    message: format!(
+                        "fold over up to {} elements appends to '{}', a list of at most {} elements",
+                        length, accumulator, max_len
+                    ),
+                    spans: vec![expr.span.clone()],
+                    suggestion: Some(format!(
+                        "Declare '{}' in '{}' with a max length of at least {}",
+                        accumulator, func, total
+                    )),
+                });
+            }
+        }
+        Ok(self.diagnostics)
+    }
+
+    fn enter_function(&mut self, name: &'a ClarityName, parameters: Option<Vec<TypedVar<'a>>>) {
+        let parameters = parameters.unwrap_or_default();
+        if let [_, accumulator] = parameters.as_slice() {
+            if let Some(max_len) = max_length(accumulator.type_expr, "list") {
+                self.accumulators.insert(name, (accumulator.name, max_len));
+            }
+        }
+        self.arguments = parameters
+            .iter()
+            .map(|parameter| (parameter.name, parameter.type_expr))
+            .collect();
+        self.current_function = Some(name);
+    }
+
+    // The maximum number of elements of a sequence, if it can be known.
+    fn sequence_length(&self, expr: &SymbolicExpression) -> Option<u32> {
+        match &expr.expr {
+            LiteralValue(Value::Sequence(sequence)) | AtomValue(Value::Sequence(sequence)) => {
+                Some(sequence_len(sequence))
+            }
+            Atom(name) => self
+                .arguments
+                .get(name)
+                .and_then(|type_expr| declared_length(type_expr)),
+            List(_) => match match_native_call(expr)? {
+                (NativeFunctions::FetchVar, [name]) => self
+                    .data_vars
+                    .get(name.match_atom()?)
+                    .and_then(|type_expr| declared_length(type_expr)),
+                (NativeFunctions::ListCons, elements) => Some(elements.len() as u32),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+fn sequence_len(sequence: &SequenceData) -> u32 {
+    match sequence {
+        SequenceData::Buffer(buffer) => buffer.data.len() as u32,
+        SequenceData::List(list) => list.data.len() as u32,
+        SequenceData::String(CharType::ASCII(string)) => string.data.len() as u32,
+        SequenceData::String(CharType::UTF8(string)) => string.data.len() as u32,
+    }
+}
+
+// The max length of a declared `(list n type)`, `(buff n)` or string type.
+fn declared_length(type_expr: &SymbolicExpression) -> Option<u32> {
+    ["list", "buff", "string-ascii", "string-utf8"]
+        .iter()
+        .find_map(|sequence| max_length(type_expr, sequence))
+}
+
+fn max_length(type_expr: &SymbolicExpression, sequence: &str) -> Option<u32> {
+    match type_expr.match_list()? {
+        [name, length, ..] if name.match_atom()?.as_str() == sequence => {
+            match length.match_literal_value()? {
+                Value::Int(length) => Some(*length as u32),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+impl<'a> ASTVisitor<'a> for AppendOverflowChecker<'a> {
+    fn visit_define_data_var(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        data_type: &'a SymbolicExpression,
+        initial: &'a SymbolicExpression,
+    ) -> bool {
+        self.data_vars.insert(name, data_type);
+        true
+    }
+
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.enter_function(name, parameters);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.enter_function(name, parameters);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.enter_function(name, parameters);
+        self.traverse_expr(body)
+    }
+
+    fn visit_append(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        list: &'a SymbolicExpression,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        if let (Some(function), Some(name)) = (self.current_function, list.match_atom()) {
+            self.appended.insert((function, name));
+        }
+        true
+    }
+
+    fn visit_fold(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: &'a ClarityName,
+        sequence: &'a SymbolicExpression,
+        initial: &'a SymbolicExpression,
+    ) -> bool {
+        if let Some(length) = self.sequence_length(sequence) {
+            let initial_length = self.sequence_length(initial).unwrap_or(0);
+            self.folds.push((expr, func, length, initial_length));
+        }
+        true
+    }
+}
+
+impl AnalysisPass for AppendOverflowChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = AppendOverflowChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn fold_overflow() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["append_overflow_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-private (collect (id uint) (ids (list 10 uint)))
+    (unwrap-panic (as-max-len? (append ids id) u10))
+)
+(define-read-only (collect-all (input (list 20 uint)))
+    (fold collect input (list u1))
+)
+(define-read-only (collect-some (input (list 10 uint)))
+    (fold collect input (list))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:6:5: {}: fold over up to 20 elements appends to 'ids', a list of at most 10 elements",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(output[1], "    (fold collect input (list u1))");
+                assert_eq!(output[2], "    ^~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some("Declare 'ids' in 'collect' with a max length of at least 21".to_string())
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,192 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::define::DefineFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+use std::collections::{HashMap, HashSet};
+
+// Notes `as-contract` within another `as-contract`, and `as-contract`
+// wrapping an expression which is not affected by the change of sender: one
+// with no transfer, burn, `contract-call?`, nor read of `tx-sender` or
+// `contract-caller`. The private functions called are followed, and the
+// calls to other functions are assumed to be affected.
+pub struct AsContractChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    // The bodies of the private functions, by name
+    private_functions: HashMap<&'a ClarityName, &'a SymbolicExpression>,
+    // The number of `as-contract` around the expression traversed
+    depth: usize,
+}
+
+impl<'a> AsContractChecker<'a> {
+    fn new() -> AsContractChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            private_functions: HashMap::new(),
+            depth: 0,
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        for expr in contract_analysis.expressions.iter() {
+            if let Some((DefineFunctions::PrivateFunction, [signature, body])) =
+                DefineFunctions::try_parse(expr)
+            {
+                if let Some(name) = signature
+                    .match_list()
+                    .and_then(|signature| signature.first())
+                    .and_then(|name| name.match_atom())
+                {
+                    self.private_functions.insert(name, body);
+                }
+            }
+        }
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    // Whether the value of an expression, or its effects, can depend on the
+    // sender.
+    fn is_sender_sensitive(
+        &self,
+        expr: &'a SymbolicExpression,
+        followed: &mut HashSet<&'a ClarityName>,
+    ) -> bool {
+        if let Some(name) = expr.match_atom() {
+            return matches!(name.as_str(), "tx-sender" | "contract-caller");
+        }
+        let list = match expr.match_list() {
+            Some(list) => list,
+            None => return false,
+        };
+        if let Some((function, _)) = match_native_call(expr) {
+            use crate::clarity::functions::NativeFunctions::*;
+            if matches!(
+                function,
+                StxTransfer
+                    | StxBurn
+                    | TransferToken
+                    | BurnToken
+                    | TransferAsset
+                    | BurnAsset
+                    | ContractCall
+            ) {
+                return true;
+            }
+        } else if let Some(name) = list.first().and_then(|head| head.match_atom()) {
+            match self.private_functions.get(name) {
+                Some(body) => {
+                    if followed.insert(name) && self.is_sender_sensitive(body, followed) {
+                        return true;
+                    }
+                }
+                None => return true,
+            }
+        }
+        list.iter()
+            .skip(1)
+            .any(|element| self.is_sender_sensitive(element, followed))
+    }
+}
+
+impl<'a> ASTVisitor<'a> for AsContractChecker<'a> {
+    fn traverse_as_contract(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        inner: &'a SymbolicExpression,
+    ) -> bool {
+        if self.depth > 0 {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("nested-as-contract".to_string()),
+                message: "this as-contract is within another as-contract, so it has no effect"
+                    .to_string(),
+                spans: vec![expr.span.clone()],
+                suggestion: Some("Remove the inner as-contract".to_string()),
+            });
+        } else if !self.is_sender_sensitive(inner, &mut HashSet::new()) {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("redundant-as-contract".to_string()),
+                message: "this as-contract wraps no transfer, burn, contract-call? nor read of tx-sender or contract-caller, so it has no effect".to_string(),
+                spans: vec![expr.span.clone()],
+                suggestion: Some("Remove the as-contract".to_string()),
+            });
+        }
+        self.depth += 1;
+        let rv = self.traverse_expr(inner);
+        self.depth -= 1;
+        rv && self.visit_as_contract(expr, inner)
+    }
+}
+
+impl AnalysisPass for AsContractChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = AsContractChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn redundant_as_contract() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["as_contract_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-data-var count uint u0)
+(define-private (pay (amount uint) (recipient principal))
+    (stx-transfer? amount tx-sender recipient)
+)
+(define-public (withdraw (amount uint))
+    (let ((recipient tx-sender))
+        (as-contract (pay amount recipient))
+    )
+)
+(define-public (bump)
+    (as-contract (ok (var-set count (+ (var-get count) u1))))
+)
+(define-public (refund (amount uint) (recipient principal))
+    (as-contract (as-contract (stx-transfer? amount tx-sender recipient)))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:12:5: {}: this as-contract wraps no transfer, burn, contract-call? nor read of tx-sender or contract-caller, so it has no effect",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[3],
+                    format!(
+                        "checker:15:18: {}: this as-contract is within another as-contract, so it has no effect",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    result.diagnostics[1].suggestion,
+                    Some("Remove the inner as-contract".to_string())
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,255 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{
+    propagate_taint, references_symbol, traverse, ASTVisitor, TypedVar,
+};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::{Span, SymbolicExpression};
+use crate::clarity::ClarityName;
+use std::collections::{HashMap, HashSet};
+
+// Words which, in the name of a parameter, suggest human-readable text.
+const USER_CONTENT_WORDS: [&str; 10] = [
+    "name",
+    "memo",
+    "title",
+    "description",
+    "bio",
+    "message",
+    "comment",
+    "label",
+    "text",
+    "note",
+];
+
+// Notes `string-ascii` parameters of public functions whose name suggests
+// human-readable text, like `display-name`, when their value flows into a
+// data-var or map. Such text is often not ASCII, so the calls storing it would
+// fail. The values are followed through `let` bindings only.
+pub struct AsciiContentChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    // The parameters holding user content, with their spans
+    parameters: Vec<(&'a ClarityName, Span)>,
+    // The symbols derived from each of these parameters
+    sources: HashMap<&'a ClarityName, HashSet<&'a ClarityName>>,
+    stored: HashSet<&'a ClarityName>,
+}
+
+impl<'a> AsciiContentChecker<'a> {
+    fn new() -> AsciiContentChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            parameters: Vec::new(),
+            sources: HashMap::new(),
+            stored: HashSet::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    // The parameters whose value is used by an expression.
+    fn check_stored(&mut self, value: &SymbolicExpression) {
+        let parameters = parameters_in(&self.sources, value);
+        self.stored.extend(parameters);
+    }
+}
+
+// The parameters an expression derives from, given the symbols derived from
+// each parameter.
+fn parameters_in<'a>(
+    sources: &HashMap<&'a ClarityName, HashSet<&'a ClarityName>>,
+    expr: &SymbolicExpression,
+) -> HashSet<&'a ClarityName> {
+    let mut parameters = HashSet::new();
+    for (symbol, sources) in sources.iter() {
+        if references_symbol(expr, symbol) {
+            parameters.extend(sources.iter());
+        }
+    }
+    parameters
+}
+
+fn is_user_content(name: &ClarityName, type_expr: &SymbolicExpression) -> bool {
+    let is_ascii = type_expr
+        .match_list()
+        .and_then(|list| list.first())
+        .and_then(|head| head.match_atom())
+        .is_some_and(|head| head.as_str() == "string-ascii");
+    is_ascii
+        && name
+            .as_str()
+            .split('-')
+            .any(|word| USER_CONTENT_WORDS.contains(&word))
+}
+
+impl<'a> ASTVisitor<'a> for AsciiContentChecker<'a> {
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.parameters.clear();
+        self.sources.clear();
+        self.stored.clear();
+        for parameter in parameters.unwrap_or_default() {
+            if is_user_content(parameter.name, parameter.type_expr) {
+                self.parameters.push((parameter.name, parameter.decl_span));
+                self.sources
+                    .insert(parameter.name, HashSet::from([parameter.name]));
+            }
+        }
+        let rv = self.traverse_expr(body);
+        for (parameter, span) in self.parameters.iter() {
+            if !self.stored.contains(parameter) {
+                continue;
+            }
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("ascii-user-content".to_string()),
+                message: format!(
+                    "parameter '{}' holds user content as a string-ascii, so text which is not ASCII cannot be stored",
+                    parameter
+                ),
+                spans: vec![span.clone()],
+                suggestion: Some("Use string-utf8 for human-readable text".to_string()),
+            });
+        }
+        rv
+    }
+
+    fn traverse_let(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        bindings: &HashMap<&'a ClarityName, &'a SymbolicExpression>,
+        body: &'a [SymbolicExpression],
+    ) -> bool {
+        let pairs: Vec<(&'a ClarityName, &'a SymbolicExpression)> = bindings
+            .iter()
+            .map(|(name, value)| (*name, *value))
+            .collect();
+        propagate_taint(&pairs, &mut self.sources, |sources, name, value| {
+            let parameters = parameters_in(sources, value);
+            if parameters.is_empty() {
+                return false;
+            }
+            let sources = sources.entry(name).or_default();
+            let count = sources.len();
+            sources.extend(parameters);
+            sources.len() > count
+        });
+        for value in bindings.values() {
+            if !self.traverse_expr(value) {
+                return false;
+            }
+        }
+        for statement in body {
+            if !self.traverse_expr(statement) {
+                return false;
+            }
+        }
+        self.visit_let(expr, bindings, body)
+    }
+
+    fn visit_var_set(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_stored(value);
+        true
+    }
+
+    fn visit_map_set(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        key: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+        value: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+    ) -> bool {
+        for stored in key.values().chain(value.values()) {
+            self.check_stored(stored);
+        }
+        true
+    }
+
+    fn visit_map_insert(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        key: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+        value: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+    ) -> bool {
+        for stored in key.values().chain(value.values()) {
+            self.check_stored(stored);
+        }
+        true
+    }
+}
+
+impl AnalysisPass for AsciiContentChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = AsciiContentChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn stored_ascii_content() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["ascii_content_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-map profiles principal { display-name: (string-ascii 32), website: (string-ascii 64) })
+(define-data-var last-memo (string-utf8 34) u\"\")
+(define-public (set-profile (display-name (string-ascii 32)) (website (string-ascii 64)))
+    (let ((profile { display-name: display-name, website: website }))
+        (ok (map-set profiles tx-sender profile))
+    )
+)
+(define-public (greet (message (string-ascii 16)))
+    (ok (print message))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:4:30: {}: parameter 'display-name' holds user content as a string-ascii, so text which is not ASCII cannot be stored",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "(define-public (set-profile (display-name (string-ascii 32)) (website (string-ascii 64)))"
+                );
+                assert_eq!(output[2], "                             ^~~~~~~~~~~~");
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some("Use string-utf8 for human-readable text".to_string())
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,228 @@
+use crate::analysis::ast_visitor::match_native_call;
+use crate::clarity::functions::define::DefineFunctions;
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::representations::SymbolicExpressionType::*;
+use crate::clarity::types::{CharType, SequenceData, Value};
+use crate::clarity::ClarityName;
+use std::collections::HashMap;
+
+// Names the types of expressions from the AST alone, for the checks which run
+// before the type-checker, using the types of literals, constants, function
+// arguments, data-vars and native functions.
+#[derive(Default)]
+pub struct AstTypes<'a> {
+    constants: HashMap<&'a ClarityName, &'static str>,
+    data_vars: HashMap<&'a ClarityName, &'a SymbolicExpression>,
+    // The type of the values of each map
+    maps: HashMap<&'a ClarityName, &'a SymbolicExpression>,
+    // The declared type of each atom naming a function argument, by id. The
+    // atoms naming a `let` or `match` binding map to `None`.
+    locals: HashMap<u64, Option<&'a SymbolicExpression>>,
+}
+
+impl<'a> AstTypes<'a> {
+    // Collect the definitions of the contract and the scope of the local names.
+    pub fn new(expressions: &'a [SymbolicExpression]) -> AstTypes<'a> {
+        let mut types = AstTypes::default();
+        for expr in expressions {
+            match DefineFunctions::try_parse(expr) {
+                Some((DefineFunctions::Constant, [name, value])) => {
+                    if let (Some(name), Some(constant_type)) =
+                        (name.match_atom(), types.type_of(value))
+                    {
+                        types.constants.insert(name, constant_type);
+                    }
+                }
+                Some((DefineFunctions::PersistedVariable, [name, data_type, _])) => {
+                    if let Some(name) = name.match_atom() {
+                        types.data_vars.insert(name, data_type);
+                    }
+                }
+                Some((DefineFunctions::Map, [name, _, value_type])) => {
+                    if let Some(name) = name.match_atom() {
+                        types.maps.insert(name, value_type);
+                    }
+                }
+                _ => {}
+            }
+            types.bind_locals(expr, &mut vec![]);
+        }
+        types
+    }
+
+    // Map the atoms of `expr` to the local names in scope. Arguments are only
+    // in scope in the body of their function, and are shadowed by the
+    // bindings of `let` and `match`.
+    fn bind_locals(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        scope: &mut Vec<(&'a ClarityName, Option<&'a SymbolicExpression>)>,
+    ) {
+        if let Some(name) = expr.match_atom() {
+            if let Some((_, type_expr)) = scope.iter().rev().find(|(local, _)| *local == name) {
+                self.locals.insert(expr.id, *type_expr);
+            }
+            return;
+        }
+        let depth = scope.len();
+        if let Some((
+            DefineFunctions::PrivateFunction
+            | DefineFunctions::ReadOnlyFunction
+            | DefineFunctions::PublicFunction,
+            [signature, body],
+        )) = DefineFunctions::try_parse(expr)
+        {
+            for parameter in signature.match_list().unwrap_or_default().iter().skip(1) {
+                if let Some([name, type_expr]) = parameter.match_list() {
+                    if let Some(name) = name.match_atom() {
+                        scope.push((name, Some(type_expr)));
+                    }
+                }
+            }
+            self.bind_locals(body, scope);
+            scope.truncate(depth);
+            return;
+        }
+        match match_native_call(expr) {
+            Some((NativeFunctions::Let, [bindings, body @ ..])) => {
+                for binding in bindings.match_list().unwrap_or_default() {
+                    if let Some([name, value]) = binding.match_list() {
+                        self.bind_locals(value, scope);
+                        if let Some(name) = name.match_atom() {
+                            scope.push((name, None));
+                        }
+                    }
+                }
+                for e in body {
+                    self.bind_locals(e, scope);
+                }
+            }
+            Some((NativeFunctions::Match, [input, some_name, some_branch, none_branch])) => {
+                self.bind_locals(input, scope);
+                if let Some(name) = some_name.match_atom() {
+                    scope.push((name, None));
+                }
+                self.bind_locals(some_branch, scope);
+                scope.truncate(depth);
+                self.bind_locals(none_branch, scope);
+            }
+            Some((NativeFunctions::Match, [input, ok_name, ok_branch, err_name, err_branch])) => {
+                self.bind_locals(input, scope);
+                if let Some(name) = ok_name.match_atom() {
+                    scope.push((name, None));
+                }
+                self.bind_locals(ok_branch, scope);
+                scope.truncate(depth);
+                if let Some(name) = err_name.match_atom() {
+                    scope.push((name, None));
+                }
+                self.bind_locals(err_branch, scope);
+            }
+            _ => {
+                for e in expr.match_list().unwrap_or_default() {
+                    self.bind_locals(e, scope);
+                }
+            }
+        }
+        scope.truncate(depth);
+    }
+
+    // Name the type of the values of a map, if it is defined.
+    pub fn map_value_type(&self, name: &ClarityName) -> Option<&'static str> {
+        declared_type(self.maps.get(name)?)
+    }
+
+    // Name the type of an expression, if it can be known from the AST.
+    pub fn type_of(&self, expr: &SymbolicExpression) -> Option<&'static str> {
+        match &expr.expr {
+            LiteralValue(value) | AtomValue(value) => Some(value_type(value)),
+            Atom(name) => {
+                if let Some(local) = self.locals.get(&expr.id) {
+                    return local.and_then(declared_type);
+                }
+                if let Some(constant_type) = self.constants.get(name) {
+                    return Some(constant_type);
+                }
+                match name.as_str() {
+                    "none" => Some("optional"),
+                    "true" | "false" => Some("bool"),
+                    "tx-sender" | "contract-caller" => Some("principal"),
+                    "block-height" | "burn-block-height" | "stx-liquid-supply" => Some("uint"),
+                    _ => None,
+                }
+            }
+            List(_) => match match_native_call(expr)? {
+                (NativeFunctions::FetchVar, [name]) => self
+                    .data_vars
+                    .get(name.match_atom()?)
+                    .and_then(|t| declared_type(t)),
+                (function, _) => native_type(function),
+            },
+            _ => None,
+        }
+    }
+}
+
+fn value_type(value: &Value) -> &'static str {
+    match value {
+        Value::Int(_) => "int",
+        Value::UInt(_) => "uint",
+        Value::Bool(_) => "bool",
+        Value::Principal(_) => "principal",
+        Value::Tuple(_) => "tuple",
+        Value::Optional(_) => "optional",
+        Value::Response(_) => "response",
+        Value::Sequence(SequenceData::Buffer(_)) => "buff",
+        Value::Sequence(SequenceData::List(_)) => "list",
+        Value::Sequence(SequenceData::String(CharType::ASCII(_))) => "string-ascii",
+        Value::Sequence(SequenceData::String(CharType::UTF8(_))) => "string-utf8",
+    }
+}
+
+fn declared_type(type_expr: &SymbolicExpression) -> Option<&'static str> {
+    let type_name = match &type_expr.expr {
+        Atom(name) => name,
+        List(list) => list.first()?.match_atom()?,
+        _ => return None,
+    };
+    [
+        "int",
+        "uint",
+        "bool",
+        "principal",
+        "buff",
+        "string-ascii",
+        "string-utf8",
+        "list",
+        "tuple",
+        "optional",
+        "response",
+    ]
+    .iter()
+    .find(|name| type_name.as_str() == **name)
+    .copied()
+}
+
+// The type returned by native functions, where it does not depend on their
+// arguments.
+fn native_type(function: NativeFunctions) -> Option<&'static str> {
+    use crate::clarity::functions::NativeFunctions::*;
+    match function {
+        ConsOkay | ConsError | StxTransfer | StxBurn | TransferToken | TransferAsset
+        | MintToken | MintAsset | BurnToken | BurnAsset => Some("response"),
+        ConsSome | FetchEntry | ElementAt | IndexOf | AsMaxLen | GetBlockInfo | GetAssetOwner => {
+            Some("optional")
+        }
+        CmpGeq | CmpLeq | CmpLess | CmpGreater | And | Or | Not | Equals | IsOkay | IsNone
+        | IsErr | IsSome | SetVar | SetEntry | InsertEntry | DeleteEntry | Secp256k1Verify => {
+            Some("bool")
+        }
+        ToInt => Some("int"),
+        ToUInt | Len | GetTokenBalance | GetTokenSupply | GetStxBalance => Some("uint"),
+        Hash160 | Sha256 | Sha512 | Sha512Trunc256 | Keccak256 => Some("buff"),
+        ListCons | Map | Filter => Some("list"),
+        TupleCons | TupleMerge => Some("tuple"),
+        _ => None,
+    }
+}
@@ -2,7 +2,9 @@ use crate::clarity::functions::define::DefineFunctions;
 use crate::clarity::functions::NativeFunctions;
 use crate::clarity::representations::SymbolicExpressionType::*;
 use crate::clarity::representations::{Span, TraitDefinition};
-use crate::clarity::types::{PrincipalData, QualifiedContractIdentifier, TraitIdentifier, Value};
+use crate::clarity::types::{
+    CharType, PrincipalData, QualifiedContractIdentifier, SequenceData, TraitIdentifier, Value,
+};
 use crate::clarity::{ClarityName, SymbolicExpression, SymbolicExpressionType};
 use std::collections::HashMap;
 
@@ -267,7 +269,7 @@ pub trait ASTVisitor<'a> {
                             self.traverse_filter(expr, args[0].match_atom().unwrap(), &args[1])
                         }
                         UnwrapErrRet => self.traverse_unwrap_err(expr, &args[0], &args[1]),
-                        UnwrapErr => self.traverse_unwrap_err(expr, &args[0], &args[1]),
+                        UnwrapErr => self.traverse_unwrap_err_panic(expr, &args[0]),
                         Match => {
                             if args.len() == 4 {
                                 self.traverse_match_option(
@@ -1875,7 +1877,7 @@ pub trait ASTVisitor<'a> {
     ) -> bool {
         self.traverse_expr(sequence)
             && self.traverse_expr(item)
-            && self.visit_element_at(expr, sequence, item)
+            && self.visit_index_of(expr, sequence, item)
     }
 
     fn visit_index_of(
@@ -1941,6 +1943,135 @@ pub fn traverse<'a>(visitor: &mut impl ASTVisitor<'a>, exprs: &'a [SymbolicExpre
     true
 }
 
+// Match a call to a native function, returning the function and its arguments.
+pub fn match_native_call(
+    expr: &SymbolicExpression,
+) -> Option<(NativeFunctions, &[SymbolicExpression])> {
+    let (function_name, args) = expr.match_list()?.split_first()?;
+    let native_function = NativeFunctions::lookup_by_name(function_name.match_atom()?)?;
+    Some((native_function, args))
+}
+
+// The length of a literal sequence, or of a `list` call.
+pub fn sequence_length(expr: &SymbolicExpression) -> Option<usize> {
+    match &expr.expr {
+        LiteralValue(Value::Sequence(sequence)) | AtomValue(Value::Sequence(sequence)) => {
+            Some(match sequence {
+                SequenceData::Buffer(buffer) => buffer.data.len(),
+                SequenceData::List(list) => list.data.len(),
+                SequenceData::String(CharType::ASCII(string)) => string.data.len(),
+                SequenceData::String(CharType::UTF8(string)) => string.data.len(),
+            })
+        }
+        List(_) => match match_native_call(expr)? {
+            (NativeFunctions::ListCons, elements) => Some(elements.len()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Check whether `name` is referenced anywhere within `expr`.
+pub fn references_symbol(expr: &SymbolicExpression, name: &ClarityName) -> bool {
+    if let Some(atom) = expr.match_atom() {
+        atom == name
+    } else if let Some(list) = expr.match_list() {
+        list.iter().any(|e| references_symbol(e, name))
+    } else {
+        false
+    }
+}
+
+// Collect the name and value of each `let` binding within `expr`.
+pub fn collect_let_bindings<'a>(
+    expr: &'a SymbolicExpression,
+    bindings: &mut Vec<(&'a ClarityName, &'a SymbolicExpression)>,
+) {
+    if let Some((NativeFunctions::Let, [binding_list, ..])) = match_native_call(expr) {
+        for binding in binding_list.match_list().unwrap_or_default() {
+            if let Some([name, value]) = binding.match_list() {
+                if let Some(name) = name.match_atom() {
+                    bindings.push((name, value));
+                }
+            }
+        }
+    }
+    for e in expr.match_list().unwrap_or_default() {
+        collect_let_bindings(e, bindings);
+    }
+}
+
+// The calls of `function` whose response is thrown away by a `begin` or
+// `let` expression: the statements before the last one, and the values bound
+// to names the body does not use. Wrappers which do not propagate the
+// response, like `is-ok`, are stripped.
+pub fn discarded_calls(
+    expr: &SymbolicExpression,
+    function: NativeFunctions,
+) -> Vec<&SymbolicExpression> {
+    let discarded: Vec<&SymbolicExpression> = match match_native_call(expr) {
+        Some((NativeFunctions::Begin, statements)) => match statements.split_last() {
+            Some((_, rest)) => rest.iter().collect(),
+            None => vec![],
+        },
+        Some((NativeFunctions::Let, [bindings, body @ ..])) => {
+            let unused = bindings
+                .match_list()
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|binding| match binding.match_list()? {
+                    [name, value] => Some((name.match_atom()?, value)),
+                    _ => None,
+                })
+                .filter(|(name, _)| !body.iter().any(|e| references_symbol(e, name)))
+                .map(|(_, value)| value);
+            let statements = match body.split_last() {
+                Some((_, rest)) => rest,
+                None => &[],
+            };
+            unused.chain(statements).collect()
+        }
+        _ => vec![],
+    };
+    discarded
+        .into_iter()
+        .filter_map(|expr| strip_discarding_wrappers(expr, function))
+        .collect()
+}
+
+fn strip_discarding_wrappers(
+    expr: &SymbolicExpression,
+    function: NativeFunctions,
+) -> Option<&SymbolicExpression> {
+    match match_native_call(expr)? {
+        (native, _) if native == function => Some(expr),
+        (NativeFunctions::IsOkay, args)
+        | (NativeFunctions::IsErr, args)
+        | (NativeFunctions::Print, args) => strip_discarding_wrappers(args.first()?, function),
+        _ => None,
+    }
+}
+
+// Propagate what the values of `let` bindings derive from to the names they
+// bind. `derive` records it for one binding in `derived`, and returns whether
+// it added anything. Bindings can derive from each other, in any order, so
+// this repeats until nothing is added.
+pub fn propagate_taint<'a, T>(
+    bindings: &[(&'a ClarityName, &'a SymbolicExpression)],
+    derived: &mut T,
+    mut derive: impl FnMut(&mut T, &'a ClarityName, &'a SymbolicExpression) -> bool,
+) {
+    loop {
+        let mut added = false;
+        for (name, value) in bindings {
+            added |= derive(derived, name, value);
+        }
+        if !added {
+            break;
+        }
+    }
+}
+
 impl<'a> SymbolicExpression {
     fn match_tuple(&'a self) -> Option<HashMap<Option<&'a ClarityName>, &SymbolicExpression>> {
         if let Some(list) = self.match_list() {
@@ -0,0 +1,106 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+
+// Warns about `get-block-info?` at `block-height`. The current block is not
+// mined yet, so its info is always none.
+pub struct BlockInfoChecker {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl BlockInfoChecker {
+    fn new() -> BlockInfoChecker {
+        Self {
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+}
+
+impl<'a> ASTVisitor<'a> for BlockInfoChecker {
+    fn visit_get_block_info(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        prop_name: &'a ClarityName,
+        block: &'a SymbolicExpression,
+    ) -> bool {
+        if block
+            .match_atom()
+            .is_some_and(|height| height.as_str() == "block-height")
+        {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                code: Some("block-info-at-block-height".to_string()),
+                message: format!(
+                    "'{}' of the block at block-height is always none, as the current block is not mined yet",
+                    prop_name
+                ),
+                spans: vec![expr.span.clone()],
+                suggestion: Some("Use (- block-height u1) for the last mined block".to_string()),
+            });
+        }
+        true
+    }
+}
+
+impl AnalysisPass for BlockInfoChecker {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = BlockInfoChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn block_info_at_block_height() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["block_info_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-read-only (current-time)
+    (get-block-info? time block-height)
+)
+(define-read-only (last-time)
+    (get-block-info? time (- block-height u1))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:5: {}: 'time' of the block at block-height is always none, as the current block is not mined yet",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(output[1], "    (get-block-info? time block-height)");
+                assert_eq!(output[2], "    ^~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some("Use (- block-height u1) for the last mined block".to_string())
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
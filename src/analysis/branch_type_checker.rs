@@ -0,0 +1,223 @@
+use crate::analysis::ast_types::AstTypes;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor};
+use crate::analysis::{pre_check_result, AnalysisResult};
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+
+// Reports the branches of `match` and `if` whose values are of different
+// types, naming the branch which diverges from the first one. A branch which
+// is a `begin` has the type of its last statement.
+pub struct BranchTypeChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    types: AstTypes<'a>,
+}
+
+impl<'a> BranchTypeChecker<'a> {
+    fn new(expressions: &'a [SymbolicExpression]) -> BranchTypeChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            types: AstTypes::new(expressions),
+        }
+    }
+
+    pub fn run(expressions: &'a [SymbolicExpression]) -> AnalysisResult {
+        let mut checker = BranchTypeChecker::new(expressions);
+        traverse(&mut checker, expressions);
+        pre_check_result(checker.diagnostics)
+    }
+
+    // The type of a branch. `binding` is the name bound by a `match` for the
+    // branch, with the type of its value, if known.
+    fn branch_type(
+        &self,
+        branch: &SymbolicExpression,
+        binding: Option<(&ClarityName, &'static str)>,
+    ) -> Option<&'static str> {
+        if let (Some(name), Some((bound, bound_type))) = (branch.match_atom(), binding) {
+            if name == bound {
+                return Some(bound_type);
+            }
+        }
+        match match_native_call(branch) {
+            Some((NativeFunctions::Begin, [.., last])) => self.branch_type(last, binding),
+            _ => self.types.type_of(branch),
+        }
+    }
+
+    fn check_branches(
+        &mut self,
+        construct: &str,
+        (first_name, first, first_type): (&str, &'a SymbolicExpression, Option<&'static str>),
+        (second_name, second, second_type): (&str, &'a SymbolicExpression, Option<&'static str>),
+    ) {
+        let (first_type, second_type) = match (first_type, second_type) {
+            (Some(first_type), Some(second_type)) => (first_type, second_type),
+            _ => return,
+        };
+        if first_type == second_type {
+            return;
+        }
+        self.diagnostics.push(Diagnostic {
+            level: Level::Error,
+            code: Some("branch-type-mismatch".to_string()),
+            message: format!(
+                "the {} branch of this {} returns a value of type '{}', but the {} branch returns a value of type '{}'",
+                second_name, construct, second_type, first_name, first_type
+            ),
+            spans: vec![second.span.clone(), first.span.clone()],
+            suggestion: None,
+        });
+    }
+}
+
+impl<'a> ASTVisitor<'a> for BranchTypeChecker<'a> {
+    fn visit_if(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        cond: &'a SymbolicExpression,
+        then_expr: &'a SymbolicExpression,
+        else_expr: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_branches(
+            "if",
+            ("then", then_expr, self.branch_type(then_expr, None)),
+            ("else", else_expr, self.branch_type(else_expr, None)),
+        );
+        true
+    }
+
+    fn visit_match_option(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        input: &'a SymbolicExpression,
+        some_name: &'a ClarityName,
+        some_branch: &'a SymbolicExpression,
+        none_branch: &'a SymbolicExpression,
+    ) -> bool {
+        // The value of an entry of a map is of the type of the map
+        let binding = match match_native_call(input) {
+            Some((NativeFunctions::FetchEntry, [map_name, _])) => map_name
+                .match_atom()
+                .and_then(|map_name| self.types.map_value_type(map_name))
+                .map(|value_type| (some_name, value_type)),
+            _ => None,
+        };
+        self.check_branches(
+            "match",
+            ("some", some_branch, self.branch_type(some_branch, binding)),
+            ("none", none_branch, self.branch_type(none_branch, None)),
+        );
+        true
+    }
+
+    fn visit_match_response(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        input: &'a SymbolicExpression,
+        ok_name: &'a ClarityName,
+        ok_branch: &'a SymbolicExpression,
+        err_name: &'a ClarityName,
+        err_branch: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_branches(
+            "match",
+            ("ok", ok_branch, self.branch_type(ok_branch, None)),
+            ("err", err_branch, self.branch_type(err_branch, None)),
+        );
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn match_branches() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-map names principal (string-ascii 16))
+(define-read-only (name-of (who principal))
+    (match (map-get? names who)
+        name name
+        u0
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Err(output) => {
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:6:9: {}: the none branch of this match returns a value of type 'uint', but the some branch returns a value of type 'string-ascii'",
+                        red!("error")
+                    )
+                );
+                assert_eq!(output[1], "        u0");
+                assert_eq!(output[2], "        ^");
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn if_branches() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-data-var total int 0)
+(define-read-only (total-or-zero (known bool))
+    (if known
+        (begin (print known) (var-get total))
+        u0
+    )
+)
+"
+        .to_string();
+        match session.interpret(snippet, None, false, None) {
+            Err((_, Some(diagnostic), _)) => {
+                assert_eq!(
+                    diagnostic.message,
+                    "the else branch of this if returns a value of type 'uint', but the then branch returns a value of type 'int'"
+                );
+                assert_eq!(diagnostic.spans.len(), 2);
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-read-only (checked (amount uint))
+    (if (> amount u0) (ok amount) (err u1))
+)
+"
+        .to_string();
+        assert!(session.interpret(snippet, None, false, None).is_ok());
+    }
+
+    #[test]
+    fn scoped_arguments() {
+        // The argument of `f` is not in scope after its definition
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-private (f (x uint)) (+ x u1))
+(let ((x (- 1 2))) (if true x 3))
+"
+        .to_string();
+        assert!(session.interpret(snippet, None, false, None).is_ok());
+
+        // Nor in other functions, where the name is bound by `let` or `match`
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-private (f (x uint) (y bool)) (if y x u0))
+(define-read-only (g (z (optional int)))
+    (+ (let ((x -1)) (if true x 2)) (match z y (if true y 2) 3))
+)
+"
+        .to_string();
+        assert!(session.interpret(snippet, None, false, None).is_ok());
+    }
+}
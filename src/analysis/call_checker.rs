@@ -1,6 +1,6 @@
 use crate::analysis::annotation::Annotation;
 use crate::analysis::ast_visitor::{traverse, ASTVisitor, TypedVar};
-use crate::analysis::{AnalysisPass, AnalysisResult};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
 use crate::clarity::analysis::analysis_db::AnalysisDatabase;
 pub use crate::clarity::analysis::types::ContractAnalysis;
 use crate::clarity::ast::ContractAST;
@@ -61,6 +61,7 @@ impl<'a> CallChecker<'a> {
     ) -> Diagnostic {
         Diagnostic {
             level: Level::Error,
+            code: Some("argument-count".to_string()),
             message: format!(
                 "incorrect number of arguments in call to '{}' (expected {} got {})",
                 name, expected, got
@@ -141,6 +142,7 @@ impl AnalysisPass for CallChecker<'_> {
         contract_analysis: &mut ContractAnalysis,
         analysis_db: &mut AnalysisDatabase,
         annotations: &Vec<Annotation>,
+        settings: &Settings,
     ) -> AnalysisResult {
         let tc = CallChecker::new();
         tc.run(contract_analysis)
@@ -0,0 +1,517 @@
+use crate::clarity::diagnostic::Level;
+
+// A diagnostic one of the built-in passes can produce. The level is the one
+// it is reported at, before "strict" raises the notes to warnings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiagnosticCatalogEntry {
+    pub code: &'static str,
+    pub level: Level,
+    pub title: &'static str,
+    // The name of the pass, as given to the analysis setting, or the module
+    // of the checks run before the type-checker
+    pub pass: &'static str,
+}
+
+// Each diagnostic a pass adds is registered here, next to the other
+// diagnostics of its pass.
+const CATALOG: &[(&str, Level, &str, &str)] = &[
+    (
+        "analysis-timeout",
+        Level::Warning,
+        "The analysis timed out before all its passes ran",
+        "analysis",
+    ),
+    (
+        "append-overflow",
+        Level::Warning,
+        "fold appends more elements than its accumulator can hold",
+        "append_overflow_checker",
+    ),
+    (
+        "nested-as-contract",
+        Level::Note,
+        "as-contract within another as-contract",
+        "as_contract_checker",
+    ),
+    (
+        "redundant-as-contract",
+        Level::Note,
+        "as-contract not affected by the change of sender",
+        "as_contract_checker",
+    ),
+    (
+        "ascii-user-content",
+        Level::Note,
+        "User content stored as a string-ascii",
+        "ascii_content_checker",
+    ),
+    (
+        "block-info-at-block-height",
+        Level::Warning,
+        "get-block-info? of the current block, which is always none",
+        "block_info_checker",
+    ),
+    (
+        "branch-type-mismatch",
+        Level::Error,
+        "Branches returning values of different types",
+        "branch_type_checker",
+    ),
+    (
+        "argument-count",
+        Level::Error,
+        "Incorrect number of arguments in a call",
+        "call_checker",
+    ),
+    (
+        "unchecked-data",
+        Level::Warning,
+        "Use of potentially unchecked data",
+        "check_checker",
+    ),
+    (
+        "untrusted-input-source",
+        Level::Note,
+        "Source of the unchecked data",
+        "check_checker",
+    ),
+    (
+        "assert-always-fails",
+        Level::Warning,
+        "Statements after an asserts! which always fails",
+        "constant_guard_checker",
+    ),
+    (
+        "constant-if",
+        Level::Warning,
+        "Branch of an if whose condition is constant",
+        "constant_guard_checker",
+    ),
+    (
+        "discarded-contract-call",
+        Level::Warning,
+        "Response of contract-call? discarded",
+        "contract_call_checker",
+    ),
+    (
+        "contract-call-always-fails",
+        Level::Note,
+        "contract-call? failing an asserts! of the called function",
+        "contract_call_guard_checker",
+    ),
+    (
+        "large-data-var",
+        Level::Note,
+        "Large initial value of a data-var",
+        "data_var_size_checker",
+    ),
+    (
+        "unknown-trait-type",
+        Level::Error,
+        "Unknown type in the definition of a trait",
+        "define_trait_checker",
+    ),
+    (
+        "duplicate-body",
+        Level::Note,
+        "Public functions with identical bodies",
+        "duplicate_body_checker",
+    ),
+    (
+        "duplicate-error",
+        Level::Note,
+        "Error value thrown by several asserts!",
+        "duplicate_error_checker",
+    ),
+    (
+        "unused-accumulator",
+        Level::Note,
+        "fold function never using its accumulator",
+        "fold_accumulator_checker",
+    ),
+    (
+        "fold-range",
+        Level::Note,
+        "Range of values a fold iterates over",
+        "fold_range_checker",
+    ),
+    (
+        "forward-reference",
+        Level::Note,
+        "Constant referencing a definition made later in the source",
+        "forward_reference_checker",
+    ),
+    (
+        "unchecked-ft-balance",
+        Level::Note,
+        "Token amount used before the balance is checked",
+        "ft_balance_checker",
+    ),
+    (
+        "hashed-key-collision",
+        Level::Note,
+        "Map key hashing concatenated inputs without a separator",
+        "hashed_key_checker",
+    ),
+    (
+        "variadic-is-eq",
+        Level::Note,
+        "is-eq with more than two arguments",
+        "is_eq_checker",
+    ),
+    (
+        "iterated-contract-call",
+        Level::Warning,
+        "contract-call? made for each element of a sequence",
+        "iterated_call_checker",
+    ),
+    (
+        "len-mixed-with-int",
+        Level::Error,
+        "uint returned by len mixed with an int",
+        "len_result_checker",
+    ),
+    (
+        "len-as-sequence",
+        Level::Error,
+        "uint returned by len used as a sequence",
+        "len_result_checker",
+    ),
+    (
+        "lookup-chain",
+        Level::Note,
+        "Chain of if expressions comparing against constants",
+        "lookup_chain_checker",
+    ),
+    (
+        "map-get-none-comparison",
+        Level::Note,
+        "map-get? compared with none",
+        "map_get_comparison_checker",
+    ),
+    (
+        "map-get-type-mismatch",
+        Level::Error,
+        "map-get? compared with a value of another type",
+        "map_get_comparison_checker",
+    ),
+    (
+        "nested-optional",
+        Level::Note,
+        "Type nesting many optionals and responses",
+        "nested_optional_checker",
+    ),
+    (
+        "other-network-principal",
+        Level::Warning,
+        "Principal of another network than the one targeted",
+        "network_principal_checker",
+    ),
+    (
+        "discarded-nft-mint",
+        Level::Warning,
+        "Result of nft-mint? discarded",
+        "nft_mint_checker",
+    ),
+    (
+        "nft-id-incremented-late",
+        Level::Warning,
+        "nft-mint? of a counter incremented after the mint",
+        "nft_mint_checker",
+    ),
+    (
+        "nft-id-increment",
+        Level::Note,
+        "Increment of the counter used as a token id",
+        "nft_mint_checker",
+    ),
+    (
+        "pow-overflow",
+        Level::Error,
+        "pow overflowing the 128-bit range",
+        "pow_overflow_checker",
+    ),
+    (
+        "unchecked-pow-exponent",
+        Level::Warning,
+        "Unchecked argument as the exponent of pow",
+        "pow_overflow_checker",
+    ),
+    (
+        "public-non-response",
+        Level::Error,
+        "Public function not returning a response",
+        "public_return_checker",
+    ),
+    (
+        "read-only-auth",
+        Level::Note,
+        "Check of the caller in a read-only function",
+        "read_only_auth_checker",
+    ),
+    (
+        "read-only-candidate",
+        Level::Note,
+        "Public function which could be read-only",
+        "read_only_candidate_checker",
+    ),
+    (
+        "redundant-unwrap",
+        Level::Note,
+        "Unwrap of a value which always succeeds",
+        "redundant_unwrap_checker",
+    ),
+    (
+        "renamed-function",
+        Level::Warning,
+        "Function deprecated in favor of a renamed one",
+        "renamed_function_checker",
+    ),
+    (
+        "repeated-literal",
+        Level::Note,
+        "Literal repeated instead of a constant",
+        "repeated_literal_checker",
+    ),
+    (
+        "self-transfer",
+        Level::Note,
+        "Transfer whose sender is also its recipient",
+        "self_transfer_checker",
+    ),
+    (
+        "unordered-comparison",
+        Level::Error,
+        "Comparison of values which cannot be ordered",
+        "sequence_comparison_checker",
+    ),
+    (
+        "as-max-len-always-none",
+        Level::Warning,
+        "as-max-len? which always returns none",
+        "sequence_length_checker",
+    ),
+    (
+        "as-max-len-always-some",
+        Level::Warning,
+        "as-max-len? which always returns some",
+        "sequence_length_checker",
+    ),
+    (
+        "element-at-out-of-bounds",
+        Level::Warning,
+        "element-at index beyond the maximum length",
+        "sequence_length_checker",
+    ),
+    (
+        "side-effect-condition",
+        Level::Warning,
+        "Condition modifying the state",
+        "side_effect_condition_checker",
+    ),
+    (
+        "stored-response",
+        Level::Note,
+        "Response stored in a map or a data-var",
+        "stored_response_checker",
+    ),
+    (
+        "stx-burn",
+        Level::Warning,
+        "stx-burn? permanently destroying STX",
+        "stx_burn_checker",
+    ),
+    (
+        "trait-alias-collision",
+        Level::Error,
+        "use-trait alias with the name of another definition",
+        "trait_alias_checker",
+    ),
+    (
+        "trait-alias-near-miss",
+        Level::Note,
+        "use-trait alias close to the name of another definition",
+        "trait_alias_checker",
+    ),
+    (
+        "trait-reentrancy",
+        Level::Warning,
+        "Call through a trait which could re-enter the contract",
+        "trait_recursion_checker",
+    ),
+    (
+        "trait-signature-mismatch",
+        Level::Error,
+        "Function not matching the method of its trait",
+        "trait_signature_checker",
+    ),
+    (
+        "tuple-order",
+        Level::Note,
+        "Tuple fields not in the order of its type",
+        "tuple_order_checker",
+    ),
+    (
+        "unbounded-mint",
+        Level::Warning,
+        "Uncapped mint of unchecked amounts to unchecked recipients",
+        "unbounded_mint_checker",
+    ),
+    (
+        "unchecked-index",
+        Level::Warning,
+        "unwrap-panic of an element whose index is not checked",
+        "unchecked_index_checker",
+    ),
+    (
+        "unused-use-trait",
+        Level::Warning,
+        "use-trait whose alias is never used",
+        "unused_trait_checker",
+    ),
+    (
+        "unwrap-argument-type",
+        Level::Error,
+        "Unwrap of a value of the wrong type",
+        "unwrap_argument_checker",
+    ),
+    (
+        "data-var-copy",
+        Level::Note,
+        "Modification of a copy of a data-var",
+        "var_copy_checker",
+    ),
+    (
+        "reserved-name",
+        Level::Error,
+        "Definition of a name reserved by the targeted version",
+        "version_syntax_checker",
+    ),
+    (
+        "unavailable-function",
+        Level::Error,
+        "Function not available in the targeted version",
+        "version_syntax_checker",
+    ),
+    (
+        "unavailable-syntax",
+        Level::Error,
+        "Syntax form not available in the targeted version",
+        "version_syntax_checker",
+    ),
+];
+
+// The diagnostics the built-in passes can produce, grouped by pass.
+pub fn diagnostic_catalog() -> Vec<DiagnosticCatalogEntry> {
+    CATALOG
+        .iter()
+        .map(|(code, level, title, pass)| DiagnosticCatalogEntry {
+            code,
+            level: level.clone(),
+            title,
+            pass,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+    use std::collections::HashSet;
+    use std::fs;
+    use std::path::Path;
+
+    #[test]
+    fn unique_codes() {
+        let catalog = diagnostic_catalog();
+        let codes: HashSet<&str> = catalog.iter().map(|entry| entry.code).collect();
+        assert_eq!(codes.len(), catalog.len());
+        for entry in catalog.iter() {
+            assert!(entry
+                .code
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'));
+        }
+    }
+
+    #[test]
+    fn grouped_by_pass() {
+        let catalog = diagnostic_catalog();
+        let mut seen = HashSet::new();
+        for (index, entry) in catalog.iter().enumerate() {
+            if index == 0 || catalog[index - 1].pass != entry.pass {
+                assert!(seen.insert(entry.pass), "{} is not grouped", entry.pass);
+            }
+        }
+    }
+
+    // Each code a pass sets is registered under that pass, and each entry of
+    // the catalog is set by its pass.
+    #[test]
+    fn registered_codes() {
+        let catalog = diagnostic_catalog();
+        let mut set = HashSet::new();
+        let analysis = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/analysis");
+        let mut paths = vec![analysis.join("check_checker/mod.rs")];
+        for entry in fs::read_dir(&analysis).unwrap() {
+            paths.push(entry.unwrap().path());
+        }
+        for path in paths.iter().filter(|path| path.is_file()) {
+            let pass = match path.file_stem().unwrap().to_str().unwrap() {
+                "mod" if path.ends_with("check_checker/mod.rs") => "check_checker",
+                "mod" => "analysis",
+                pass => pass,
+            };
+            let source = fs::read_to_string(path).unwrap();
+            let prefix = "code: Some(\"";
+            for (start, _) in source.match_indices(prefix) {
+                let rest = &source[start + prefix.len()..];
+                let code = &rest[..rest.find('"').unwrap()];
+                set.insert(code.to_string());
+                assert!(
+                    catalog
+                        .iter()
+                        .any(|entry| entry.code == code && entry.pass == pass),
+                    "{} is not registered for {}",
+                    code,
+                    pass
+                );
+            }
+        }
+        assert_eq!(set.len(), catalog.len());
+    }
+
+    #[test]
+    fn emitted_codes() {
+        let catalog = diagnostic_catalog();
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["strict".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-map counts principal uint)
+(define-public (count (n uint))
+    (begin
+        (map-set counts tx-sender (pow u10 n))
+        (print (is-eq n u1 u2))
+        (ok (element-at (list u1 u2) u3))
+    )
+)
+";
+        let mut emitted = vec![];
+        for (name, contract) in [
+            ("bns", include_str!("../contracts/bns.clar")),
+            ("checker", snippet),
+        ] {
+            let (_, errors, warnings, notes) =
+                session.interpret_split(contract.to_string(), Some(name.to_string()));
+            emitted.extend(errors.into_iter().chain(warnings).chain(notes));
+        }
+        assert!(!emitted.is_empty());
+        for diagnostic in emitted {
+            let code = diagnostic.code.expect("diagnostic without a code");
+            assert!(catalog.iter().any(|entry| entry.code == code));
+        }
+    }
+}
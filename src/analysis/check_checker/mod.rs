@@ -1,6 +1,6 @@
 use crate::analysis::annotation::{Annotation, AnnotationKind, WarningKind};
 use crate::analysis::ast_visitor::{traverse, ASTVisitor, TypedVar};
-use crate::analysis::{AnalysisPass, AnalysisResult};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
 use crate::clarity::analysis::analysis_db::AnalysisDatabase;
 use crate::clarity::analysis::types::ContractAnalysis;
 use crate::clarity::diagnostic::{DiagnosableError, Diagnostic, Level};
@@ -202,6 +202,7 @@ impl<'a, 'b> CheckChecker<'a, 'b> {
         let mut diagnostics: Vec<Diagnostic> = Vec::new();
         let diagnostic = Diagnostic {
             level: Level::Warning,
+            code: Some("unchecked-data".to_string()),
             message: "use of potentially unchecked data".to_string(),
             spans: vec![expr.span.clone()],
             suggestion: None,
@@ -219,6 +220,7 @@ impl<'a, 'b> CheckChecker<'a, 'b> {
         for span in source_spans {
             let diagnostic = Diagnostic {
                 level: Level::Note,
+                code: Some("untrusted-input-source".to_string()),
                 message: "source of untrusted input here".to_string(),
                 spans: vec![span],
                 suggestion: None,
@@ -697,6 +699,7 @@ impl AnalysisPass for CheckChecker<'_, '_> {
         contract_analysis: &mut ContractAnalysis,
         analysis_db: &mut AnalysisDatabase,
         annotations: &Vec<Annotation>,
+        settings: &Settings,
     ) -> AnalysisResult {
         let tc = CheckChecker::new(analysis_db, annotations);
         tc.run(contract_analysis)
@@ -0,0 +1,298 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::{Span, SymbolicExpression};
+use crate::clarity::types::Value;
+use crate::clarity::ClarityName;
+use std::collections::HashMap;
+
+// Warns about the logic which is never evaluated because it is guarded by a
+// condition folding to a constant: the branch of an `if` not taken, and the
+// statements following an `asserts!` which always fails. Only the conditions
+// made of literals and constants, with `not`, `and`, `or` and comparisons,
+// are folded. A branch which is a single value is not reported, as it is
+// usually the default of a disabled feature.
+pub struct ConstantGuardChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    constants: HashMap<&'a ClarityName, Value>,
+}
+
+impl<'a> ConstantGuardChecker<'a> {
+    fn new() -> ConstantGuardChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            constants: HashMap::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    // The value of an expression, if it only depends on literals and
+    // constants.
+    fn fold(&self, expr: &SymbolicExpression) -> Option<Value> {
+        if let Some(value) = expr
+            .match_literal_value()
+            .or_else(|| expr.match_atom_value())
+        {
+            return Some(value.clone());
+        }
+        if let Some(name) = expr.match_atom() {
+            return match name.as_str() {
+                "true" => Some(Value::Bool(true)),
+                "false" => Some(Value::Bool(false)),
+                _ => self.constants.get(name).cloned(),
+            };
+        }
+        let (function, args) = match_native_call(expr)?;
+        match function {
+            NativeFunctions::Not => match args {
+                [arg] => Some(Value::Bool(!self.fold_bool(arg)?)),
+                _ => None,
+            },
+            NativeFunctions::And => args
+                .iter()
+                .map(|arg| self.fold_bool(arg))
+                .collect::<Option<Vec<bool>>>()
+                .map(|values| Value::Bool(values.iter().all(|value| *value))),
+            NativeFunctions::Or => args
+                .iter()
+                .map(|arg| self.fold_bool(arg))
+                .collect::<Option<Vec<bool>>>()
+                .map(|values| Value::Bool(values.iter().any(|value| *value))),
+            NativeFunctions::Equals => {
+                let values = args
+                    .iter()
+                    .map(|arg| self.fold(arg))
+                    .collect::<Option<Vec<Value>>>()?;
+                Some(Value::Bool(
+                    values.windows(2).all(|pair| pair[0] == pair[1]),
+                ))
+            }
+            NativeFunctions::CmpLess
+            | NativeFunctions::CmpLeq
+            | NativeFunctions::CmpGreater
+            | NativeFunctions::CmpGeq => {
+                let (lhs, rhs) = match args {
+                    [lhs, rhs] => (self.fold(lhs)?, self.fold(rhs)?),
+                    _ => return None,
+                };
+                let ordering = match (lhs, rhs) {
+                    (Value::Int(lhs), Value::Int(rhs)) => lhs.cmp(&rhs),
+                    (Value::UInt(lhs), Value::UInt(rhs)) => lhs.cmp(&rhs),
+                    _ => return None,
+                };
+                Some(Value::Bool(match function {
+                    NativeFunctions::CmpLess => ordering.is_lt(),
+                    NativeFunctions::CmpLeq => ordering.is_le(),
+                    NativeFunctions::CmpGreater => ordering.is_gt(),
+                    _ => ordering.is_ge(),
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    fn fold_bool(&self, expr: &SymbolicExpression) -> Option<bool> {
+        match self.fold(expr)? {
+            Value::Bool(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn check_statements(&mut self, statements: &'a [SymbolicExpression]) {
+        for (index, statement) in statements.iter().enumerate() {
+            let cond = match match_native_call(statement) {
+                Some((NativeFunctions::Asserts, [cond, _])) => cond,
+                _ => continue,
+            };
+            let following = &statements[index + 1..];
+            if let (Some(false), [first, .., last] | [first @ last]) =
+                (self.fold_bool(cond), following)
+            {
+                self.diagnostics.push(Diagnostic {
+                    level: Level::Warning,
+                    code: Some("assert-always-fails".to_string()),
+                    message: "these statements are never evaluated, as the asserts! before them always fails".to_string(),
+                    spans: vec![
+                        Span {
+                            start_line: first.span.start_line,
+                            start_column: first.span.start_column,
+                            end_line: last.span.end_line,
+                            end_column: last.span.end_column,
+                        },
+                        cond.span.clone(),
+                    ],
+                    suggestion: Some("Make the condition depend on the state or the arguments, or remove the dead statements".to_string()),
+                });
+                return;
+            }
+        }
+    }
+}
+
+impl<'a> ASTVisitor<'a> for ConstantGuardChecker<'a> {
+    fn visit_define_constant(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        if let Some(value) = self.fold(value) {
+            self.constants.insert(name, value);
+        }
+        true
+    }
+
+    fn visit_if(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        cond: &'a SymbolicExpression,
+        then_expr: &'a SymbolicExpression,
+        else_expr: &'a SymbolicExpression,
+    ) -> bool {
+        let (dead, outcome) = match self.fold_bool(cond) {
+            Some(true) => (else_expr, "true"),
+            Some(false) => (then_expr, "false"),
+            None => return true,
+        };
+        if dead.match_list().is_some() {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                code: Some("constant-if".to_string()),
+                message: format!(
+                    "this branch is never evaluated, as the condition of the if is always {}",
+                    outcome
+                ),
+                spans: vec![dead.span.clone(), cond.span.clone()],
+                suggestion: Some("Make the condition depend on the state or the arguments, or remove the dead branch".to_string()),
+            });
+        }
+        true
+    }
+
+    fn visit_begin(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        statements: &'a [SymbolicExpression],
+    ) -> bool {
+        self.check_statements(statements);
+        true
+    }
+
+    fn visit_let(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        bindings: &HashMap<&'a ClarityName, &'a SymbolicExpression>,
+        body: &'a [SymbolicExpression],
+    ) -> bool {
+        self.check_statements(body);
+        true
+    }
+}
+
+impl AnalysisPass for ConstantGuardChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = ConstantGuardChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn constant_if() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["constant_guard_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-constant ENABLED false)
+(define-constant VERSION u1)
+(define-data-var count uint u0)
+(define-public (bump)
+    (if ENABLED
+        (ok (var-set count (+ (var-get count) u1)))
+        (ok true)
+    )
+)
+(define-read-only (get-count)
+    (if (and (not ENABLED) (>= VERSION u1)) (var-get count) u0)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:7:9: {}: this branch is never evaluated, as the condition of the if is always false",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "        (ok (var-set count (+ (var-get count) u1)))"
+                );
+                assert_eq!(
+                    output[2],
+                    "        ^~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn constant_asserts() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["constant_guard_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-constant PAUSED true)
+(define-data-var count uint u0)
+(define-public (bump)
+    (begin
+        (asserts! (not PAUSED) (err u1))
+        (var-set count (+ (var-get count) u1))
+        (ok true)
+    )
+)
+(define-public (reset (owner principal))
+    (begin
+        (asserts! (is-eq owner tx-sender) (err u2))
+        (ok (var-set count u0))
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:7:9: {}: these statements are never evaluated, as the asserts! before them always fails",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(result.diagnostics[0].spans[0].end_line, 8);
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,203 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{discarded_calls, traverse, ASTVisitor, TypedVar};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+
+pub struct ContractCallChecker {
+    diagnostics: Vec<Diagnostic>,
+    in_public: bool,
+}
+
+impl ContractCallChecker {
+    fn new() -> ContractCallChecker {
+        Self {
+            diagnostics: Vec::new(),
+            in_public: false,
+        }
+    }
+
+    fn run(mut self, contract_analysis: &ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    // Report the `contract-call?` expressions in public functions whose
+    // response is thrown away by `expr`, either directly or through a wrapper
+    // like `is-ok`.
+    fn check_discarded(&mut self, expr: &SymbolicExpression) {
+        if !self.in_public {
+            return;
+        }
+        for call in discarded_calls(expr, NativeFunctions::ContractCall) {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                code: Some("discarded-contract-call".to_string()),
+                message: "response of contract-call? is discarded, so an error in the called contract will be silently ignored".to_string(),
+                spans: vec![call.span.clone()],
+                suggestion: Some(
+                    "Propagate the error with try! or handle it explicitly with match".to_string(),
+                ),
+            });
+        }
+    }
+}
+
+impl<'a> ASTVisitor<'a> for ContractCallChecker {
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.in_public = false;
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.in_public = false;
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.in_public = true;
+        let result = self.traverse_expr(body);
+        self.in_public = false;
+        result
+    }
+
+    fn visit_list(&mut self, expr: &'a SymbolicExpression, list: &'a [SymbolicExpression]) -> bool {
+        self.check_discarded(expr);
+        true
+    }
+}
+
+impl AnalysisPass for ContractCallChecker {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = ContractCallChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    fn deploy_callee(session: &mut Session) {
+        let callee = "(define-public (ping) (if true (ok true) (err u1)))".to_string();
+        session
+            .formatted_interpretation(callee, Some("callee".to_string()), false, None)
+            .unwrap();
+    }
+
+    #[test]
+    fn discarded_contract_call() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["contract_call_checker".to_string()];
+        let mut session = Session::new(settings);
+        deploy_callee(&mut session);
+        let snippet = "
+(define-public (call)
+    (begin
+        (is-ok (contract-call? .callee ping))
+        (ok true)
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, _)) => {
+                assert_eq!(output.len(), 3);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:4:16: {}: response of contract-call? is discarded, so an error in the called contract will be silently ignored",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(output[1], "        (is-ok (contract-call? .callee ping))");
+                assert_eq!(output[2], "               ^~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn unused_let_binding() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["contract_call_checker".to_string()];
+        let mut session = Session::new(settings);
+        deploy_callee(&mut session);
+        let snippet = "
+(define-public (call)
+    (let ((result (contract-call? .callee ping)))
+        (ok true)
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, _)) => {
+                assert_eq!(output.len(), 3);
+                assert_eq!(
+                    output[1],
+                    "    (let ((result (contract-call? .callee ping)))"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn private_and_checked_calls() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["contract_call_checker".to_string()];
+        let mut session = Session::new(settings);
+        deploy_callee(&mut session);
+        let snippet = "
+(define-private (call-private)
+    (begin
+        (is-ok (contract-call? .callee ping))
+        (ok true)
+    )
+)
+(define-public (call)
+    (begin
+        (try! (contract-call? .callee ping))
+        (ok true)
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((_, result)) => {
+                assert_eq!(result.diagnostics.len(), 0);
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -1,5 +1,5 @@
 use crate::analysis::annotation::Annotation;
-use crate::analysis::{AnalysisPass, AnalysisResult};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
 use crate::clarity::analysis::analysis_db::AnalysisDatabase;
 pub use crate::clarity::analysis::types::ContractAnalysis;
 use crate::clarity::ast::ContractAST;
@@ -44,6 +44,7 @@ impl AnalysisPass for ContractCallDetector {
         contract_analysis: &mut ContractAnalysis,
         analysis_db: &mut AnalysisDatabase,
         annotations: &Vec<Annotation>,
+        settings: &Settings,
     ) -> AnalysisResult {
         let mut contract_calls = BTreeSet::new();
         traverse(
@@ -0,0 +1,277 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::ast;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::types::{QualifiedContractIdentifier, Value};
+use crate::clarity::ClarityName;
+
+// Notes calls to `contract-call?` whose arguments are all literals, when one
+// of them fails an `asserts!` at the start of the called function, so that the
+// call always returns an err. The guard must compare an argument with a
+// literal, and throw an `(err ...)`, to avoid false positives.
+pub struct ContractCallGuardChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    calls: Vec<(
+        QualifiedContractIdentifier,
+        &'a ClarityName,
+        &'a [SymbolicExpression],
+    )>,
+}
+
+impl<'a> ContractCallGuardChecker<'a> {
+    fn new() -> ContractCallGuardChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            calls: Vec::new(),
+        }
+    }
+
+    fn run(
+        mut self,
+        contract_analysis: &'a ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+    ) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        // The source of the called contracts is only available from the
+        // database, once the calls are collected
+        for (contract_identifier, function_name, args) in self.calls.iter() {
+            analysis_db.begin();
+            let source = analysis_db.get_contract_src(contract_identifier);
+            analysis_db.roll_back();
+            let source = match source {
+                Some(source) => source,
+                None => continue,
+            };
+            let callee = match ast::build_ast(contract_identifier, &source, &mut ()) {
+                Ok(callee) => callee,
+                Err(_) => continue,
+            };
+            let (parameters, body) = match find_function(&callee.expressions, function_name) {
+                Some(function) => function,
+                None => continue,
+            };
+            let (parameter, arg) = match failed_guard(&parameters, args, body) {
+                Some(failed) => failed,
+                None => continue,
+            };
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("contract-call-always-fails".to_string()),
+                message: format!(
+                    "this call to '{}' of '{}' always returns an err, as argument '{}' fails an asserts! of the function",
+                    function_name, contract_identifier.name, parameter
+                ),
+                spans: vec![arg.span.clone()],
+                suggestion: Some(
+                    "Check the argument against the conditions asserted by the function"
+                        .to_string(),
+                ),
+            });
+        }
+        Ok(self.diagnostics)
+    }
+}
+
+// The parameter names and body of a public or read-only function.
+fn find_function<'b>(
+    expressions: &'b [SymbolicExpression],
+    function_name: &ClarityName,
+) -> Option<(Vec<&'b ClarityName>, &'b SymbolicExpression)> {
+    expressions.iter().find_map(|expr| {
+        let (define, rest) = expr.match_list()?.split_first()?;
+        if !matches!(
+            define.match_atom()?.as_str(),
+            "define-public" | "define-read-only"
+        ) {
+            return None;
+        }
+        let (signature, body) = match rest {
+            [signature, body] => (signature.match_list()?, body),
+            _ => return None,
+        };
+        let (name, parameters) = signature.split_first()?;
+        if name.match_atom()? != function_name {
+            return None;
+        }
+        let parameters = parameters
+            .iter()
+            .map(|parameter| parameter.match_list()?.first()?.match_atom())
+            .collect::<Option<_>>()?;
+        Some((parameters, body))
+    })
+}
+
+// The first parameter, with its argument, failing one of the `asserts!`
+// which start the body of a function.
+fn failed_guard<'b, 'c>(
+    parameters: &[&'b ClarityName],
+    args: &'c [SymbolicExpression],
+    body: &SymbolicExpression,
+) -> Option<(&'b ClarityName, &'c SymbolicExpression)> {
+    if parameters.len() != args.len() {
+        return None;
+    }
+    let values: Vec<&Value> = args
+        .iter()
+        .map(|arg| arg.match_literal_value())
+        .collect::<Option<_>>()?;
+    let statements = match match_native_call(body) {
+        Some((NativeFunctions::Begin, statements)) => statements,
+        _ => std::slice::from_ref(body),
+    };
+    for statement in statements {
+        let (cond, thrown) = match match_native_call(statement) {
+            Some((NativeFunctions::Asserts, [cond, thrown])) => (cond, thrown),
+            _ => return None,
+        };
+        if !matches!(
+            match_native_call(thrown),
+            Some((NativeFunctions::ConsError, _))
+        ) {
+            return None;
+        }
+        // A failing guard which is not understood returns an err as well, so
+        // the following guards can still be checked
+        if let Some((index, false)) = evaluate(cond, parameters, &values) {
+            return Some((parameters[index], &args[index]));
+        }
+    }
+    None
+}
+
+// The result of a comparison between arguments and literals, with the index
+// of an argument compared.
+fn evaluate(
+    cond: &SymbolicExpression,
+    parameters: &[&ClarityName],
+    values: &[&Value],
+) -> Option<(usize, bool)> {
+    let (func, operands) = match match_native_call(cond)? {
+        (func, [lhs, rhs]) => (func, [lhs, rhs]),
+        _ => return None,
+    };
+    let mut index = None;
+    let mut operand_values = vec![];
+    for operand in operands {
+        if let Some(value) = operand.match_literal_value() {
+            operand_values.push(value);
+            continue;
+        }
+        let position = parameters
+            .iter()
+            .position(|parameter| Some(*parameter) == operand.match_atom())?;
+        index = Some(position);
+        operand_values.push(values[position]);
+    }
+    Some((index?, compare(func, operand_values[0], operand_values[1])?))
+}
+
+// The result of a comparison of two values, if it can be determined.
+fn compare(func: NativeFunctions, lhs: &Value, rhs: &Value) -> Option<bool> {
+    if func == NativeFunctions::Equals {
+        return Some(lhs == rhs);
+    }
+    let ordering = match (lhs, rhs) {
+        (Value::Int(lhs), Value::Int(rhs)) => lhs.cmp(rhs),
+        (Value::UInt(lhs), Value::UInt(rhs)) => lhs.cmp(rhs),
+        _ => return None,
+    };
+    match func {
+        NativeFunctions::CmpLess => Some(ordering.is_lt()),
+        NativeFunctions::CmpLeq => Some(ordering.is_le()),
+        NativeFunctions::CmpGreater => Some(ordering.is_gt()),
+        NativeFunctions::CmpGeq => Some(ordering.is_ge()),
+        _ => None,
+    }
+}
+
+impl<'a> ASTVisitor<'a> for ContractCallGuardChecker<'a> {
+    fn visit_static_contract_call(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        contract_identifier: &QualifiedContractIdentifier,
+        function_name: &'a ClarityName,
+        args: &'a [SymbolicExpression],
+    ) -> bool {
+        self.calls
+            .push((contract_identifier.clone(), function_name, args));
+        true
+    }
+}
+
+impl AnalysisPass for ContractCallGuardChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = ContractCallGuardChecker::new();
+        checker.run(contract_analysis, analysis_db)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn call_failing_guard() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["contract_call_guard_checker".to_string()];
+        let mut session = Session::new(settings);
+        let callee = "
+(define-public (transfer (amount uint) (memo (string-ascii 8)))
+    (begin
+        (asserts! (> amount u0) (err u1))
+        (asserts! (not (is-eq memo \"\")) (err u2))
+        (ok amount)
+    )
+)
+"
+        .to_string();
+        session
+            .formatted_interpretation(callee, Some("callee".to_string()), false, None)
+            .unwrap();
+        let snippet = "
+(define-public (pay)
+    (begin
+        (try! (contract-call? .callee transfer u10 \"rent\"))
+        (contract-call? .callee transfer u0 \"rent\")
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:5:42: {}: this call to 'transfer' of 'callee' always returns an err, as argument 'amount' fails an asserts! of the function",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "        (contract-call? .callee transfer u0 \"rent\")"
+                );
+                assert_eq!(output[2], "                                         ^");
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some(
+                        "Check the argument against the conditions asserted by the function"
+                            .to_string()
+                    )
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,145 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+use crate::clarity::analysis::type_checker::contexts::TypeMap;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+
+// Notes data-vars whose initial value is larger than a threshold, as it is
+// written to storage, and paid for, when the contract is deployed. The size
+// is computed from the type of the initial value, which is exact for
+// literals.
+pub struct DataVarSizeChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    threshold: u32,
+    type_map: Option<&'a TypeMap>,
+}
+
+impl<'a> DataVarSizeChecker<'a> {
+    fn new(settings: &Settings) -> DataVarSizeChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            threshold: settings.data_var_size_threshold,
+            type_map: None,
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        self.type_map = contract_analysis.type_map.as_ref();
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+}
+
+impl<'a> ASTVisitor<'a> for DataVarSizeChecker<'a> {
+    fn visit_define_data_var(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        data_type: &'a SymbolicExpression,
+        initial: &'a SymbolicExpression,
+    ) -> bool {
+        let size = match self
+            .type_map
+            .and_then(|type_map| type_map.get_type(initial))
+        {
+            Some(initial_type) => initial_type.size(),
+            None => return true,
+        };
+        if size > self.threshold {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("large-data-var".to_string()),
+                message: format!(
+                    "initial value of data-var '{}' is {} bytes, written to storage when the contract is deployed",
+                    name, size
+                ),
+                spans: vec![initial.span.clone()],
+                suggestion: Some(
+                    "Start from a smaller value, and fill the data-var after deployment if needed"
+                        .to_string(),
+                ),
+            });
+        }
+        true
+    }
+}
+
+impl AnalysisPass for DataVarSizeChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = DataVarSizeChecker::new(settings);
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    const SNIPPET: &str = "
+(define-data-var admins (list 10 principal) (list))
+(define-data-var tiers (list 10 { min: uint, rate: uint }) (list
+    { min: u0, rate: u100 } { min: u1000, rate: u90 } { min: u10000, rate: u80 }
+))
+";
+
+    #[test]
+    fn large_initial_value() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["data_var_size_checker".to_string()];
+        settings.analysis_settings.data_var_size_threshold = 64;
+        let mut session = Session::new(settings);
+        match session.formatted_interpretation(
+            SNIPPET.to_string(),
+            Some("checker".to_string()),
+            false,
+            None,
+        ) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:60: {}: initial value of data-var 'tiers' is 186 bytes, written to storage when the contract is deployed",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some(
+                        "Start from a smaller value, and fill the data-var after deployment if needed"
+                            .to_string()
+                    )
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn default_threshold() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["data_var_size_checker".to_string()];
+        let mut session = Session::new(settings);
+        match session.formatted_interpretation(
+            SNIPPET.to_string(),
+            Some("checker".to_string()),
+            false,
+            None,
+        ) {
+            Ok((_, result)) => {
+                assert_eq!(result.diagnostics.len(), 0);
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,220 @@
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{pre_check_result, AnalysisResult};
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::representations::SymbolicExpressionType::*;
+use crate::clarity::util::closest_match;
+use crate::clarity::ClarityName;
+
+const ATOMIC_TYPES: [&str; 4] = ["int", "uint", "bool", "principal"];
+const COMPOUND_TYPES: [&str; 7] = [
+    "list",
+    "buff",
+    "string-ascii",
+    "string-utf8",
+    "tuple",
+    "optional",
+    "response",
+];
+
+// Validates that the types used in the method signatures of each
+// `define-trait` exist. Trait references are already resolved while building
+// the AST (including forward references), so this pass runs on the AST,
+// before type-checking, to report unknown type names at their location.
+pub struct DefineTraitChecker {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl DefineTraitChecker {
+    fn new() -> DefineTraitChecker {
+        Self {
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub fn run(expressions: &[SymbolicExpression]) -> AnalysisResult {
+        let mut checker = DefineTraitChecker::new();
+        traverse(&mut checker, expressions);
+        pre_check_result(checker.diagnostics)
+    }
+
+    fn report_unknown_type(
+        &mut self,
+        expr: &SymbolicExpression,
+        type_name: &str,
+        trait_name: &ClarityName,
+        candidates: &[&'static str],
+    ) {
+        self.diagnostics.push(Diagnostic {
+            level: Level::Error,
+            code: Some("unknown-trait-type".to_string()),
+            message: format!("unknown type '{}' in trait '{}'", type_name, trait_name),
+            spans: vec![expr.span.clone()],
+            suggestion: closest_match(type_name, candidates.iter().copied())
+                .map(|candidate| format!("did you mean '{}'?", candidate)),
+        });
+    }
+
+    // Returns false once an unknown type has been reported.
+    fn check_type(&mut self, expr: &SymbolicExpression, trait_name: &ClarityName) -> bool {
+        match &expr.expr {
+            Atom(type_name) => {
+                if ATOMIC_TYPES.contains(&type_name.as_str()) {
+                    return true;
+                }
+                self.report_unknown_type(expr, type_name, trait_name, &ATOMIC_TYPES);
+                false
+            }
+            List(list) => {
+                let (compound_type, args) = match list.split_first() {
+                    Some((compound_type, args)) => (compound_type, args),
+                    None => return true,
+                };
+                let type_name = match compound_type.match_atom() {
+                    Some(type_name) => type_name,
+                    None => return true,
+                };
+                match type_name.as_str() {
+                    "list" => match args.last() {
+                        Some(entry_type) => self.check_type(entry_type, trait_name),
+                        None => true,
+                    },
+                    "optional" | "response" => {
+                        args.iter().all(|arg| self.check_type(arg, trait_name))
+                    }
+                    "tuple" => args.iter().all(|field| match field.match_list() {
+                        Some([_, field_type]) => self.check_type(field_type, trait_name),
+                        _ => true,
+                    }),
+                    "buff" | "string-ascii" | "string-utf8" => true,
+                    _ => {
+                        self.report_unknown_type(
+                            compound_type,
+                            type_name,
+                            trait_name,
+                            &COMPOUND_TYPES,
+                        );
+                        false
+                    }
+                }
+            }
+            // Other malformed signatures are reported by the type-checker.
+            _ => true,
+        }
+    }
+}
+
+impl<'a> ASTVisitor<'a> for DefineTraitChecker {
+    fn visit_define_trait(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        functions: &'a [SymbolicExpression],
+    ) -> bool {
+        let methods = match functions.first().and_then(|f| f.match_list()) {
+            Some(methods) => methods,
+            None => return true,
+        };
+        for method in methods {
+            if let Some([_, args, return_type]) = method.match_list() {
+                let args = args.match_list().unwrap_or_default();
+                for arg in args.iter().chain(std::iter::once(return_type)) {
+                    if !self.check_type(arg, name) {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn unknown_type() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-trait my-trait (
+    (transfer (uint principal) (response bool uintt))
+))
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Err(output) => {
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:47: {}: unknown type 'uintt' in trait 'my-trait'",
+                        red!("error")
+                    )
+                );
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn unknown_compound_type() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-trait my-trait (
+    (get-items () (response (lst uint 10) uint))
+))
+"
+        .to_string();
+        match session.interpret(snippet, None, false, None) {
+            Err((_, Some(diagnostic), _)) => {
+                assert_eq!(diagnostic.message, "unknown type 'lst' in trait 'my-trait'");
+                assert_eq!(
+                    diagnostic.suggestion,
+                    Some("did you mean 'list'?".to_string())
+                );
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn unknown_trait() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-trait my-trait (
+    (call (<other-traits>) (response bool uint))
+))
+(define-trait other-trait (
+    (ping () (response bool uint))
+))
+"
+        .to_string();
+        match session.interpret(snippet, None, false, None) {
+            Err((_, Some(diagnostic), _)) => {
+                assert_eq!(diagnostic.message, "use of undeclared trait <other-traits>");
+                assert_eq!(
+                    diagnostic.suggestion,
+                    Some("did you mean <other-trait>?".to_string())
+                );
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn valid_traits() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-trait my-trait (
+    (call (<other-trait> (list 10 { a: uint, b: (optional principal) })) (response bool uint))
+    (name () (response (string-ascii 32) uint))
+))
+(define-trait other-trait (
+    (ping () (response bool uint))
+))
+"
+        .to_string();
+        assert!(session.interpret(snippet, None, false, None).is_ok());
+    }
+}
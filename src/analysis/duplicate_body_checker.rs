@@ -0,0 +1,213 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{
+    collect_let_bindings, match_native_call, traverse, ASTVisitor, TypedVar,
+};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::representations::SymbolicExpressionType::{Atom, List};
+use crate::clarity::ClarityName;
+use std::collections::{HashMap, HashSet};
+
+// Notes the public functions whose bodies are the same, up to the names of
+// their parameters and local bindings, as a copy-paste which could be moved
+// to a private function. The bodies are compared once normalized, with each
+// local name replaced by its order of appearance.
+pub struct DuplicateBodyChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    // The functions by normalized body, in the order they are visited
+    bodies: HashMap<String, Vec<(&'a SymbolicExpression, &'a ClarityName)>>,
+}
+
+impl<'a> DuplicateBodyChecker<'a> {
+    fn new() -> DuplicateBodyChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            bodies: HashMap::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        let mut duplicates: Vec<_> = self
+            .bodies
+            .into_values()
+            .filter(|functions| functions.len() > 1)
+            .collect();
+        // The expressions were sorted, so the functions are put back in the
+        // order of the source
+        for functions in duplicates.iter_mut() {
+            functions.sort_by_key(|(expr, _)| (expr.span.start_line, expr.span.start_column));
+        }
+        duplicates.sort_by_key(|functions| {
+            let (expr, _) = functions[0];
+            (expr.span.start_line, expr.span.start_column)
+        });
+        for functions in duplicates {
+            let mut names: Vec<String> = functions
+                .iter()
+                .map(|(_, name)| format!("'{}'", name))
+                .collect();
+            let last = names.pop().unwrap();
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("duplicate-body".to_string()),
+                message: format!(
+                    "public functions {} and {} have identical bodies",
+                    names.join(", "),
+                    last
+                ),
+                spans: functions
+                    .iter()
+                    .map(|(expr, _)| expr.span.clone())
+                    .collect(),
+                suggestion: Some(
+                    "Move the body to a private function called by each of them".to_string(),
+                ),
+            });
+        }
+        Ok(self.diagnostics)
+    }
+}
+
+// The names bound in the body of a function, by `let` and `match`.
+fn collect_locals<'a>(expr: &'a SymbolicExpression, locals: &mut HashSet<&'a ClarityName>) {
+    match match_native_call(expr) {
+        Some((NativeFunctions::Match, [_, some_name, _, _])) => {
+            locals.extend(some_name.match_atom());
+        }
+        Some((NativeFunctions::Match, [_, ok_name, _, err_name, _])) => {
+            locals.extend(ok_name.match_atom());
+            locals.extend(err_name.match_atom());
+        }
+        _ => {}
+    }
+    for e in expr.match_list().unwrap_or_default() {
+        collect_locals(e, locals);
+    }
+}
+
+fn normalize<'a>(
+    expr: &'a SymbolicExpression,
+    locals: &HashSet<&'a ClarityName>,
+    renamed: &mut HashMap<&'a ClarityName, usize>,
+    normalized: &mut String,
+) {
+    match &expr.expr {
+        Atom(name) if locals.contains(name) => {
+            let next = renamed.len();
+            let index = *renamed.entry(name).or_insert(next);
+            normalized.push_str(&format!("#{}", index));
+        }
+        List(list) => {
+            normalized.push('(');
+            for (i, element) in list.iter().enumerate() {
+                if i > 0 {
+                    normalized.push(' ');
+                }
+                normalize(element, locals, renamed, normalized);
+            }
+            normalized.push(')');
+        }
+        _ => normalized.push_str(&expr.to_string()),
+    }
+}
+
+impl<'a> ASTVisitor<'a> for DuplicateBodyChecker<'a> {
+    fn visit_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        // Bodies as short as a call on values, like `(ok true)`, are expected
+        // to repeat
+        match body.match_list() {
+            Some(list) if list.iter().any(|element| element.match_list().is_some()) => {}
+            _ => return true,
+        }
+        let parameters = parameters.unwrap_or_default();
+        let mut locals: HashSet<&'a ClarityName> =
+            parameters.iter().map(|parameter| parameter.name).collect();
+        let mut bindings = vec![];
+        collect_let_bindings(body, &mut bindings);
+        locals.extend(bindings.iter().map(|(name, _)| *name));
+        collect_locals(body, &mut locals);
+
+        // The parameters are numbered first, in order, so that the same
+        // parameters used in a different order do not match
+        let mut renamed = HashMap::new();
+        for parameter in parameters.iter() {
+            let next = renamed.len();
+            renamed.entry(parameter.name).or_insert(next);
+        }
+        let mut normalized = String::new();
+        normalize(body, &locals, &mut renamed, &mut normalized);
+        self.bodies
+            .entry(normalized)
+            .or_default()
+            .push((expr, name));
+        true
+    }
+}
+
+impl AnalysisPass for DuplicateBodyChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = DuplicateBodyChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn identical_bodies() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["duplicate_body_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-map balances principal uint)
+(define-public (withdraw-stx (amount uint))
+    (let ((balance (default-to u0 (map-get? balances tx-sender))))
+        (asserts! (>= balance amount) (err u1))
+        (ok (map-set balances tx-sender (- balance amount)))))
+(define-public (withdraw-all (requested uint))
+    (let ((held (default-to u0 (map-get? balances tx-sender))))
+        (asserts! (>= held requested) (err u1))
+        (ok (map-set balances tx-sender (- held requested)))))
+(define-public (withdraw-reversed (amount uint))
+    (let ((balance (default-to u0 (map-get? balances tx-sender))))
+        (asserts! (>= amount balance) (err u1))
+        (ok (map-set balances tx-sender (- balance amount)))))
+(define-public (ping) (ok true))
+(define-public (pong) (ok true))
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:1: {}: public functions 'withdraw-stx' and 'withdraw-all' have identical bodies",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(output[3], "  checker:7:1:");
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
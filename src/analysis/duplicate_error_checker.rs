@@ -0,0 +1,145 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpressionType::*;
+use crate::clarity::representations::{Span, SymbolicExpression};
+
+// Reports literal error values, like `(err u0)`, thrown by more than one
+// `asserts!`, since the failing assertion cannot be identified from the
+// error. Errors named by a constant are assumed to be shared on purpose.
+pub struct DuplicateErrorChecker {
+    // Each thrown value with the locations where it is thrown, in order of first use
+    errors: Vec<(String, Vec<Span>)>,
+}
+
+impl DuplicateErrorChecker {
+    fn new() -> DuplicateErrorChecker {
+        Self { errors: Vec::new() }
+    }
+
+    fn run(mut self, contract_analysis: &ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self
+            .errors
+            .into_iter()
+            .filter(|(_, spans)| spans.len() > 1)
+            .map(|(error, spans)| {
+                let locations: Vec<String> = spans
+                    .iter()
+                    .map(|span| format!("{}:{}", span.start_line, span.start_column))
+                    .collect();
+                Diagnostic {
+                    level: Level::Note,
+                    code: Some("duplicate-error".to_string()),
+                    message: format!(
+                        "error value {} is thrown by {} asserts!, at {}",
+                        error,
+                        spans.len(),
+                        locations.join(", ")
+                    ),
+                    spans,
+                    suggestion: Some(
+                        "Use a distinct error code for each assertion, so that failures can be told apart"
+                            .to_string(),
+                    ),
+                }
+            })
+            .collect())
+    }
+}
+
+// Render an expression built only from literals, like `(err u0)`.
+fn literal_text(expr: &SymbolicExpression) -> Option<String> {
+    match &expr.expr {
+        LiteralValue(value) | AtomValue(value) => Some(value.to_string()),
+        List(list) => {
+            let (function, args) = list.split_first()?;
+            let mut parts = vec![function.match_atom()?.to_string()];
+            for arg in args {
+                parts.push(literal_text(arg)?);
+            }
+            Some(format!("({})", parts.join(" ")))
+        }
+        _ => None,
+    }
+}
+
+impl<'a> ASTVisitor<'a> for DuplicateErrorChecker {
+    fn visit_asserts(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        cond: &'a SymbolicExpression,
+        thrown: &'a SymbolicExpression,
+    ) -> bool {
+        if let Some(error) = literal_text(thrown) {
+            match self.errors.iter_mut().find(|(e, _)| *e == error) {
+                Some((_, spans)) => spans.push(thrown.span.clone()),
+                None => self.errors.push((error, vec![thrown.span.clone()])),
+            }
+        }
+        true
+    }
+}
+
+impl AnalysisPass for DuplicateErrorChecker {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = DuplicateErrorChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn duplicate_errors() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["duplicate_error_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-constant ERR_UNAUTHORIZED (err u401))
+(define-public (transfer (amount uint) (to principal))
+    (begin
+        (asserts! (is-eq tx-sender contract-caller) ERR_UNAUTHORIZED)
+        (asserts! (> amount u0) (err u0))
+        (asserts! (not (is-eq to tx-sender)) (err u0))
+        (asserts! (< amount u1000) (err u1))
+        (ok true)
+    )
+)
+(define-public (burn (amount uint))
+    (begin
+        (asserts! (is-eq tx-sender contract-caller) ERR_UNAUTHORIZED)
+        (asserts! (> amount u0) (err u0))
+        (ok true)
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:6:33: {}: error value (err u0) is thrown by 3 asserts!, at 6:33, 7:46, 15:33",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(output[1], "        (asserts! (> amount u0) (err u0))");
+                assert_eq!(output[2], "                                ^~~~~~~~");
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,163 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{references_symbol, traverse, ASTVisitor, TypedVar};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+use std::collections::HashMap;
+
+// Reports calls to `fold` whose function never reads its accumulator, the
+// second parameter. Each element is then processed independently, so the
+// contract most likely meant to use `map`, or drops the accumulated value.
+pub struct FoldAccumulatorChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    // The accumulator of each function and whether its body reads it
+    accumulators: HashMap<&'a ClarityName, (&'a ClarityName, bool)>,
+    folds: Vec<(&'a SymbolicExpression, &'a ClarityName)>,
+}
+
+impl<'a> FoldAccumulatorChecker<'a> {
+    fn new() -> FoldAccumulatorChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            accumulators: HashMap::new(),
+            folds: Vec::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        // Functions can be folded before they are defined
+        for (expr, func) in self.folds.iter() {
+            if let Some((accumulator, false)) = self.accumulators.get(func) {
+                self.diagnostics.push(Diagnostic {
+                    level: Level::Note,
+                    code: Some("unused-accumulator".to_string()),
+                    message: format!(
+                        "fold function '{}' never uses its accumulator '{}'",
+                        func, accumulator
+                    ),
+                    spans: vec![expr.span.clone()],
+                    suggestion: Some(
+                        "Use map to transform each element of the sequence independently"
+                            .to_string(),
+                    ),
+                });
+            }
+        }
+        Ok(self.diagnostics)
+    }
+
+    fn add_function(
+        &mut self,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) {
+        if let Some([_, accumulator]) = parameters.as_deref() {
+            self.accumulators.insert(
+                name,
+                (accumulator.name, references_symbol(body, accumulator.name)),
+            );
+        }
+    }
+}
+
+impl<'a> ASTVisitor<'a> for FoldAccumulatorChecker<'a> {
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.add_function(name, parameters, body);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.add_function(name, parameters, body);
+        self.traverse_expr(body)
+    }
+
+    fn visit_fold(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: &'a ClarityName,
+        sequence: &'a SymbolicExpression,
+        initial: &'a SymbolicExpression,
+    ) -> bool {
+        self.folds.push((expr, func));
+        true
+    }
+}
+
+impl AnalysisPass for FoldAccumulatorChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = FoldAccumulatorChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn unused_accumulator() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["fold_accumulator_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-read-only (last-doubled (amounts (list 10 uint)))
+    (fold double amounts u0)
+)
+(define-private (double (amount uint) (result uint))
+    (* amount u2)
+)
+(define-private (add (amount uint) (total uint))
+    (+ amount total)
+)
+(define-read-only (sum (amounts (list 10 uint)))
+    (fold add amounts u0)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:5: {}: fold function 'double' never uses its accumulator 'result'",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(output[1], "    (fold double amounts u0)");
+                assert_eq!(output[2], "    ^~~~~~~~~~~~~~~~~~~~~~~~");
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some(
+                        "Use map to transform each element of the sequence independently"
+                            .to_string()
+                    )
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,247 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor, TypedVar};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::types::Value;
+use crate::clarity::ClarityName;
+use std::collections::{HashMap, HashSet};
+
+// Notes calls to `fold` over a literal range, like `(list u0 u1 u2)`, whose
+// function uses the element in arithmetic, as when simulating a loop. The
+// bounds of the range are reported, since such loops are often off by one.
+pub struct FoldRangeChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    // Constants defined as a literal range, with its bounds and length
+    ranges: HashMap<&'a ClarityName, (Value, Value, usize)>,
+    // The private function being traversed, with its first parameter
+    function: Option<(&'a ClarityName, &'a ClarityName)>,
+    // Functions using their element in arithmetic
+    computing: HashSet<&'a ClarityName>,
+    folds: Vec<(
+        &'a SymbolicExpression,
+        &'a ClarityName,
+        (Value, Value, usize),
+    )>,
+}
+
+impl<'a> FoldRangeChecker<'a> {
+    fn new() -> FoldRangeChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            ranges: HashMap::new(),
+            function: None,
+            computing: HashSet::new(),
+            folds: Vec::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        // Functions can be folded before they are defined
+        for (expr, func, (first, last, length)) in self.folds.iter() {
+            if !self.computing.contains(func) {
+                continue;
+            }
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("fold-range".to_string()),
+                message: format!(
+                    "fold iterates over the {} values from {} to {}",
+                    length, first, last
+                ),
+                spans: vec![expr.span.clone()],
+                suggestion: Some(
+                    "Check that the range includes its first and last intended values".to_string(),
+                ),
+            });
+        }
+        Ok(self.diagnostics)
+    }
+
+    fn range(&self, sequence: &SymbolicExpression) -> Option<(Value, Value, usize)> {
+        if let Some(name) = sequence.match_atom() {
+            return self.ranges.get(name).cloned();
+        }
+        literal_range(sequence)
+    }
+
+    fn check_operands(&mut self, operands: &'a [SymbolicExpression]) {
+        if let Some((name, element)) = self.function {
+            if operands
+                .iter()
+                .any(|operand| operand.match_atom() == Some(element))
+            {
+                self.computing.insert(name);
+            }
+        }
+    }
+}
+
+// The first and last values and the length of a list of consecutive ints or
+// uints, in increasing order.
+fn literal_range(sequence: &SymbolicExpression) -> Option<(Value, Value, usize)> {
+    let elements = match match_native_call(sequence)? {
+        (NativeFunctions::ListCons, elements) if elements.len() > 1 => elements,
+        _ => return None,
+    };
+    let values: Vec<&Value> = elements
+        .iter()
+        .map(|element| element.match_literal_value())
+        .collect::<Option<_>>()?;
+    let consecutive = values.windows(2).all(|pair| match (pair[0], pair[1]) {
+        (Value::UInt(a), Value::UInt(b)) => a.checked_add(1) == Some(*b),
+        (Value::Int(a), Value::Int(b)) => a.checked_add(1) == Some(*b),
+        _ => false,
+    });
+    if !consecutive {
+        return None;
+    }
+    Some((
+        values[0].clone(),
+        values[values.len() - 1].clone(),
+        values.len(),
+    ))
+}
+
+impl<'a> ASTVisitor<'a> for FoldRangeChecker<'a> {
+    fn visit_define_constant(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        if let Some(range) = literal_range(value) {
+            self.ranges.insert(name, range);
+        }
+        true
+    }
+
+    // Only private functions can be folded
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.function = parameters
+            .as_deref()
+            .and_then(|parameters| parameters.first())
+            .map(|parameter| (name, parameter.name));
+        let rv = self.traverse_expr(body);
+        self.function = None;
+        rv
+    }
+
+    fn visit_arithmetic(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: NativeFunctions,
+        operands: &'a [SymbolicExpression],
+    ) -> bool {
+        self.check_operands(operands);
+        true
+    }
+
+    fn visit_comparison(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: NativeFunctions,
+        operands: &'a [SymbolicExpression],
+    ) -> bool {
+        self.check_operands(operands);
+        true
+    }
+
+    fn visit_fold(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: &'a ClarityName,
+        sequence: &'a SymbolicExpression,
+        initial: &'a SymbolicExpression,
+    ) -> bool {
+        if let Some(range) = self.range(sequence) {
+            self.folds.push((expr, func, range));
+        }
+        true
+    }
+}
+
+impl AnalysisPass for FoldRangeChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = FoldRangeChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn fold_over_range() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["fold_range_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-constant STEPS (list u1 u2 u3 u4))
+(define-private (add-index (index uint) (total uint))
+    (+ total (* index u10))
+)
+(define-private (count (index uint) (total uint))
+    (+ total u1)
+)
+(define-read-only (sums)
+    (list
+        (fold add-index (list u0 u1 u2 u3 u4 u5 u6 u7 u8 u9) u0)
+        (fold add-index STEPS u0)
+        (fold count STEPS u0)
+        (fold add-index (list u0 u2 u4) u0)
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:11:9: {}: fold iterates over the 10 values from u0 to u9",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "        (fold add-index (list u0 u1 u2 u3 u4 u5 u6 u7 u8 u9) u0)"
+                );
+                assert_eq!(
+                    output[2],
+                    "        ^~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~"
+                );
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some(
+                        "Check that the range includes its first and last intended values"
+                            .to_string()
+                    )
+                );
+                assert_eq!(
+                    result.diagnostics[1].message,
+                    "fold iterates over the 4 values from u1 to u4"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
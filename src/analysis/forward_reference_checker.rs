@@ -0,0 +1,221 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor, TypedVar};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::representations::SymbolicExpressionType::{Atom, List};
+use crate::clarity::ClarityName;
+use std::collections::{BTreeSet, HashMap};
+
+// Notes the constants whose initializer references a constant, a function, a
+// map or a data-var defined later in the source. The definitions are sorted
+// by their dependencies before they are evaluated, so this is accepted, but
+// the contract no longer reads in the order it runs.
+pub struct ForwardReferenceChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    definitions: HashMap<&'a ClarityName, &'a SymbolicExpression>,
+    constants: Vec<(&'a SymbolicExpression, &'a SymbolicExpression)>,
+}
+
+impl<'a> ForwardReferenceChecker<'a> {
+    fn new() -> ForwardReferenceChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            definitions: HashMap::new(),
+            constants: Vec::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        // The expressions were sorted, so the constants are put back in the
+        // order of the source
+        self.constants
+            .sort_by_key(|(expr, _)| (expr.span.start_line, expr.span.start_column));
+        for (expr, value) in self.constants.iter() {
+            let mut references = vec![];
+            collect_references(value, &mut references);
+            let mut reported = BTreeSet::new();
+            for (reference, name) in references {
+                let definition = match self.definitions.get(name) {
+                    Some(definition) => definition,
+                    None => continue,
+                };
+                if (definition.span.start_line, definition.span.start_column)
+                    <= (expr.span.start_line, expr.span.start_column)
+                    || !reported.insert(name)
+                {
+                    continue;
+                }
+                self.diagnostics.push(Diagnostic {
+                    level: Level::Note,
+                    code: Some("forward-reference".to_string()),
+                    message: format!(
+                        "'{}' is defined later at line {}",
+                        name, definition.span.start_line
+                    ),
+                    spans: vec![reference.span.clone(), definition.span.clone()],
+                    suggestion: Some(format!(
+                        "Move the definition of '{}' before this constant",
+                        name
+                    )),
+                });
+            }
+        }
+        Ok(self.diagnostics)
+    }
+}
+
+fn collect_references<'a>(
+    expr: &'a SymbolicExpression,
+    references: &mut Vec<(&'a SymbolicExpression, &'a ClarityName)>,
+) {
+    match &expr.expr {
+        Atom(name) => references.push((expr, name)),
+        List(list) => {
+            for element in list.iter() {
+                collect_references(element, references);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl<'a> ASTVisitor<'a> for ForwardReferenceChecker<'a> {
+    fn visit_define_constant(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        self.definitions.insert(name, expr);
+        self.constants.push((expr, value));
+        true
+    }
+
+    fn visit_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.definitions.insert(name, expr);
+        true
+    }
+
+    fn visit_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.definitions.insert(name, expr);
+        true
+    }
+
+    fn visit_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.definitions.insert(name, expr);
+        true
+    }
+
+    fn visit_define_map(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        key_type: &'a SymbolicExpression,
+        value_type: &'a SymbolicExpression,
+    ) -> bool {
+        self.definitions.insert(name, expr);
+        true
+    }
+
+    fn visit_define_data_var(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        data_type: &'a SymbolicExpression,
+        initial: &'a SymbolicExpression,
+    ) -> bool {
+        self.definitions.insert(name, expr);
+        true
+    }
+}
+
+impl AnalysisPass for ForwardReferenceChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = ForwardReferenceChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn forward_references() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["forward_reference_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-constant FEE (* BASE-FEE u2))
+(define-constant LIMIT (+ (get-limit) (* BASE-FEE u3)))
+(define-constant BASE-FEE u10)
+(define-constant OWNER-SET (is-some (map-get? owners tx-sender)))
+(define-read-only (get-limit) (* BASE-FEE u100))
+(define-map owners principal bool)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 4);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:2:25: {}: 'BASE-FEE' is defined later at line 4",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(output[3], "  checker:4:1:");
+                assert_eq!(
+                    output[6],
+                    format!(
+                        "checker:3:28: {}: 'get-limit' is defined later at line 6",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[12],
+                    format!(
+                        "checker:3:42: {}: 'BASE-FEE' is defined later at line 4",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[18],
+                    format!(
+                        "checker:5:47: {}: 'owners' is defined later at line 7",
+                        blue!("note")
+                    )
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,246 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{
+    collect_let_bindings, propagate_taint, references_symbol, traverse, ASTVisitor, TypedVar,
+};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+use std::collections::HashSet;
+
+// Notes `ft-transfer?` and `ft-burn?` of an amount derived from a parameter,
+// when the balance of that token is not read with `ft-get-balance` before,
+// in the same function. The transfer fails on an insufficient balance, but
+// with a generic error, and the failure can be ignored by the caller.
+pub struct FtBalanceChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    // Parameters of the current function, and the `let` bindings derived
+    // from them
+    inputs: HashSet<&'a ClarityName>,
+    // Tokens whose balance was read in the current function
+    checked_tokens: HashSet<&'a ClarityName>,
+}
+
+impl<'a> FtBalanceChecker<'a> {
+    fn new() -> FtBalanceChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            inputs: HashSet::new(),
+            checked_tokens: HashSet::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    fn set_function(
+        &mut self,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) {
+        self.inputs = parameters
+            .unwrap_or_default()
+            .iter()
+            .map(|parameter| parameter.name)
+            .collect();
+        self.checked_tokens.clear();
+
+        let mut bindings = vec![];
+        collect_let_bindings(body, &mut bindings);
+        propagate_taint(&bindings, &mut self.inputs, |inputs, name, value| {
+            !inputs.contains(name)
+                && inputs.iter().any(|input| references_symbol(value, input))
+                && inputs.insert(name)
+        });
+    }
+
+    // The first input referenced by an expression.
+    fn input_in(&self, expr: &SymbolicExpression) -> Option<&'a ClarityName> {
+        let mut inputs: Vec<&'a ClarityName> = self.inputs.iter().copied().collect();
+        inputs.sort();
+        inputs
+            .into_iter()
+            .find(|input| references_symbol(expr, input))
+    }
+
+    fn check_amount(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: NativeFunctions,
+        token: &'a ClarityName,
+        amount: &'a SymbolicExpression,
+    ) {
+        if self.checked_tokens.contains(token) {
+            return;
+        }
+        let input = match self.input_in(amount) {
+            Some(input) => input,
+            None => return,
+        };
+        self.diagnostics.push(Diagnostic {
+            level: Level::Note,
+            code: Some("unchecked-ft-balance".to_string()),
+            message: format!(
+                "the amount of this {} derives from '{}', but the balance of '{}' is not checked first",
+                func.get_name(),
+                input,
+                token
+            ),
+            spans: vec![expr.span.clone()],
+            suggestion: Some(format!(
+                "Compare the amount to (ft-get-balance {} ...) first, to return a specific error",
+                token
+            )),
+        });
+    }
+}
+
+impl<'a> ASTVisitor<'a> for FtBalanceChecker<'a> {
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.set_function(parameters, body);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.set_function(parameters, body);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.set_function(parameters, body);
+        self.traverse_expr(body)
+    }
+
+    fn visit_ft_get_balance(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        token: &'a ClarityName,
+        owner: &'a SymbolicExpression,
+    ) -> bool {
+        self.checked_tokens.insert(token);
+        true
+    }
+
+    fn visit_ft_transfer(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        token: &'a ClarityName,
+        amount: &'a SymbolicExpression,
+        sender: &'a SymbolicExpression,
+        recipient: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_amount(expr, NativeFunctions::TransferToken, token, amount);
+        true
+    }
+
+    fn visit_ft_burn(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        token: &'a ClarityName,
+        amount: &'a SymbolicExpression,
+        sender: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_amount(expr, NativeFunctions::BurnToken, token, amount);
+        true
+    }
+}
+
+impl AnalysisPass for FtBalanceChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = FtBalanceChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn unchecked_balance() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["ft_balance_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-fungible-token gold)
+(define-fungible-token silver)
+(define-public (pay (amount uint) (recipient principal))
+    (let ((total (+ amount u10)))
+        (ft-transfer? gold total tx-sender recipient)
+    )
+)
+(define-public (checked-pay (amount uint) (recipient principal))
+    (begin
+        (asserts! (>= (ft-get-balance gold tx-sender) amount) (err u1))
+        (try! (ft-transfer? gold amount tx-sender recipient))
+        (ft-burn? silver amount tx-sender)
+    )
+)
+(define-public (burn-fee)
+    (ft-burn? gold u10 tx-sender)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:6:9: {}: the amount of this ft-transfer? derives from 'total', but the balance of 'gold' is not checked first",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "        (ft-transfer? gold total tx-sender recipient)"
+                );
+                assert_eq!(
+                    output[2],
+                    "        ^~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~"
+                );
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some(
+                        "Compare the amount to (ft-get-balance gold ...) first, to return a specific error"
+                            .to_string()
+                    )
+                );
+                assert_eq!(
+                    result.diagnostics[1].message,
+                    "the amount of this ft-burn? derives from 'amount', but the balance of 'silver' is not checked first"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
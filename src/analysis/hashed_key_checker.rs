@@ -0,0 +1,299 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{
+    collect_let_bindings, match_native_call, propagate_taint, references_symbol, traverse,
+    ASTVisitor, TypedVar,
+};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::representations::SymbolicExpressionType::{Atom, AtomValue, LiteralValue};
+use crate::clarity::ClarityName;
+use std::collections::{HashMap, HashSet};
+
+// Notes the `map-set` and `map-insert` whose key is a hash of inputs of the
+// function concatenated without a domain separator: `(concat a b)` is the same
+// for several splits of the bytes between `a` and `b`, so unrelated entries
+// can share a key. The key is followed through the `let` bindings it is
+// computed in, and into the fields of a tuple.
+pub struct HashedKeyChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    constants: HashSet<&'a ClarityName>,
+    // Parameters of the current function, and the `let` bindings derived
+    // from them
+    inputs: HashSet<&'a ClarityName>,
+    bindings: HashMap<&'a ClarityName, &'a SymbolicExpression>,
+}
+
+impl<'a> HashedKeyChecker<'a> {
+    fn new() -> HashedKeyChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            constants: HashSet::new(),
+            inputs: HashSet::new(),
+            bindings: HashMap::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    fn set_function(
+        &mut self,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) {
+        self.inputs = parameters
+            .unwrap_or_default()
+            .iter()
+            .map(|parameter| parameter.name)
+            .collect();
+
+        let mut bindings = vec![];
+        collect_let_bindings(body, &mut bindings);
+        self.bindings = bindings.iter().copied().collect();
+        propagate_taint(&bindings, &mut self.inputs, |inputs, name, value| {
+            !inputs.contains(name)
+                && inputs.iter().any(|input| references_symbol(value, input))
+                && inputs.insert(name)
+        });
+    }
+
+    fn is_input(&self, expr: &SymbolicExpression) -> bool {
+        self.inputs
+            .iter()
+            .any(|input| references_symbol(expr, input))
+    }
+
+    // The expression a `let` binding stands for.
+    fn resolve(&self, mut expr: &'a SymbolicExpression) -> &'a SymbolicExpression {
+        while let Some(value) = expr.match_atom().and_then(|name| self.bindings.get(name)) {
+            expr = value;
+        }
+        expr
+    }
+
+    fn concatenated(
+        &self,
+        expr: &'a SymbolicExpression,
+        operands: &mut Vec<&'a SymbolicExpression>,
+    ) {
+        let expr = self.resolve(expr);
+        match match_native_call(expr) {
+            Some((NativeFunctions::Concat, [lhs, rhs])) => {
+                self.concatenated(lhs, operands);
+                self.concatenated(rhs, operands);
+            }
+            _ => operands.push(expr),
+        }
+    }
+
+    fn is_separator(&self, expr: &SymbolicExpression) -> bool {
+        match &expr.expr {
+            LiteralValue(_) | AtomValue(_) => true,
+            Atom(name) => self.constants.contains(name),
+            _ => false,
+        }
+    }
+
+    fn check_key(
+        &mut self,
+        func: &str,
+        name: &'a ClarityName,
+        key: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+    ) {
+        for key_value in key.values() {
+            let resolved = self.resolve(key_value);
+            let parts: Vec<&'a SymbolicExpression> = match match_native_call(resolved) {
+                Some((NativeFunctions::TupleCons, fields)) => fields
+                    .iter()
+                    .filter_map(|field| field.match_list())
+                    .filter_map(|pair| pair.get(1))
+                    .map(|value| self.resolve(value))
+                    .collect(),
+                _ => vec![resolved],
+            };
+            for part in parts {
+                let (hash, preimage) = match match_native_call(part) {
+                    Some((
+                        hash @ (NativeFunctions::Sha256
+                        | NativeFunctions::Keccak256
+                        | NativeFunctions::Sha512
+                        | NativeFunctions::Sha512Trunc256
+                        | NativeFunctions::Hash160),
+                        [preimage],
+                    )) => (hash, preimage),
+                    _ => continue,
+                };
+                let mut operands = vec![];
+                self.concatenated(preimage, &mut operands);
+                let inputs = operands
+                    .iter()
+                    .filter(|operand| self.is_input(operand))
+                    .count();
+                if inputs < 2 || operands.iter().any(|operand| self.is_separator(operand)) {
+                    continue;
+                }
+                // The hash is pointed at too, when it is computed in a binding
+                let mut spans = vec![key_value.span.clone()];
+                if part.id != key_value.id {
+                    spans.push(part.span.clone());
+                }
+                self.diagnostics.push(Diagnostic {
+                    level: Level::Note,
+                    code: Some("hashed-key-collision".to_string()),
+                    message: format!(
+                        "the key of this {} on '{}' is a {} of {} inputs concatenated without a separator, which different inputs can collide on",
+                        func,
+                        name,
+                        hash.get_name(),
+                        inputs
+                    ),
+                    spans,
+                    suggestion: Some(
+                        "Key the map with a tuple of the inputs, or concatenate a constant separator between them"
+                            .to_string(),
+                    ),
+                });
+            }
+        }
+    }
+}
+
+impl<'a> ASTVisitor<'a> for HashedKeyChecker<'a> {
+    fn visit_define_constant(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        self.constants.insert(name);
+        true
+    }
+
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.set_function(parameters, body);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.set_function(parameters, body);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.set_function(parameters, body);
+        self.traverse_expr(body)
+    }
+
+    fn visit_map_set(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        key: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+        value: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+    ) -> bool {
+        self.check_key("map-set", name, key);
+        true
+    }
+
+    fn visit_map_insert(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        key: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+        value: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+    ) -> bool {
+        self.check_key("map-insert", name, key);
+        true
+    }
+}
+
+impl AnalysisPass for HashedKeyChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = HashedKeyChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn hashed_key_collision() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["hashed_key_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-constant ORDER-TAG 0x6f72646572)
+(define-map orders (buff 32) uint)
+(define-map claims { id: (buff 32), round: uint } bool)
+(define-public (place (owner (buff 20)) (memo (buff 12)) (amount uint))
+    (let ((key (sha256 (concat owner memo))))
+        (ok (map-set orders key amount))
+    )
+)
+(define-public (claim (owner (buff 20)) (memo (buff 12)) (round uint))
+    (ok (map-insert claims { id: (keccak256 (concat owner memo)), round: round } true))
+)
+(define-public (place-tagged (owner (buff 20)) (memo (buff 12)) (amount uint))
+    (ok (map-set orders (sha256 (concat ORDER-TAG (concat owner memo))) amount))
+)
+(define-public (place-single (owner (buff 20)) (amount uint))
+    (ok (map-set orders (sha256 owner) amount))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:7:29: {}: the key of this map-set on 'orders' is a sha256 of 2 inputs concatenated without a separator, which different inputs can collide on",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(output[3], "  checker:6:16:");
+                assert_eq!(
+                    output[6],
+                    format!(
+                        "checker:11:34: {}: the key of this map-insert on 'claims' is a keccak256 of 2 inputs concatenated without a separator, which different inputs can collide on",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(output.len(), 9);
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,137 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+
+// Surfaces `is-eq` calls with three or more arguments, which are only true
+// when all of the arguments are equal.
+pub struct IsEqChecker {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl IsEqChecker {
+    fn new() -> IsEqChecker {
+        Self {
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+}
+
+impl<'a> ASTVisitor<'a> for IsEqChecker {
+    fn visit_comparison(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: NativeFunctions,
+        operands: &'a [SymbolicExpression],
+    ) -> bool {
+        if func == NativeFunctions::Equals && operands.len() > 2 {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("variadic-is-eq".to_string()),
+                message: format!(
+                    "is-eq with {} arguments is true only if all of them are equal",
+                    operands.len()
+                ),
+                spans: vec![expr.span.clone()],
+                suggestion: None,
+            });
+        }
+        true
+    }
+}
+
+impl AnalysisPass for IsEqChecker {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = IsEqChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn is_eq_with_three_args() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["is_eq_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-read-only (all-different (a uint) (b uint) (c uint))
+    (not (is-eq a b c))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, _)) => {
+                assert_eq!(output.len(), 3);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:10: {}: is-eq with 3 arguments is true only if all of them are equal",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(output[1], "    (not (is-eq a b c))");
+                assert_eq!(output[2], "         ^~~~~~~~~~~~~");
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn is_eq_with_two_args() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["is_eq_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-read-only (same (a uint) (b uint))
+    (and (is-eq a b) (> a u1))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((_, result)) => {
+                assert_eq!(result.diagnostics.len(), 0);
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn opt_in() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["all".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-read-only (all-different (a uint) (b uint) (c uint))
+    (not (is-eq a b c))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((_, result)) => {
+                assert!(result
+                    .diagnostics
+                    .iter()
+                    .all(|diagnostic| !diagnostic.message.starts_with("is-eq with")));
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
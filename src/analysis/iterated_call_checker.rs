@@ -0,0 +1,224 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor, TypedVar};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::types::{PrincipalData, Value};
+use crate::clarity::ClarityName;
+use std::collections::{HashMap, HashSet};
+
+// Warns about `map`, `fold` and `filter` applying a function which makes a
+// `contract-call?`, directly or through the functions it calls: the external
+// call is made once per element, which multiplies its cost and its effects.
+pub struct IteratedCallChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    // The bodies of the functions of the contract, by name
+    functions: HashMap<&'a ClarityName, &'a SymbolicExpression>,
+    iterations: Vec<(&'a SymbolicExpression, &'static str, &'a ClarityName)>,
+}
+
+impl<'a> IteratedCallChecker<'a> {
+    fn new() -> IteratedCallChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            functions: HashMap::new(),
+            iterations: Vec::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        // Functions can be applied before they are defined
+        for (expr, iterator, func) in self.iterations.iter() {
+            let body = match self.functions.get(func) {
+                Some(body) => body,
+                None => continue,
+            };
+            let mut followed = HashSet::from([*func]);
+            if let Some((call, target)) = self.find_contract_call(body, &mut followed) {
+                self.diagnostics.push(Diagnostic {
+                    level: Level::Warning,
+                    code: Some("iterated-contract-call".to_string()),
+                    message: format!(
+                        "{} applies '{}' to each element of the sequence, and it calls {} with contract-call?",
+                        iterator, func, target
+                    ),
+                    spans: vec![expr.span.clone(), call.span.clone()],
+                    suggestion: Some(
+                        "Make a single contract-call? with the whole sequence".to_string(),
+                    ),
+                });
+            }
+        }
+        Ok(self.diagnostics)
+    }
+
+    // The first `contract-call?` reached from an expression, with a
+    // description of its target, following the calls to the functions of the
+    // contract.
+    fn find_contract_call(
+        &self,
+        expr: &'a SymbolicExpression,
+        followed: &mut HashSet<&'a ClarityName>,
+    ) -> Option<(&'a SymbolicExpression, String)> {
+        let list = expr.match_list()?;
+        if let Some((NativeFunctions::ContractCall, [contract, function_name, ..])) =
+            match_native_call(expr)
+        {
+            let function_name = function_name.match_atom()?;
+            let target = match (contract.match_literal_value(), contract.match_atom()) {
+                (Some(Value::Principal(PrincipalData::Contract(contract_identifier))), _) => {
+                    format!("'{}' of '{}'", function_name, contract_identifier.name)
+                }
+                (_, Some(trait_ref)) => {
+                    format!("'{}' of the contract '{}'", function_name, trait_ref)
+                }
+                _ => format!("'{}'", function_name),
+            };
+            return Some((expr, target));
+        }
+        if let Some(name) = list.first().and_then(|head| head.match_atom()) {
+            if let Some(body) = self.functions.get(name) {
+                if followed.insert(name) {
+                    if let Some(found) = self.find_contract_call(body, followed) {
+                        return Some(found);
+                    }
+                }
+            }
+        }
+        list.iter()
+            .skip(1)
+            .find_map(|element| self.find_contract_call(element, followed))
+    }
+}
+
+impl<'a> ASTVisitor<'a> for IteratedCallChecker<'a> {
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.functions.insert(name, body);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.functions.insert(name, body);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.functions.insert(name, body);
+        self.traverse_expr(body)
+    }
+
+    fn visit_map(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: &'a ClarityName,
+        sequences: &'a [SymbolicExpression],
+    ) -> bool {
+        self.iterations.push((expr, "map", func));
+        true
+    }
+
+    fn visit_fold(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: &'a ClarityName,
+        sequence: &'a SymbolicExpression,
+        initial: &'a SymbolicExpression,
+    ) -> bool {
+        self.iterations.push((expr, "fold", func));
+        true
+    }
+
+    fn visit_filter(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: &'a ClarityName,
+        sequence: &'a SymbolicExpression,
+    ) -> bool {
+        self.iterations.push((expr, "filter", func));
+        true
+    }
+}
+
+impl AnalysisPass for IteratedCallChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = IteratedCallChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn iterated_contract_call() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["iterated_call_checker".to_string()];
+        let mut session = Session::new(settings);
+        let callee = "(define-public (pay (amount uint)) (ok amount))".to_string();
+        session
+            .formatted_interpretation(callee, Some("callee".to_string()), false, None)
+            .unwrap();
+        let snippet = "
+(define-private (pay-one (amount uint))
+    (unwrap-panic (contract-call? .callee pay amount))
+)
+(define-private (pay-twice (amount uint))
+    (+ (pay-one amount) (pay-one amount))
+)
+(define-private (double (amount uint))
+    (* amount u2)
+)
+(define-public (pay-all (amounts (list 10 uint)))
+    (ok (map pay-twice amounts))
+)
+(define-read-only (double-all (amounts (list 10 uint)))
+    (map double amounts)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:12:9: {}: map applies 'pay-twice' to each element of the sequence, and it calls 'pay' of 'callee' with contract-call?",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(output[1], "    (ok (map pay-twice amounts))");
+                assert_eq!(result.diagnostics[0].spans[1].start_line, 3);
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
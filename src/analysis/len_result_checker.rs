@@ -0,0 +1,201 @@
+use crate::analysis::ast_types::AstTypes;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor};
+use crate::analysis::{pre_check_result, AnalysisResult};
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+
+// Reports the result of `len`, a uint, combined with an int in arithmetic or
+// comparisons, or used as a sequence.
+pub struct LenResultChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    types: AstTypes<'a>,
+}
+
+impl<'a> LenResultChecker<'a> {
+    fn new(expressions: &'a [SymbolicExpression]) -> LenResultChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            types: AstTypes::new(expressions),
+        }
+    }
+
+    pub fn run(expressions: &'a [SymbolicExpression]) -> AnalysisResult {
+        let mut checker = LenResultChecker::new(expressions);
+        traverse(&mut checker, expressions);
+        pre_check_result(checker.diagnostics)
+    }
+
+    fn check_operands(&mut self, func: NativeFunctions, operands: &'a [SymbolicExpression]) {
+        let length = match operands.iter().find(|operand| is_len(operand)) {
+            Some(length) => length,
+            None => return,
+        };
+        let int_operand = match operands
+            .iter()
+            .find(|operand| self.types.type_of(operand) == Some("int"))
+        {
+            Some(int_operand) => int_operand,
+            None => return,
+        };
+        self.diagnostics.push(Diagnostic {
+            level: Level::Error,
+            code: Some("len-mixed-with-int".to_string()),
+            message: format!(
+                "'{}' mixes the uint returned by len with an int",
+                func.get_name()
+            ),
+            spans: vec![length.span.clone(), int_operand.span.clone()],
+            suggestion: Some(
+                "Use a uint, such as u1, or convert the length with to-int".to_string(),
+            ),
+        });
+    }
+
+    fn check_sequence(&mut self, func: NativeFunctions, sequence: &'a SymbolicExpression) {
+        if !is_len(sequence) {
+            return;
+        }
+        self.diagnostics.push(Diagnostic {
+            level: Level::Error,
+            code: Some("len-as-sequence".to_string()),
+            message: format!(
+                "'{}' expects a sequence, but len returns a uint",
+                func.get_name()
+            ),
+            spans: vec![sequence.span.clone()],
+            suggestion: Some("Pass the sequence itself, rather than its length".to_string()),
+        });
+    }
+}
+
+fn is_len(expr: &SymbolicExpression) -> bool {
+    matches!(match_native_call(expr), Some((NativeFunctions::Len, _)))
+}
+
+impl<'a> ASTVisitor<'a> for LenResultChecker<'a> {
+    fn visit_arithmetic(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: NativeFunctions,
+        operands: &'a [SymbolicExpression],
+    ) -> bool {
+        self.check_operands(func, operands);
+        true
+    }
+
+    fn visit_comparison(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: NativeFunctions,
+        operands: &'a [SymbolicExpression],
+    ) -> bool {
+        self.check_operands(func, operands);
+        true
+    }
+
+    fn visit_append(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        list: &'a SymbolicExpression,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_sequence(NativeFunctions::Append, list);
+        true
+    }
+
+    fn visit_concat(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        lhs: &'a SymbolicExpression,
+        rhs: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_sequence(NativeFunctions::Concat, lhs);
+        self.check_sequence(NativeFunctions::Concat, rhs);
+        true
+    }
+
+    fn visit_len(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        sequence: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_sequence(NativeFunctions::Len, sequence);
+        true
+    }
+
+    fn visit_element_at(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        sequence: &'a SymbolicExpression,
+        index: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_sequence(NativeFunctions::ElementAt, sequence);
+        true
+    }
+
+    fn visit_index_of(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        sequence: &'a SymbolicExpression,
+        item: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_sequence(NativeFunctions::IndexOf, sequence);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn len_with_int() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-read-only (last-index (names (list 10 (string-ascii 8))))
+    (- (len names) 1)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Err(output) => {
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:8: {}: '-' mixes the uint returned by len with an int",
+                        red!("error")
+                    )
+                );
+                assert_eq!(output[1], "    (- (len names) 1)");
+                assert_eq!(output[2], "       ^~~~~~~~~~~");
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn len_as_sequence() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-read-only (label (name (string-ascii 8)))
+    (concat \"name: \" (len name))
+)
+"
+        .to_string();
+        match session.interpret(snippet, None, false, None) {
+            Err((_, Some(diagnostic), _)) => {
+                assert_eq!(
+                    diagnostic.message,
+                    "'concat' expects a sequence, but len returns a uint"
+                );
+                assert_eq!(
+                    diagnostic.suggestion,
+                    Some("Pass the sequence itself, rather than its length".to_string())
+                );
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+}
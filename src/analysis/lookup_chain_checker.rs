@@ -0,0 +1,230 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::representations::SymbolicExpressionType::*;
+use crate::clarity::ClarityName;
+use std::collections::HashSet;
+
+// Surfaces long chains of `if` expressions comparing the same variable
+// against constants, which map keys to values and are usually better
+// expressed as a map lookup. Clarity's `match` only has two arms, so such
+// chains are always built from nested `if` expressions.
+pub struct LookupChainChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    threshold: usize,
+    constants: HashSet<&'a ClarityName>,
+    // `if` expressions already reported as part of an enclosing chain
+    chained: HashSet<u64>,
+}
+
+impl<'a> LookupChainChecker<'a> {
+    fn new(settings: &Settings) -> LookupChainChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            threshold: settings.lookup_chain_threshold,
+            constants: HashSet::new(),
+            chained: HashSet::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    fn is_constant(&self, expr: &SymbolicExpression) -> bool {
+        match &expr.expr {
+            LiteralValue(_) | AtomValue(_) => true,
+            Atom(name) => self.constants.contains(name),
+            _ => false,
+        }
+    }
+
+    // Return the variable compared in a condition like `(is-eq x u1)`.
+    fn match_comparison<'b>(&self, cond: &'b SymbolicExpression) -> Option<&'b ClarityName> {
+        match match_native_call(cond)? {
+            (NativeFunctions::Equals, [left, right]) => {
+                if self.is_constant(right) {
+                    left.match_atom()
+                        .filter(|name| !self.constants.contains(name))
+                } else if self.is_constant(left) {
+                    right
+                        .match_atom()
+                        .filter(|name| !self.constants.contains(name))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn check_chain(&mut self, expr: &SymbolicExpression) {
+        let mut subject = None;
+        let mut links = vec![];
+        let mut current = expr;
+        while let Some((NativeFunctions::If, [cond, _, else_expr])) = match_native_call(current) {
+            match (self.match_comparison(cond), subject) {
+                (Some(name), None) => subject = Some(name),
+                (Some(name), Some(subject)) if name == subject => {}
+                _ => break,
+            }
+            links.push(current.id);
+            current = else_expr;
+        }
+
+        let subject = match subject {
+            Some(subject) if links.len() >= self.threshold => subject,
+            _ => return,
+        };
+        self.diagnostics.push(Diagnostic {
+            level: Level::Note,
+            code: Some("lookup-chain".to_string()),
+            message: format!(
+                "chain of {} if expressions compares '{}' against constants",
+                links.len(),
+                subject
+            ),
+            spans: vec![expr.span.clone()],
+            suggestion: Some(
+                "Consider a define-map lookup, or a fold over a list of key-value pairs"
+                    .to_string(),
+            ),
+        });
+        self.chained.extend(links);
+    }
+}
+
+impl<'a> ASTVisitor<'a> for LookupChainChecker<'a> {
+    fn visit_define_constant(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        self.constants.insert(name);
+        true
+    }
+
+    fn traverse_if(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        cond: &'a SymbolicExpression,
+        then_expr: &'a SymbolicExpression,
+        else_expr: &'a SymbolicExpression,
+    ) -> bool {
+        // Check the outermost `if` of a chain before its nested links
+        if !self.chained.contains(&expr.id) {
+            self.check_chain(expr);
+        }
+        self.traverse_expr(then_expr)
+            && self.traverse_expr(else_expr)
+            && self.visit_if(expr, cond, then_expr, else_expr)
+    }
+}
+
+impl AnalysisPass for LookupChainChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = LookupChainChecker::new(settings);
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    const SNIPPET: &str = "
+(define-constant TIER-GOLD u3)
+(define-read-only (fee (tier uint))
+    (if (is-eq tier u1)
+        u100
+        (if (is-eq tier u2)
+            u80
+            (if (is-eq TIER-GOLD tier)
+                u50
+                u200
+            )
+        )
+    )
+)
+";
+
+    #[test]
+    fn long_chain() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["lookup_chain_checker".to_string()];
+        settings.analysis_settings.lookup_chain_threshold = 3;
+        let mut session = Session::new(settings);
+        match session.formatted_interpretation(
+            SNIPPET.to_string(),
+            Some("checker".to_string()),
+            false,
+            None,
+        ) {
+            Ok((output, _)) => {
+                assert_eq!(output.len(), 3);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:4:5: {}: chain of 3 if expressions compares 'tier' against constants",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(output[1], "    (if (is-eq tier u1)");
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn short_chain() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["lookup_chain_checker".to_string()];
+        let mut session = Session::new(settings);
+        match session.formatted_interpretation(
+            SNIPPET.to_string(),
+            Some("checker".to_string()),
+            false,
+            None,
+        ) {
+            Ok((_, result)) => {
+                assert_eq!(result.diagnostics.len(), 0);
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn opt_in() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["all".to_string()];
+        settings.analysis_settings.lookup_chain_threshold = 3;
+        let mut session = Session::new(settings);
+        match session.formatted_interpretation(
+            SNIPPET.to_string(),
+            Some("checker".to_string()),
+            false,
+            None,
+        ) {
+            Ok((_, result)) => {
+                assert!(result
+                    .diagnostics
+                    .iter()
+                    .all(|diagnostic| !diagnostic.message.starts_with("chain of")));
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,191 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_types::AstTypes;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor};
+use crate::analysis::{pre_check_result, AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+
+// Checks `is-eq` comparing the result of `map-get?` with an optional. With
+// `none`, a note suggests `is-none`. With `(some v)`, a value whose type is
+// not that of the map is an error, checked by `run` before type-checking.
+// The notes are reported by the pass, after it.
+pub struct MapGetComparisonChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    types: AstTypes<'a>,
+}
+
+impl<'a> MapGetComparisonChecker<'a> {
+    fn new(expressions: &'a [SymbolicExpression]) -> MapGetComparisonChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            types: AstTypes::new(expressions),
+        }
+    }
+
+    // Report the comparisons with values of the wrong type only.
+    pub fn run(expressions: &'a [SymbolicExpression]) -> AnalysisResult {
+        let mut checker = MapGetComparisonChecker::new(expressions);
+        traverse(&mut checker, expressions);
+        pre_check_result(
+            checker
+                .diagnostics
+                .into_iter()
+                .filter(|diagnostic| diagnostic.level == Level::Error)
+                .collect(),
+        )
+    }
+
+    fn check_comparison(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        lookup: &'a SymbolicExpression,
+        compared: &'a SymbolicExpression,
+    ) {
+        let map_name = match match_native_call(lookup) {
+            Some((NativeFunctions::FetchEntry, [map_name, _])) => match map_name.match_atom() {
+                Some(map_name) => map_name,
+                None => return,
+            },
+            _ => return,
+        };
+        if compared
+            .match_atom()
+            .is_some_and(|name| name.as_str() == "none")
+        {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("map-get-none-comparison".to_string()),
+                message: format!(
+                    "this comparison with none checks that map '{}' has no entry for the key",
+                    map_name
+                ),
+                spans: vec![expr.span.clone()],
+                suggestion: Some("Use is-none on the result of map-get?".to_string()),
+            });
+            return;
+        }
+        let value = match match_native_call(compared) {
+            Some((NativeFunctions::ConsSome, [value])) => value,
+            _ => return,
+        };
+        let (value_type, map_value_type) = match (
+            self.types.type_of(value),
+            self.types.map_value_type(map_name),
+        ) {
+            (Some(value_type), Some(map_value_type)) => (value_type, map_value_type),
+            _ => return,
+        };
+        if value_type == map_value_type {
+            return;
+        }
+        self.diagnostics.push(Diagnostic {
+            level: Level::Error,
+            code: Some("map-get-type-mismatch".to_string()),
+            message: format!(
+                "map '{}' holds values of type '{}', but this value is of type '{}'",
+                map_name, map_value_type, value_type
+            ),
+            spans: vec![value.span.clone(), lookup.span.clone()],
+            suggestion: None,
+        });
+    }
+}
+
+impl<'a> ASTVisitor<'a> for MapGetComparisonChecker<'a> {
+    fn visit_comparison(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: NativeFunctions,
+        operands: &'a [SymbolicExpression],
+    ) -> bool {
+        if let (NativeFunctions::Equals, [lhs, rhs]) = (func, operands) {
+            self.check_comparison(expr, lhs, rhs);
+            self.check_comparison(expr, rhs, lhs);
+        }
+        true
+    }
+}
+
+impl AnalysisPass for MapGetComparisonChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let mut checker = MapGetComparisonChecker::new(&contract_analysis.expressions);
+        traverse(&mut checker, &contract_analysis.expressions);
+        Ok(checker.diagnostics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn compared_with_none() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["map_get_comparison_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-map owners uint principal)
+(define-read-only (is-free (id uint))
+    (is-eq (map-get? owners id) none)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:4:5: {}: this comparison with none checks that map 'owners' has no entry for the key",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(output[1], "    (is-eq (map-get? owners id) none)");
+                assert_eq!(output[2], "    ^~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some("Use is-none on the result of map-get?".to_string())
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn compared_with_wrong_type() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-map balances principal uint)
+(define-read-only (has-balance (who principal) (amount int))
+    (is-eq (some amount) (map-get? balances who))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Err(output) => {
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:4:18: {}: map 'balances' holds values of type 'uint', but this value is of type 'int'",
+                        red!("error")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "    (is-eq (some amount) (map-get? balances who))"
+                );
+                assert_eq!(output[2], "                 ^~~~~~");
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+}
@@ -1,49 +1,391 @@
 pub mod annotation;
+pub mod append_overflow_checker;
+pub mod as_contract_checker;
+pub mod ascii_content_checker;
+pub mod ast_types;
 pub mod ast_visitor;
+pub mod block_info_checker;
+pub mod branch_type_checker;
 pub mod call_checker;
+pub mod catalog;
 pub mod check_checker;
+pub mod constant_guard_checker;
+pub mod contract_call_checker;
 pub mod contract_call_detector;
+pub mod contract_call_guard_checker;
+pub mod data_var_size_checker;
+pub mod define_trait_checker;
+pub mod duplicate_body_checker;
+pub mod duplicate_error_checker;
+pub mod fold_accumulator_checker;
+pub mod fold_range_checker;
+pub mod forward_reference_checker;
+pub mod ft_balance_checker;
+pub mod hashed_key_checker;
+pub mod is_eq_checker;
+pub mod iterated_call_checker;
+pub mod len_result_checker;
+pub mod lookup_chain_checker;
+pub mod map_get_comparison_checker;
+pub mod nested_optional_checker;
+pub mod network_principal_checker;
+pub mod nft_mint_checker;
+pub mod pow_overflow_checker;
+pub mod public_return_checker;
+pub mod read_only_auth_checker;
+pub mod read_only_candidate_checker;
+pub mod redundant_unwrap_checker;
+pub mod renamed_function_checker;
+pub mod repeated_literal_checker;
+pub mod self_transfer_checker;
+pub mod sequence_comparison_checker;
+pub mod sequence_length_checker;
+pub mod side_effect_condition_checker;
+pub mod stored_response_checker;
+pub mod stx_burn_checker;
+pub mod trait_alias_checker;
+pub mod trait_recursion_checker;
+pub mod trait_signature_checker;
+pub mod tuple_order_checker;
+pub mod unbounded_mint_checker;
+pub mod unchecked_index_checker;
+pub mod unused_trait_checker;
+pub mod unwrap_argument_checker;
+pub mod var_copy_checker;
+pub mod version_syntax_checker;
+pub mod versions;
 
 use crate::analysis::annotation::Annotation;
 use crate::clarity::analysis::analysis_db::AnalysisDatabase;
 use crate::clarity::analysis::types::ContractAnalysis;
-use crate::clarity::diagnostic::Diagnostic;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::version::ClarityVersion;
+use crate::repl::settings::PrincipalVersions;
+use std::collections::HashSet;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use self::append_overflow_checker::AppendOverflowChecker;
+use self::as_contract_checker::AsContractChecker;
+use self::ascii_content_checker::AsciiContentChecker;
+use self::block_info_checker::BlockInfoChecker;
 use self::call_checker::CallChecker;
 use self::check_checker::CheckChecker;
+use self::constant_guard_checker::ConstantGuardChecker;
+use self::contract_call_checker::ContractCallChecker;
 use self::contract_call_detector::ContractCallDetector;
+use self::contract_call_guard_checker::ContractCallGuardChecker;
+use self::data_var_size_checker::DataVarSizeChecker;
+use self::duplicate_body_checker::DuplicateBodyChecker;
+use self::duplicate_error_checker::DuplicateErrorChecker;
+use self::fold_accumulator_checker::FoldAccumulatorChecker;
+use self::fold_range_checker::FoldRangeChecker;
+use self::forward_reference_checker::ForwardReferenceChecker;
+use self::ft_balance_checker::FtBalanceChecker;
+use self::hashed_key_checker::HashedKeyChecker;
+use self::is_eq_checker::IsEqChecker;
+use self::iterated_call_checker::IteratedCallChecker;
+use self::lookup_chain_checker::LookupChainChecker;
+use self::map_get_comparison_checker::MapGetComparisonChecker;
+use self::nested_optional_checker::NestedOptionalChecker;
+use self::network_principal_checker::NetworkPrincipalChecker;
+use self::nft_mint_checker::NftMintChecker;
+use self::pow_overflow_checker::PowOverflowChecker;
+use self::read_only_auth_checker::ReadOnlyAuthChecker;
+use self::read_only_candidate_checker::ReadOnlyCandidateChecker;
+use self::redundant_unwrap_checker::RedundantUnwrapChecker;
+use self::renamed_function_checker::RenamedFunctionChecker;
+use self::repeated_literal_checker::RepeatedLiteralChecker;
+use self::self_transfer_checker::SelfTransferChecker;
+use self::sequence_length_checker::SequenceLengthChecker;
+use self::side_effect_condition_checker::SideEffectConditionChecker;
+use self::stored_response_checker::StoredResponseChecker;
+use self::stx_burn_checker::StxBurnChecker;
+use self::trait_alias_checker::TraitAliasChecker;
+use self::trait_recursion_checker::TraitRecursionChecker;
+use self::tuple_order_checker::TupleOrderChecker;
+use self::unbounded_mint_checker::UnboundedMintChecker;
+use self::unchecked_index_checker::UncheckedIndexChecker;
+use self::unused_trait_checker::UnusedTraitChecker;
+use self::var_copy_checker::VarCopyChecker;
+
+// Options for the REPL-only analysis passes.
+#[derive(Clone, Debug)]
+pub struct Settings {
+    pub clarity_version: ClarityVersion,
+    // Number of chained `if` comparisons reported by the lookup_chain_checker
+    pub lookup_chain_threshold: usize,
+    // Number of occurrences of a literal reported by the repeated_literal_checker
+    pub repeated_literal_threshold: usize,
+    // Size, in bytes, of the initial value of a data-var reported by the
+    // data_var_size_checker
+    pub data_var_size_threshold: u32,
+    // Number of optionals and responses nested in a type reported by the
+    // nested_optional_checker
+    pub nested_optional_threshold: usize,
+    // The network the contracts are deployed to, whose principals only are
+    // expected by the network_principal_checker
+    pub network: Option<Network>,
+    // The version bytes of the principals of each network, checked by
+    // `Session::start`, and used for the default deployer
+    pub principal_versions: PrincipalVersions,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Network::Mainnet => write!(f, "mainnet"),
+            Network::Testnet => write!(f, "testnet"),
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            clarity_version: ClarityVersion::default(),
+            lookup_chain_threshold: 5,
+            repeated_literal_threshold: 3,
+            data_var_size_threshold: 1024,
+            nested_optional_threshold: 3,
+            network: None,
+            principal_versions: PrincipalVersions::default(),
+        }
+    }
+}
+
+// Called with each diagnostic as soon as the analysis producing it completes,
+// to report diagnostics progressively.
+#[derive(Clone)]
+pub struct DiagnosticCallback(Arc<Mutex<Callback>>);
+
+type Callback = dyn FnMut(&Diagnostic) + Send;
+
+impl DiagnosticCallback {
+    pub fn new<F: FnMut(&Diagnostic) + Send + 'static>(callback: F) -> DiagnosticCallback {
+        DiagnosticCallback(Arc::new(Mutex::new(callback)))
+    }
+
+    // A panic in the callback is caught, so that it cannot abort the analysis.
+    pub fn notify(&self, diagnostics: &[Diagnostic]) {
+        let mut callback = match self.0.lock() {
+            Ok(callback) => callback,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        for diagnostic in diagnostics {
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| (*callback)(diagnostic)));
+        }
+    }
+}
+
+impl fmt::Debug for DiagnosticCallback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DiagnosticCallback")
+    }
+}
 
 pub type AnalysisResult = Result<Vec<Diagnostic>, Vec<Diagnostic>>;
 
+// The result of a check run on the AST before type-checking, which fails with
+// any diagnostic it reports.
+pub fn pre_check_result(diagnostics: Vec<Diagnostic>) -> AnalysisResult {
+    if diagnostics.is_empty() {
+        Ok(diagnostics)
+    } else {
+        Err(diagnostics)
+    }
+}
+
 pub trait AnalysisPass {
     fn run_pass(
         contract_analysis: &mut ContractAnalysis,
         analysis_db: &mut AnalysisDatabase,
         annotations: &Vec<Annotation>,
+        settings: &Settings,
     ) -> AnalysisResult;
 }
 
+type Pass =
+    fn(&mut ContractAnalysis, &mut AnalysisDatabase, &Vec<Annotation>, &Settings) -> AnalysisResult;
+
+// The passes enabled by "all". The advisory and stylistic ones are listed in
+// `opt_in_passes`.
+fn all_passes() -> Vec<Pass> {
+    vec![
+        AppendOverflowChecker::run_pass,
+        BlockInfoChecker::run_pass,
+        CallChecker::run_pass,
+        CheckChecker::run_pass,
+        ConstantGuardChecker::run_pass,
+        ContractCallChecker::run_pass,
+        ContractCallGuardChecker::run_pass,
+        DataVarSizeChecker::run_pass,
+        DuplicateErrorChecker::run_pass,
+        FoldAccumulatorChecker::run_pass,
+        FoldRangeChecker::run_pass,
+        FtBalanceChecker::run_pass,
+        IteratedCallChecker::run_pass,
+        MapGetComparisonChecker::run_pass,
+        NetworkPrincipalChecker::run_pass,
+        NftMintChecker::run_pass,
+        PowOverflowChecker::run_pass,
+        ReadOnlyAuthChecker::run_pass,
+        ReadOnlyCandidateChecker::run_pass,
+        RedundantUnwrapChecker::run_pass,
+        RenamedFunctionChecker::run_pass,
+        RepeatedLiteralChecker::run_pass,
+        SelfTransferChecker::run_pass,
+        SequenceLengthChecker::run_pass,
+        SideEffectConditionChecker::run_pass,
+        StxBurnChecker::run_pass,
+        TraitAliasChecker::run_pass,
+        TraitRecursionChecker::run_pass,
+        UnboundedMintChecker::run_pass,
+        UncheckedIndexChecker::run_pass,
+        UnusedTraitChecker::run_pass,
+        VarCopyChecker::run_pass,
+    ]
+}
+
+// The passes enabled only by their name or by "strict".
+fn opt_in_passes() -> Vec<Pass> {
+    vec![
+        AsContractChecker::run_pass,
+        AsciiContentChecker::run_pass,
+        DuplicateBodyChecker::run_pass,
+        ForwardReferenceChecker::run_pass,
+        HashedKeyChecker::run_pass,
+        IsEqChecker::run_pass,
+        LookupChainChecker::run_pass,
+        NestedOptionalChecker::run_pass,
+        StoredResponseChecker::run_pass,
+        TupleOrderChecker::run_pass,
+    ]
+}
+
 pub fn run_analysis(
     contract_analysis: &mut ContractAnalysis,
     analysis_db: &mut AnalysisDatabase,
     pass_list: &Vec<String>,
     annotations: &Vec<Annotation>,
+    settings: &Settings,
+    callback: Option<&DiagnosticCallback>,
+    deadline: Option<(Instant, Duration)>,
 ) -> AnalysisResult {
     let mut errors: Vec<Diagnostic> = Vec::new();
-    let mut passes: Vec<
-        fn(&mut ContractAnalysis, &mut AnalysisDatabase, &Vec<Annotation>) -> AnalysisResult,
-    > = vec![ContractCallDetector::run_pass, CallChecker::run_pass];
+    let mut passes: Vec<Pass> = vec![ContractCallDetector::run_pass, CallChecker::run_pass];
+    // The passes run even after the deadline
+    let required = passes.len();
+    let strict = pass_list.iter().any(|pass| pass == "strict");
     for pass in pass_list {
         match pass.as_str() {
-            "all" => passes.append(&mut vec![CallChecker::run_pass, CheckChecker::run_pass]),
+            "all" => passes.append(&mut all_passes()),
+            // The passes of "all" and the opt-in ones, with notes raised to
+            // warnings
+            "strict" => {
+                passes.append(&mut all_passes());
+                passes.append(&mut opt_in_passes());
+            }
+            "append_overflow_checker" => passes.push(AppendOverflowChecker::run_pass),
+            "as_contract_checker" => passes.push(AsContractChecker::run_pass),
+            "ascii_content_checker" => passes.push(AsciiContentChecker::run_pass),
+            "block_info_checker" => passes.push(BlockInfoChecker::run_pass),
             "check_checker" => passes.push(CheckChecker::run_pass),
+            "constant_guard_checker" => passes.push(ConstantGuardChecker::run_pass),
+            "contract_call_checker" => passes.push(ContractCallChecker::run_pass),
+            "contract_call_guard_checker" => passes.push(ContractCallGuardChecker::run_pass),
+            "data_var_size_checker" => passes.push(DataVarSizeChecker::run_pass),
+            "duplicate_body_checker" => passes.push(DuplicateBodyChecker::run_pass),
+            "duplicate_error_checker" => passes.push(DuplicateErrorChecker::run_pass),
+            "fold_accumulator_checker" => passes.push(FoldAccumulatorChecker::run_pass),
+            "fold_range_checker" => passes.push(FoldRangeChecker::run_pass),
+            "forward_reference_checker" => passes.push(ForwardReferenceChecker::run_pass),
+            "ft_balance_checker" => passes.push(FtBalanceChecker::run_pass),
+            "hashed_key_checker" => passes.push(HashedKeyChecker::run_pass),
+            "is_eq_checker" => passes.push(IsEqChecker::run_pass),
+            "iterated_call_checker" => passes.push(IteratedCallChecker::run_pass),
+            "lookup_chain_checker" => passes.push(LookupChainChecker::run_pass),
+            "map_get_comparison_checker" => passes.push(MapGetComparisonChecker::run_pass),
+            "nested_optional_checker" => passes.push(NestedOptionalChecker::run_pass),
+            "network_principal_checker" => passes.push(NetworkPrincipalChecker::run_pass),
+            "nft_mint_checker" => passes.push(NftMintChecker::run_pass),
+            "pow_overflow_checker" => passes.push(PowOverflowChecker::run_pass),
+            "read_only_auth_checker" => passes.push(ReadOnlyAuthChecker::run_pass),
+            "read_only_candidate_checker" => passes.push(ReadOnlyCandidateChecker::run_pass),
+            "redundant_unwrap_checker" => passes.push(RedundantUnwrapChecker::run_pass),
+            "renamed_function_checker" => passes.push(RenamedFunctionChecker::run_pass),
+            "repeated_literal_checker" => passes.push(RepeatedLiteralChecker::run_pass),
+            "self_transfer_checker" => passes.push(SelfTransferChecker::run_pass),
+            "sequence_length_checker" => passes.push(SequenceLengthChecker::run_pass),
+            "side_effect_condition_checker" => passes.push(SideEffectConditionChecker::run_pass),
+            "stored_response_checker" => passes.push(StoredResponseChecker::run_pass),
+            "stx_burn_checker" => passes.push(StxBurnChecker::run_pass),
+            "trait_alias_checker" => passes.push(TraitAliasChecker::run_pass),
+            "trait_recursion_checker" => passes.push(TraitRecursionChecker::run_pass),
+            "tuple_order_checker" => passes.push(TupleOrderChecker::run_pass),
+            "unbounded_mint_checker" => passes.push(UnboundedMintChecker::run_pass),
+            "unchecked_index_checker" => passes.push(UncheckedIndexChecker::run_pass),
+            "unused_trait_checker" => passes.push(UnusedTraitChecker::run_pass),
+            "var_copy_checker" => passes.push(VarCopyChecker::run_pass),
             _ => panic!("{}: Unrecognized analysis pass: {}", red!("error"), pass),
         }
     }
+    // A pass enabled by several entries, like "strict" and its own name, runs
+    // once
+    let mut enabled = HashSet::new();
+    passes.retain(|pass| enabled.insert(*pass as usize));
 
-    for pass in passes {
+    let count = passes.len();
+    for (index, pass) in passes.into_iter().enumerate() {
+        // The time is checked between passes, so the pass running at the
+        // deadline completes
+        if let Some((deadline, timeout)) = deadline {
+            if index >= required && Instant::now() >= deadline {
+                let timeout = Diagnostic {
+                    level: Level::Warning,
+                    code: Some("analysis-timeout".to_string()),
+                    message: format!(
+                        "the analysis timed out after {}ms, so {} of its passes did not run",
+                        timeout.as_millis(),
+                        count - index
+                    ),
+                    spans: vec![],
+                    suggestion: Some("Increase the analysis timeout".to_string()),
+                };
+                if let Some(callback) = callback {
+                    callback.notify(std::slice::from_ref(&timeout));
+                }
+                errors.push(timeout);
+                break;
+            }
+        }
         // Collect warnings and continue, or if there is an error, return.
-        match pass(contract_analysis, analysis_db, annotations) {
+        let mut result = pass(contract_analysis, analysis_db, annotations, settings);
+        if strict {
+            match result {
+                Ok(ref mut diagnostics) | Err(ref mut diagnostics) => {
+                    for diagnostic in diagnostics.iter_mut() {
+                        if diagnostic.level == Level::Note {
+                            diagnostic.level = Level::Warning;
+                        }
+                    }
+                }
+            }
+        }
+        if let Some(callback) = callback {
+            match result {
+                Ok(ref diagnostics) | Err(ref diagnostics) => callback.notify(diagnostics),
+            }
+        }
+        match result {
             Ok(mut w) => errors.append(&mut w),
             Err(mut e) => {
                 errors.append(&mut e);
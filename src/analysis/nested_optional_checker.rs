@@ -0,0 +1,256 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor, TypedVar};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::{Span, SymbolicExpression};
+use crate::clarity::types::{FunctionType, SequenceSubtype, TypeSignature};
+use crate::clarity::ClarityName;
+
+// Surfaces the types of function arguments and return values, map values and
+// data-vars which nest optionals and responses, like
+// `(optional (optional uint))`, usually left by a refactor wrapping a value
+// twice.
+pub struct NestedOptionalChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    threshold: usize,
+    contract_analysis: Option<&'a ContractAnalysis>,
+}
+
+impl<'a> NestedOptionalChecker<'a> {
+    fn new(settings: &Settings) -> NestedOptionalChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            threshold: settings.nested_optional_threshold,
+            contract_analysis: None,
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        self.contract_analysis = Some(contract_analysis);
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    fn check_type(&mut self, described: String, type_signature: &TypeSignature, span: &Span) {
+        let depth = nesting_depth(type_signature);
+        if depth < self.threshold {
+            return;
+        }
+        self.diagnostics.push(Diagnostic {
+            level: Level::Note,
+            code: Some("nested-optional".to_string()),
+            message: format!("{} nests {} optionals and responses", described, depth),
+            spans: vec![span.clone()],
+            suggestion: Some(
+                "Flatten the type, for example by unwrapping the inner values".to_string(),
+            ),
+        });
+    }
+
+    fn check_function(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        function_type: Option<&'a FunctionType>,
+    ) {
+        let function = match function_type {
+            Some(FunctionType::Fixed(function)) => function,
+            _ => return,
+        };
+        for (parameter, arg) in parameters.unwrap_or_default().iter().zip(&function.args) {
+            self.check_type(
+                format!("the type of argument '{}'", parameter.name),
+                &arg.signature,
+                &parameter.type_expr.span,
+            );
+        }
+        // The return type is not written, so point at the name
+        let name_span = expr
+            .match_list()
+            .and_then(|list| list.get(1))
+            .and_then(|signature| signature.match_list())
+            .and_then(|signature| signature.first())
+            .map_or(&expr.span, |name_expr| &name_expr.span);
+        self.check_type(
+            format!("the return type of '{}'", name),
+            &function.returns,
+            name_span,
+        );
+    }
+}
+
+// The number of optionals and responses nested in a type, following the
+// deepest path through lists and tuples.
+fn nesting_depth(type_signature: &TypeSignature) -> usize {
+    match type_signature {
+        TypeSignature::OptionalType(inner) => 1 + nesting_depth(inner),
+        TypeSignature::ResponseType(types) => {
+            1 + nesting_depth(&types.0).max(nesting_depth(&types.1))
+        }
+        TypeSignature::SequenceType(SequenceSubtype::ListType(list)) => {
+            nesting_depth(list.get_list_item_type())
+        }
+        TypeSignature::TupleType(tuple) => tuple
+            .get_type_map()
+            .values()
+            .map(nesting_depth)
+            .max()
+            .unwrap_or(0),
+        _ => 0,
+    }
+}
+
+impl<'a> ASTVisitor<'a> for NestedOptionalChecker<'a> {
+    fn visit_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        let function_type = self
+            .contract_analysis
+            .and_then(|analysis| analysis.get_private_function(name));
+        self.check_function(expr, name, parameters, function_type);
+        true
+    }
+
+    fn visit_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        let function_type = self
+            .contract_analysis
+            .and_then(|analysis| analysis.get_read_only_function_type(name));
+        self.check_function(expr, name, parameters, function_type);
+        true
+    }
+
+    fn visit_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        let function_type = self
+            .contract_analysis
+            .and_then(|analysis| analysis.get_public_function_type(name));
+        self.check_function(expr, name, parameters, function_type);
+        true
+    }
+
+    fn visit_define_map(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        key_type: &'a SymbolicExpression,
+        value_type: &'a SymbolicExpression,
+    ) -> bool {
+        if let Some((_, map_value_type)) = self
+            .contract_analysis
+            .and_then(|analysis| analysis.map_types.get(name))
+        {
+            self.check_type(
+                format!("the type of the values of map '{}'", name),
+                map_value_type,
+                &value_type.span,
+            );
+        }
+        true
+    }
+
+    fn visit_define_data_var(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        data_type: &'a SymbolicExpression,
+        initial: &'a SymbolicExpression,
+    ) -> bool {
+        if let Some(var_type) = self
+            .contract_analysis
+            .and_then(|analysis| analysis.get_persisted_variable_type(name))
+        {
+            self.check_type(
+                format!("the type of data-var '{}'", name),
+                var_type,
+                &data_type.span,
+            );
+        }
+        true
+    }
+}
+
+impl AnalysisPass for NestedOptionalChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = NestedOptionalChecker::new(settings);
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn nested_optionals() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["nested_optional_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-map offers uint (optional (optional (response uint uint))))
+(define-data-var last (optional uint) none)
+(define-read-only (get-offer (id uint) (fallback (optional (optional (optional uint)))))
+    (map-get? offers id)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 3);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:2:25: {}: the type of the values of map 'offers' nests 3 optionals and responses",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "(define-map offers uint (optional (optional (response uint uint))))"
+                );
+                assert_eq!(
+                    output[2],
+                    "                        ^~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~"
+                );
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some(
+                        "Flatten the type, for example by unwrapping the inner values".to_string()
+                    )
+                );
+                assert_eq!(
+                    result.diagnostics[1].message,
+                    "the type of argument 'fallback' nests 3 optionals and responses"
+                );
+                assert_eq!(
+                    result.diagnostics[2].message,
+                    "the return type of 'get-offer' nests 4 optionals and responses"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
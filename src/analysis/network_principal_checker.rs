@@ -0,0 +1,159 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Network, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::types::{PrincipalData, StandardPrincipalData, Value};
+
+// Warns about the principal literals of another network than the one the
+// contracts are deployed to, like a testnet address in a mainnet contract.
+// A contract principal is checked by its deployer, unless it is that of the
+// contract analyzed, as `.name` references are expanded with it.
+pub struct NetworkPrincipalChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    settings: &'a Settings,
+    network: Network,
+    deployer: &'a StandardPrincipalData,
+}
+
+impl<'a> NetworkPrincipalChecker<'a> {
+    fn new(
+        settings: &'a Settings,
+        network: Network,
+        deployer: &'a StandardPrincipalData,
+    ) -> NetworkPrincipalChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            settings,
+            network,
+            deployer,
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    // The other network an address belongs to, if it does not belong to the
+    // one targeted.
+    fn other_network(&self, address: &StandardPrincipalData) -> Option<Network> {
+        let versions = &self.settings.principal_versions;
+        let (other, versions) = match self.network {
+            Network::Mainnet => (
+                Network::Testnet,
+                [versions.testnet_singlesig, versions.testnet_multisig],
+            ),
+            Network::Testnet => (
+                Network::Mainnet,
+                [versions.mainnet_singlesig, versions.mainnet_multisig],
+            ),
+        };
+        if versions.contains(&address.0) {
+            Some(other)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> ASTVisitor<'a> for NetworkPrincipalChecker<'a> {
+    fn visit_literal_value(&mut self, expr: &'a SymbolicExpression, value: &Value) -> bool {
+        let (address, described) = match value {
+            Value::Principal(PrincipalData::Standard(address)) => {
+                (address, format!("principal '{}' is", address))
+            }
+            Value::Principal(PrincipalData::Contract(contract_identifier))
+                if contract_identifier.issuer != *self.deployer =>
+            {
+                (
+                    &contract_identifier.issuer,
+                    format!("contract '{}' is deployed by", contract_identifier),
+                )
+            }
+            _ => return true,
+        };
+        if let Some(other) = self.other_network(address) {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                code: Some("other-network-principal".to_string()),
+                message: format!(
+                    "{} a {} address, but the contract targets the {}",
+                    described, other, self.network
+                ),
+                spans: vec![expr.span.clone()],
+                suggestion: Some(format!("Use the {} address of the account", self.network)),
+            });
+        }
+        true
+    }
+}
+
+impl AnalysisPass for NetworkPrincipalChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let network = match settings.network {
+            Some(network) => network,
+            None => return Ok(vec![]),
+        };
+        let deployer = &contract_analysis.contract_identifier.issuer;
+        let checker = NetworkPrincipalChecker::new(settings, network, deployer);
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::Network;
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn principals_of_other_network() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["network_principal_checker".to_string()];
+        settings.analysis_settings.network = Some(Network::Mainnet);
+        let mut session = Session::new(settings);
+        session
+            .formatted_interpretation(
+                "(define-read-only (ping) true)".to_string(),
+                Some("pinger".to_string()),
+                false,
+                None,
+            )
+            .unwrap();
+        let snippet = "
+(define-constant TREASURY 'SP000000000000000000002Q6VF78)
+(define-constant OPERATOR 'ST000000000000000000002AMW42H)
+(define-constant ORACLE 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.oracle)
+(define-read-only (ping) (contract-call? .pinger ping))
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:27: {}: principal 'ST000000000000000000002AMW42H' is a testnet address, but the contract targets the mainnet",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(
+                    output[3],
+                    format!(
+                        "checker:4:25: {}: contract 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.oracle' is deployed by a testnet address, but the contract targets the mainnet",
+                        yellow!("warning")
+                    )
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,287 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{
+    discarded_calls, match_native_call, traverse, ASTVisitor, TypedVar,
+};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+
+pub struct NftMintChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    // For each `nft-mint?` in the current function which uses the current
+    // value of a data-var as the token id, record the mint and the var name.
+    counter_mints: Vec<(&'a SymbolicExpression, &'a ClarityName)>,
+}
+
+impl<'a> NftMintChecker<'a> {
+    fn new() -> NftMintChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            counter_mints: Vec::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    // Report the `nft-mint?` expressions whose result is thrown away by
+    // `expr`, either directly or through a wrapper like `is-ok`.
+    fn check_discarded(&mut self, expr: &'a SymbolicExpression) {
+        for mint in discarded_calls(expr, NativeFunctions::MintAsset) {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                code: Some("discarded-nft-mint".to_string()),
+                message: "result of nft-mint? is discarded, but the mint fails if the token id already exists".to_string(),
+                spans: vec![mint.span.clone()],
+                suggestion: Some("Check the result with try!, unwrap! or asserts!".to_string()),
+            });
+        }
+    }
+}
+
+impl<'a> ASTVisitor<'a> for NftMintChecker<'a> {
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.counter_mints.clear();
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.counter_mints.clear();
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.counter_mints.clear();
+        self.traverse_expr(body)
+    }
+
+    fn visit_nft_mint(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        token: &'a ClarityName,
+        identifier: &'a SymbolicExpression,
+        recipient: &'a SymbolicExpression,
+    ) -> bool {
+        if let Some(var) = match_var_get(identifier) {
+            self.counter_mints.push((expr, var));
+        }
+        true
+    }
+
+    fn visit_var_set(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        // Since `var-set` is visited after the mint, any mint recorded for
+        // this var was evaluated before the counter was incremented.
+        let mints: Vec<_> = self
+            .counter_mints
+            .iter()
+            .filter(|(_, var)| *var == name)
+            .map(|(mint, _)| *mint)
+            .collect();
+        for mint in mints {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                code: Some("nft-id-incremented-late".to_string()),
+                message: format!(
+                    "nft-mint? uses the current value of '{}' as the token id, but '{}' is only incremented after the mint",
+                    name, name
+                ),
+                spans: vec![mint.span.clone()],
+                suggestion: Some(format!(
+                    "Increment '{}' before minting to avoid reusing a token id",
+                    name
+                )),
+            });
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("nft-id-increment".to_string()),
+                message: format!("'{}' is incremented here", name),
+                spans: vec![expr.span.clone()],
+                suggestion: None,
+            });
+        }
+        self.counter_mints.retain(|(_, var)| *var != name);
+        true
+    }
+
+    fn visit_list(&mut self, expr: &'a SymbolicExpression, list: &'a [SymbolicExpression]) -> bool {
+        self.check_discarded(expr);
+        true
+    }
+}
+
+fn match_var_get(expr: &SymbolicExpression) -> Option<&ClarityName> {
+    match match_native_call(expr)? {
+        (NativeFunctions::FetchVar, args) => args.first()?.match_atom(),
+        _ => None,
+    }
+}
+
+impl AnalysisPass for NftMintChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = NftMintChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn discarded_mint() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["nft_mint_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-non-fungible-token nft uint)
+(define-public (mint (id uint))
+    (begin
+        (is-ok (nft-mint? nft id tx-sender))
+        (ok true)
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, _)) => {
+                assert_eq!(output.len(), 3);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:5:16: {}: result of nft-mint? is discarded, but the mint fails if the token id already exists",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(output[1], "        (is-ok (nft-mint? nft id tx-sender))");
+                assert_eq!(output[2], "               ^~~~~~~~~~~~~~~~~~~~~~~~~~~~");
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn unused_let_binding() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["nft_mint_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-non-fungible-token nft uint)
+(define-public (mint (id uint))
+    (let ((minted (nft-mint? nft id tx-sender)))
+        (ok true)
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, _)) => {
+                assert_eq!(output.len(), 3);
+                assert_eq!(
+                    output[1],
+                    "    (let ((minted (nft-mint? nft id tx-sender)))"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn increment_after_mint() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["nft_mint_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-non-fungible-token nft uint)
+(define-data-var last-id uint u0)
+(define-public (mint)
+    (begin
+        (try! (nft-mint? nft (var-get last-id) tx-sender))
+        (var-set last-id (+ (var-get last-id) u1))
+        (ok true)
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, _)) => {
+                assert_eq!(output.len(), 6);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:6:15: {}: nft-mint? uses the current value of 'last-id' as the token id, but 'last-id' is only incremented after the mint",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(
+                    output[3],
+                    format!(
+                        "checker:7:9: {}: 'last-id' is incremented here",
+                        blue!("note")
+                    )
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn checked_mint() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["nft_mint_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-non-fungible-token nft uint)
+(define-data-var last-id uint u0)
+(define-public (mint)
+    (let ((id (+ (var-get last-id) u1)))
+        (try! (nft-mint? nft id tx-sender))
+        (var-set last-id id)
+        (ok id)
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((_, result)) => {
+                assert_eq!(result.diagnostics.len(), 0);
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,313 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{references_symbol, traverse, ASTVisitor, TypedVar};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::representations::SymbolicExpressionType::*;
+use crate::clarity::types::Value;
+use crate::clarity::ClarityName;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+// Detects calls to `pow` which overflow the 128-bit integer range. When both
+// operands are known, the result is computed and an error is reported if it
+// overflows. When the exponent is an argument of a public or read-only
+// function which has not been checked by a preceding `asserts!`, a warning
+// is reported instead.
+pub struct PowOverflowChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    constants: HashMap<&'a ClarityName, &'a Value>,
+    // Arguments of the current function, and whether they have been bounded
+    unchecked_args: HashMap<&'a ClarityName, bool>,
+}
+
+impl<'a> PowOverflowChecker<'a> {
+    fn new() -> PowOverflowChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            constants: HashMap::new(),
+            unchecked_args: HashMap::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        if self
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.level == Level::Error)
+        {
+            Err(self.diagnostics)
+        } else {
+            Ok(self.diagnostics)
+        }
+    }
+
+    fn value_of(&self, expr: &'a SymbolicExpression) -> Option<&'a Value> {
+        match &expr.expr {
+            LiteralValue(value) | AtomValue(value) => Some(value),
+            Atom(name) => self.constants.get(name).copied(),
+            _ => None,
+        }
+    }
+
+    fn set_arguments(&mut self, parameters: Option<Vec<TypedVar<'a>>>) {
+        self.unchecked_args = parameters
+            .unwrap_or_default()
+            .iter()
+            .map(|parameter| (parameter.name, true))
+            .collect();
+    }
+
+    fn check_pow(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        base: &'a SymbolicExpression,
+        exponent: &'a SymbolicExpression,
+    ) {
+        let base_value = self.value_of(base);
+        match (base_value, self.value_of(exponent)) {
+            (Some(base_value), Some(exponent_value)) => {
+                if let Some((base, exponent)) = overflowing_pow(base_value, exponent_value) {
+                    self.diagnostics.push(Diagnostic {
+                        level: Level::Error,
+                        code: Some("pow-overflow".to_string()),
+                        message: format!(
+                            "pow overflows: {}^{} is approximately {}, which exceeds the 128-bit range",
+                            base,
+                            exponent,
+                            magnitude(base, exponent)
+                        ),
+                        spans: vec![expr.span.clone()],
+                        suggestion: None,
+                    });
+                }
+            }
+            (base_value, None) => {
+                // Bases of 0, 1 and -1 can never overflow
+                let can_overflow = match base_value {
+                    Some(Value::Int(base)) => base.abs() > 1,
+                    Some(Value::UInt(base)) => *base > 1,
+                    _ => true,
+                };
+                let argument = match exponent.match_atom() {
+                    Some(name) if self.unchecked_args.get(name) == Some(&true) => name,
+                    _ => return,
+                };
+                if can_overflow {
+                    self.diagnostics.push(Diagnostic {
+                        level: Level::Warning,
+                        code: Some("unchecked-pow-exponent".to_string()),
+                        message: format!(
+                            "exponent of pow is the unchecked argument '{}', which may overflow the 128-bit range",
+                            argument
+                        ),
+                        spans: vec![expr.span.clone()],
+                        suggestion: Some(format!(
+                            "Bound '{}' with asserts! before calling pow",
+                            argument
+                        )),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Return the operands of `pow` if the result is outside the 128-bit range.
+// Negative exponents are a different runtime error, which is not reported.
+fn overflowing_pow(base: &Value, exponent: &Value) -> Option<(i128, u128)> {
+    let (overflows, base, exponent) = match (base, exponent) {
+        (Value::Int(base), Value::Int(exponent)) if *exponent >= 0 => {
+            let overflows = match u32::try_from(*exponent) {
+                Ok(exponent) => base.checked_pow(exponent).is_none(),
+                Err(_) => base.abs() > 1,
+            };
+            (overflows, *base, *exponent as u128)
+        }
+        (Value::UInt(base), Value::UInt(exponent)) => {
+            let overflows = match u32::try_from(*exponent) {
+                Ok(exponent) => base.checked_pow(exponent).is_none(),
+                Err(_) => *base > 1,
+            };
+            (overflows, *base as i128, *exponent)
+        }
+        _ => return None,
+    };
+    if overflows {
+        Some((base, exponent))
+    } else {
+        None
+    }
+}
+
+// Format an estimate of `base^exponent` in scientific notation, like "3.40e38".
+fn magnitude(base: i128, exponent: u128) -> String {
+    let log = exponent as f64 * (base.abs() as f64).log10();
+    let mantissa = 10f64.powf(log.fract());
+    let sign = if base < 0 && exponent % 2 == 1 {
+        "-"
+    } else {
+        ""
+    };
+    format!("{}{:.2}e{}", sign, mantissa, log.trunc())
+}
+
+impl<'a> ASTVisitor<'a> for PowOverflowChecker<'a> {
+    fn visit_define_constant(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        if let Some(value) = self.value_of(value) {
+            self.constants.insert(name, value);
+        }
+        true
+    }
+
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.unchecked_args.clear();
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.set_arguments(parameters);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.set_arguments(parameters);
+        self.traverse_expr(body)
+    }
+
+    fn visit_asserts(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        cond: &'a SymbolicExpression,
+        thrown: &'a SymbolicExpression,
+    ) -> bool {
+        for (name, unchecked) in self.unchecked_args.iter_mut() {
+            if references_symbol(cond, name) {
+                *unchecked = false;
+            }
+        }
+        true
+    }
+
+    fn visit_arithmetic(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: NativeFunctions,
+        operands: &'a [SymbolicExpression],
+    ) -> bool {
+        if let (NativeFunctions::Power, [base, exponent]) = (func, operands) {
+            self.check_pow(expr, base, exponent);
+        }
+        true
+    }
+}
+
+impl AnalysisPass for PowOverflowChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = PowOverflowChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn literal_overflow() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["pow_overflow_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-constant EXPONENT u128)
+(define-read-only (too-big)
+    (pow u2 EXPONENT)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Err(output) => {
+                assert_eq!(output.len(), 3);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:4:5: {}: pow overflows: 2^128 is approximately 3.40e38, which exceeds the 128-bit range",
+                        red!("error")
+                    )
+                );
+                assert_eq!(output[1], "    (pow u2 EXPONENT)");
+                assert_eq!(output[2], "    ^~~~~~~~~~~~~~~~~");
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn unchecked_exponent() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["pow_overflow_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-read-only (scale (e uint))
+    (pow u10 e)
+)
+(define-read-only (checked-scale (e uint))
+    (begin
+        (asserts! (< e u38) (err u1))
+        (ok (pow u10 e))
+    )
+)
+(define-read-only (small)
+    (- (pow 2 126) (pow -2 127))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:5: {}: exponent of pow is the unchecked argument 'e', which may overflow the 128-bit range",
+                        yellow!("warning")
+                    )
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
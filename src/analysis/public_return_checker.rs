@@ -0,0 +1,157 @@
+use crate::analysis::ast_types::AstTypes;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor, TypedVar};
+use crate::analysis::{pre_check_result, AnalysisResult};
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+
+// Reports public functions returning a value which is not a response, such
+// as `(some x)` or a plain value, at the value returned.
+pub struct PublicReturnChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    types: AstTypes<'a>,
+}
+
+impl<'a> PublicReturnChecker<'a> {
+    fn new(expressions: &'a [SymbolicExpression]) -> PublicReturnChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            types: AstTypes::new(expressions),
+        }
+    }
+
+    pub fn run(expressions: &'a [SymbolicExpression]) -> AnalysisResult {
+        let mut checker = PublicReturnChecker::new(expressions);
+        traverse(&mut checker, expressions);
+        pre_check_result(checker.diagnostics)
+    }
+}
+
+// The expressions whose value can be returned by an expression, following
+// `begin`, `let`, `if` and `match`.
+fn returned_expressions<'a>(
+    expr: &'a SymbolicExpression,
+    returned: &mut Vec<&'a SymbolicExpression>,
+) {
+    match match_native_call(expr) {
+        Some((NativeFunctions::Begin, [.., last]))
+        | Some((NativeFunctions::Let, [_, .., last])) => returned_expressions(last, returned),
+        Some((NativeFunctions::If, [_, then_branch, else_branch])) => {
+            returned_expressions(then_branch, returned);
+            returned_expressions(else_branch, returned);
+        }
+        // Matching an optional, then a response
+        Some((NativeFunctions::Match, [_, _, some_branch, none_branch])) => {
+            returned_expressions(some_branch, returned);
+            returned_expressions(none_branch, returned);
+        }
+        Some((NativeFunctions::Match, [_, _, ok_branch, _, err_branch])) => {
+            returned_expressions(ok_branch, returned);
+            returned_expressions(err_branch, returned);
+        }
+        _ => returned.push(expr),
+    }
+}
+
+impl<'a> ASTVisitor<'a> for PublicReturnChecker<'a> {
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        let mut returned = vec![];
+        returned_expressions(body, &mut returned);
+        for value in returned {
+            let value_type = match self.types.type_of(value) {
+                Some(value_type) if value_type != "response" => value_type,
+                _ => continue,
+            };
+            self.diagnostics.push(Diagnostic {
+                level: Level::Error,
+                code: Some("public-non-response".to_string()),
+                message: format!(
+                    "public function '{}' must return a response, but this value is of type '{}'",
+                    name, value_type
+                ),
+                spans: vec![value.span.clone()],
+                suggestion: Some(
+                    "Wrap the value in (ok ...) on success, or (err ...) on failure".to_string(),
+                ),
+            });
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn public_optional_return() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-map owners uint principal)
+(define-public (get-owner (id uint))
+    (let ((owner (map-get? owners id)))
+        (if (is-some owner) (ok owner) (some tx-sender))
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Err(output) => {
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:5:40: {}: public function 'get-owner' must return a response, but this value is of type 'optional'",
+                        red!("error")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "        (if (is-some owner) (ok owner) (some tx-sender))"
+                );
+                assert_eq!(
+                    output[2],
+                    "                                       ^~~~~~~~~~~~~~~~"
+                );
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn public_plain_return() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-data-var counter uint u0)
+(define-public (get-counter)
+    (begin
+        (var-get counter)
+    )
+)
+"
+        .to_string();
+        match session.interpret(snippet, None, false, None) {
+            Err((_, Some(diagnostic), _)) => {
+                assert_eq!(
+                    diagnostic.message,
+                    "public function 'get-counter' must return a response, but this value is of type 'uint'"
+                );
+                assert_eq!(
+                    diagnostic.suggestion,
+                    Some(
+                        "Wrap the value in (ok ...) on success, or (err ...) on failure"
+                            .to_string()
+                    )
+                );
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+}
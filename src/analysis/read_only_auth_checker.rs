@@ -0,0 +1,191 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor, TypedVar};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+use std::collections::HashSet;
+
+// Notes `asserts!` in read-only functions whose condition compares
+// `tx-sender`, `contract-caller` or a principal parameter with `is-eq`.
+// Read-only functions can be called off-chain with any sender and any
+// arguments, so such a check does not restrict who gets the result.
+pub struct ReadOnlyAuthChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    in_read_only: bool,
+    // The principal parameters of the current read-only function
+    principal_parameters: HashSet<&'a ClarityName>,
+}
+
+impl<'a> ReadOnlyAuthChecker<'a> {
+    fn new() -> ReadOnlyAuthChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            in_read_only: false,
+            principal_parameters: HashSet::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    // The first `is-eq` of a condition comparing a principal controlled by
+    // the caller, with a description of that principal.
+    fn find_auth_comparison(
+        &self,
+        expr: &'a SymbolicExpression,
+    ) -> Option<(&'a SymbolicExpression, String)> {
+        if let Some((NativeFunctions::Equals, operands)) = match_native_call(expr) {
+            for operand in operands {
+                match operand.match_atom() {
+                    Some(name)
+                        if name.as_str() == "tx-sender" || name.as_str() == "contract-caller" =>
+                    {
+                        return Some((expr, format!("'{}'", name)));
+                    }
+                    Some(name) if self.principal_parameters.contains(name) => {
+                        return Some((expr, format!("parameter '{}'", name)));
+                    }
+                    _ => (),
+                }
+            }
+        }
+        expr.match_list()?
+            .iter()
+            .find_map(|e| self.find_auth_comparison(e))
+    }
+}
+
+impl<'a> ASTVisitor<'a> for ReadOnlyAuthChecker<'a> {
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.principal_parameters = parameters
+            .unwrap_or_default()
+            .iter()
+            .filter(|parameter| {
+                parameter
+                    .type_expr
+                    .match_atom()
+                    .is_some_and(|type_name| type_name.as_str() == "principal")
+            })
+            .map(|parameter| parameter.name)
+            .collect();
+        self.in_read_only = true;
+        let result = self.traverse_expr(body);
+        self.in_read_only = false;
+        result
+    }
+
+    fn visit_asserts(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        cond: &'a SymbolicExpression,
+        thrown: &'a SymbolicExpression,
+    ) -> bool {
+        if !self.in_read_only {
+            return true;
+        }
+        if let Some((comparison, principal)) = self.find_auth_comparison(cond) {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("read-only-auth".to_string()),
+                message: format!(
+                    "read-only calls are not authenticated, so this check of {} does not secure anything",
+                    principal
+                ),
+                spans: vec![comparison.span.clone()],
+                suggestion: Some(
+                    "Check authorization in the public functions which change state".to_string(),
+                ),
+            });
+        }
+        true
+    }
+}
+
+impl AnalysisPass for ReadOnlyAuthChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = ReadOnlyAuthChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn read_only_auth() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["read_only_auth_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-constant owner tx-sender)
+(define-map secrets principal uint)
+(define-read-only (get-secret (who principal))
+    (begin
+        (asserts! (is-eq tx-sender owner) (err u1))
+        (ok (map-get? secrets who))
+    )
+)
+(define-read-only (get-own-secret (who principal))
+    (begin
+        (asserts! (or (is-eq who owner) (> u1 u0)) (err u1))
+        (ok (map-get? secrets who))
+    )
+)
+(define-public (set-secret (secret uint))
+    (begin
+        (asserts! (is-eq tx-sender owner) (err u1))
+        (ok (map-set secrets tx-sender secret))
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:6:19: {}: read-only calls are not authenticated, so this check of 'tx-sender' does not secure anything",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "        (asserts! (is-eq tx-sender owner) (err u1))"
+                );
+                assert_eq!(output[2], "                  ^~~~~~~~~~~~~~~~~~~~~~~");
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some(
+                        "Check authorization in the public functions which change state"
+                            .to_string()
+                    )
+                );
+                assert_eq!(
+                    result.diagnostics[1].message,
+                    "read-only calls are not authenticated, so this check of parameter 'who' does not secure anything"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,154 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor, TypedVar};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+use crate::clarity::analysis::read_only_checker::ReadOnlyChecker;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+use std::collections::HashMap;
+
+// Surfaces public functions which do not modify any state, and could be
+// declared with `define-read-only` instead. Calls to public functions of
+// other contracts are considered writes by the read-only checker.
+pub struct ReadOnlyCandidateChecker {
+    diagnostics: Vec<Diagnostic>,
+    read_only_functions: HashMap<ClarityName, bool>,
+}
+
+impl ReadOnlyCandidateChecker {
+    fn new(read_only_functions: HashMap<ClarityName, bool>) -> ReadOnlyCandidateChecker {
+        Self {
+            diagnostics: Vec::new(),
+            read_only_functions,
+        }
+    }
+
+    fn run(mut self, contract_analysis: &ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+}
+
+impl<'a> ASTVisitor<'a> for ReadOnlyCandidateChecker {
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        if self.read_only_functions.get(name) == Some(&true) {
+            // Point at the name of the function in its signature
+            let span = expr
+                .match_list()
+                .and_then(|list| list.get(1))
+                .and_then(|signature| signature.match_list())
+                .and_then(|signature| signature.first())
+                .map_or(expr.span.clone(), |name| name.span.clone());
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("read-only-candidate".to_string()),
+                message: format!(
+                    "public function '{}' does not modify any state and could be read-only",
+                    name
+                ),
+                spans: vec![span],
+                suggestion: Some("Declare it with define-read-only".to_string()),
+            });
+        }
+        true
+    }
+}
+
+impl AnalysisPass for ReadOnlyCandidateChecker {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        analysis_db.begin();
+        let result = ReadOnlyChecker::check_defined_functions(analysis_db, contract_analysis);
+        analysis_db.roll_back();
+        let read_only_functions = match result {
+            Ok(read_only_functions) => read_only_functions,
+            Err(error) => return Err(vec![error.diagnostic]),
+        };
+        let checker = ReadOnlyCandidateChecker::new(read_only_functions);
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn public_without_writes() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["read_only_candidate_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-data-var counter uint u0)
+(define-public (get-counter)
+    (ok (var-get counter))
+)
+(define-public (increment)
+    (ok (var-set counter (+ (var-get counter) u1)))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, _)) => {
+                assert_eq!(output.len(), 3);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:17: {}: public function 'get-counter' does not modify any state and could be read-only",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(output[1], "(define-public (get-counter)");
+                assert_eq!(output[2], "                ^~~~~~~~~~~");
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn external_public_call() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["read_only_candidate_checker".to_string()];
+        let mut session = Session::new(settings);
+        let callee = "
+(define-public (ping) (ok true))
+(define-read-only (peek) (ok true))
+"
+        .to_string();
+        session
+            .formatted_interpretation(callee, Some("callee".to_string()), false, None)
+            .unwrap();
+        let snippet = "
+(define-public (call-ping)
+    (contract-call? .callee ping)
+)
+(define-private (peek-callee)
+    (contract-call? .callee peek)
+)
+(define-public (call-peek)
+    (peek-callee)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, _)) => {
+                assert_eq!(output.len(), 3);
+                assert_eq!(output[1], "(define-public (call-peek)");
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
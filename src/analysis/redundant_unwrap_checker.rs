@@ -0,0 +1,215 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+use std::collections::HashMap;
+
+// Reports unwrapping a value which is known to be built by `some`, `ok` or
+// `err`, directly or through a constant, like `(unwrap-panic (some x))`. The
+// unwrap always succeeds, so it only obscures the wrapped value.
+pub struct RedundantUnwrapChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    // The constructor of each constant built by one
+    constants: HashMap<&'a ClarityName, NativeFunctions>,
+}
+
+impl<'a> RedundantUnwrapChecker<'a> {
+    fn new() -> RedundantUnwrapChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            constants: HashMap::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    fn constructor(&self, expr: &SymbolicExpression) -> Option<NativeFunctions> {
+        if let Some(name) = expr.match_atom() {
+            return self.constants.get(name).copied();
+        }
+        match match_native_call(expr)? {
+            (
+                constructor @ (NativeFunctions::ConsSome
+                | NativeFunctions::ConsOkay
+                | NativeFunctions::ConsError),
+                _,
+            ) => Some(constructor),
+            _ => None,
+        }
+    }
+
+    // `succeeds_on` lists the constructors for which `func` cannot fail.
+    fn check_unwrap(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: NativeFunctions,
+        input: &'a SymbolicExpression,
+        succeeds_on: &[NativeFunctions],
+    ) {
+        let constructor = match self.constructor(input) {
+            Some(constructor) if succeeds_on.contains(&constructor) => constructor,
+            _ => return,
+        };
+        self.diagnostics.push(Diagnostic {
+            level: Level::Note,
+            code: Some("redundant-unwrap".to_string()),
+            message: format!(
+                "'{}' is applied to a value built by '{}', so it always succeeds",
+                func.get_name(),
+                constructor.get_name()
+            ),
+            spans: vec![expr.span.clone()],
+            suggestion: Some(format!(
+                "Remove the '{}' and use the wrapped value directly",
+                func.get_name()
+            )),
+        });
+    }
+}
+
+impl<'a> ASTVisitor<'a> for RedundantUnwrapChecker<'a> {
+    fn visit_define_constant(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        if let Some(constructor) = self.constructor(value) {
+            self.constants.insert(name, constructor);
+        }
+        true
+    }
+
+    fn visit_unwrap(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        input: &'a SymbolicExpression,
+        throws: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_unwrap(
+            expr,
+            NativeFunctions::UnwrapRet,
+            input,
+            &[NativeFunctions::ConsSome, NativeFunctions::ConsOkay],
+        );
+        true
+    }
+
+    fn visit_unwrap_err(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        input: &'a SymbolicExpression,
+        throws: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_unwrap(
+            expr,
+            NativeFunctions::UnwrapErrRet,
+            input,
+            &[NativeFunctions::ConsError],
+        );
+        true
+    }
+
+    fn visit_unwrap_panic(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        input: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_unwrap(
+            expr,
+            NativeFunctions::Unwrap,
+            input,
+            &[NativeFunctions::ConsSome, NativeFunctions::ConsOkay],
+        );
+        true
+    }
+
+    fn visit_unwrap_err_panic(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        input: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_unwrap(
+            expr,
+            NativeFunctions::UnwrapErr,
+            input,
+            &[NativeFunctions::ConsError],
+        );
+        true
+    }
+}
+
+impl AnalysisPass for RedundantUnwrapChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = RedundantUnwrapChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn redundant_unwrap() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["redundant_unwrap_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-constant DEFAULT_OWNER (some tx-sender))
+(define-map owners uint principal)
+(define-read-only (get-owner (id uint))
+    (unwrap-panic (some (unwrap-panic (map-get? owners id))))
+)
+(define-read-only (get-default-owner)
+    (ok (unwrap! DEFAULT_OWNER (err u1)))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:5:5: {}: 'unwrap-panic' is applied to a value built by 'some', so it always succeeds",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "    (unwrap-panic (some (unwrap-panic (map-get? owners id))))"
+                );
+                assert_eq!(
+                    output[2],
+                    "    ^~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~"
+                );
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some(
+                        "Remove the 'unwrap-panic' and use the wrapped value directly".to_string()
+                    )
+                );
+                assert_eq!(
+                    result.diagnostics[1].message,
+                    "'unwrap!' is applied to a value built by 'some', so it always succeeds"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
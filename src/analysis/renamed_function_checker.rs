@@ -0,0 +1,122 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::versions::replacing;
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::version::ClarityVersion;
+
+// Warns about the calls of functions under a name deprecated in the
+// configured Clarity version, suggesting the name replacing it. The new
+// names are reported by `VersionSyntaxChecker` in the older versions.
+pub struct RenamedFunctionChecker {
+    diagnostics: Vec<Diagnostic>,
+    version: ClarityVersion,
+}
+
+impl RenamedFunctionChecker {
+    fn new(settings: &Settings) -> RenamedFunctionChecker {
+        Self {
+            diagnostics: Vec::new(),
+            version: settings.clarity_version,
+        }
+    }
+
+    fn run(mut self, contract_analysis: &ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    fn check_name(&mut self, expr: &SymbolicExpression) {
+        let function = match expr.match_list().and_then(|list| list.first()) {
+            Some(function) => function,
+            None => return,
+        };
+        let name = match function.match_atom() {
+            Some(name) => name,
+            None => return,
+        };
+        if let Some(replacing) = replacing(name).filter(|new| self.version >= new.version) {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                code: Some("renamed-function".to_string()),
+                message: format!(
+                    "'{}' is deprecated from {}, in favor of '{}'",
+                    name, replacing.version, replacing.name
+                ),
+                spans: vec![function.span.clone()],
+                suggestion: Some(format!("Use {}", replacing.name)),
+            });
+        }
+    }
+}
+
+impl<'a> ASTVisitor<'a> for RenamedFunctionChecker {
+    fn visit_list(&mut self, expr: &'a SymbolicExpression, list: &'a [SymbolicExpression]) -> bool {
+        self.check_name(expr);
+        true
+    }
+}
+
+impl AnalysisPass for RenamedFunctionChecker {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = RenamedFunctionChecker::new(settings);
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clarity::version::ClarityVersion;
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn deprecated_in_clarity2() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["renamed_function_checker".to_string()];
+        settings.analysis_settings.clarity_version = ClarityVersion::Clarity2;
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-read-only (second (items (list 4 uint)))
+    (element-at items u1)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:6: {}: 'element-at' is deprecated from Clarity 2, in favor of 'element-at?'",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(output[1], "    (element-at items u1)");
+                assert_eq!(output[2], "     ^~~~~~~~~~");
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some("Use element-at?".to_string())
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["renamed_function_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "(define-read-only (position (items (list 4 uint))) (index-of items u1))";
+        let result = session
+            .interpret(snippet.to_string(), None, false, None)
+            .unwrap();
+        assert!(result.diagnostics.is_empty());
+    }
+}
@@ -0,0 +1,177 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::{Span, SymbolicExpression};
+use crate::clarity::types::{SequenceData, Value};
+use crate::clarity::ClarityName;
+
+// Surfaces numeric and string literals repeated throughout a contract, which
+// would be easier to maintain as a `define-constant`.
+pub struct RepeatedLiteralChecker {
+    threshold: usize,
+    // Each literal with the locations where it appears, in order of first use
+    literals: Vec<(String, Vec<Span>)>,
+}
+
+impl RepeatedLiteralChecker {
+    fn new(settings: &Settings) -> RepeatedLiteralChecker {
+        Self {
+            threshold: settings.repeated_literal_threshold,
+            literals: Vec::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        let threshold = self.threshold;
+        Ok(self
+            .literals
+            .into_iter()
+            .filter(|(_, spans)| spans.len() >= threshold)
+            .map(|(literal, spans)| {
+                let locations: Vec<String> = spans
+                    .iter()
+                    .map(|span| format!("{}:{}", span.start_line, span.start_column))
+                    .collect();
+                Diagnostic {
+                    level: Level::Note,
+                    code: Some("repeated-literal".to_string()),
+                    message: format!(
+                        "literal {} is repeated {} times, at {}",
+                        literal,
+                        spans.len(),
+                        locations.join(", ")
+                    ),
+                    spans,
+                    suggestion: Some("Extract it to a define-constant".to_string()),
+                }
+            })
+            .collect())
+    }
+
+    fn count(&mut self, expr: &SymbolicExpression, value: &Value) {
+        if is_trivial(value) {
+            return;
+        }
+        let literal = value.to_string();
+        match self.literals.iter_mut().find(|(l, _)| *l == literal) {
+            Some((_, spans)) => spans.push(expr.span.clone()),
+            None => self.literals.push((literal, vec![expr.span.clone()])),
+        }
+    }
+}
+
+// Only numbers and strings are counted, except for the most common values.
+fn is_trivial(value: &Value) -> bool {
+    match value {
+        Value::Int(i) => *i == 0 || *i == 1,
+        Value::UInt(u) => *u == 0 || *u == 1,
+        Value::Sequence(SequenceData::String(_)) | Value::Sequence(SequenceData::Buffer(_)) => {
+            value.size() == 0
+        }
+        _ => true,
+    }
+}
+
+impl<'a> ASTVisitor<'a> for RepeatedLiteralChecker {
+    // The value of a constant is already named.
+    fn traverse_define_constant(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        true
+    }
+
+    fn visit_atom_value(&mut self, expr: &'a SymbolicExpression, value: &Value) -> bool {
+        self.count(expr, value);
+        true
+    }
+
+    fn visit_literal_value(&mut self, expr: &'a SymbolicExpression, value: &Value) -> bool {
+        self.count(expr, value);
+        true
+    }
+}
+
+impl AnalysisPass for RepeatedLiteralChecker {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = RepeatedLiteralChecker::new(settings);
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn repeated_literal() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["repeated_literal_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-constant FEE u30)
+(define-read-only (fee (amount uint))
+    (/ (* amount u30) u10000)
+)
+(define-read-only (net (amount uint))
+    (- amount (/ (* amount u30) u10000))
+)
+(define-read-only (gross (amount uint))
+    (+ amount (/ (* amount u30) u10000) u1 u1 u1)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:4:18: {}: literal u30 is repeated 3 times, at 4:18, 7:28, 10:28",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(output[1], "    (/ (* amount u30) u10000)");
+                assert_eq!(output[2], "                 ^~");
+                assert_eq!(
+                    result.diagnostics[1].message,
+                    "literal u10000 is repeated 3 times, at 4:23, 7:33, 10:33"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn threshold() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["repeated_literal_checker".to_string()];
+        settings.analysis_settings.repeated_literal_threshold = 4;
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-read-only (greet (name (string-ascii 10)))
+    (if (is-eq name \"bob\") (concat \"hi \" name) (concat \"hi \" \"bob\"))
+)
+(define-read-only (farewell) \"bob\")
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((_, result)) => {
+                assert_eq!(result.diagnostics.len(), 0);
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
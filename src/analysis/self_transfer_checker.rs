@@ -0,0 +1,167 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::representations::SymbolicExpressionType::{Atom, AtomValue, LiteralValue};
+use crate::clarity::ClarityName;
+
+// Notes the `stx-transfer?`, `ft-transfer?` and `nft-transfer?` whose sender
+// and recipient are the same variable or literal, which always fail with
+// `(err u2)`, and often pass the wrong variable. The comparison is syntactic,
+// so computed principals are not checked.
+pub struct SelfTransferChecker {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl SelfTransferChecker {
+    fn new() -> SelfTransferChecker {
+        Self {
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    fn check_transfer(
+        &mut self,
+        func: &str,
+        sender: &SymbolicExpression,
+        recipient: &SymbolicExpression,
+    ) {
+        let same = match (&sender.expr, &recipient.expr) {
+            (Atom(sender), Atom(recipient)) => sender == recipient,
+            (
+                AtomValue(sender) | LiteralValue(sender),
+                AtomValue(recipient) | LiteralValue(recipient),
+            ) => sender == recipient,
+            _ => false,
+        };
+        if same {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("self-transfer".to_string()),
+                message: format!(
+                    "{} from '{}' to itself, which always fails with (err u2)",
+                    func, sender
+                ),
+                spans: vec![recipient.span.clone()],
+                suggestion: Some("Check the recipient of the transfer".to_string()),
+            });
+        }
+    }
+}
+
+impl<'a> ASTVisitor<'a> for SelfTransferChecker {
+    fn visit_stx_transfer(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        amount: &'a SymbolicExpression,
+        sender: &'a SymbolicExpression,
+        recipient: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_transfer("stx-transfer?", sender, recipient);
+        true
+    }
+
+    fn visit_ft_transfer(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        token: &'a ClarityName,
+        amount: &'a SymbolicExpression,
+        sender: &'a SymbolicExpression,
+        recipient: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_transfer("ft-transfer?", sender, recipient);
+        true
+    }
+
+    fn visit_nft_transfer(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        token: &'a ClarityName,
+        identifier: &'a SymbolicExpression,
+        sender: &'a SymbolicExpression,
+        recipient: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_transfer("nft-transfer?", sender, recipient);
+        true
+    }
+}
+
+impl AnalysisPass for SelfTransferChecker {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = SelfTransferChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn transfer_to_sender() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["self_transfer_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-fungible-token gold)
+(define-non-fungible-token badge uint)
+(define-public (refund (amount uint) (owner principal))
+    (stx-transfer? amount tx-sender tx-sender)
+)
+(define-public (pay (amount uint))
+    (ft-transfer? gold amount 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM)
+)
+(define-public (give (id uint) (owner principal))
+    (nft-transfer? badge id owner owner)
+)
+(define-public (send (amount uint) (recipient principal))
+    (begin
+        (try! (stx-transfer? amount tx-sender recipient))
+        (stx-transfer? amount tx-sender (as-contract tx-sender))
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 3);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:5:37: {}: stx-transfer? from 'tx-sender' to itself, which always fails with (err u2)",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[3],
+                    format!(
+                        "checker:8:74: {}: ft-transfer? from 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM' to itself, which always fails with (err u2)",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[6],
+                    format!(
+                        "checker:11:35: {}: nft-transfer? from 'owner' to itself, which always fails with (err u2)",
+                        blue!("note")
+                    )
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
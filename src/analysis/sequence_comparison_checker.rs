@@ -0,0 +1,180 @@
+use crate::analysis::ast_types::AstTypes;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{pre_check_result, AnalysisResult, Settings};
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::version::ClarityVersion;
+
+// Sequence types which can be ordered with `<`, `<=`, `>` and `>=`.
+fn ordered_sequences(version: ClarityVersion) -> &'static [&'static str] {
+    match version {
+        ClarityVersion::Clarity1 => &[],
+        ClarityVersion::Clarity2 => &["buff", "string-ascii", "string-utf8"],
+    }
+}
+
+// Reports ordering comparisons applied to sequences which cannot be ordered
+// in the configured Clarity version.
+pub struct SequenceComparisonChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    version: ClarityVersion,
+    types: AstTypes<'a>,
+}
+
+impl<'a> SequenceComparisonChecker<'a> {
+    fn new(
+        expressions: &'a [SymbolicExpression],
+        settings: &Settings,
+    ) -> SequenceComparisonChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            version: settings.clarity_version,
+            types: AstTypes::new(expressions),
+        }
+    }
+
+    pub fn run(expressions: &'a [SymbolicExpression], settings: &Settings) -> AnalysisResult {
+        let mut checker = SequenceComparisonChecker::new(expressions, settings);
+        traverse(&mut checker, expressions);
+        pre_check_result(checker.diagnostics)
+    }
+
+    // Name the sequence type of an expression, if it can be known from the AST.
+    fn sequence_type(&self, expr: &SymbolicExpression) -> Option<&'static str> {
+        match self.types.type_of(expr)? {
+            sequence @ ("buff" | "string-ascii" | "string-utf8" | "list") => Some(sequence),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> ASTVisitor<'a> for SequenceComparisonChecker<'a> {
+    fn visit_comparison(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        func: NativeFunctions,
+        operands: &'a [SymbolicExpression],
+    ) -> bool {
+        if func == NativeFunctions::Equals {
+            return true;
+        }
+        let ordered = ordered_sequences(self.version);
+        for operand in operands {
+            match self.sequence_type(operand) {
+                Some(sequence) if !ordered.contains(&sequence) => {
+                    let message = if ordered.is_empty() {
+                        format!(
+                            "'{}' cannot compare values of type '{}' in {}, only int and uint can be ordered",
+                            func.get_name(),
+                            sequence,
+                            self.version
+                        )
+                    } else {
+                        format!(
+                            "'{}' cannot compare values of type '{}' in {}, only int, uint, {} can be ordered",
+                            func.get_name(),
+                            sequence,
+                            self.version,
+                            ordered.join(", ")
+                        )
+                    };
+                    self.diagnostics.push(Diagnostic {
+                        level: Level::Error,
+                        code: Some("unordered-comparison".to_string()),
+                        message,
+                        spans: vec![operand.span.clone()],
+                        suggestion: match sequence {
+                            "list" => None,
+                            _ => Some("Use is-eq to check for equality".to_string()),
+                        },
+                    });
+                    return false;
+                }
+                _ => {}
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clarity::version::ClarityVersion;
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn compare_strings() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-read-only (before (name (string-ascii 10)))
+    (< name \"m\")
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Err(output) => {
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:8: {}: '<' cannot compare values of type 'string-ascii' in Clarity 1, only int and uint can be ordered",
+                        red!("error")
+                    )
+                );
+                assert_eq!(output[1], "    (< name \"m\")");
+                assert_eq!(output[2], "       ^~~~");
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn compare_buffers_clarity2() {
+        let mut settings = SessionSettings::default();
+        settings.analysis_settings.clarity_version = ClarityVersion::Clarity2;
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-data-var last (buff 4) 0x00)
+(define-read-only (after)
+    (>= 0x0102 (var-get last))
+)
+(define-read-only (is-first)
+    (is-eq (var-get last) 0x00)
+)
+"
+        .to_string();
+        match session.interpret(snippet, None, false, None) {
+            // Clarity 2 orders buffers, but the interpreter implements Clarity 1
+            Err((_, Some(diagnostic), _)) => {
+                assert!(!diagnostic.message.contains("cannot compare"));
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+
+        let mut settings = SessionSettings::default();
+        settings.analysis_settings.clarity_version = ClarityVersion::Clarity2;
+        let mut session = Session::new(settings);
+        let snippet = "(define-read-only (shorter) (< (list u1) (list u1 u2)))";
+        match session.interpret(snippet.to_string(), None, false, None) {
+            Err((_, Some(diagnostic), _)) => {
+                assert_eq!(
+                    diagnostic.message,
+                    "'<' cannot compare values of type 'list' in Clarity 2, only int, uint, buff, string-ascii, string-utf8 can be ordered"
+                );
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn scoped_arguments() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-private (f (a (buff 2))) a)
+(let ((a 1)) (< a 2))
+"
+        .to_string();
+        assert!(session.interpret(snippet, None, false, None).is_ok());
+    }
+}
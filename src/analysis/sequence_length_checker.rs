@@ -0,0 +1,190 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{sequence_length, traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+use crate::clarity::analysis::type_checker::contexts::TypeMap;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::types::{SequenceSubtype, StringSubtype, TypeSignature, Value};
+
+// Checks literal lengths and indexes against the maximum length of the
+// sequence they apply to, from its type. `as-max-len?` to a length the
+// sequence cannot exceed always returns `some`, and to a length below that
+// of a literal sequence always returns `none`. `element-at` an index past
+// the maximum length always returns `none`.
+pub struct SequenceLengthChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    type_map: Option<&'a TypeMap>,
+}
+
+impl<'a> SequenceLengthChecker<'a> {
+    fn new() -> SequenceLengthChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            type_map: None,
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        self.type_map = contract_analysis.type_map.as_ref();
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    fn max_length(&self, sequence: &SymbolicExpression) -> Option<u32> {
+        match self.type_map?.get_type(sequence)? {
+            TypeSignature::SequenceType(SequenceSubtype::ListType(list)) => {
+                Some(list.get_max_len())
+            }
+            TypeSignature::SequenceType(SequenceSubtype::BufferType(length))
+            | TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(
+                length,
+            ))) => Some(length.into()),
+            TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(
+                length,
+            ))) => Some(length.into()),
+            _ => None,
+        }
+    }
+}
+
+impl<'a> ASTVisitor<'a> for SequenceLengthChecker<'a> {
+    fn visit_as_max_len(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        sequence: &'a SymbolicExpression,
+        length: u128,
+    ) -> bool {
+        if let Some(literal_length) = sequence_length(sequence) {
+            if length < literal_length as u128 {
+                self.diagnostics.push(Diagnostic {
+                    level: Level::Warning,
+                    code: Some("as-max-len-always-none".to_string()),
+                    message: format!(
+                        "as-max-len? to u{} always returns none, as the sequence has a length of {}",
+                        length, literal_length
+                    ),
+                    spans: vec![expr.span.clone()],
+                    suggestion: Some(format!(
+                        "Use a length of at least u{}",
+                        literal_length
+                    )),
+                });
+                return true;
+            }
+        }
+        let max_length = match self.max_length(sequence) {
+            Some(max_length) => max_length,
+            None => return true,
+        };
+        if length >= max_length as u128 {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                code: Some("as-max-len-always-some".to_string()),
+                message: format!(
+                    "as-max-len? to u{} always returns some, as the sequence has a maximum length of {}",
+                    length, max_length
+                ),
+                spans: vec![expr.span.clone()],
+                suggestion: Some(format!(
+                    "Use the sequence directly, or a length below u{}",
+                    max_length
+                )),
+            });
+        }
+        true
+    }
+
+    fn visit_element_at(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        sequence: &'a SymbolicExpression,
+        index: &'a SymbolicExpression,
+    ) -> bool {
+        let index = match index.match_literal_value() {
+            Some(Value::UInt(index)) => *index,
+            _ => return true,
+        };
+        let max_length = match self.max_length(sequence) {
+            Some(max_length) => max_length,
+            None => return true,
+        };
+        if index >= max_length as u128 {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                code: Some("element-at-out-of-bounds".to_string()),
+                message: format!(
+                    "element-at index u{} always returns none, as the sequence has a maximum length of {}",
+                    index, max_length
+                ),
+                spans: vec![expr.span.clone()],
+                suggestion: None,
+            });
+        }
+        true
+    }
+}
+
+impl AnalysisPass for SequenceLengthChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = SequenceLengthChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn sequence_length() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["sequence_length_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-private (truncate (data (buff 32)) (names (list 5 (string-ascii 10))))
+    (begin
+        (as-max-len? data u64)
+        (as-max-len? names u3)
+        (as-max-len? 0x010203 u2)
+        (element-at names u5)
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 3);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:4:9: {}: as-max-len? to u64 always returns some, as the sequence has a maximum length of 32",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(output[1], "        (as-max-len? data u64)");
+                assert_eq!(output[2], "        ^~~~~~~~~~~~~~~~~~~~~~");
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some("Use the sequence directly, or a length below u32".to_string())
+                );
+                assert_eq!(
+                    result.diagnostics[1].message,
+                    "as-max-len? to u2 always returns none, as the sequence has a length of 3"
+                );
+                assert_eq!(
+                    result.diagnostics[2].message,
+                    "element-at index u5 always returns none, as the sequence has a maximum length of 5"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
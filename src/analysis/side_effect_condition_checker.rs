@@ -0,0 +1,191 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+use crate::clarity::analysis::read_only_checker::ReadOnlyChecker;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+
+// Warns about the conditions of `asserts!` and `if`, and the operands of
+// `and` and `or`, which modify the state or transfer assets: the change is
+// hidden in a predicate, and `and` and `or` only perform it when the operands
+// before do not short-circuit. Writes are detected by the read-only checker,
+// so a call to a public function of another contract is one.
+pub struct SideEffectConditionChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    conditions: Vec<(&'a SymbolicExpression, &'static str)>,
+}
+
+impl<'a> SideEffectConditionChecker<'a> {
+    fn new() -> SideEffectConditionChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            conditions: Vec::new(),
+        }
+    }
+
+    fn run(
+        mut self,
+        contract_analysis: &'a ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+    ) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        let targets: Vec<&SymbolicExpression> =
+            self.conditions.iter().map(|(cond, _)| *cond).collect();
+        analysis_db.begin();
+        let result = ReadOnlyChecker::check_expressions(analysis_db, contract_analysis, &targets);
+        analysis_db.roll_back();
+        let read_only = result.map_err(|error| vec![error.diagnostic])?;
+        let writes: Vec<(&SymbolicExpression, &str)> = self
+            .conditions
+            .iter()
+            .zip(read_only)
+            .filter(|(_, read_only)| !read_only)
+            .map(|(condition, _)| *condition)
+            .collect();
+        for (cond, construct) in writes.iter() {
+            // Only the outermost condition writing is reported
+            if writes
+                .iter()
+                .any(|(outer, _)| outer.id != cond.id && contains(outer, cond))
+            {
+                continue;
+            }
+            let message = match *construct {
+                "and" | "or" => format!(
+                    "this operand of {} modifies the state, which is hidden in a condition and only done if the operands before do not short-circuit",
+                    construct
+                ),
+                _ => format!(
+                    "the condition of this {} modifies the state, which is hidden in a predicate",
+                    construct
+                ),
+            };
+            self.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                code: Some("side-effect-condition".to_string()),
+                message,
+                spans: vec![cond.span.clone()],
+                suggestion: Some(
+                    "Make the change before the condition, and check its result".to_string(),
+                ),
+            });
+        }
+        Ok(self.diagnostics)
+    }
+}
+
+fn contains(outer: &SymbolicExpression, inner: &SymbolicExpression) -> bool {
+    outer.match_list().is_some_and(|list| {
+        list.iter()
+            .any(|element| element.id == inner.id || contains(element, inner))
+    })
+}
+
+impl<'a> ASTVisitor<'a> for SideEffectConditionChecker<'a> {
+    fn visit_asserts(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        cond: &'a SymbolicExpression,
+        thrown: &'a SymbolicExpression,
+    ) -> bool {
+        self.conditions.push((cond, "asserts!"));
+        true
+    }
+
+    fn visit_if(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        cond: &'a SymbolicExpression,
+        then_expr: &'a SymbolicExpression,
+        else_expr: &'a SymbolicExpression,
+    ) -> bool {
+        self.conditions.push((cond, "if"));
+        true
+    }
+
+    fn visit_lazy_logical(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        function: NativeFunctions,
+        operands: &'a [SymbolicExpression],
+    ) -> bool {
+        let construct = match function {
+            NativeFunctions::And => "and",
+            _ => "or",
+        };
+        for operand in operands {
+            self.conditions.push((operand, construct));
+        }
+        true
+    }
+}
+
+impl AnalysisPass for SideEffectConditionChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = SideEffectConditionChecker::new();
+        checker.run(contract_analysis, analysis_db)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn side_effect_condition() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["side_effect_condition_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-data-var flag bool false)
+(define-data-var count uint u0)
+(define-private (bump)
+    (var-set count (+ (var-get count) u1))
+)
+(define-public (toggle)
+    (begin
+        (asserts! (var-set flag true) (err u1))
+        (ok true)
+    )
+)
+(define-public (maybe-bump (enabled bool))
+    (ok (and enabled (bump)))
+)
+(define-read-only (is-enabled)
+    (if (var-get flag) (var-get count) u0)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:9:19: {}: the condition of this asserts! modifies the state, which is hidden in a predicate",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(output[1], "        (asserts! (var-set flag true) (err u1))");
+                assert_eq!(output[2], "                  ^~~~~~~~~~~~~~~~~~~");
+                assert_eq!(
+                    output[3],
+                    format!(
+                        "checker:14:22: {}: this operand of and modifies the state, which is hidden in a condition and only done if the operands before do not short-circuit",
+                        yellow!("warning")
+                    )
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,181 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::types::{SequenceSubtype, TypeSignature};
+use crate::clarity::ClarityName;
+
+// Notes the values of maps and data-vars whose type contains a response.
+// Storing one is allowed, but responses are meant to be returned by public
+// functions, so it is usually a value stored before being unwrapped.
+pub struct StoredResponseChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    contract_analysis: Option<&'a ContractAnalysis>,
+}
+
+impl<'a> StoredResponseChecker<'a> {
+    fn new() -> StoredResponseChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            contract_analysis: None,
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        self.contract_analysis = Some(contract_analysis);
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    fn check_type(
+        &mut self,
+        described: String,
+        type_signature: &TypeSignature,
+        type_expr: &SymbolicExpression,
+    ) {
+        if !contains_response(type_signature) {
+            return;
+        }
+        // Point at the response within the type, when it is written
+        let span = find_response(type_expr).map_or(&type_expr.span, |response| &response.span);
+        self.diagnostics.push(Diagnostic {
+            level: Level::Note,
+            code: Some("stored-response".to_string()),
+            message: format!("{} contains a response", described),
+            spans: vec![span.clone()],
+            suggestion: Some("Store the data of the response, unwrapped".to_string()),
+        });
+    }
+}
+
+// Whether a response is nested in a type, through optionals, lists and
+// tuples.
+fn contains_response(type_signature: &TypeSignature) -> bool {
+    match type_signature {
+        TypeSignature::ResponseType(_) => true,
+        TypeSignature::OptionalType(inner) => contains_response(inner),
+        TypeSignature::SequenceType(SequenceSubtype::ListType(list)) => {
+            contains_response(list.get_list_item_type())
+        }
+        TypeSignature::TupleType(tuple) => tuple.get_type_map().values().any(contains_response),
+        _ => false,
+    }
+}
+
+fn find_response(type_expr: &SymbolicExpression) -> Option<&SymbolicExpression> {
+    let list = type_expr.match_list()?;
+    if list
+        .first()
+        .and_then(|head| head.match_atom())
+        .is_some_and(|head| head.as_str() == "response")
+    {
+        return Some(type_expr);
+    }
+    list.iter().find_map(find_response)
+}
+
+impl<'a> ASTVisitor<'a> for StoredResponseChecker<'a> {
+    fn visit_define_map(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        key_type: &'a SymbolicExpression,
+        value_type: &'a SymbolicExpression,
+    ) -> bool {
+        if let Some((_, map_value_type)) = self
+            .contract_analysis
+            .and_then(|analysis| analysis.map_types.get(name))
+        {
+            self.check_type(
+                format!("the type of the values of map '{}'", name),
+                map_value_type,
+                value_type,
+            );
+        }
+        true
+    }
+
+    fn visit_define_data_var(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        data_type: &'a SymbolicExpression,
+        initial: &'a SymbolicExpression,
+    ) -> bool {
+        if let Some(var_type) = self
+            .contract_analysis
+            .and_then(|analysis| analysis.get_persisted_variable_type(name))
+        {
+            self.check_type(
+                format!("the type of data-var '{}'", name),
+                var_type,
+                data_type,
+            );
+        }
+        true
+    }
+}
+
+impl AnalysisPass for StoredResponseChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = StoredResponseChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn stored_responses() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["stored_response_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-map results uint { value: (response uint uint), height: uint })
+(define-data-var last (optional (response bool uint)) none)
+(define-data-var count uint u0)
+(define-map owners uint (optional principal))
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:2:35: {}: the type of the values of map 'results' contains a response",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "(define-map results uint { value: (response uint uint), height: uint })"
+                );
+                assert_eq!(
+                    output[2],
+                    "                                  ^~~~~~~~~~~~~~~~~~~~"
+                );
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some("Store the data of the response, unwrapped".to_string())
+                );
+                assert_eq!(
+                    result.diagnostics[1].message,
+                    "the type of data-var 'last' contains a response"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
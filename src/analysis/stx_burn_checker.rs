@@ -0,0 +1,208 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{
+    collect_let_bindings, match_native_call, propagate_taint, traverse, ASTVisitor, TypedVar,
+};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+use std::collections::HashMap;
+
+// Warns about every `stx-burn?`, which destroys STX for good. When the amount
+// derives from balances stored by the contract in a map or data-var, or from
+// the STX balance of an account, directly or through `let` bindings, the
+// burn is likely to destroy funds deposited by users.
+pub struct StxBurnChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    // The `let` bindings of the current function derived from a balance,
+    // with a description of that balance
+    balances: HashMap<&'a ClarityName, String>,
+}
+
+impl<'a> StxBurnChecker<'a> {
+    fn new() -> StxBurnChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            balances: HashMap::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    fn traverse_function(&mut self, body: &'a SymbolicExpression) -> bool {
+        let mut bindings = vec![];
+        collect_let_bindings(body, &mut bindings);
+        propagate_taint(&bindings, &mut self.balances, |balances, name, value| {
+            if balances.contains_key(name) {
+                return false;
+            }
+            match balance_in(balances, value) {
+                Some(balance) => balances.insert(name, balance).is_none(),
+                None => false,
+            }
+        });
+        let result = self.traverse_expr(body);
+        self.balances.clear();
+        result
+    }
+}
+
+// The first balance an expression derives from, given the bindings derived
+// from a balance.
+fn balance_in(
+    balances: &HashMap<&ClarityName, String>,
+    expr: &SymbolicExpression,
+) -> Option<String> {
+    if let Some(name) = expr.match_atom() {
+        return balances.get(name).cloned();
+    }
+    match match_native_call(expr) {
+        Some((NativeFunctions::FetchEntry, [map, ..])) => {
+            return Some(format!("map '{}'", map.match_atom()?));
+        }
+        Some((NativeFunctions::FetchVar, [var])) => {
+            return Some(format!("data-var '{}'", var.match_atom()?));
+        }
+        Some((NativeFunctions::GetStxBalance, _)) => {
+            return Some("stx-get-balance".to_string());
+        }
+        _ => (),
+    }
+    expr.match_list()?
+        .iter()
+        .find_map(|e| balance_in(balances, e))
+}
+
+impl<'a> ASTVisitor<'a> for StxBurnChecker<'a> {
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.traverse_function(body)
+    }
+
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.traverse_function(body)
+    }
+
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.traverse_function(body)
+    }
+
+    fn visit_stx_burn(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        amount: &'a SymbolicExpression,
+        sender: &'a SymbolicExpression,
+    ) -> bool {
+        let message = match balance_in(&self.balances, amount) {
+            Some(balance) => format!(
+                "stx-burn? destroys an amount read from {}, which could permanently lock user funds",
+                balance
+            ),
+            None => "stx-burn? permanently destroys STX".to_string(),
+        };
+        self.diagnostics.push(Diagnostic {
+            level: Level::Warning,
+            code: Some("stx-burn".to_string()),
+            message,
+            spans: vec![expr.span.clone()],
+            suggestion: Some(
+                "Confirm that the burn is intentional, or use stx-transfer? to move the funds"
+                    .to_string(),
+            ),
+        });
+        true
+    }
+}
+
+impl AnalysisPass for StxBurnChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = StxBurnChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn stx_burn() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["stx_burn_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-map deposits principal uint)
+(define-public (close (account principal))
+    (let ((deposit (default-to u0 (map-get? deposits account))))
+        (map-delete deposits account)
+        (as-contract (stx-burn? deposit tx-sender))
+    )
+)
+(define-public (burn-fee)
+    (stx-burn? u100 tx-sender)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:6:22: {}: stx-burn? destroys an amount read from map 'deposits', which could permanently lock user funds",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "        (as-contract (stx-burn? deposit tx-sender))"
+                );
+                assert_eq!(
+                    output[2],
+                    "                     ^~~~~~~~~~~~~~~~~~~~~~~~~~~~~"
+                );
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some(
+                        "Confirm that the burn is intentional, or use stx-transfer? to move the funds"
+                            .to_string()
+                    )
+                );
+                assert_eq!(
+                    result.diagnostics[1].message,
+                    "stx-burn? permanently destroys STX"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,349 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor, TypedVar};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::types::TraitIdentifier;
+use crate::clarity::util::closest_match;
+use crate::clarity::ClarityName;
+
+struct Alias<'a> {
+    expr: &'a SymbolicExpression,
+    name: &'a ClarityName,
+    trait_name: ClarityName,
+}
+
+// Checks the `use-trait` aliases against the other top-level names. An alias
+// lives in a namespace of its own, so naming it like a function, a constant or
+// the trait imported by another `use-trait` is allowed, but reads as a
+// reference to the other definition, and a function using it in its signature
+// is sorted as depending on the definition. An alias which is only close to
+// another name is reported as a note.
+pub struct TraitAliasChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    aliases: Vec<Alias<'a>>,
+    definitions: Vec<(&'a SymbolicExpression, &'a ClarityName, &'static str)>,
+}
+
+impl<'a> TraitAliasChecker<'a> {
+    fn new() -> TraitAliasChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            aliases: Vec::new(),
+            definitions: Vec::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        for alias in self.aliases.iter() {
+            let collision = self
+                .definitions
+                .iter()
+                .find(|(_, name, _)| *name == alias.name)
+                .map(|(expr, _, kind)| (*expr, format!("the {} '{}'", kind, alias.name)))
+                .or_else(|| {
+                    self.aliases
+                        .iter()
+                        .find(|other| other.name != alias.name && other.trait_name == *alias.name)
+                        .map(|other| {
+                            (
+                                other.expr,
+                                format!("the trait imported as '{}'", other.name),
+                            )
+                        })
+                });
+            if let Some((expr, described)) = collision {
+                self.diagnostics.push(Diagnostic {
+                    level: Level::Error,
+                    code: Some("trait-alias-collision".to_string()),
+                    message: format!(
+                        "trait alias '{}' has the same name as {}",
+                        alias.name, described
+                    ),
+                    spans: vec![alias.expr.span.clone(), expr.span.clone()],
+                    suggestion: Some("Rename the trait alias".to_string()),
+                });
+                continue;
+            }
+
+            let candidates = self
+                .definitions
+                .iter()
+                .map(|(_, name, _)| name.as_str())
+                .chain(
+                    self.aliases
+                        .iter()
+                        .filter(|other| other.name != alias.name)
+                        .map(|other| other.name.as_str()),
+                );
+            if let Some(close) = closest_match(alias.name, candidates) {
+                let (expr, kind) = match self
+                    .definitions
+                    .iter()
+                    .find(|(_, name, _)| name.as_str() == close)
+                {
+                    Some((expr, _, kind)) => (*expr, *kind),
+                    None => {
+                        let other = self
+                            .aliases
+                            .iter()
+                            .find(|other| other.name.as_str() == close)
+                            .unwrap();
+                        (other.expr, "trait alias")
+                    }
+                };
+                self.diagnostics.push(Diagnostic {
+                    level: Level::Note,
+                    code: Some("trait-alias-near-miss".to_string()),
+                    message: format!(
+                        "trait alias '{}' is close to the name of the {} '{}'",
+                        alias.name, kind, close
+                    ),
+                    spans: vec![alias.expr.span.clone(), expr.span.clone()],
+                    suggestion: Some("Use a more distinct trait alias".to_string()),
+                });
+            }
+        }
+        if self
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.level == Level::Error)
+        {
+            // The error is expected last
+            self.diagnostics
+                .sort_by_key(|diagnostic| diagnostic.level == Level::Error);
+            Err(self.diagnostics)
+        } else {
+            Ok(self.diagnostics)
+        }
+    }
+}
+
+impl<'a> ASTVisitor<'a> for TraitAliasChecker<'a> {
+    fn visit_define_constant(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        self.definitions.push((expr, name, "constant"));
+        true
+    }
+
+    fn visit_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.definitions.push((expr, name, "function"));
+        true
+    }
+
+    fn visit_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.definitions.push((expr, name, "function"));
+        true
+    }
+
+    fn visit_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.definitions.push((expr, name, "function"));
+        true
+    }
+
+    fn visit_define_nft(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        nft_type: &'a SymbolicExpression,
+    ) -> bool {
+        self.definitions.push((expr, name, "token"));
+        true
+    }
+
+    fn visit_define_ft(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        supply: Option<&'a SymbolicExpression>,
+    ) -> bool {
+        self.definitions.push((expr, name, "token"));
+        true
+    }
+
+    fn visit_define_map(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        key_type: &'a SymbolicExpression,
+        value_type: &'a SymbolicExpression,
+    ) -> bool {
+        self.definitions.push((expr, name, "map"));
+        true
+    }
+
+    fn visit_define_data_var(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        data_type: &'a SymbolicExpression,
+        initial: &'a SymbolicExpression,
+    ) -> bool {
+        self.definitions.push((expr, name, "data-var"));
+        true
+    }
+
+    fn visit_define_trait(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        functions: &'a [SymbolicExpression],
+    ) -> bool {
+        self.definitions.push((expr, name, "trait"));
+        true
+    }
+
+    fn visit_use_trait(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        trait_identifier: &TraitIdentifier,
+    ) -> bool {
+        self.aliases.push(Alias {
+            expr,
+            name,
+            trait_name: trait_identifier.name.clone(),
+        });
+        true
+    }
+}
+
+impl AnalysisPass for TraitAliasChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = TraitAliasChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    fn deploy_traits(session: &mut Session) {
+        let snippet = "
+(define-trait vault (
+    (deposit (uint) (response bool uint))
+))
+(define-trait oracle (
+    (price () (response uint uint))
+))
+"
+        .to_string();
+        session
+            .interpret(snippet, Some("traits".to_string()), false, None)
+            .unwrap();
+    }
+
+    fn checked_session() -> Session {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["trait_alias_checker".to_string()];
+        let mut session = Session::new(settings);
+        deploy_traits(&mut session);
+        session
+    }
+
+    #[test]
+    fn alias_named_like_function() {
+        let mut session = checked_session();
+        let snippet = "
+(use-trait deposit .traits.vault)
+(define-read-only (deposit) true)
+(define-read-only (check (target <deposit>)) true)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Err(output) => {
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:2:1: {}: trait alias 'deposit' has the same name as the function 'deposit'",
+                        red!("error")
+                    )
+                );
+                assert_eq!(output[3], "  checker:3:1:");
+                assert_eq!(output[4], "(define-read-only (deposit) true)");
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn alias_named_like_other_trait() {
+        let mut session = checked_session();
+        let snippet = "
+(use-trait pool .traits.vault)
+(use-trait vault .traits.oracle)
+(define-read-only (check (target <pool>) (source <vault>)) true)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Err(output) => {
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:1: {}: trait alias 'vault' has the same name as the trait imported as 'pool'",
+                        red!("error")
+                    )
+                );
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn alias_close_to_definition() {
+        let mut session = checked_session();
+        let snippet = "
+(use-trait oracle .traits.oracle)
+(define-data-var round uint u0)
+(define-map oracles principal bool)
+(define-read-only (check (source <oracle>)) true)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:2:1: {}: trait alias 'oracle' is close to the name of the map 'oracles'",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(output[3], "  checker:4:1:");
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,207 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor, TypedVar};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::representations::SymbolicExpressionType::TraitReference;
+use crate::clarity::representations::TraitDefinition;
+use crate::clarity::types::TraitIdentifier;
+use crate::clarity::ClarityName;
+use std::collections::{HashMap, HashSet};
+
+// Reports `contract-call?` on a trait parameter, when the contract implements
+// that trait itself. The parameter could then be bound to the contract, and
+// the call would re-enter it, a recursion the type-checker cannot see. Trait
+// dispatch is dynamic, so this is only a warning.
+pub struct TraitRecursionChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    implemented: HashSet<TraitIdentifier>,
+    // The trait of each parameter of the current function
+    trait_parameters: HashMap<&'a ClarityName, TraitIdentifier>,
+    calls: Vec<(&'a SymbolicExpression, &'a ClarityName, TraitIdentifier)>,
+}
+
+impl<'a> TraitRecursionChecker<'a> {
+    fn new() -> TraitRecursionChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            implemented: HashSet::new(),
+            trait_parameters: HashMap::new(),
+            calls: Vec::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        // Traits can be implemented after the functions are defined
+        for (expr, parameter, trait_identifier) in self.calls.iter() {
+            if !self.implemented.contains(trait_identifier) {
+                continue;
+            }
+            self.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                code: Some("trait-reentrancy".to_string()),
+                message: format!(
+                    "'{}' may be this contract, which implements '{}', so this call could re-enter it",
+                    parameter, trait_identifier
+                ),
+                spans: vec![expr.span.clone()],
+                suggestion: Some(format!(
+                    "Check that (contract-of {}) is not this contract before calling it",
+                    parameter
+                )),
+            });
+        }
+        Ok(self.diagnostics)
+    }
+
+    fn set_parameters(&mut self, parameters: Option<Vec<TypedVar<'a>>>) {
+        self.trait_parameters = parameters
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|parameter| match &parameter.type_expr.expr {
+                TraitReference(_, TraitDefinition::Defined(trait_identifier))
+                | TraitReference(_, TraitDefinition::Imported(trait_identifier)) => {
+                    Some((parameter.name, trait_identifier.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+    }
+}
+
+impl<'a> ASTVisitor<'a> for TraitRecursionChecker<'a> {
+    fn visit_impl_trait(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        trait_identifier: &TraitIdentifier,
+    ) -> bool {
+        self.implemented.insert(trait_identifier.clone());
+        true
+    }
+
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.set_parameters(parameters);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.set_parameters(parameters);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.set_parameters(parameters);
+        self.traverse_expr(body)
+    }
+
+    fn visit_dynamic_contract_call(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        trait_ref: &'a SymbolicExpression,
+        function_name: &'a ClarityName,
+        args: &'a [SymbolicExpression],
+    ) -> bool {
+        if let Some(parameter) = trait_ref.match_atom() {
+            if let Some(trait_identifier) = self.trait_parameters.get(parameter) {
+                self.calls.push((expr, parameter, trait_identifier.clone()));
+            }
+        }
+        true
+    }
+}
+
+impl AnalysisPass for TraitRecursionChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = TraitRecursionChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn call_through_implemented_trait() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["trait_recursion_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-trait relay-trait (
+    (relay (uint) (response uint uint))
+))
+(define-trait oracle-trait (
+    (price () (response uint uint))
+))
+"
+        .to_string();
+        session
+            .interpret(snippet, Some("traits".to_string()), false, None)
+            .unwrap();
+
+        let snippet = "
+(use-trait relay-trait .traits.relay-trait)
+(use-trait oracle-trait .traits.oracle-trait)
+(define-public (relay (hops uint))
+    (ok hops)
+)
+(define-public (forward (next <relay-trait>) (oracle <oracle-trait>) (hops uint))
+    (begin
+        (try! (contract-call? oracle price))
+        (contract-call? next relay (+ hops u1))
+    )
+)
+(impl-trait .traits.relay-trait)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("relay".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "relay:10:9: {}: 'next' may be this contract, which implements 'ST000000000000000000002AMW42H.traits.relay-trait', so this call could re-enter it",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(output[1], "        (contract-call? next relay (+ hops u1))");
+                assert_eq!(output[2], "        ^~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some(
+                        "Check that (contract-of next) is not this contract before calling it"
+                            .to_string()
+                    )
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,197 @@
+use crate::analysis::ast_visitor::{traverse, ASTVisitor, TypedVar};
+use crate::analysis::{pre_check_result, AnalysisResult};
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::types::{FunctionSignature, TraitIdentifier, TypeSignature};
+use crate::clarity::ClarityName;
+use std::collections::{BTreeMap, HashMap};
+
+// Reports functions named like a method of an implemented trait, but whose
+// arguments do not match the method's signature. The type-checker reports
+// these the same way as a missing method, without a location, so this pass
+// runs before it, with the definitions of the implemented traits. Return
+// types are only known after type-checking, so they are left to it.
+pub struct TraitSignatureChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    traits: &'a HashMap<TraitIdentifier, BTreeMap<ClarityName, FunctionSignature>>,
+    // Each function with the types of its arguments, if they can be parsed
+    functions: Vec<(
+        &'a SymbolicExpression,
+        &'a ClarityName,
+        Option<Vec<TypeSignature>>,
+    )>,
+    implemented: Vec<(&'a SymbolicExpression, TraitIdentifier)>,
+}
+
+impl<'a> TraitSignatureChecker<'a> {
+    fn new(
+        traits: &'a HashMap<TraitIdentifier, BTreeMap<ClarityName, FunctionSignature>>,
+    ) -> TraitSignatureChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            traits,
+            functions: Vec::new(),
+            implemented: Vec::new(),
+        }
+    }
+
+    pub fn run(
+        expressions: &'a [SymbolicExpression],
+        traits: &'a HashMap<TraitIdentifier, BTreeMap<ClarityName, FunctionSignature>>,
+    ) -> AnalysisResult {
+        let mut checker = TraitSignatureChecker::new(traits);
+        traverse(&mut checker, expressions);
+        // Traits can be implemented after the functions are defined
+        for (expr, name, args) in checker.functions.iter() {
+            let args = match args {
+                Some(args) => args,
+                None => continue,
+            };
+            for (impl_expr, trait_identifier) in checker.implemented.iter() {
+                let signature = match checker
+                    .traits
+                    .get(trait_identifier)
+                    .and_then(|methods| methods.get(*name))
+                {
+                    Some(signature) => signature,
+                    None => continue,
+                };
+                if signature.check_args_trait_compliance(args.clone()) {
+                    continue;
+                }
+                checker.diagnostics.push(Diagnostic {
+                    level: Level::Error,
+                    code: Some("trait-signature-mismatch".to_string()),
+                    message: format!(
+                        "function '{}' takes {}, but method '{}' of trait '{}' takes {}",
+                        name,
+                        types_text(args),
+                        name,
+                        trait_identifier,
+                        types_text(&signature.args)
+                    ),
+                    spans: vec![expr.span.clone(), impl_expr.span.clone()],
+                    suggestion: None,
+                });
+            }
+        }
+        pre_check_result(checker.diagnostics)
+    }
+
+    fn add_function(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+    ) {
+        let args = parameters.and_then(|parameters| {
+            parameters
+                .iter()
+                .map(|parameter| TypeSignature::parse_type_repr(parameter.type_expr, &mut ()).ok())
+                .collect()
+        });
+        self.functions.push((expr, name, args));
+    }
+}
+
+fn types_text(types: &[TypeSignature]) -> String {
+    let types: Vec<String> = types.iter().map(|t| t.to_string()).collect();
+    format!("({})", types.join(" "))
+}
+
+impl<'a> ASTVisitor<'a> for TraitSignatureChecker<'a> {
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.add_function(expr, name, parameters);
+        true
+    }
+
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.add_function(expr, name, parameters);
+        true
+    }
+
+    fn visit_impl_trait(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        trait_identifier: &TraitIdentifier,
+    ) -> bool {
+        self.implemented.push((expr, trait_identifier.clone()));
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn mismatched_signature() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-trait token-trait (
+    (transfer (uint principal) (response bool uint))
+    (get-balance (principal) (response uint uint))
+))
+"
+        .to_string();
+        session
+            .interpret(snippet, Some("traits".to_string()), false, None)
+            .unwrap();
+
+        let snippet = "
+(impl-trait .traits.token-trait)
+(define-public (transfer (amount uint) (recipient uint))
+    (ok true)
+)
+(define-read-only (get-balance (owner principal))
+    (ok u0)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("token".to_string()), false, None) {
+            Err(output) => {
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "token:3:1: {}: function 'transfer' takes (uint uint), but method 'transfer' of trait 'ST000000000000000000002AMW42H.traits.token-trait' takes (uint principal)",
+                        red!("error")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "(define-public (transfer (amount uint) (recipient uint))"
+                );
+                assert_eq!(output[2], "^");
+                assert_eq!(output[3], "  token:2:1:");
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+
+        let snippet = "
+(impl-trait .traits.token-trait)
+(define-public (transfer (amount uint) (recipient principal))
+    (ok true)
+)
+(define-read-only (get-balance (owner principal))
+    (ok u0)
+)
+"
+        .to_string();
+        assert!(session
+            .interpret(snippet, Some("token".to_string()), false, None)
+            .is_ok());
+    }
+}
@@ -0,0 +1,326 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::define::DefineFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+use std::collections::HashMap;
+
+// Reports tuple literals whose fields are listed in a different order than in
+// the type expected for them: the type of a data-var, of the key or value of
+// a map, or of a parameter of a function. The order of the fields does not
+// matter to Clarity, so this is a purely stylistic note.
+pub struct TupleOrderChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    var_types: HashMap<&'a ClarityName, &'a SymbolicExpression>,
+    // The key and value types of each map
+    map_types: HashMap<&'a ClarityName, (&'a SymbolicExpression, &'a SymbolicExpression)>,
+    parameter_types: HashMap<&'a ClarityName, Vec<&'a SymbolicExpression>>,
+}
+
+impl<'a> TupleOrderChecker<'a> {
+    fn new() -> TupleOrderChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            var_types: HashMap::new(),
+            map_types: HashMap::new(),
+            parameter_types: HashMap::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        // Functions can be called before they are defined
+        for expr in contract_analysis.expressions.iter() {
+            if let Some((name, types)) = parameter_types(expr) {
+                self.parameter_types.insert(name, types);
+            }
+        }
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    // Check a value against the type expected for it, going through the
+    // optionals, responses and lists wrapping tuples.
+    fn check(&mut self, type_expr: &'a SymbolicExpression, value: &'a SymbolicExpression) {
+        if let (Some(types), Some(values)) = (tuple_fields(type_expr), tuple_fields(value)) {
+            let expected: Vec<&ClarityName> = types.iter().map(|(name, _)| *name).collect();
+            let actual: Vec<&ClarityName> = values.iter().map(|(name, _)| *name).collect();
+            let mut sorted_expected = expected.clone();
+            let mut sorted_actual = actual.clone();
+            sorted_expected.sort();
+            sorted_actual.sort();
+            // Leave the tuples which do not match their type to the type-checker
+            if sorted_expected != sorted_actual {
+                return;
+            }
+            if expected != actual {
+                self.diagnostics.push(Diagnostic {
+                    level: Level::Note,
+                    code: Some("tuple-order".to_string()),
+                    message: "the fields of this tuple are not in the order of its type"
+                        .to_string(),
+                    spans: vec![value.span.clone()],
+                    suggestion: Some(format!(
+                        "List the fields in the order of the type: {}",
+                        field_list(&expected)
+                    )),
+                });
+            }
+            for (name, field_value) in values.iter() {
+                if let Some((_, field_type)) = types.iter().find(|(n, _)| n == name) {
+                    self.check(field_type, field_value);
+                }
+            }
+            return;
+        }
+
+        let (type_name, type_args) = match split_call(type_expr) {
+            Some(call) => call,
+            None => return,
+        };
+        let (value_name, value_args) = match split_call(value) {
+            Some(call) => call,
+            None => return,
+        };
+        match (type_name, type_args, value_name, value_args) {
+            ("optional", [inner], "some", [value]) => self.check(inner, value),
+            ("response", [ok, _], "ok", [value]) => self.check(ok, value),
+            ("response", [_, err], "err", [value]) => self.check(err, value),
+            ("list", [_, inner], "list", values) => {
+                for value in values {
+                    self.check(inner, value);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+fn split_call(expr: &SymbolicExpression) -> Option<(&str, &[SymbolicExpression])> {
+    let (name, args) = expr.match_list()?.split_first()?;
+    Some((name.match_atom()?.as_str(), args))
+}
+
+// The fields of a tuple, literal or type, in the order they are listed.
+fn tuple_fields(expr: &SymbolicExpression) -> Option<Vec<(&ClarityName, &SymbolicExpression)>> {
+    match split_call(expr)? {
+        ("tuple", fields) => fields
+            .iter()
+            .map(|field| match field.match_list()? {
+                [name, value] => Some((name.match_atom()?, value)),
+                _ => None,
+            })
+            .collect(),
+        _ => None,
+    }
+}
+
+fn field_list(fields: &[&ClarityName]) -> String {
+    let fields: Vec<&str> = fields.iter().map(|name| name.as_str()).collect();
+    format!("{{{}}}", fields.join(", "))
+}
+
+// The name of a function, with the type of each of its parameters.
+fn parameter_types(expr: &SymbolicExpression) -> Option<(&ClarityName, Vec<&SymbolicExpression>)> {
+    let signature = match DefineFunctions::try_parse(expr)? {
+        (
+            DefineFunctions::PrivateFunction
+            | DefineFunctions::ReadOnlyFunction
+            | DefineFunctions::PublicFunction,
+            [signature, _],
+        ) => signature,
+        _ => return None,
+    };
+    let (name, parameters) = signature.match_list()?.split_first()?;
+    let types = parameters
+        .iter()
+        .map(|parameter| match parameter.match_list()? {
+            [_, type_expr] => Some(type_expr),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some((name.match_atom()?, types))
+}
+
+impl<'a> ASTVisitor<'a> for TupleOrderChecker<'a> {
+    fn visit_define_data_var(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        data_type: &'a SymbolicExpression,
+        initial: &'a SymbolicExpression,
+    ) -> bool {
+        self.var_types.insert(name, data_type);
+        self.check(data_type, initial);
+        true
+    }
+
+    fn visit_define_map(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        key_type: &'a SymbolicExpression,
+        value_type: &'a SymbolicExpression,
+    ) -> bool {
+        self.map_types.insert(name, (key_type, value_type));
+        true
+    }
+
+    fn visit_var_set(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        value: &'a SymbolicExpression,
+    ) -> bool {
+        if let Some(data_type) = self.var_types.get(name) {
+            self.check(data_type, value);
+        }
+        true
+    }
+
+    fn visit_map_get(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        key: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+    ) -> bool {
+        if let (Some(&(key_type, _)), Some([_, _, key])) =
+            (self.map_types.get(name), expr.match_list())
+        {
+            self.check(key_type, key);
+        }
+        true
+    }
+
+    fn visit_map_set(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        key: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+        value: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+    ) -> bool {
+        if let (Some(&(key_type, value_type)), Some([_, _, key, value])) =
+            (self.map_types.get(name), expr.match_list())
+        {
+            self.check(key_type, key);
+            self.check(value_type, value);
+        }
+        true
+    }
+
+    fn visit_map_insert(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        key: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+        value: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+    ) -> bool {
+        if let (Some(&(key_type, value_type)), Some([_, _, key, value])) =
+            (self.map_types.get(name), expr.match_list())
+        {
+            self.check(key_type, key);
+            self.check(value_type, value);
+        }
+        true
+    }
+
+    fn visit_map_delete(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        key: &HashMap<Option<&'a ClarityName>, &'a SymbolicExpression>,
+    ) -> bool {
+        if let (Some(&(key_type, _)), Some([_, _, key])) =
+            (self.map_types.get(name), expr.match_list())
+        {
+            self.check(key_type, key);
+        }
+        true
+    }
+
+    fn visit_call_user_defined(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        args: &'a [SymbolicExpression],
+    ) -> bool {
+        if let Some(types) = self.parameter_types.get(name).cloned() {
+            for (type_expr, arg) in types.iter().zip(args.iter()) {
+                self.check(type_expr, arg);
+            }
+        }
+        true
+    }
+}
+
+impl AnalysisPass for TupleOrderChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = TupleOrderChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn tuple_order() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["tuple_order_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-map orders { owner: principal, id: uint } { amount: uint, price: uint })
+(define-data-var last (optional { id: uint, amount: uint }) none)
+(define-public (place (id uint) (amount uint) (price uint))
+    (begin
+        (map-set orders { id: id, owner: tx-sender } { amount: amount, price: price })
+        (var-set last (some { amount: amount, id: id }))
+        (ok (record { id: id, amount: amount }))
+    )
+)
+(define-private (record (order { id: uint, amount: uint }))
+    (get id order)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:6:25: {}: the fields of this tuple are not in the order of its type",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "        (map-set orders { id: id, owner: tx-sender } { amount: amount, price: price })"
+                );
+                assert_eq!(
+                    output[2],
+                    "                        ^~~~~~~~~~~~~~~~~~~~~~~~~~~~"
+                );
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some("List the fields in the order of the type: {owner, id}".to_string())
+                );
+                assert_eq!(
+                    result.diagnostics[1].suggestion,
+                    Some("List the fields in the order of the type: {id, amount}".to_string())
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,259 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{references_symbol, traverse, ASTVisitor, TypedVar};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+use std::collections::{HashMap, HashSet};
+
+// Warns about the `ft-mint?` of a token without a supply cap, in a public
+// function, whose amount and recipient both derive from arguments which are
+// not checked before: anyone can mint any amount to any account. An argument
+// is checked when it is referenced by the condition of an `asserts!` or of an
+// `if`, and the function is guarded when such a condition references
+// `tx-sender` or `contract-caller`.
+pub struct UnboundedMintChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    uncapped_tokens: HashSet<&'a ClarityName>,
+    // The arguments of the current public function, and the `let` bindings
+    // derived from them, with whether they are still unchecked
+    inputs: HashMap<&'a ClarityName, bool>,
+    // Whether the caller of the current function has been checked
+    guarded: bool,
+}
+
+impl<'a> UnboundedMintChecker<'a> {
+    fn new() -> UnboundedMintChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            uncapped_tokens: HashSet::new(),
+            inputs: HashMap::new(),
+            guarded: false,
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    fn is_unchecked(&self, expr: &SymbolicExpression) -> bool {
+        self.inputs
+            .iter()
+            .any(|(name, unchecked)| *unchecked && references_symbol(expr, name))
+    }
+
+    fn check_condition(&mut self, cond: &SymbolicExpression) {
+        for (name, unchecked) in self.inputs.iter_mut() {
+            if references_symbol(cond, name) {
+                *unchecked = false;
+            }
+        }
+        if references_caller(cond) {
+            self.guarded = true;
+        }
+    }
+}
+
+fn references_caller(expr: &SymbolicExpression) -> bool {
+    if let Some(atom) = expr.match_atom() {
+        matches!(atom.as_str(), "tx-sender" | "contract-caller")
+    } else {
+        expr.match_list()
+            .unwrap_or_default()
+            .iter()
+            .any(references_caller)
+    }
+}
+
+impl<'a> ASTVisitor<'a> for UnboundedMintChecker<'a> {
+    fn visit_define_ft(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        supply: Option<&'a SymbolicExpression>,
+    ) -> bool {
+        if supply.is_none() {
+            self.uncapped_tokens.insert(name);
+        }
+        true
+    }
+
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.inputs.clear();
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.inputs.clear();
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.inputs = parameters
+            .unwrap_or_default()
+            .iter()
+            .map(|parameter| (parameter.name, true))
+            .collect();
+        self.guarded = false;
+        self.traverse_expr(body)
+    }
+
+    fn traverse_let(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        bindings: &HashMap<&'a ClarityName, &'a SymbolicExpression>,
+        body: &'a [SymbolicExpression],
+    ) -> bool {
+        for (name, value) in bindings {
+            if !self.traverse_expr(value) {
+                return false;
+            }
+            if self.is_unchecked(value) {
+                self.inputs.insert(name, true);
+            }
+        }
+        for expr in body {
+            if !self.traverse_expr(expr) {
+                return false;
+            }
+        }
+        self.visit_let(expr, bindings, body)
+    }
+
+    fn traverse_if(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        cond: &'a SymbolicExpression,
+        then_expr: &'a SymbolicExpression,
+        else_expr: &'a SymbolicExpression,
+    ) -> bool {
+        if !self.traverse_expr(cond) {
+            return false;
+        }
+        self.check_condition(cond);
+        self.traverse_expr(then_expr)
+            && self.traverse_expr(else_expr)
+            && self.visit_if(expr, cond, then_expr, else_expr)
+    }
+
+    fn visit_asserts(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        cond: &'a SymbolicExpression,
+        thrown: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_condition(cond);
+        true
+    }
+
+    fn visit_ft_mint(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        token: &'a ClarityName,
+        amount: &'a SymbolicExpression,
+        recipient: &'a SymbolicExpression,
+    ) -> bool {
+        if self.uncapped_tokens.contains(token)
+            && !self.guarded
+            && self.is_unchecked(amount)
+            && self.is_unchecked(recipient)
+        {
+            self.diagnostics.push(Diagnostic {
+                level: Level::Warning,
+                code: Some("unbounded-mint".to_string()),
+                message: format!(
+                    "unbounded mint to arbitrary recipient: the amount and the recipient of this ft-mint? are unchecked arguments, and '{}' has no supply cap",
+                    token
+                ),
+                spans: vec![expr.span.clone()],
+                suggestion: Some(
+                    "Check the caller with asserts!, or give the token a supply cap".to_string(),
+                ),
+            });
+        }
+        true
+    }
+}
+
+impl AnalysisPass for UnboundedMintChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = UnboundedMintChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn unbounded_mint() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["unbounded_mint_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-constant OWNER tx-sender)
+(define-fungible-token points)
+(define-fungible-token shares u1000000)
+(define-public (mint (amount uint) (recipient principal))
+    (let ((total (* amount u10)))
+        (ft-mint? points total recipient)
+    )
+)
+(define-public (mint-shares (amount uint) (recipient principal))
+    (ft-mint? shares amount recipient)
+)
+(define-public (mint-owner (amount uint) (recipient principal))
+    (begin
+        (asserts! (is-eq tx-sender OWNER) (err u1))
+        (ft-mint? points amount recipient)
+    )
+)
+(define-public (claim (amount uint))
+    (ft-mint? points amount tx-sender)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:7:9: {}: unbounded mint to arbitrary recipient: the amount and the recipient of this ft-mint? are unchecked arguments, and 'points' has no supply cap",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(output[1], "        (ft-mint? points total recipient)");
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
@@ -0,0 +1,189 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{
+    match_native_call, sequence_length, traverse, ASTVisitor, TypedVar,
+};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::types::Value;
+use crate::clarity::ClarityName;
+
+// Reports `(unwrap-panic (element-at seq index))`, which aborts when the
+// sequence has no element at that index. Sequences of a known length with a
+// literal index in bounds are skipped, as well as named sequences whose
+// length is read, with `len`, in the same function.
+pub struct UncheckedIndexChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    current_body: Option<&'a SymbolicExpression>,
+}
+
+impl<'a> UncheckedIndexChecker<'a> {
+    fn new() -> UncheckedIndexChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            current_body: None,
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    fn is_checked(&self, sequence: &SymbolicExpression, index: &SymbolicExpression) -> bool {
+        if let (Some(length), Some(Value::UInt(index))) =
+            (sequence_length(sequence), index.match_literal_value())
+        {
+            return *index < length as u128;
+        }
+        match (sequence.match_atom(), self.current_body) {
+            (Some(name), Some(body)) => reads_length(body, name),
+            _ => false,
+        }
+    }
+}
+
+// Whether `(len name)` is called anywhere in an expression.
+fn reads_length(expr: &SymbolicExpression, name: &ClarityName) -> bool {
+    if let Some((NativeFunctions::Len, [sequence])) = match_native_call(expr) {
+        if sequence.match_atom() == Some(name) {
+            return true;
+        }
+    }
+    match expr.match_list() {
+        Some(list) => list.iter().any(|e| reads_length(e, name)),
+        None => false,
+    }
+}
+
+impl<'a> ASTVisitor<'a> for UncheckedIndexChecker<'a> {
+    fn traverse_define_private(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.current_body = Some(body);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_read_only(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.current_body = Some(body);
+        self.traverse_expr(body)
+    }
+
+    fn traverse_define_public(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        parameters: Option<Vec<TypedVar<'a>>>,
+        body: &'a SymbolicExpression,
+    ) -> bool {
+        self.current_body = Some(body);
+        self.traverse_expr(body)
+    }
+
+    fn visit_unwrap_panic(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        input: &'a SymbolicExpression,
+    ) -> bool {
+        let (sequence, index) = match match_native_call(input) {
+            Some((NativeFunctions::ElementAt, [sequence, index])) => (sequence, index),
+            _ => return true,
+        };
+        if self.is_checked(sequence, index) {
+            return true;
+        }
+        let sequence_name = match sequence.match_atom() {
+            Some(name) => format!("'{}'", name),
+            None => "the sequence".to_string(),
+        };
+        self.diagnostics.push(Diagnostic {
+            level: Level::Warning,
+            code: Some("unchecked-index".to_string()),
+            message: format!(
+                "unwrap-panic aborts if {} has no element at this index, and its length is not checked",
+                sequence_name
+            ),
+            spans: vec![expr.span.clone()],
+            suggestion: Some(
+                "Check the length of the sequence with len first, or use default-to".to_string(),
+            ),
+        });
+        true
+    }
+}
+
+impl AnalysisPass for UncheckedIndexChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = UncheckedIndexChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn unchecked_index() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["unchecked_index_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-read-only (first-item (items (list 10 uint)))
+    (unwrap-panic (element-at items u0))
+)
+(define-read-only (checked-first-item (items (list 10 uint)))
+    (if (> (len items) u0) (unwrap-panic (element-at items u0)) u0)
+)
+(define-read-only (second-prime)
+    (+ (unwrap-panic (element-at (list u2 u3 u5) u1)) (unwrap-panic (element-at (list u2) u1)))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:5: {}: unwrap-panic aborts if 'items' has no element at this index, and its length is not checked",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(output[1], "    (unwrap-panic (element-at items u0))");
+                assert_eq!(output[2], "    ^~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some(
+                        "Check the length of the sequence with len first, or use default-to"
+                            .to_string()
+                    )
+                );
+                assert_eq!(
+                    result.diagnostics[1].message,
+                    "unwrap-panic aborts if the sequence has no element at this index, and its length is not checked"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
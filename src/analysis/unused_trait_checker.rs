@@ -0,0 +1,137 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::representations::SymbolicExpressionType::{List, TraitReference};
+use crate::clarity::types::TraitIdentifier;
+use crate::clarity::ClarityName;
+use std::collections::HashSet;
+
+// Warns about the `use-trait` whose alias is never referenced. An alias is
+// only written as `<alias>`, in the types of the parameters of the functions,
+// so these types are searched too, as the visitor does not traverse them.
+pub struct UnusedTraitChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    imports: Vec<(&'a SymbolicExpression, &'a ClarityName)>,
+}
+
+impl<'a> UnusedTraitChecker<'a> {
+    fn new() -> UnusedTraitChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            imports: Vec::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        traverse(&mut self, &contract_analysis.expressions);
+        let mut references = HashSet::new();
+        for expr in contract_analysis.expressions.iter() {
+            collect_trait_references(expr, &mut references);
+        }
+        for (expr, name) in self.imports.iter() {
+            if !references.contains(name) {
+                self.diagnostics.push(Diagnostic {
+                    level: Level::Warning,
+                    code: Some("unused-use-trait".to_string()),
+                    message: format!("trait alias '{}' is imported but never used", name),
+                    spans: vec![expr.span.clone()],
+                    suggestion: Some("Remove the use-trait".to_string()),
+                });
+            }
+        }
+        Ok(self.diagnostics)
+    }
+}
+
+fn collect_trait_references<'a>(
+    expr: &'a SymbolicExpression,
+    references: &mut HashSet<&'a ClarityName>,
+) {
+    match &expr.expr {
+        TraitReference(name, _) => {
+            references.insert(name);
+        }
+        List(list) => {
+            for element in list.iter() {
+                collect_trait_references(element, references);
+            }
+        }
+        _ => {}
+    }
+}
+
+impl<'a> ASTVisitor<'a> for UnusedTraitChecker<'a> {
+    fn visit_use_trait(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        name: &'a ClarityName,
+        trait_identifier: &TraitIdentifier,
+    ) -> bool {
+        self.imports.push((expr, name));
+        true
+    }
+}
+
+impl AnalysisPass for UnusedTraitChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = UnusedTraitChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn unused_use_trait() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["unused_trait_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-trait relay-trait (
+    (relay (uint) (response uint uint))
+))
+(define-trait oracle-trait (
+    (price () (response uint uint))
+))
+"
+        .to_string();
+        session
+            .interpret(snippet, Some("traits".to_string()), false, None)
+            .unwrap();
+
+        let snippet = "
+(use-trait relay-trait .traits.relay-trait)
+(use-trait oracle-trait .traits.oracle-trait)
+(define-read-only (is-oracle (oracle <oracle-trait>))
+    true
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 1);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:2:1: {}: trait alias 'relay-trait' is imported but never used",
+                        yellow!("warning")
+                    )
+                );
+                assert_eq!(output[1], "(use-trait relay-trait .traits.relay-trait)");
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
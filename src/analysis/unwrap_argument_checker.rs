@@ -0,0 +1,177 @@
+use crate::analysis::ast_types::AstTypes;
+use crate::analysis::ast_visitor::{traverse, ASTVisitor};
+use crate::analysis::{pre_check_result, AnalysisResult};
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+
+// Reports `try!`, `unwrap!`, `unwrap-panic`, `unwrap-err!` and
+// `unwrap-err-panic` applied to a value which is not a response (or an
+// optional, for those which accept one).
+pub struct UnwrapArgumentChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    types: AstTypes<'a>,
+}
+
+impl<'a> UnwrapArgumentChecker<'a> {
+    fn new(expressions: &'a [SymbolicExpression]) -> UnwrapArgumentChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            types: AstTypes::new(expressions),
+        }
+    }
+
+    pub fn run(expressions: &'a [SymbolicExpression]) -> AnalysisResult {
+        let mut checker = UnwrapArgumentChecker::new(expressions);
+        traverse(&mut checker, expressions);
+        pre_check_result(checker.diagnostics)
+    }
+
+    fn check_argument(
+        &mut self,
+        func: NativeFunctions,
+        input: &'a SymbolicExpression,
+        accepts_optional: bool,
+    ) {
+        let input_type = match self.types.type_of(input) {
+            Some(input_type) => input_type,
+            None => return,
+        };
+        if input_type == "response" || (accepts_optional && input_type == "optional") {
+            return;
+        }
+        let expected = if accepts_optional {
+            "a response or optional value"
+        } else {
+            "a response value"
+        };
+        self.diagnostics.push(Diagnostic {
+            level: Level::Error,
+            code: Some("unwrap-argument-type".to_string()),
+            message: format!(
+                "'{}' expects {}, but its argument is of type '{}'",
+                func.get_name(),
+                expected,
+                input_type
+            ),
+            spans: vec![input.span.clone()],
+            suggestion: match input_type {
+                "optional" => Some("Use unwrap! or match to handle optional values".to_string()),
+                _ => None,
+            },
+        });
+    }
+}
+
+impl<'a> ASTVisitor<'a> for UnwrapArgumentChecker<'a> {
+    fn visit_try(&mut self, expr: &'a SymbolicExpression, input: &'a SymbolicExpression) -> bool {
+        self.check_argument(NativeFunctions::TryRet, input, true);
+        true
+    }
+
+    fn visit_unwrap(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        input: &'a SymbolicExpression,
+        throws: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_argument(NativeFunctions::UnwrapRet, input, true);
+        true
+    }
+
+    fn visit_unwrap_err(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        input: &'a SymbolicExpression,
+        throws: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_argument(NativeFunctions::UnwrapErrRet, input, false);
+        true
+    }
+
+    fn visit_unwrap_panic(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        input: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_argument(NativeFunctions::Unwrap, input, true);
+        true
+    }
+
+    fn visit_unwrap_err_panic(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        input: &'a SymbolicExpression,
+    ) -> bool {
+        self.check_argument(NativeFunctions::UnwrapErr, input, false);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn unwrap_plain_value() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-data-var counter uint u0)
+(define-public (increment)
+    (ok (+ (unwrap! (var-get counter) (err u1)) u1))
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Err(output) => {
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:4:21: {}: 'unwrap!' expects a response or optional value, but its argument is of type 'uint'",
+                        red!("error")
+                    )
+                );
+                assert_eq!(
+                    output[1],
+                    "    (ok (+ (unwrap! (var-get counter) (err u1)) u1))"
+                );
+                assert_eq!(output[2], "                    ^~~~~~~~~~~~~~~~~");
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn unwrap_err_optional() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-map owners uint principal)
+(define-read-only (get-owner (id uint))
+    (unwrap-err-panic (map-get? owners id))
+)
+"
+        .to_string();
+        match session.interpret(snippet, None, false, None) {
+            Err((_, Some(diagnostic), _)) => {
+                assert_eq!(
+                    diagnostic.message,
+                    "'unwrap-err-panic' expects a response value, but its argument is of type 'optional'"
+                );
+                assert_eq!(
+                    diagnostic.suggestion,
+                    Some("Use unwrap! or match to handle optional values".to_string())
+                );
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+
+        let snippet = "
+(define-read-only (checked (amount (response uint uint)) (limit (optional uint)))
+    (ok (+ (try! amount) (unwrap-panic limit)))
+)
+"
+        .to_string();
+        assert!(session.interpret(snippet, None, false, None).is_ok());
+    }
+}
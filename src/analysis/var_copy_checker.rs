@@ -0,0 +1,215 @@
+use crate::analysis::annotation::Annotation;
+use crate::analysis::ast_visitor::{match_native_call, traverse, ASTVisitor};
+use crate::analysis::{AnalysisPass, AnalysisResult, Settings};
+use crate::clarity::analysis::analysis_db::AnalysisDatabase;
+pub use crate::clarity::analysis::types::ContractAnalysis;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::functions::define::DefineFunctions;
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::ClarityName;
+use std::collections::HashMap;
+
+// Reports values read from a data-var, directly or through a function
+// returning `(var-get ...)`, which are then modified by `append`, `concat` or
+// `merge` in a statement whose result is discarded. Clarity values are
+// copied, so this is most likely an attempt to modify the data-var through a
+// reference. This is a heuristic, so it only emits notes.
+pub struct VarCopyChecker<'a> {
+    diagnostics: Vec<Diagnostic>,
+    // Functions returning the value of a data-var, with that data-var
+    getters: HashMap<&'a ClarityName, &'a ClarityName>,
+}
+
+impl<'a> VarCopyChecker<'a> {
+    fn new() -> VarCopyChecker<'a> {
+        Self {
+            diagnostics: Vec::new(),
+            getters: HashMap::new(),
+        }
+    }
+
+    fn run(mut self, contract_analysis: &'a ContractAnalysis) -> AnalysisResult {
+        // Functions can be called before they are defined
+        for expr in contract_analysis.expressions.iter() {
+            if let Some((name, var)) = getter(expr) {
+                self.getters.insert(name, var);
+            }
+        }
+        traverse(&mut self, &contract_analysis.expressions);
+        Ok(self.diagnostics)
+    }
+
+    // The data-var copied by an expression, with the function returning it.
+    fn copied_var(
+        &self,
+        expr: &'a SymbolicExpression,
+        bindings: &HashMap<&'a ClarityName, &'a SymbolicExpression>,
+    ) -> Option<(&'a ClarityName, Option<&'a ClarityName>)> {
+        if let Some(name) = expr.match_atom() {
+            return self.copied_var(bindings.get(name)?, &HashMap::new());
+        }
+        if let Some((NativeFunctions::FetchVar, [var])) = match_native_call(expr) {
+            return Some((var.match_atom()?, None));
+        }
+        let function = expr.match_list()?.first()?.match_atom()?;
+        let var = self.getters.get(function)?;
+        Some((var, Some(function)))
+    }
+
+    // Check the statements whose result is discarded.
+    fn check_statements(
+        &mut self,
+        statements: &'a [SymbolicExpression],
+        bindings: &HashMap<&'a ClarityName, &'a SymbolicExpression>,
+    ) {
+        let discarded = match statements.split_last() {
+            Some((_, discarded)) => discarded,
+            None => return,
+        };
+        for statement in discarded {
+            let (func, target) = match match_native_call(statement) {
+                Some((
+                    func @ (NativeFunctions::Append
+                    | NativeFunctions::Concat
+                    | NativeFunctions::TupleMerge),
+                    [target, ..],
+                )) => (func, target),
+                _ => continue,
+            };
+            let (var, function) = match self.copied_var(target, bindings) {
+                Some(copied) => copied,
+                None => continue,
+            };
+            let source = match function {
+                Some(function) => format!("'{}' returns", function),
+                None => "var-get returns".to_string(),
+            };
+            self.diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: Some("data-var-copy".to_string()),
+                message: format!(
+                    "{} a copy of data-var '{}', so this {} does not modify '{}'",
+                    source,
+                    var,
+                    func.get_name(),
+                    var
+                ),
+                spans: vec![statement.span.clone()],
+                suggestion: Some(format!(
+                    "Clarity values are copied, not referenced: save the new value with (var-set {} ...)",
+                    var
+                )),
+            });
+        }
+    }
+}
+
+// A function whose body is `(var-get name)`, with that name.
+fn getter(expr: &SymbolicExpression) -> Option<(&ClarityName, &ClarityName)> {
+    let (signature, body) = match DefineFunctions::try_parse(expr)? {
+        (
+            DefineFunctions::PrivateFunction | DefineFunctions::ReadOnlyFunction,
+            [signature, body],
+        ) => (signature, body),
+        _ => return None,
+    };
+    let name = signature.match_list()?.first()?.match_atom()?;
+    match match_native_call(body)? {
+        (NativeFunctions::FetchVar, [var]) => Some((name, var.match_atom()?)),
+        _ => None,
+    }
+}
+
+impl<'a> ASTVisitor<'a> for VarCopyChecker<'a> {
+    fn visit_begin(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        statements: &'a [SymbolicExpression],
+    ) -> bool {
+        self.check_statements(statements, &HashMap::new());
+        true
+    }
+
+    fn visit_let(
+        &mut self,
+        expr: &'a SymbolicExpression,
+        bindings: &HashMap<&'a ClarityName, &'a SymbolicExpression>,
+        body: &'a [SymbolicExpression],
+    ) -> bool {
+        self.check_statements(body, bindings);
+        true
+    }
+}
+
+impl AnalysisPass for VarCopyChecker<'_> {
+    fn run_pass(
+        contract_analysis: &mut ContractAnalysis,
+        analysis_db: &mut AnalysisDatabase,
+        annotations: &Vec<Annotation>,
+        settings: &Settings,
+    ) -> AnalysisResult {
+        let checker = VarCopyChecker::new();
+        checker.run(contract_analysis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn modified_copy() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["var_copy_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-data-var members (list 10 principal) (list))
+(define-public (join)
+    (let ((current (get-members)))
+        (as-max-len? (append current tx-sender) u10)
+        (append current tx-sender)
+        (ok true)
+    )
+)
+(define-private (get-members)
+    (var-get members)
+)
+(define-public (join-twice)
+    (begin
+        (append (var-get members) tx-sender)
+        (var-set members (unwrap-panic (as-max-len? (append (var-get members) tx-sender) u10)))
+        (ok true)
+    )
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Ok((output, result)) => {
+                assert_eq!(result.diagnostics.len(), 2);
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:6:9: {}: 'get-members' returns a copy of data-var 'members', so this append does not modify 'members'",
+                        blue!("note")
+                    )
+                );
+                assert_eq!(output[1], "        (append current tx-sender)");
+                assert_eq!(output[2], "        ^~~~~~~~~~~~~~~~~~~~~~~~~~");
+                assert_eq!(
+                    result.diagnostics[0].suggestion,
+                    Some(
+                        "Clarity values are copied, not referenced: save the new value with (var-set members ...)"
+                            .to_string()
+                    )
+                );
+                assert_eq!(
+                    result.diagnostics[1].message,
+                    "var-get returns a copy of data-var 'members', so this append does not modify 'members'"
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+}
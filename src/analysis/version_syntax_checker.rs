@@ -0,0 +1,438 @@
+use crate::analysis::versions::{introduced, reserved, Form};
+use crate::analysis::{pre_check_result, AnalysisResult, Settings};
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use crate::clarity::representations::{Span, SymbolicExpression};
+use crate::clarity::version::ClarityVersion;
+use crate::clarity::ClarityName;
+
+const NESTED_TRAIT: &str = "trait reference nested in a type";
+
+// Reports the functions, keywords and syntax forms which are not available
+// in the configured Clarity version, with a hint to migrate. From the version
+// introducing them, the names of the functions and keywords are reserved, so
+// defining them is reported instead.
+pub struct VersionSyntaxChecker {
+    diagnostics: Vec<Diagnostic>,
+    version: ClarityVersion,
+}
+
+impl VersionSyntaxChecker {
+    fn new(settings: &Settings) -> VersionSyntaxChecker {
+        Self {
+            diagnostics: Vec::new(),
+            version: settings.clarity_version,
+        }
+    }
+
+    pub fn run(expressions: &[SymbolicExpression], settings: &Settings) -> AnalysisResult {
+        let mut checker = VersionSyntaxChecker::new(settings);
+        let mut bound = Vec::new();
+        for expr in expressions {
+            bound_names(expr, &mut bound);
+        }
+        for (name, span) in bound.iter() {
+            checker.check_definition(name, span);
+        }
+        for expr in expressions {
+            checker.check_uses(expr, &bound);
+            checker.check_signatures(expr);
+        }
+        pre_check_result(checker.diagnostics)
+    }
+
+    fn check_definition(&mut self, name: &ClarityName, span: &Span) {
+        let version = match reserved(name) {
+            Some(reserved) if self.version >= reserved.version => reserved.version,
+            _ => return,
+        };
+        self.diagnostics.push(Diagnostic {
+            level: Level::Error,
+            code: Some("reserved-name".to_string()),
+            message: format!(
+                "'{}' is reserved from {}, so it cannot be defined",
+                name, version
+            ),
+            spans: vec![span.clone()],
+            suggestion: Some("Rename the definition".to_string()),
+        });
+    }
+
+    // Report a use of a function or keyword unavailable in the version,
+    // unless the contract defines it.
+    fn check_use(
+        &mut self,
+        expr: &SymbolicExpression,
+        keyword: bool,
+        bound: &[(&ClarityName, Span)],
+    ) {
+        let name = match expr.match_atom() {
+            Some(name) => name,
+            None => return,
+        };
+        let form = if keyword {
+            Form::Keyword
+        } else {
+            Form::Function
+        };
+        let (version, hint) = match introduced(name, form) {
+            Some(introduced)
+                if self.version < introduced.version
+                    && !bound.iter().any(|(bound, _)| *bound == name) =>
+            {
+                (introduced.version, introduced.hint)
+            }
+            _ => return,
+        };
+        self.diagnostics.push(Diagnostic {
+            level: Level::Error,
+            code: Some("unavailable-function".to_string()),
+            message: format!(
+                "'{}' is only available from {}, but {} is targeted",
+                name, version, self.version
+            ),
+            spans: vec![expr.span.clone()],
+            suggestion: Some(hint.to_string()),
+        });
+    }
+
+    // Report the uses of functions and keywords unavailable in the version:
+    // the functions called or passed to `map`, `filter` and `fold`, and the
+    // keywords used as values. Names which are neither, like the keys of
+    // tuples, parameters and types, are skipped.
+    fn check_uses(&mut self, expr: &SymbolicExpression, bound: &[(&ClarityName, Span)]) {
+        let list = match expr.match_list() {
+            Some(list) => list,
+            None => return self.check_use(expr, true, bound),
+        };
+        let (head, args) = match list.split_first() {
+            Some(split) => split,
+            None => return,
+        };
+        let function = match head.match_atom() {
+            Some(function) => function,
+            None => {
+                for element in list {
+                    self.check_uses(element, bound);
+                }
+                return;
+            }
+        };
+        self.check_use(head, false, bound);
+        // The arguments which are evaluated
+        let values: Vec<&SymbolicExpression> = match (function.as_str(), args) {
+            ("tuple", pairs) => pairs
+                .iter()
+                .filter_map(|pair| pair.match_list()?.get(1))
+                .collect(),
+            ("let", [bindings, body @ ..]) => bindings
+                .match_list()
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|binding| binding.match_list()?.get(1))
+                .chain(body)
+                .collect(),
+            ("match", [input, _, some_branch, none_branch]) => {
+                vec![input, some_branch, none_branch]
+            }
+            ("match", [input, _, ok_branch, _, err_branch]) => vec![input, ok_branch, err_branch],
+            ("get", [_, tuple]) => vec![tuple],
+            ("contract-call?", [contract, _, args @ ..]) => {
+                std::iter::once(contract).chain(args).collect()
+            }
+            ("map" | "filter" | "fold", [function, args @ ..]) => {
+                self.check_use(function, false, bound);
+                args.iter().collect()
+            }
+            (
+                "define-public" | "define-read-only" | "define-private" | "define-constant",
+                [_, value],
+            ) => vec![value],
+            ("define-data-var", [_, _, initial]) => vec![initial],
+            ("define-fungible-token", [_, supply]) => vec![supply],
+            (
+                "define-map"
+                | "define-non-fungible-token"
+                | "define-trait"
+                | "define-fungible-token"
+                | "use-trait"
+                | "impl-trait",
+                _,
+            ) => vec![],
+            _ => args.iter().collect(),
+        };
+        for value in values {
+            self.check_uses(value, bound);
+        }
+    }
+
+    // Report the trait references nested in the types of the parameters,
+    // data vars and maps of a definition.
+    fn check_signatures(&mut self, expr: &SymbolicExpression) {
+        let list = match expr.match_list() {
+            Some(list) => list,
+            None => return,
+        };
+        let head = list.first().and_then(|head| head.match_atom());
+        let types: Vec<&SymbolicExpression> = match (head.map(|head| head.as_str()), list) {
+            (Some("define-public" | "define-read-only" | "define-private"), [_, signature, ..]) => {
+                signature
+                    .match_list()
+                    .unwrap_or_default()
+                    .iter()
+                    .skip(1)
+                    .filter_map(|parameter| parameter.match_list()?.get(1))
+                    .collect()
+            }
+            (Some("define-data-var"), [_, _, data_type, _]) => vec![data_type],
+            (Some("define-map"), [_, _, key_type, value_type]) => vec![key_type, value_type],
+            _ => vec![],
+        };
+        for type_expr in types {
+            self.check_nested_traits(type_expr);
+        }
+    }
+
+    // A trait reference can be the type of a parameter, but only
+    // be nested in another type from the version introducing it.
+    fn check_nested_traits(&mut self, type_expr: &SymbolicExpression) {
+        let introduced = match introduced(NESTED_TRAIT, Form::Syntax) {
+            Some(introduced) if self.version < introduced.version => introduced,
+            _ => return,
+        };
+        for element in type_expr.match_list().unwrap_or_default() {
+            if element.match_trait_reference().is_none() {
+                self.check_nested_traits(element);
+                continue;
+            }
+            self.diagnostics.push(Diagnostic {
+                level: Level::Error,
+                code: Some("unavailable-syntax".to_string()),
+                message: format!(
+                    "a {} is only available from {}, but {} is targeted",
+                    NESTED_TRAIT, introduced.version, self.version
+                ),
+                spans: vec![element.span.clone()],
+                suggestion: Some(introduced.hint.to_string()),
+            });
+        }
+    }
+}
+
+// Collect the names defined by a contract, or bound by function parameters,
+// `let` and `match`, with the span of their definition, to skip their uses.
+fn bound_names<'a>(expr: &'a SymbolicExpression, bound: &mut Vec<(&'a ClarityName, Span)>) {
+    let list = match expr.match_list() {
+        Some(list) => list,
+        None => return,
+    };
+    let head = list.first().and_then(|head| head.match_atom());
+    match (head.map(|head| head.as_str()), list) {
+        (Some("define-public" | "define-read-only" | "define-private"), [_, signature, ..]) => {
+            if let Some(signature) = signature.match_list() {
+                if let Some(name) = signature.first() {
+                    if let Some(atom) = name.match_atom() {
+                        bound.push((atom, name.span.clone()));
+                    }
+                }
+                for parameter in signature.iter().skip(1) {
+                    if let Some(name) = parameter
+                        .match_list()
+                        .and_then(|parameter| parameter.first())
+                        .and_then(|name| name.match_atom())
+                    {
+                        bound.push((name, parameter.span.clone()));
+                    }
+                }
+            }
+        }
+        (
+            Some(
+                "define-constant"
+                | "define-data-var"
+                | "define-map"
+                | "define-fungible-token"
+                | "define-non-fungible-token"
+                | "define-trait",
+            ),
+            [_, name, ..],
+        ) => {
+            if let Some(atom) = name.match_atom() {
+                bound.push((atom, name.span.clone()));
+            }
+        }
+        (Some("let"), [_, bindings, ..]) => {
+            for binding in bindings.match_list().into_iter().flatten() {
+                if let Some(name) = binding
+                    .match_list()
+                    .and_then(|binding| binding.first())
+                    .and_then(|name| name.match_atom())
+                {
+                    bound.push((name, binding.span.clone()));
+                }
+            }
+        }
+        (Some("match"), [_, _, some_name, _, _]) => {
+            if let Some(atom) = some_name.match_atom() {
+                bound.push((atom, some_name.span.clone()));
+            }
+        }
+        (Some("match"), [_, _, ok_name, _, err_name, _]) => {
+            for name in [ok_name, err_name] {
+                if let Some(atom) = name.match_atom() {
+                    bound.push((atom, name.span.clone()));
+                }
+            }
+        }
+        _ => (),
+    }
+    for element in list {
+        bound_names(element, bound);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::clarity::version::ClarityVersion;
+    use crate::repl::session::Session;
+    use crate::repl::SessionSettings;
+
+    #[test]
+    fn clarity2_syntax_in_clarity1() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-read-only (flags (value uint))
+    (bit-and value u3)
+)
+"
+        .to_string();
+        match session.formatted_interpretation(snippet, Some("checker".to_string()), false, None) {
+            Err(output) => {
+                assert_eq!(
+                    output[0],
+                    format!(
+                        "checker:3:6: {}: 'bit-and' is only available from Clarity 2, but Clarity 1 is targeted",
+                        red!("error")
+                    )
+                );
+                assert_eq!(output[1], "    (bit-and value u3)");
+                assert_eq!(output[2], "     ^~~~~~~");
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "(define-read-only (network) chain-id)".to_string();
+        match session.interpret(snippet, None, false, None) {
+            Err((_, Some(diagnostic), _)) => {
+                assert_eq!(
+                    diagnostic.message,
+                    "'chain-id' is only available from Clarity 2, but Clarity 1 is targeted"
+                );
+                assert_eq!(
+                    diagnostic.suggestion,
+                    Some("Use a constant holding the chain id of the network".to_string())
+                );
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+
+        // A name defined by the contract is not reported
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-constant chain-id u1)
+(define-read-only (network) chain-id)
+"
+        .to_string();
+        assert!(session.interpret(snippet, None, false, None).is_ok());
+    }
+
+    #[test]
+    fn reserved_names_in_clarity2() {
+        let mut settings = SessionSettings::default();
+        settings.analysis_settings.clarity_version = ClarityVersion::Clarity2;
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-private (slice? (items (list 4 uint)))
+    (let ((chain-id u1))
+        (+ chain-id u1)
+    )
+)
+"
+        .to_string();
+        match session.interpret(snippet, None, false, None) {
+            Err((_, Some(diagnostic), _)) => {
+                assert_eq!(
+                    diagnostic.message,
+                    "'slice?' is reserved from Clarity 2, so it cannot be defined"
+                );
+                assert_eq!(
+                    diagnostic.suggestion,
+                    Some("Rename the definition".to_string())
+                );
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn nested_trait_in_clarity1() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-trait token ((get-supply () (response uint uint))))
+(define-public (supply (tokens (list 2 <token>)))
+    (ok u0)
+)
+"
+        .to_string();
+        match session.interpret(snippet, None, false, None) {
+            Err((_, Some(diagnostic), _)) => {
+                assert_eq!(
+                    diagnostic.message,
+                    "a trait reference nested in a type is only available from Clarity 2, but Clarity 1 is targeted"
+                );
+                assert_eq!(
+                    diagnostic.suggestion,
+                    Some("Pass each trait reference as a parameter of its own".to_string())
+                );
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+
+        // A trait reference can be the type of a parameter
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-trait token ((get-supply () (response uint uint))))
+(define-public (supply (contract <token>))
+    (contract-call? contract get-supply)
+)
+"
+        .to_string();
+        assert!(session.interpret(snippet, None, false, None).is_ok());
+    }
+
+    #[test]
+    fn tuple_keys_in_clarity1() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-map m {chain-id: uint} uint)
+(define-read-only (flags)
+    (get bit-and {bit-and: u1, b: u2})
+)
+"
+        .to_string();
+        assert!(session.interpret(snippet, None, false, None).is_ok());
+
+        // Functions are only reported when called, and keywords as values
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "(define-read-only (total (values (list 4 uint))) (fold bit-or values u0))";
+        match session.interpret(snippet.to_string(), None, false, None) {
+            Err((_, Some(diagnostic), _)) => {
+                assert_eq!(
+                    diagnostic.message,
+                    "'bit-or' is only available from Clarity 2, but Clarity 1 is targeted"
+                );
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+}
@@ -0,0 +1,300 @@
+use crate::clarity::version::ClarityVersion;
+
+const NO_EQUIVALENT: &str = "There is no equivalent in Clarity 1, so target Clarity 2";
+
+// How a versioned element appears in the source.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Form {
+    // A function, called or passed to `map`, `filter` and `fold`
+    Function,
+    // A keyword, used as a value
+    Keyword,
+    // A syntax construct, named by its description
+    Syntax,
+}
+
+// An element of the language introduced by a Clarity version.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Versioned {
+    pub name: &'static str,
+    pub form: Form,
+    pub version: ClarityVersion,
+    // How to migrate the code using it to the previous versions
+    pub hint: &'static str,
+    // The name it replaces, deprecated from the same version
+    pub replaces: Option<&'static str>,
+}
+
+// The functions, keywords and syntax forms introduced by each Clarity
+// version. The passes checking the targeted version all read this table.
+const VERSIONED: &[(&str, Form, ClarityVersion, &str, Option<&str>)] = &[
+    (
+        "stx-transfer-memo?",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Use stx-transfer?, and print the memo",
+        None,
+    ),
+    (
+        "stx-account",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Use stx-get-balance for the unlocked balance",
+        None,
+    ),
+    (
+        "is-standard",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        NO_EQUIVALENT,
+        None,
+    ),
+    (
+        "principal-destruct?",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        NO_EQUIVALENT,
+        None,
+    ),
+    (
+        "principal-construct?",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        NO_EQUIVALENT,
+        None,
+    ),
+    (
+        "string-to-int?",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Parse the digits with a fold over the string",
+        None,
+    ),
+    (
+        "string-to-uint?",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Parse the digits with a fold over the string",
+        None,
+    ),
+    (
+        "int-to-ascii",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Build the string from its digits with a fold",
+        None,
+    ),
+    (
+        "int-to-utf8",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Build the string from its digits with a fold",
+        None,
+    ),
+    (
+        "buff-to-int-le",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Decode the bytes with a fold over the buffer",
+        None,
+    ),
+    (
+        "buff-to-uint-le",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Decode the bytes with a fold over the buffer",
+        None,
+    ),
+    (
+        "buff-to-int-be",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Decode the bytes with a fold over the buffer",
+        None,
+    ),
+    (
+        "buff-to-uint-be",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Decode the bytes with a fold over the buffer",
+        None,
+    ),
+    (
+        "slice?",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Select the elements with a fold over the sequence",
+        None,
+    ),
+    (
+        "replace-at?",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Rebuild the sequence with a fold",
+        None,
+    ),
+    (
+        "element-at?",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Use element-at",
+        Some("element-at"),
+    ),
+    (
+        "index-of?",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Use index-of",
+        Some("index-of"),
+    ),
+    (
+        "to-consensus-buff?",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        NO_EQUIVALENT,
+        None,
+    ),
+    (
+        "from-consensus-buff?",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        NO_EQUIVALENT,
+        None,
+    ),
+    (
+        "get-burn-block-info?",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Use get-block-info? with burnchain-header-hash",
+        None,
+    ),
+    (
+        "bit-and",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        NO_EQUIVALENT,
+        None,
+    ),
+    (
+        "bit-or",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        NO_EQUIVALENT,
+        None,
+    ),
+    (
+        "bit-xor",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Use xor",
+        None,
+    ),
+    (
+        "bit-not",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Subtract the value from -1",
+        None,
+    ),
+    (
+        "bit-shift-left",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Multiply by a power of two, with pow",
+        None,
+    ),
+    (
+        "bit-shift-right",
+        Form::Function,
+        ClarityVersion::Clarity2,
+        "Divide by a power of two, with pow",
+        None,
+    ),
+    (
+        "chain-id",
+        Form::Keyword,
+        ClarityVersion::Clarity2,
+        "Use a constant holding the chain id of the network",
+        None,
+    ),
+    (
+        "tx-sponsor?",
+        Form::Keyword,
+        ClarityVersion::Clarity2,
+        NO_EQUIVALENT,
+        None,
+    ),
+    (
+        "is-in-mainnet",
+        Form::Keyword,
+        ClarityVersion::Clarity2,
+        "Use a constant holding whether the contract targets the mainnet",
+        None,
+    ),
+    (
+        "trait reference nested in a type",
+        Form::Syntax,
+        ClarityVersion::Clarity2,
+        "Pass each trait reference as a parameter of its own",
+        None,
+    ),
+];
+
+fn entry(
+    (name, form, version, hint, replaces): &(
+        &'static str,
+        Form,
+        ClarityVersion,
+        &'static str,
+        Option<&'static str>,
+    ),
+) -> Versioned {
+    Versioned {
+        name,
+        form: *form,
+        version: *version,
+        hint,
+        replaces: *replaces,
+    }
+}
+
+// The element of the given form introduced under this name.
+pub fn introduced(name: &str, form: Form) -> Option<Versioned> {
+    VERSIONED
+        .iter()
+        .find(|(versioned, versioned_form, _, _, _)| *versioned == name && *versioned_form == form)
+        .map(entry)
+}
+
+// The name, function or keyword, reserved from its version.
+pub fn reserved(name: &str) -> Option<Versioned> {
+    VERSIONED
+        .iter()
+        .find(|(versioned, form, _, _, _)| *versioned == name && *form != Form::Syntax)
+        .map(entry)
+}
+
+// The element replacing a deprecated name.
+pub fn replacing(old_name: &str) -> Option<Versioned> {
+    VERSIONED
+        .iter()
+        .find(|(_, _, _, _, replaces)| *replaces == Some(old_name))
+        .map(entry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn unique_names() {
+        let names: HashSet<&str> = VERSIONED.iter().map(|(name, _, _, _, _)| *name).collect();
+        assert_eq!(names.len(), VERSIONED.len());
+        for (_, _, _, _, replaces) in VERSIONED.iter() {
+            if let Some(replaced) = replaces {
+                assert!(!names.contains(replaced));
+            }
+        }
+    }
+}
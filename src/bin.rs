@@ -24,13 +24,14 @@ pub mod contracts;
 pub mod frontend;
 pub mod repl;
 
-use frontend::Terminal;
+use frontend::{Stream, Terminal};
 use pico_args::Arguments;
 use repl::{settings, Session, SessionSettings};
 use std::env;
 
 fn main() {
     let mut args = Arguments::from_env();
+    let stream = args.contains("--stream");
     let subcommand = args.subcommand().unwrap().unwrap_or_default();
     let code = args.subcommand().unwrap();
 
@@ -55,6 +56,10 @@ fn main() {
                 println!("{}", line);
             }
         }
+        None if stream => {
+            let mut stream = Stream::new(settings);
+            stream.start();
+        }
         None => {
             let mut terminal = Terminal::new(settings);
             terminal.start();
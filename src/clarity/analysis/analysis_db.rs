@@ -1,7 +1,8 @@
 use crate::clarity::analysis::errors::{CheckError, CheckErrors, CheckResult};
 use crate::clarity::analysis::type_checker::ContractAnalysis;
 use crate::clarity::database::{
-    ClarityBackingStore, ClarityDeserializable, ClaritySerializable, Datastore, RollbackWrapper,
+    ClarityBackingStore, ClarityDatabase, ClarityDeserializable, ClaritySerializable, Datastore,
+    RollbackWrapper, StoreType,
 };
 use crate::clarity::representations::ClarityName;
 use crate::clarity::types::signatures::FunctionSignature;
@@ -82,6 +83,18 @@ impl<'a> AnalysisDatabase<'a> {
             .map(|x| ContractAnalysis::deserialize(&x))
     }
 
+    // The source of a deployed contract, if it was stored with it.
+    pub fn get_contract_src(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+    ) -> Option<String> {
+        let key = ClarityDatabase::make_metadata_key(StoreType::Contract, "contract-src");
+        self.store
+            .get_metadata(contract_identifier, &key)
+            .ok()
+            .flatten()
+    }
+
     pub fn insert_contract(
         &mut self,
         contract_identifier: &QualifiedContractIdentifier,
@@ -27,6 +27,7 @@ pub fn build_contract_interface(contract_analysis: &ContractAnalysis) -> Contrac
         contract_interface: _,
         is_cost_contract_eligible: _,
         dependencies: _,
+        test_mode: _,
     } = contract_analysis;
 
     contract_interface
@@ -36,6 +36,7 @@ pub fn type_check(
         analysis_db,
         insert_contract,
         LimitedCostTracker::new_free(),
+        false,
     )
     .map_err(|(e, _cost_tracker)| e)
 }
@@ -46,12 +47,14 @@ pub fn run_analysis(
     analysis_db: &mut AnalysisDatabase,
     save_contract: bool,
     cost_tracker: LimitedCostTracker,
+    test_mode: bool,
 ) -> Result<ContractAnalysis, (CheckError, LimitedCostTracker)> {
     let mut contract_analysis = ContractAnalysis::new(
         contract_identifier.clone(),
         expressions.to_vec(),
         cost_tracker,
     );
+    contract_analysis.test_mode = test_mode;
     let result = analysis_db.execute(|db| {
         ReadOnlyChecker::run_pass(&mut contract_analysis, db)?;
         TypeChecker::run_pass(&mut contract_analysis, db)?;
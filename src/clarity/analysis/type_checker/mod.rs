@@ -12,6 +12,7 @@ use crate::clarity::representations::SymbolicExpressionType::{
     Atom, AtomValue, Field, List, LiteralValue, TraitReference,
 };
 use crate::clarity::representations::{depth_traverse, ClarityName, SymbolicExpression};
+use crate::clarity::test_natives;
 use crate::clarity::types::signatures::{FunctionSignature, BUFF_20};
 use crate::clarity::types::{
     parse_name_type_pairs, FixedFunction, FunctionArg, FunctionType, PrincipalData,
@@ -56,6 +57,8 @@ pub struct TypeChecker<'a, 'b> {
     function_return_tracker: Option<Option<TypeSignature>>,
     db: &'a mut AnalysisDatabase<'b>,
     pub cost_track: LimitedCostTracker,
+    // Whether the test natives can be called
+    test_mode: bool,
 }
 
 impl CostTracker for TypeChecker<'_, '_> {
@@ -96,7 +99,7 @@ impl AnalysisPass for TypeChecker<'_, '_> {
         analysis_db: &mut AnalysisDatabase,
     ) -> CheckResult<()> {
         let cost_track = contract_analysis.take_contract_cost_tracker();
-        let mut command = TypeChecker::new(analysis_db, cost_track);
+        let mut command = TypeChecker::new(analysis_db, cost_track, contract_analysis.test_mode);
         // run the analysis, and replace the cost tracker whether or not the
         //   analysis succeeded.
         match command.run(contract_analysis) {
@@ -296,10 +299,12 @@ impl<'a, 'b> TypeChecker<'a, 'b> {
     fn new(
         db: &'a mut AnalysisDatabase<'b>,
         cost_track: LimitedCostTracker,
+        test_mode: bool,
     ) -> TypeChecker<'a, 'b> {
         Self {
             db,
             cost_track,
+            test_mode,
             contract_context: ContractContext::new(),
             function_return_tracker: None,
             type_map: TypeMap::new(),
@@ -595,6 +600,20 @@ impl<'a, 'b> TypeChecker<'a, 'b> {
         }
     }
 
+    // The functions defined by the contract shadow the test natives.
+    fn try_test_function_check(
+        &mut self,
+        function: &str,
+        args: &[SymbolicExpression],
+        context: &TypingContext,
+    ) -> Option<TypeResult> {
+        if self.test_mode && self.get_function_type(function).is_none() {
+            test_natives::check_test_function(self, function, args, context)
+        } else {
+            None
+        }
+    }
+
     fn type_check_function_application(
         &mut self,
         expression: &[SymbolicExpression],
@@ -611,6 +630,9 @@ impl<'a, 'b> TypeChecker<'a, 'b> {
 
         if let Some(type_result) = self.try_native_function_check(function_name, args, context) {
             type_result
+        } else if let Some(type_result) = self.try_test_function_check(function_name, args, context)
+        {
+            type_result
         } else {
             let function = match self.get_function_type(function_name) {
                 Some(FunctionType::Fixed(function)) => Ok(function),
@@ -45,6 +45,9 @@ pub struct ContractAnalysis {
     pub type_map: Option<TypeMap>,
     #[serde(skip)]
     pub cost_track: Option<LimitedCostTracker>,
+    // Whether the test natives can be called
+    #[serde(skip)]
+    pub test_mode: bool,
 }
 
 impl ContractAnalysis {
@@ -71,6 +74,7 @@ impl ContractAnalysis {
             cost_track: Some(cost_track),
             is_cost_contract_eligible: false,
             dependencies: Vec::new(),
+            test_mode: false,
         }
     }
 
@@ -12,6 +12,7 @@ use crate::clarity::representations::{
     ClarityName, PreSymbolicExpression, SymbolicExpression, TraitDefinition,
 };
 use crate::clarity::types::{QualifiedContractIdentifier, TraitIdentifier, Value};
+use crate::clarity::util::closest_match;
 
 pub struct TraitsResolver {}
 
@@ -149,6 +150,10 @@ impl TraitsResolver {
                     trait_reference.to_string(),
                 ));
                 err.set_pre_expression(&expr);
+                let declared = contract_ast.referenced_traits.keys().map(|t| t.as_str());
+                if let Some(candidate) = closest_match(&trait_reference, declared) {
+                    err.diagnostic.suggestion = Some(format!("did you mean <{}>?", candidate));
+                }
                 return Err(err.into());
             }
         }
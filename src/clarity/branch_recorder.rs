@@ -0,0 +1,31 @@
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::types::QualifiedContractIdentifier;
+use std::collections::HashMap;
+
+// Counts the evaluations of the expressions of a contract during a call, to
+// tell which branches of its conditionals were taken.
+#[derive(Debug, Clone)]
+pub struct BranchRecorder {
+    pub contract_identifier: QualifiedContractIdentifier,
+    // The number of evaluations of each expression, by id
+    pub evaluations: HashMap<u64, u64>,
+}
+
+impl BranchRecorder {
+    pub fn new(contract_identifier: QualifiedContractIdentifier) -> BranchRecorder {
+        BranchRecorder {
+            contract_identifier,
+            evaluations: HashMap::new(),
+        }
+    }
+
+    pub fn record(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+        expr: &SymbolicExpression,
+    ) {
+        if *contract_identifier == self.contract_identifier {
+            *self.evaluations.entry(expr.id).or_default() += 1;
+        }
+    }
+}
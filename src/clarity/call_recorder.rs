@@ -0,0 +1,46 @@
+use crate::clarity::types::{PrincipalData, QualifiedContractIdentifier, Value};
+use crate::clarity::ClarityName;
+
+// Captures the next invocation of a given function, with enough context to
+// replay it in isolation.
+#[derive(Debug, Clone)]
+pub struct CallRecorder {
+    pub contract_identifier: QualifiedContractIdentifier,
+    pub function_name: ClarityName,
+    pub recorded_call: Option<RecordedCall>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordedCall {
+    pub contract_identifier: QualifiedContractIdentifier,
+    pub function_name: ClarityName,
+    pub args: Vec<Value>,
+    pub sender: Option<PrincipalData>,
+    pub caller: Option<PrincipalData>,
+    // Raw values of the keys read from the datastore during the call, or None
+    // for the keys which were absent.
+    pub reads: Vec<(String, Option<String>)>,
+}
+
+impl CallRecorder {
+    pub fn new(
+        contract_identifier: QualifiedContractIdentifier,
+        function_name: ClarityName,
+    ) -> CallRecorder {
+        CallRecorder {
+            contract_identifier,
+            function_name,
+            recorded_call: None,
+        }
+    }
+
+    pub fn is_armed_for(
+        &self,
+        contract_identifier: &QualifiedContractIdentifier,
+        function_name: &str,
+    ) -> bool {
+        self.recorded_call.is_none()
+            && &self.contract_identifier == contract_identifier
+            && self.function_name.as_str() == function_name
+    }
+}
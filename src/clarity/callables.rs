@@ -8,6 +8,7 @@ use crate::clarity::events::StacksTransactionEvent;
 use crate::clarity::costs::{cost_functions, runtime_cost};
 
 use crate::clarity::analysis::errors::CheckErrors;
+use crate::clarity::call_recorder::RecordedCall;
 use crate::clarity::contexts::ContractContext;
 use crate::clarity::costs::cost_functions::ClarityCostFunction;
 use crate::clarity::errors::{check_argument_count, Error, InterpreterResult as Result};
@@ -158,14 +159,49 @@ impl DefinedFunction {
             }
         }
 
+        let recording = match env.global_context.call_recorder {
+            Some(ref mut recorder)
+                if recorder.is_armed_for(&env.contract_context.contract_identifier, &self.name) =>
+            {
+                recorder.recorded_call = Some(RecordedCall {
+                    contract_identifier: env.contract_context.contract_identifier.clone(),
+                    function_name: self.name.clone(),
+                    args: args.to_vec(),
+                    sender: env.sender.clone(),
+                    caller: env.caller.clone(),
+                    reads: vec![],
+                });
+                env.global_context.database.store.record_reads();
+                true
+            }
+            _ => false,
+        };
+
         let result = eval(&self.body, env, &context);
 
+        if recording {
+            let reads = env.global_context.database.store.take_recorded_reads();
+            if let Some(recorded_call) = env
+                .global_context
+                .call_recorder
+                .as_mut()
+                .and_then(|recorder| recorder.recorded_call.as_mut())
+            {
+                recorded_call.reads = reads;
+            }
+        }
+
         // if the error wasn't actually an error, but a function return,
         //    pull that out and return it.
         match result {
             Ok(r) => Ok(r),
             Err(e) => match e {
-                Error::ShortReturn(v) => Ok(v.into()),
+                Error::ShortReturn(v) => {
+                    if let Some(recorder) = env.global_context.guard_failures.as_mut() {
+                        recorder.returned();
+                    }
+                    Ok(v.into())
+                }
                 _ => Err(e),
             },
         }
@@ -552,6 +552,7 @@ impl<'a> ClarityTransactionConnection<'a> {
                     db,
                     false,
                     cost_track,
+                    false,
                 );
 
                 match result {
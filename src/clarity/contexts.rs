@@ -5,8 +5,11 @@ use std::mem::replace;
 
 use crate::clarity::ast;
 use crate::clarity::ast::ContractAST;
+use crate::clarity::branch_recorder::BranchRecorder;
+use crate::clarity::call_recorder::CallRecorder;
 use crate::clarity::callables::{DefinedFunction, FunctionIdentifier};
 use crate::clarity::contracts::Contract;
+use crate::clarity::cost_tree::CostTreeRecorder;
 use crate::clarity::costs::cost_functions::ClarityCostFunction;
 use crate::clarity::costs::{
     cost_functions, runtime_cost, CostErrors, CostTracker, ExecutionCost, LimitedCostTracker,
@@ -19,14 +22,17 @@ use crate::clarity::database::ClarityDatabase;
 use crate::clarity::errors::{
     CheckErrors, InterpreterError, InterpreterResult as Result, RuntimeErrorType,
 };
+use crate::clarity::guard_failures::GuardFailureRecorder;
+use crate::clarity::let_bindings::LetBindingsCapture;
+use crate::clarity::native_hooks::NativeCallHooks;
 use crate::clarity::representations::{ClarityName, ContractName, SymbolicExpression};
 use crate::clarity::stx_transfer_consolidated;
 use crate::clarity::types::signatures::FunctionSignature;
 use crate::clarity::types::{
-    AssetIdentifier, PrincipalData, QualifiedContractIdentifier, TraitIdentifier, TypeSignature,
-    Value,
+    AssetIdentifier, BlockInfoProperty, PrincipalData, QualifiedContractIdentifier,
+    TraitIdentifier, TypeSignature, Value, MAX_VALUE_SIZE,
 };
-use crate::clarity::{eval, is_reserved};
+use crate::clarity::{eval, is_reserved, MAX_CALL_STACK_DEPTH};
 
 use crate::clarity::events::*;
 use crate::clarity::StacksBlockId;
@@ -35,6 +41,31 @@ use serde::Serialize;
 
 pub const MAX_CONTEXT_DEPTH: u16 = 256;
 
+// The bounds on the repetitions of an evaluation. The defaults are those of
+// consensus.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IterationLimits {
+    // The most elements a `fold`, `map` or `filter` iterates over. By
+    // consensus, the only bound is the length of the sequences, which cannot
+    // hold more elements than the size of the largest value
+    pub max_iterations: u32,
+    // The most calls which can be nested
+    pub max_call_stack_depth: usize,
+    // The most `contract-call?` which can be nested. By consensus, the only
+    // bound is the depth of the call stack
+    pub max_contract_call_depth: usize,
+}
+
+impl Default for IterationLimits {
+    fn default() -> Self {
+        IterationLimits {
+            max_iterations: MAX_VALUE_SIZE,
+            max_call_stack_depth: MAX_CALL_STACK_DEPTH,
+            max_contract_call_depth: MAX_CALL_STACK_DEPTH,
+        }
+    }
+}
+
 // TODO:
 //    hide the environment's instance variables.
 //     we don't want many of these changing after instantiation.
@@ -75,6 +106,8 @@ pub struct AssetMap {
 #[derive(Debug, Clone)]
 pub struct EventBatch {
     pub events: Vec<StacksTransactionEvent>,
+    // The contract executing when each of the events was emitted
+    pub contracts: Vec<QualifiedContractIdentifier>,
 }
 
 /** GlobalContext represents the outermost context for a single transaction's
@@ -92,6 +125,21 @@ pub struct GlobalContext<'a> {
     pub mainnet: bool,
     pub coverage_reporting: Option<TestCoverageReport>,
     pub costs_reporting: Option<CostsReport>,
+    pub call_recorder: Option<CallRecorder>,
+    pub let_bindings: Option<LetBindingsCapture>,
+    pub guard_failures: Option<GuardFailureRecorder>,
+    pub branch_recorder: Option<BranchRecorder>,
+    pub cost_tree: Option<CostTreeRecorder>,
+    pub native_call_hooks: NativeCallHooks,
+    // Cap on the cumulative size of the values constructed, and the current total.
+    pub max_value_allocation: Option<u64>,
+    pub value_allocation: u64,
+    pub iteration_limits: IterationLimits,
+    // The values returned by `get-block-info?` for a height and property,
+    // instead of those of the block
+    pub block_info_mocks: HashMap<(u32, BlockInfoProperty), Value>,
+    // Whether the test natives can be called
+    pub test_mode: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -420,7 +468,10 @@ impl fmt::Display for AssetMap {
 
 impl EventBatch {
     pub fn new() -> EventBatch {
-        EventBatch { events: vec![] }
+        EventBatch {
+            events: vec![],
+            contracts: vec![],
+        }
     }
 }
 
@@ -823,6 +874,13 @@ impl<'a, 'b> Environment<'a, 'b> {
             if self.call_stack.contains(&func_identifier) {
                 return Err(CheckErrors::CircularReference(vec![func_identifier.to_string()]).into())
             }
+            // The contracts called, from the one called by the transaction
+            let mut chain = self.call_stack.contract_chain();
+            chain.push(contract_identifier.to_string());
+            let max_contract_call_depth = self.global_context.iteration_limits.max_contract_call_depth;
+            if chain.len() > max_contract_call_depth {
+                return Err(RuntimeErrorType::ContractCallDepthExceeded(chain, max_contract_call_depth).into())
+            }
             self.call_stack.insert(&func_identifier, true);
             let res = self.execute_function_as_transaction(&func, &args, Some(&contract.contract_context));
             self.call_stack.remove(&func_identifier, true)?;
@@ -1005,6 +1063,15 @@ impl<'a, 'b> Environment<'a, 'b> {
         }
     }
 
+    fn push_event(&mut self, event: StacksTransactionEvent) {
+        if let Some(batch) = self.global_context.event_batches.last_mut() {
+            batch.events.push(event);
+            batch
+                .contracts
+                .push(self.contract_context.contract_identifier.clone());
+        }
+    }
+
     pub fn register_print_event(&mut self, value: Value) -> Result<()> {
         let print_event = SmartContractEventData {
             key: (
@@ -1014,11 +1081,7 @@ impl<'a, 'b> Environment<'a, 'b> {
             value,
         };
 
-        if let Some(batch) = self.global_context.event_batches.last_mut() {
-            batch
-                .events
-                .push(StacksTransactionEvent::SmartContractEvent(print_event));
-        }
+        self.push_event(StacksTransactionEvent::SmartContractEvent(print_event));
         Ok(())
     }
 
@@ -1034,22 +1097,18 @@ impl<'a, 'b> Environment<'a, 'b> {
             amount,
         };
 
-        if let Some(batch) = self.global_context.event_batches.last_mut() {
-            batch.events.push(StacksTransactionEvent::STXEvent(
-                STXEventType::STXTransferEvent(event_data),
-            ));
-        }
+        self.push_event(StacksTransactionEvent::STXEvent(
+            STXEventType::STXTransferEvent(event_data),
+        ));
         Ok(())
     }
 
     pub fn register_stx_burn_event(&mut self, sender: PrincipalData, amount: u128) -> Result<()> {
         let event_data = STXBurnEventData { sender, amount };
 
-        if let Some(batch) = self.global_context.event_batches.last_mut() {
-            batch.events.push(StacksTransactionEvent::STXEvent(
-                STXEventType::STXBurnEvent(event_data),
-            ));
-        }
+        self.push_event(StacksTransactionEvent::STXEvent(
+            STXEventType::STXBurnEvent(event_data),
+        ));
         Ok(())
     }
 
@@ -1067,11 +1126,9 @@ impl<'a, 'b> Environment<'a, 'b> {
             value,
         };
 
-        if let Some(batch) = self.global_context.event_batches.last_mut() {
-            batch.events.push(StacksTransactionEvent::NFTEvent(
-                NFTEventType::NFTTransferEvent(event_data),
-            ));
-        }
+        self.push_event(StacksTransactionEvent::NFTEvent(
+            NFTEventType::NFTTransferEvent(event_data),
+        ));
         Ok(())
     }
 
@@ -1087,11 +1144,9 @@ impl<'a, 'b> Environment<'a, 'b> {
             value,
         };
 
-        if let Some(batch) = self.global_context.event_batches.last_mut() {
-            batch.events.push(StacksTransactionEvent::NFTEvent(
-                NFTEventType::NFTMintEvent(event_data),
-            ));
-        }
+        self.push_event(StacksTransactionEvent::NFTEvent(
+            NFTEventType::NFTMintEvent(event_data),
+        ));
         Ok(())
     }
 
@@ -1107,11 +1162,9 @@ impl<'a, 'b> Environment<'a, 'b> {
             value,
         };
 
-        if let Some(batch) = self.global_context.event_batches.last_mut() {
-            batch.events.push(StacksTransactionEvent::NFTEvent(
-                NFTEventType::NFTBurnEvent(event_data),
-            ));
-        }
+        self.push_event(StacksTransactionEvent::NFTEvent(
+            NFTEventType::NFTBurnEvent(event_data),
+        ));
         Ok(())
     }
 
@@ -1129,11 +1182,9 @@ impl<'a, 'b> Environment<'a, 'b> {
             amount,
         };
 
-        if let Some(batch) = self.global_context.event_batches.last_mut() {
-            batch.events.push(StacksTransactionEvent::FTEvent(
-                FTEventType::FTTransferEvent(event_data),
-            ));
-        }
+        self.push_event(StacksTransactionEvent::FTEvent(
+            FTEventType::FTTransferEvent(event_data),
+        ));
         Ok(())
     }
 
@@ -1149,13 +1200,9 @@ impl<'a, 'b> Environment<'a, 'b> {
             amount,
         };
 
-        if let Some(batch) = self.global_context.event_batches.last_mut() {
-            batch
-                .events
-                .push(StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(
-                    event_data,
-                )));
-        }
+        self.push_event(StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(
+            event_data,
+        )));
         Ok(())
     }
 
@@ -1171,13 +1218,9 @@ impl<'a, 'b> Environment<'a, 'b> {
             amount,
         };
 
-        if let Some(batch) = self.global_context.event_batches.last_mut() {
-            batch
-                .events
-                .push(StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(
-                    event_data,
-                )));
-        }
+        self.push_event(StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(
+            event_data,
+        )));
         Ok(())
     }
 }
@@ -1198,6 +1241,17 @@ impl<'a> GlobalContext<'a> {
             mainnet,
             coverage_reporting: None,
             costs_reporting: None,
+            call_recorder: None,
+            let_bindings: None,
+            guard_failures: None,
+            branch_recorder: None,
+            cost_tree: None,
+            native_call_hooks: NativeCallHooks::default(),
+            max_value_allocation: None,
+            value_allocation: 0,
+            iteration_limits: IterationLimits::default(),
+            block_info_mocks: HashMap::new(),
+            test_mode: false,
         }
     }
 
@@ -1307,6 +1361,7 @@ impl<'a> GlobalContext<'a> {
         let out_batch = match self.event_batches.last_mut() {
             Some(tail_back) => {
                 tail_back.events.append(&mut event_batch.events);
+                tail_back.contracts.append(&mut event_batch.contracts);
                 None
             }
             None => Some(event_batch),
@@ -1476,6 +1531,23 @@ impl CallStack {
         self.set.contains(function)
     }
 
+    // The contracts calling another one through the `contract-call?` being
+    // evaluated, outermost first.
+    pub fn contract_chain(&self) -> Vec<String> {
+        let mut chain = vec![];
+        let mut contract = None;
+        for function in self.stack.iter() {
+            match function.identifier.rsplit_once(':') {
+                Some(("_native_", "special_contract-call")) => {
+                    chain.extend(contract.map(str::to_string));
+                }
+                Some(("_native_", _)) | None => {}
+                Some((caller, _)) => contract = Some(caller),
+            }
+        }
+        chain
+    }
+
     pub fn insert(&mut self, function: &FunctionIdentifier, track: bool) {
         self.stack.push(function.clone());
         if track {
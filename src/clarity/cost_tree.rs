@@ -0,0 +1,72 @@
+use crate::clarity::costs::ExecutionCost;
+use crate::clarity::representations::{Span, SymbolicExpression};
+
+// A call to a user-defined function, or a `contract-call?`, with the cost of
+// its evaluation, including the calls it made.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostTreeNode {
+    pub function: String,
+    pub span: Span,
+    pub cost: ExecutionCost,
+    pub children: Vec<CostTreeNode>,
+}
+
+// Records the calls made during an evaluation as a tree, from the total cost
+// of the tracker when each call is entered and exited.
+#[derive(Debug, Clone, Default)]
+pub struct CostTreeRecorder {
+    // The calls being evaluated, with the total cost when they were entered
+    stack: Vec<(CostTreeNode, ExecutionCost)>,
+    pub roots: Vec<CostTreeNode>,
+}
+
+// The name of a call in the tree. A `contract-call?` is named with the
+// contract and function it calls, like `contract-call? .token transfer`.
+pub fn call_name(function_name: &str, args: &[SymbolicExpression]) -> String {
+    if function_name != "contract-call?" {
+        return function_name.to_string();
+    }
+    let contract = match args.first() {
+        Some(contract) => match (contract.match_literal_value(), contract.match_atom()) {
+            (Some(value), _) => value.to_string(),
+            (None, Some(name)) => name.to_string(),
+            _ => return function_name.to_string(),
+        },
+        None => return function_name.to_string(),
+    };
+    match args.get(1).and_then(|method| method.match_atom()) {
+        Some(method) => format!("contract-call? {} {}", contract, method),
+        None => format!("contract-call? {}", contract),
+    }
+}
+
+impl CostTreeRecorder {
+    pub fn new() -> CostTreeRecorder {
+        CostTreeRecorder::default()
+    }
+
+    pub fn enter(&mut self, function: String, span: Span, total: ExecutionCost) {
+        let node = CostTreeNode {
+            function,
+            span,
+            cost: ExecutionCost::zero(),
+            children: vec![],
+        };
+        self.stack.push((node, total));
+    }
+
+    // Exit the last call entered, whether it succeeded or not.
+    pub fn exit(&mut self, total: ExecutionCost) {
+        let (mut node, entered) = match self.stack.pop() {
+            Some(call) => call,
+            None => return,
+        };
+        node.cost = total;
+        // The total cannot decrease during a call
+        let _ = node.cost.sub(&entered);
+        match self.stack.last_mut() {
+            Some((parent, _)) => parent.children.push(node),
+            None => self.roots.push(node),
+        }
+    }
+}
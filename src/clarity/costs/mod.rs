@@ -1,7 +1,6 @@
 pub mod constants;
 pub mod cost_functions;
 
-use regex::internal::Exec;
 use std::convert::{TryFrom, TryInto};
 use std::{cmp, fmt};
 
@@ -934,6 +933,27 @@ impl ExecutionCost {
         .clone()
     }
 
+    /// Returns the fraction of `block_limits` this cost fills, as a single
+    /// figure: the largest of the ratios of each dimension to its limit,
+    /// since the dimension filling up first bounds how many such calls a
+    /// block can hold. A dimension with no limit only counts if it is used.
+    pub fn to_scalar(&self, block_limits: &ExecutionCost) -> f64 {
+        [
+            (self.runtime, block_limits.runtime),
+            (self.write_length, block_limits.write_length),
+            (self.write_count, block_limits.write_count),
+            (self.read_length, block_limits.read_length),
+            (self.read_count, block_limits.read_count),
+        ]
+        .iter()
+        .map(|(consumed, limit)| match (consumed, limit) {
+            (0, _) => 0.0,
+            (_, 0) => f64::INFINITY,
+            _ => *consumed as f64 / *limit as f64,
+        })
+        .fold(0.0, f64::max)
+    }
+
     pub fn max_value() -> ExecutionCost {
         Self {
             runtime: u64::max_value(),
@@ -1017,3 +1037,31 @@ fn int_log2(input: u64) -> Option<u64> {
         }
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cost_fullness() {
+        let limit = ExecutionCost {
+            runtime: 1000,
+            write_length: 100,
+            write_count: 10,
+            read_length: 100,
+            read_count: 0,
+        };
+        let cost = ExecutionCost {
+            runtime: 100,
+            write_length: 50,
+            ..ExecutionCost::zero()
+        };
+        assert_eq!(cost.to_scalar(&limit), 0.5);
+        assert_eq!(ExecutionCost::zero().to_scalar(&limit), 0.0);
+        let read = ExecutionCost {
+            read_count: 1,
+            ..ExecutionCost::zero()
+        };
+        assert_eq!(read.to_scalar(&limit), f64::INFINITY);
+    }
+}
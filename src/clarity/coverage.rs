@@ -2,17 +2,24 @@ use std::{
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs::File,
     io::Write,
+    iter::FromIterator,
 };
 
 use crate::clarity::ast::ContractAST;
 use crate::clarity::functions::define::DefineFunctionsParsed;
 use crate::clarity::representations::SymbolicExpression;
 use crate::clarity::types::QualifiedContractIdentifier;
+use serde::de::Error as de_Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value as JsonValue;
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct CoverageReporter {
     pub reports: Vec<TestCoverageReport>,
+    #[serde(
+        serialize_with = "contract_map_serialize",
+        deserialize_with = "contract_map_deserialize"
+    )]
     pub asts: BTreeMap<QualifiedContractIdentifier, ContractAST>,
     pub contract_paths: BTreeMap<String, String>,
 }
@@ -20,6 +27,10 @@ pub struct CoverageReporter {
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct TestCoverageReport {
     test_name: String,
+    #[serde(
+        serialize_with = "contract_map_serialize",
+        deserialize_with = "contract_map_deserialize"
+    )]
     contracts_coverage: HashMap<QualifiedContractIdentifier, ContractCoverageReport>,
 }
 
@@ -33,6 +44,36 @@ pub struct ContractCoverageReport {
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 pub struct CostsReport {}
 
+// The maps keyed by contract are written as JSON objects keyed by contract id,
+// as JSON keys can only be strings.
+fn contract_map_serialize<'a, M, V, S>(map: &'a M, s: S) -> Result<S::Ok, S::Error>
+where
+    &'a M: IntoIterator<Item = (&'a QualifiedContractIdentifier, &'a V)>,
+    V: Serialize + 'a,
+    S: Serializer,
+{
+    s.collect_map(
+        map.into_iter()
+            .map(|(contract_id, value)| (contract_id.to_string(), value)),
+    )
+}
+
+fn contract_map_deserialize<'de, M, V, D>(d: D) -> Result<M, D::Error>
+where
+    M: FromIterator<(QualifiedContractIdentifier, V)>,
+    V: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    BTreeMap::<String, V>::deserialize(d)?
+        .into_iter()
+        .map(|(contract_id, value)| {
+            QualifiedContractIdentifier::parse(&contract_id)
+                .map(|contract_id| (contract_id, value))
+                .map_err(de_Error::custom)
+        })
+        .collect()
+}
+
 impl CoverageReporter {
     pub fn new() -> CoverageReporter {
         CoverageReporter {
@@ -54,6 +95,30 @@ impl CoverageReporter {
         self.reports.append(&mut reports.clone());
     }
 
+    // Merge the coverage recorded by another session, like the one exported
+    // by each test of a suite. The reports of a test found in both are merged
+    // into one, so that the test is listed once, with the hits of both.
+    pub fn merge(&mut self, other: CoverageReporter) {
+        for report in other.reports.into_iter() {
+            match self
+                .reports
+                .iter_mut()
+                .find(|existing| existing.test_name == report.test_name)
+            {
+                Some(existing) => existing.merge(&report),
+                None => self.reports.push(report),
+            }
+        }
+        for (contract_id, ast) in other.asts.into_iter() {
+            self.asts.entry(contract_id).or_insert(ast);
+        }
+        for (contract_name, contract_path) in other.contract_paths.into_iter() {
+            self.contract_paths
+                .entry(contract_name)
+                .or_insert(contract_path);
+        }
+    }
+
     pub fn write_lcov_file<P: AsRef<std::path::Path> + Copy>(
         &self,
         filename: P,
@@ -257,6 +322,15 @@ impl TestCoverageReport {
         self.contracts_coverage
             .insert(contract.clone(), contract_report);
     }
+
+    pub fn merge(&mut self, other: &TestCoverageReport) {
+        for (contract, other_report) in other.contracts_coverage.iter() {
+            self.contracts_coverage
+                .entry(contract.clone())
+                .or_default()
+                .merge(other_report);
+        }
+    }
 }
 
 impl ContractCoverageReport {
@@ -284,4 +358,64 @@ impl ContractCoverageReport {
         }
         self.executed_statements.insert(expr.id);
     }
+
+    // The hits are summed, while the statements executed are only unioned, as
+    // the same statement is executed in both.
+    pub fn merge(&mut self, other: &ContractCoverageReport) {
+        for (function, count) in other.functions_coverage.iter() {
+            *self.functions_coverage.entry(function.clone()).or_insert(0) += count;
+        }
+        for (line, count) in other.execution_counts.iter() {
+            *self.execution_counts.entry(*line).or_insert(0) += count;
+        }
+        self.executed_statements
+            .extend(other.executed_statements.iter().cloned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clarity::representations::Span;
+
+    fn atom(id: u64, line: u32) -> SymbolicExpression {
+        let mut expr = SymbolicExpression::atom("x".into());
+        expr.id = id;
+        expr.span = Span {
+            start_line: line,
+            start_column: 1,
+            end_line: line,
+            end_column: 1,
+        };
+        expr
+    }
+
+    #[test]
+    fn merge_reports() {
+        let contract = QualifiedContractIdentifier::transient();
+        let mut first = CoverageReporter::new();
+        let mut report = TestCoverageReport::new("incr".to_string());
+        report.report_eval(&contract, &atom(1, 2));
+        report.report_eval(&contract, &atom(2, 3));
+        first.add_reports(&vec![report]);
+
+        let mut second = CoverageReporter::new();
+        let mut report = TestCoverageReport::new("incr".to_string());
+        report.report_eval(&contract, &atom(1, 2));
+        let mut other = TestCoverageReport::new("decr".to_string());
+        other.report_eval(&contract, &atom(3, 4));
+        second.add_reports(&vec![report, other]);
+        second.register_contract("counter".to_string(), "counter.clar".to_string());
+
+        first.merge(second);
+        assert_eq!(first.reports.len(), 2);
+        assert_eq!(first.contract_paths["counter"], "counter.clar");
+        let merged = &first.reports[0].contracts_coverage[&contract];
+        assert_eq!(merged.execution_counts[&2], 2);
+        assert_eq!(merged.execution_counts[&3], 1);
+        assert_eq!(
+            merged.executed_statements,
+            vec![1, 2].into_iter().collect::<BTreeSet<u64>>()
+        );
+    }
 }
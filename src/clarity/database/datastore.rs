@@ -8,7 +8,7 @@ use crate::clarity::errors::{
 use crate::clarity::types::QualifiedContractIdentifier;
 use crate::clarity::util::hash::Sha512Trunc256Sum;
 use crate::clarity::StacksBlockId;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::hash::Hash;
 
 #[derive(Clone, Debug)]
@@ -106,7 +106,9 @@ impl ClarityBackingStore for Datastore {
     ///   used to implement time-shifted evaluation.
     /// returns the previous block header hash on success
     fn set_block_hash(&mut self, bhh: StacksBlockId) -> Result<StacksBlockId> {
-        let prior_tip = self.open_chain_tip;
+        // The tip reads were served from, so that nested time-shifted
+        // evaluations restore their outer block
+        let prior_tip = self.current_chain_tip;
         self.current_chain_tip = bhh;
         Ok(prior_tip)
     }
@@ -226,6 +228,42 @@ impl Datastore {
     }
 
     pub fn put(&mut self, key: &str, value: &str) {
+        self.open_block_store()
+            .insert(key.to_string(), value.to_string());
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.open_block_store().remove(key)
+    }
+
+    // The entries of the open chain tip whose key starts with `prefix`.
+    pub fn get_entries_with_prefix(&self, prefix: &str) -> BTreeMap<String, String> {
+        let lookup_id = self
+            .block_id_lookup
+            .get(&self.open_chain_tip)
+            .expect("Could not find current chain tip in block_id_lookup map");
+        match self.store.get(lookup_id) {
+            Some(map) => map
+                .iter()
+                .filter(|(key, _)| key.starts_with(prefix))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            None => panic!("Block does not exist for current chain tip"),
+        }
+    }
+
+    // Replace the entries of the open chain tip whose key starts with
+    // `prefix`, removing those which are not in `entries`.
+    pub fn set_entries_with_prefix(&mut self, prefix: &str, entries: &BTreeMap<String, String>) {
+        let map = self.open_block_store();
+        map.retain(|key, _| !key.starts_with(prefix));
+        for (key, value) in entries.iter() {
+            map.insert(key.clone(), value.clone());
+        }
+    }
+
+    // The entries of the open chain tip, which are written to.
+    fn open_block_store(&mut self) -> &mut HashMap<String, String> {
         let lookup_id = self
             .block_id_lookup
             .get(&self.open_chain_tip)
@@ -246,10 +284,9 @@ impl Datastore {
                 .insert(self.open_chain_tip, self.current_chain_tip);
         }
 
-        if let Some(map) = self.store.get_mut(&self.open_chain_tip) {
-            map.insert(key.to_string(), value.to_string());
-        } else {
-            panic!("Block does not exist for current chain tip");
+        match self.store.get_mut(&self.open_chain_tip) {
+            Some(map) => map,
+            None => panic!("Block does not exist for current chain tip"),
         }
     }
 
@@ -4,7 +4,7 @@ use crate::clarity::types::{QualifiedContractIdentifier, TypeSignature};
 use crate::clarity::util::hash::Sha512Trunc256Sum;
 use crate::clarity::StacksBlockId;
 use crate::clarity::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::{clone::Clone, cmp::Eq, hash::Hash};
 
 #[cfg(rollback_value_check)]
@@ -100,6 +100,11 @@ pub struct RollbackWrapper<'a> {
     //   to indicate a given contexts "start depth".
     stack: Vec<RollbackContext>,
     query_pending_data: bool,
+    // when set, the first value read for each key is recorded, or None if
+    //   the key was absent. keys written before being read are not recorded,
+    //   as their value does not come from the state the recording started from.
+    read_log: Option<Vec<(String, Option<String>)>>,
+    written_keys: HashSet<String>,
 }
 
 // This is used for preserving rollback data longer
@@ -170,6 +175,8 @@ impl<'a> RollbackWrapper<'a> {
             metadata_lookup_map: HashMap::new(),
             stack: Vec::new(),
             query_pending_data: true,
+            read_log: None,
+            written_keys: HashSet::new(),
         }
     }
 
@@ -183,6 +190,8 @@ impl<'a> RollbackWrapper<'a> {
             metadata_lookup_map: log.metadata_lookup_map,
             stack: log.stack,
             query_pending_data: true,
+            read_log: None,
+            written_keys: HashSet::new(),
         }
     }
 
@@ -276,6 +285,9 @@ impl<'a> RollbackWrapper<'a> {
             .last_mut()
             .expect("ERROR: Clarity VM attempted PUT on non-nested context.");
 
+        if self.read_log.is_some() {
+            self.written_keys.insert(key.to_string());
+        }
         inner_put(
             &mut self.lookup_map,
             &mut current.edits,
@@ -312,6 +324,10 @@ impl<'a> RollbackWrapper<'a> {
             .last()
             .expect("ERROR: Clarity VM attempted GET on non-nested context.");
 
+        if self.read_log.is_some() {
+            return self.get_and_record(key).map(|x| T::deserialize(&x));
+        }
+
         let lookup_result = if self.query_pending_data {
             self.lookup_map
                 .get(key)
@@ -329,6 +345,12 @@ impl<'a> RollbackWrapper<'a> {
             .last()
             .expect("ERROR: Clarity VM attempted GET on non-nested context.");
 
+        if self.read_log.is_some() {
+            return self
+                .get_and_record(key)
+                .map(|x| Value::deserialize(&x, expected));
+        }
+
         let lookup_result = if self.query_pending_data {
             self.lookup_map
                 .get(key)
@@ -345,6 +367,32 @@ impl<'a> RollbackWrapper<'a> {
         })
     }
 
+    fn get_and_record(&mut self, key: &str) -> Option<String> {
+        let value = if self.query_pending_data {
+            self.lookup_map.get(key).and_then(|x| x.last()).cloned()
+        } else {
+            None
+        }
+        .or_else(|| self.store.get(key));
+
+        if let Some(read_log) = self.read_log.as_mut() {
+            if !self.written_keys.contains(key) && !read_log.iter().any(|(k, _)| k == key) {
+                read_log.push((key.to_string(), value.clone()));
+            }
+        }
+        value
+    }
+
+    pub fn record_reads(&mut self) {
+        self.read_log = Some(vec![]);
+        self.written_keys.clear();
+    }
+
+    pub fn take_recorded_reads(&mut self) -> Vec<(String, Option<String>)> {
+        self.written_keys.clear();
+        self.read_log.take().unwrap_or_default()
+    }
+
     pub fn get_current_block_height(&mut self) -> u32 {
         self.store.get_current_block_height()
     }
@@ -6,7 +6,7 @@ pub mod structures;
 use std::collections::HashMap;
 
 pub use self::clarity_db::{
-    ClarityDatabase, HeadersDB, NULL_HEADER_DB, STORE_CONTRACT_SRC_INTERFACE,
+    ClarityDatabase, HeadersDB, StoreType, NULL_HEADER_DB, STORE_CONTRACT_SRC_INTERFACE,
 };
 pub use self::datastore::Datastore;
 pub use self::key_value_wrapper::{RollbackWrapper, RollbackWrapperPersistedLog};
@@ -3,7 +3,7 @@ use std::fmt;
 
 /// In a near future, we can go further in our static analysis and provide different levels
 /// of diagnostics, such as warnings, hints, best practices, etc.
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum Level {
     Note,
     Warning,
@@ -25,9 +25,12 @@ pub trait DiagnosableError {
     fn suggestion(&self) -> Option<String>;
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Diagnostic {
     pub level: Level,
+    // The code of the diagnostic in the catalog of the analysis passes, or
+    // None for the errors of the interpreter
+    pub code: Option<String>,
     pub message: String,
     pub spans: Vec<Span>,
     pub suggestion: Option<String>,
@@ -38,6 +41,7 @@ impl Diagnostic {
         Diagnostic {
             spans: vec![],
             level: Level::Error,
+            code: None,
             message: error.message(),
             suggestion: error.suggestion(),
         }
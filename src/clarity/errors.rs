@@ -72,6 +72,16 @@ pub enum RuntimeErrorType {
     BadNameValue(&'static str, String),
     BadBlockHash(Vec<u8>),
     UnwrapFailure,
+    // function which exceeded the cap, total allocation, cap
+    ValueAllocationExceeded(String, u64, u64),
+    // operation, number of elements, limit
+    IterationLimitExceeded(String, usize, u32),
+    // function called, limit
+    CallStackDepthExceeded(String, usize),
+    // contracts called, outermost first, limit
+    ContractCallDepthExceeded(Vec<String>, usize),
+    // the failed assertion of a test native
+    TestAssertionFailed(String),
 }
 
 #[derive(Debug, PartialEq)]
@@ -123,7 +133,33 @@ impl fmt::Display for Error {
 
 impl fmt::Display for RuntimeErrorType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            RuntimeErrorType::ValueAllocationExceeded(function, total, cap) => write!(
+                f,
+                "values allocated exceeded the cap of {} bytes ({} bytes) in {}",
+                cap, total, function
+            ),
+            RuntimeErrorType::IterationLimitExceeded(operation, length, limit) => write!(
+                f,
+                "{} over {} elements exceeds the iteration limit of {}",
+                operation, length, limit
+            ),
+            RuntimeErrorType::CallStackDepthExceeded(function, limit) => write!(
+                f,
+                "call to {} exceeds the call stack depth limit of {}",
+                function, limit
+            ),
+            RuntimeErrorType::ContractCallDepthExceeded(chain, limit) => write!(
+                f,
+                "contract-call? chain exceeds the depth limit of {}: {}",
+                limit,
+                chain.join(" -> ")
+            ),
+            RuntimeErrorType::TestAssertionFailed(message) => {
+                write!(f, "assertion failed: {}", message)
+            }
+            _ => write!(f, "{:?}", self),
+        }
     }
 }
 
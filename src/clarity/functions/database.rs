@@ -428,6 +428,14 @@ pub fn special_get_block_info(
         _ => return Ok(Value::none()),
     };
 
+    if let Some(value) = env
+        .global_context
+        .block_info_mocks
+        .get(&(height_value, block_info_prop))
+    {
+        return Value::some(value.clone());
+    }
+
     let current_block_height = env.global_context.database.get_current_block_height();
     if height_value >= current_block_height {
         return Ok(Value::none());
@@ -572,7 +572,17 @@ fn special_let(
                     return Err(CheckErrors::NameAlreadyUsed(binding_name.clone().into()).into())
                 }
 
-            let binding_value = eval(var_sexp, env, &inner_context)?;
+            if let Some(capture) = env.global_context.let_bindings.as_mut() {
+                capture.enter(binding_name);
+            }
+            let binding_value = eval(var_sexp, env, &inner_context);
+            if let Some(capture) = env.global_context.let_bindings.as_mut() {
+                capture.exit();
+            }
+            let binding_value = binding_value?;
+            if let Some(capture) = env.global_context.let_bindings.as_mut() {
+                capture.record(binding_name, &binding_value);
+            }
 
             let bind_mem_use = binding_value.get_memory_use();
             env.add_memory(bind_mem_use)?;
@@ -32,6 +32,18 @@ pub fn list_cons(
     Value::list_from(args)
 }
 
+// The number of elements a `fold`, `map` or `filter` iterates over must be
+// within the limit of the session.
+fn check_iterations(operation: &str, length: usize, env: &Environment) -> Result<()> {
+    let limit = env.global_context.iteration_limits.max_iterations;
+    if length > limit as usize {
+        return Err(
+            RuntimeErrorType::IterationLimitExceeded(operation.to_string(), length, limit).into(),
+        );
+    }
+    Ok(())
+}
+
 pub fn special_filter(
     args: &[SymbolicExpression],
     env: &mut Environment,
@@ -48,6 +60,7 @@ pub fn special_filter(
 
     match sequence {
         Value::Sequence(ref mut sequence_data) => {
+            check_iterations("filter", sequence_data.len(), env)?;
             sequence_data.filter(&mut |atom_value: SymbolicExpression| {
                 let argument = [atom_value];
                 let filter_eval = apply(&function, &argument, env, context)?;
@@ -80,6 +93,7 @@ pub fn special_fold(
 
     match sequence {
         Value::Sequence(ref mut sequence_data) => {
+            check_iterations("fold", sequence_data.len(), env)?;
             sequence_data
                 .atom_values()
                 .into_iter()
@@ -135,6 +149,8 @@ pub fn special_map(
         }
     }
 
+    check_iterations("map", min_args_len, env)?;
+
     // We can now apply the map
     let mut mapped_results = vec![];
     let mut previous_len = None;
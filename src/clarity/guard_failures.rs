@@ -0,0 +1,65 @@
+use crate::clarity::errors::ShortReturnType;
+use crate::clarity::representations::Span;
+use crate::clarity::types::{QualifiedContractIdentifier, Value};
+
+// A guard, like `asserts!` or `unwrap!`, which failed during an evaluation,
+// with the value it returned early.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuardFailure {
+    pub contract_identifier: QualifiedContractIdentifier,
+    pub guard: String,
+    pub span: Span,
+    pub value: Value,
+}
+
+// Records the guards failing during an evaluation, for debugging. A failure
+// returns early from the function, through the guards around the failing
+// one, which are not recorded.
+#[derive(Debug, Clone, Default)]
+pub struct GuardFailureRecorder {
+    // Set from a failure until the function it returns from
+    returning: bool,
+    pub failures: Vec<GuardFailure>,
+}
+
+impl GuardFailureRecorder {
+    pub fn new() -> GuardFailureRecorder {
+        GuardFailureRecorder::default()
+    }
+
+    pub fn is_guard(function_name: &str) -> bool {
+        matches!(
+            function_name,
+            "asserts!" | "unwrap!" | "unwrap-err!" | "try!"
+        )
+    }
+
+    pub fn record(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+        guard: &str,
+        span: &Span,
+        short_return: &ShortReturnType,
+    ) {
+        if self.returning {
+            return;
+        }
+        self.returning = true;
+        let value = match short_return {
+            ShortReturnType::ExpectedValue(value) | ShortReturnType::AssertionFailed(value) => {
+                value.clone()
+            }
+        };
+        self.failures.push(GuardFailure {
+            contract_identifier: contract_identifier.clone(),
+            guard: guard.to_string(),
+            span: span.clone(),
+            value,
+        });
+    }
+
+    // The function returned the value of the failure.
+    pub fn returned(&mut self) {
+        self.returning = false;
+    }
+}
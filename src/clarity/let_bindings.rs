@@ -0,0 +1,38 @@
+use crate::clarity::types::Value;
+use crate::clarity::ClarityName;
+use std::collections::BTreeMap;
+
+// Captures the value of each `let` binding evaluated, for debugging. The
+// bindings of a `let` evaluated while computing another binding are scoped
+// under its name, as `outer.inner`. When a binding is evaluated several
+// times, the last value is kept.
+#[derive(Debug, Clone, Default)]
+pub struct LetBindingsCapture {
+    scope: Vec<ClarityName>,
+    pub values: BTreeMap<String, Value>,
+}
+
+impl LetBindingsCapture {
+    pub fn new() -> LetBindingsCapture {
+        LetBindingsCapture::default()
+    }
+
+    // Enter the evaluation of a binding, whose nested bindings are scoped.
+    pub fn enter(&mut self, binding_name: &ClarityName) {
+        self.scope.push(binding_name.clone());
+    }
+
+    pub fn exit(&mut self) {
+        self.scope.pop();
+    }
+
+    pub fn record(&mut self, binding_name: &ClarityName, value: &Value) {
+        let mut name = self
+            .scope
+            .iter()
+            .map(|scope| format!("{}.", scope))
+            .collect::<String>();
+        name.push_str(binding_name);
+        self.values.insert(name, value.clone());
+    }
+}
@@ -22,18 +22,27 @@ pub mod contexts;
 pub mod database;
 pub mod representations;
 
-mod callables;
+pub mod callables;
 pub mod functions;
 pub mod variables;
 
 pub mod analysis;
 pub mod docs;
 
+pub mod branch_recorder;
+pub mod call_recorder;
+pub mod cost_tree;
 pub mod coverage;
+pub mod guard_failures;
+pub mod let_bindings;
+pub mod native_hooks;
+pub mod test_natives;
+pub mod version;
 
 use crate::clarity::callables::CallableType;
 use crate::clarity::contexts::GlobalContext;
 use crate::clarity::contexts::{CallStack, ContractContext, Environment, LocalContext};
+use crate::clarity::cost_tree::call_name;
 use crate::clarity::costs::{
     cost_functions, runtime_cost, CostOverflowingMath, CostTracker, LimitedCostTracker,
     MemoryConsumer,
@@ -43,6 +52,7 @@ use crate::clarity::errors::{
     CheckErrors, Error, InterpreterError, InterpreterResult as Result, RuntimeErrorType,
 };
 use crate::clarity::functions::define::DefineResult;
+use crate::clarity::guard_failures::GuardFailureRecorder;
 pub use crate::clarity::types::Value;
 use crate::clarity::types::{
     PrincipalData, QualifiedContractIdentifier, TraitIdentifier, TypeSignature,
@@ -119,12 +129,13 @@ pub fn lookup_function(name: &str, env: &mut Environment) -> Result<CallableType
 
     if let Some(result) = functions::lookup_reserved_functions(name) {
         Ok(result)
-    } else {
-        let user_function = env
-            .contract_context
-            .lookup_function(name)
-            .ok_or(CheckErrors::UndefinedFunction(name.to_string()))?;
+    } else if let Some(user_function) = env.contract_context.lookup_function(name) {
         Ok(CallableType::UserFunction(user_function))
+    } else if env.global_context.test_mode {
+        test_natives::lookup_test_function(name)
+            .ok_or(CheckErrors::UndefinedFunction(name.to_string()).into())
+    } else {
+        Err(CheckErrors::UndefinedFunction(name.to_string()).into())
     }
 }
 
@@ -156,13 +167,30 @@ pub fn apply(
         return Err(CheckErrors::CircularReference(vec![identifier.to_string()]).into());
     }
 
-    if env.call_stack.depth() >= MAX_CALL_STACK_DEPTH {
-        return Err(RuntimeErrorType::MaxStackDepthReached.into());
+    let max_call_stack_depth = env.global_context.iteration_limits.max_call_stack_depth;
+    if env.call_stack.depth() >= max_call_stack_depth {
+        // The identifier is prefixed with the contract, or `_native_`
+        let function_name = identifier.identifier.rsplit(':').next().unwrap_or_default();
+        return Err(RuntimeErrorType::CallStackDepthExceeded(
+            function_name.to_string(),
+            max_call_stack_depth,
+        )
+        .into());
     }
 
+    // Handlers registered for native functions
+    let hook = match function {
+        CallableType::UserFunction(_) => None,
+        _ if env.global_context.native_call_hooks.is_empty() => None,
+        _ => env.global_context.native_call_hooks.hook_for(&identifier),
+    };
+
     if let CallableType::SpecialFunction(_, function) = function {
         env.call_stack.insert(&identifier, track_recursion);
-        let mut resp = function(args, env, context);
+        let mut resp = match hook {
+            Some(hook) => hook.intercept(args, None, || function(args, env, context)),
+            None => function(args, env, context),
+        };
         add_stack_trace(&mut resp, env);
         env.call_stack.remove(&identifier, track_recursion)?;
         resp
@@ -196,9 +224,18 @@ pub fn apply(
         env.call_stack.insert(&identifier, track_recursion);
         let mut resp = match function {
             CallableType::NativeFunction(_, function, cost_function) => {
-                runtime_cost(*cost_function, env, evaluated_args.len())
-                    .map_err(Error::from)
-                    .and_then(|_| function.apply(evaluated_args))
+                let mut apply_native = |evaluated_args: Vec<Value>| {
+                    runtime_cost(*cost_function, env, evaluated_args.len())
+                        .map_err(Error::from)
+                        .and_then(|_| function.apply(evaluated_args))
+                };
+                match hook {
+                    Some(hook) => {
+                        let values = evaluated_args.clone();
+                        hook.intercept(args, Some(&values), || apply_native(evaluated_args))
+                    }
+                    None => apply_native(evaluated_args),
+                }
             }
             CallableType::UserFunction(function) => function.apply(&evaluated_args, env),
             _ => panic!("Should be unreachable."),
@@ -222,6 +259,9 @@ pub fn eval<'a>(
     if let Some(ref mut coverage_tracker) = env.global_context.coverage_reporting {
         coverage_tracker.report_eval(&env.contract_context.contract_identifier, exp);
     }
+    if let Some(ref mut recorder) = env.global_context.branch_recorder {
+        recorder.record(&env.contract_context.contract_identifier, exp);
+    }
 
     let mut res = match exp.expr {
         AtomValue(ref value) | LiteralValue(ref value) => Ok(value.clone()),
@@ -242,7 +282,58 @@ pub fn eval<'a>(
                 .match_atom()
                 .ok_or(CheckErrors::BadFunctionName)?;
             let f = lookup_function(&function_name, env)?;
-            apply(&f, &rest, env, context)
+            // Record the calls in the cost tree, if enabled
+            let recorded = env.global_context.cost_tree.is_some()
+                && match f {
+                    CallableType::UserFunction(_) => true,
+                    CallableType::SpecialFunction(..) => function_name.as_str() == "contract-call?",
+                    _ => false,
+                };
+            if recorded {
+                let total = env.global_context.cost_track.get_total();
+                if let Some(ref mut recorder) = env.global_context.cost_tree {
+                    recorder.enter(call_name(function_name, rest), exp.span.clone(), total);
+                }
+            }
+            let result = apply(&f, rest, env, context);
+            if let (Err(Error::ShortReturn(short_return)), Some(recorder)) =
+                (&result, env.global_context.guard_failures.as_mut())
+            {
+                if GuardFailureRecorder::is_guard(function_name) {
+                    recorder.record(
+                        &env.contract_context.contract_identifier,
+                        function_name,
+                        &exp.span,
+                        short_return,
+                    );
+                }
+            }
+            if recorded {
+                let total = env.global_context.cost_track.get_total();
+                if let Some(ref mut recorder) = env.global_context.cost_tree {
+                    recorder.exit(total);
+                }
+            }
+            match (&result, env.global_context.max_value_allocation) {
+                (Ok(value), Some(cap)) => {
+                    let total = env
+                        .global_context
+                        .value_allocation
+                        .saturating_add(value.get_memory_use());
+                    env.global_context.value_allocation = total;
+                    if total > cap {
+                        Err(RuntimeErrorType::ValueAllocationExceeded(
+                            function_name.to_string(),
+                            total,
+                            cap,
+                        )
+                        .into())
+                    } else {
+                        result
+                    }
+                }
+                _ => result,
+            }
         }
         TraitReference(_, _) | Field(_) => unreachable!("can't be evaluated"),
     };
@@ -0,0 +1,120 @@
+use crate::clarity::callables::FunctionIdentifier;
+use crate::clarity::errors::InterpreterResult as Result;
+use crate::clarity::functions::lookup_reserved_functions;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::types::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+
+// A call to a native function, as seen by a `NativeCallHandler`.
+pub struct NativeCall<'a> {
+    pub name: &'a str,
+    // The arguments as written in the source
+    pub args: &'a [SymbolicExpression],
+    // The evaluated arguments, or `None` for the special functions, such as
+    // `if` or `stx-transfer?`, which evaluate their arguments themselves
+    pub values: Option<&'a [Value]>,
+}
+
+// Observes the calls to a native function, for tracing or testing. A panic in
+// `before` or `after` is caught, so that it cannot abort the evaluation.
+pub trait NativeCallHandler: Send + Sync {
+    fn before(&self, call: &NativeCall) {}
+
+    fn after(&self, call: &NativeCall, result: &Result<Value>) {}
+
+    // Only consulted when fault injection is enabled. A result returned here
+    // replaces the evaluation of the call.
+    fn inject(&self, call: &NativeCall) -> Option<Result<Value>> {
+        None
+    }
+}
+
+// The handlers registered for each native function.
+#[derive(Clone, Default)]
+pub struct NativeCallHooks {
+    // Keyed by the identifier of the function, with its name
+    handlers: HashMap<String, (String, Arc<dyn NativeCallHandler>)>,
+    // Let the handlers replace the results of the calls, which changes the
+    // behavior of the contracts
+    pub fault_injection: bool,
+}
+
+impl NativeCallHooks {
+    pub fn new() -> NativeCallHooks {
+        NativeCallHooks::default()
+    }
+
+    // Replaces the handler previously registered for the function, if any.
+    pub fn register<H: NativeCallHandler + 'static>(
+        &mut self,
+        function_name: &str,
+        handler: H,
+    ) -> std::result::Result<(), String> {
+        let function = lookup_reserved_functions(function_name)
+            .ok_or_else(|| format!("'{}' is not a native function", function_name))?;
+        self.handlers.insert(
+            function.get_identifier().to_string(),
+            (function_name.to_string(), Arc::new(handler)),
+        );
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    pub fn hook_for(&self, identifier: &FunctionIdentifier) -> Option<NativeCallHook> {
+        let (name, handler) = self.handlers.get(&identifier.to_string())?;
+        Some(NativeCallHook {
+            name: name.clone(),
+            handler: handler.clone(),
+            fault_injection: self.fault_injection,
+        })
+    }
+}
+
+impl fmt::Debug for NativeCallHooks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut names: Vec<&String> = self.handlers.values().map(|(name, _)| name).collect();
+        names.sort();
+        f.debug_struct("NativeCallHooks")
+            .field("functions", &names)
+            .field("fault_injection", &self.fault_injection)
+            .finish()
+    }
+}
+
+// The handler of a function about to be applied, detached from the hooks so
+// that the function can borrow the environment.
+pub struct NativeCallHook {
+    name: String,
+    handler: Arc<dyn NativeCallHandler>,
+    fault_injection: bool,
+}
+
+impl NativeCallHook {
+    pub fn intercept<F: FnOnce() -> Result<Value>>(
+        &self,
+        args: &[SymbolicExpression],
+        values: Option<&[Value]>,
+        apply: F,
+    ) -> Result<Value> {
+        let call = NativeCall {
+            name: &self.name,
+            args,
+            values,
+        };
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| self.handler.before(&call)));
+        let injected = if self.fault_injection {
+            self.handler.inject(&call)
+        } else {
+            None
+        };
+        let result = injected.unwrap_or_else(apply);
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| self.handler.after(&call, &result)));
+        result
+    }
+}
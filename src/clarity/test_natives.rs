@@ -0,0 +1,127 @@
+use crate::clarity::analysis::type_checker::contexts::TypingContext;
+use crate::clarity::analysis::type_checker::{TypeChecker, TypeResult};
+use crate::clarity::callables::CallableType;
+use crate::clarity::contexts::{Environment, LocalContext};
+use crate::clarity::costs::cost_functions::ClarityCostFunction;
+use crate::clarity::costs::runtime_cost;
+use crate::clarity::errors::{
+    check_argument_count, CheckErrors, InterpreterResult as Result, RuntimeErrorType,
+};
+use crate::clarity::eval;
+use crate::clarity::representations::SymbolicExpression;
+use crate::clarity::types::{TypeSignature, Value};
+
+// Functions only available in test mode, to write assertions in the snippets
+// and contracts under test. They are looked up after the functions defined by
+// the contract, so that they never shadow them.
+pub const TEST_NATIVES: [&str; 3] = ["assert", "assert-eq", "print-and-continue"];
+
+pub fn lookup_test_function(name: &str) -> Option<CallableType> {
+    use crate::clarity::callables::CallableType::SpecialFunction;
+    match name {
+        "assert" => Some(SpecialFunction("test_assert", &special_assert)),
+        "assert-eq" => Some(SpecialFunction("test_assert-eq", &special_assert_eq)),
+        "print-and-continue" => Some(SpecialFunction(
+            "test_print-and-continue",
+            &special_print_and_continue,
+        )),
+        _ => None,
+    }
+}
+
+// A failed assertion aborts the evaluation with a runtime error.
+fn special_assert(
+    args: &[SymbolicExpression],
+    env: &mut Environment,
+    context: &LocalContext,
+) -> Result<Value> {
+    check_argument_count(1, args)?;
+
+    runtime_cost(ClarityCostFunction::Asserts, env, 0)?;
+    match eval(&args[0], env, context)? {
+        Value::Bool(true) => Ok(Value::Bool(true)),
+        Value::Bool(false) => {
+            Err(RuntimeErrorType::TestAssertionFailed(args[0].to_string()).into())
+        }
+        value => Err(CheckErrors::TypeValueError(TypeSignature::BoolType, value).into()),
+    }
+}
+
+// Values of different types are not equal, rather than ill-typed.
+fn special_assert_eq(
+    args: &[SymbolicExpression],
+    env: &mut Environment,
+    context: &LocalContext,
+) -> Result<Value> {
+    check_argument_count(2, args)?;
+
+    let expected = eval(&args[0], env, context)?;
+    let actual = eval(&args[1], env, context)?;
+    runtime_cost(ClarityCostFunction::Eq, env, 2)?;
+    if expected == actual {
+        Ok(Value::Bool(true))
+    } else {
+        Err(
+            RuntimeErrorType::TestAssertionFailed(format!("expected {}, got {}", expected, actual))
+                .into(),
+        )
+    }
+}
+
+fn special_print_and_continue(
+    args: &[SymbolicExpression],
+    env: &mut Environment,
+    context: &LocalContext,
+) -> Result<Value> {
+    check_argument_count(1, args)?;
+
+    let input = eval(&args[0], env, context)?;
+    runtime_cost(ClarityCostFunction::Print, env, input.size())?;
+    env.register_print_event(input.clone())?;
+    Ok(input)
+}
+
+// Returns `None` if `name` is not a test native.
+pub fn check_test_function(
+    checker: &mut TypeChecker,
+    name: &str,
+    args: &[SymbolicExpression],
+    context: &TypingContext,
+) -> Option<TypeResult> {
+    match name {
+        "assert" => Some(check_assert(checker, args, context)),
+        "assert-eq" => Some(check_assert_eq(checker, args, context)),
+        "print-and-continue" => Some(check_print_and_continue(checker, args, context)),
+        _ => None,
+    }
+}
+
+fn check_assert(
+    checker: &mut TypeChecker,
+    args: &[SymbolicExpression],
+    context: &TypingContext,
+) -> TypeResult {
+    check_argument_count(1, args)?;
+    checker.type_check_expects(&args[0], context, &TypeSignature::BoolType)?;
+    Ok(TypeSignature::BoolType)
+}
+
+fn check_assert_eq(
+    checker: &mut TypeChecker,
+    args: &[SymbolicExpression],
+    context: &TypingContext,
+) -> TypeResult {
+    check_argument_count(2, args)?;
+    checker.type_check(&args[0], context)?;
+    checker.type_check(&args[1], context)?;
+    Ok(TypeSignature::BoolType)
+}
+
+fn check_print_and_continue(
+    checker: &mut TypeChecker,
+    args: &[SymbolicExpression],
+    context: &TypingContext,
+) -> TypeResult {
+    check_argument_count(1, args)?;
+    checker.type_check(&args[0], context)
+}
@@ -29,6 +29,7 @@ pub mod retry;
 pub mod secp256k1;
 pub mod uint;
 
+use std::cmp;
 use std::error;
 use std::fmt;
 use std::thread;
@@ -46,6 +47,39 @@ use address::AddressHashMode;
 pub const C32_ADDRESS_VERSION_MAINNET_SINGLESIG: u8 = 22; // P
 pub const C32_ADDRESS_VERSION_TESTNET_SINGLESIG: u8 = 26; // T
 
+/// Find the candidate closest to `name`, if any is within a small edit distance.
+pub fn closest_match<'a, I: IntoIterator<Item = &'a str>>(
+    name: &str,
+    candidates: I,
+) -> Option<&'a str> {
+    let max_distance = cmp::max(1, name.chars().count() / 3);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + cmp::min(previous, cmp::min(row[j], row[j + 1]))
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
 pub fn get_epoch_time_secs() -> u64 {
     let start = SystemTime::now();
     let since_the_epoch = start
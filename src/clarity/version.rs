@@ -0,0 +1,20 @@
+use std::fmt;
+
+// The version of the Clarity language targeted by a contract. The interpreter
+// implements Clarity 1; later versions are only taken into account by the
+// REPL's own analyses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ClarityVersion {
+    #[default]
+    Clarity1,
+    Clarity2,
+}
+
+impl fmt::Display for ClarityVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClarityVersion::Clarity1 => write!(f, "Clarity 1"),
+            ClarityVersion::Clarity2 => write!(f, "Clarity 2"),
+        }
+    }
+}
@@ -1,2 +1,4 @@
+pub mod stream;
 pub mod terminal;
+pub use stream::Stream;
 pub use terminal::Terminal;
@@ -0,0 +1,254 @@
+use crate::repl::{settings::SessionSettings, Session};
+
+use std::io::{stdin, stdout, BufRead, Write};
+
+// Splits a stream of source text into complete top-level forms. Input is
+// buffered until the brackets of the current form are balanced. REPL
+// commands (starting with "::") extend to the end of their line. Comments
+// are kept with the form following them, so that annotations reach the
+// parser.
+#[derive(Default)]
+pub struct FormBuffer {
+    buffer: String,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    in_comment: bool,
+    // whether the buffer holds more than whitespace and comments
+    has_code: bool,
+}
+
+impl FormBuffer {
+    pub fn new() -> FormBuffer {
+        FormBuffer::default()
+    }
+
+    fn is_command(&self) -> bool {
+        self.buffer.trim_start().starts_with("::")
+    }
+
+    fn take(&mut self) -> String {
+        let form = self.buffer.trim().to_string();
+        self.buffer.clear();
+        self.has_code = false;
+        form
+    }
+
+    // The forms completed by the input. On an unbalanced closing bracket,
+    // the error is returned with the forms completed before it.
+    pub fn feed(&mut self, input: &str) -> Result<Vec<String>, (Vec<String>, String)> {
+        let mut forms = vec![];
+        for character in input.chars() {
+            if self.in_comment {
+                if character == '\n' {
+                    self.in_comment = false;
+                } else {
+                    self.buffer.push(character);
+                    continue;
+                }
+            } else if self.in_string {
+                self.buffer.push(character);
+                if self.escaped {
+                    self.escaped = false;
+                } else if character == '\\' {
+                    self.escaped = true;
+                } else if character == '"' {
+                    self.in_string = false;
+                }
+                continue;
+            }
+
+            match character {
+                ';' if !self.is_command() => {
+                    self.buffer.push(character);
+                    self.in_comment = true;
+                }
+                '"' => {
+                    self.buffer.push(character);
+                    self.has_code = true;
+                    self.in_string = true;
+                }
+                '(' | '{' => {
+                    self.buffer.push(character);
+                    self.has_code = true;
+                    self.depth += 1;
+                }
+                ')' | '}' => {
+                    if self.depth == 0 {
+                        let has_code = self.has_code;
+                        let form = self.take();
+                        if has_code {
+                            forms.push(form);
+                        }
+                        return Err((forms, format!("unexpected closing {}", character)));
+                    }
+                    self.buffer.push(character);
+                    self.depth -= 1;
+                    if self.depth == 0 && !self.is_command() {
+                        forms.push(self.take());
+                    }
+                }
+                c if c.is_whitespace() && self.depth == 0 => {
+                    if self.buffer.trim().is_empty() {
+                        self.buffer.clear();
+                    } else if !self.has_code {
+                        self.buffer.push(c);
+                    } else if !self.is_command() || c == '\n' {
+                        forms.push(self.take());
+                    } else {
+                        self.buffer.push(c);
+                    }
+                }
+                c => {
+                    self.buffer.push(c);
+                    self.has_code = true;
+                }
+            }
+        }
+        Ok(forms)
+    }
+
+    // Flush the remaining input once the stream is closed.
+    pub fn finish(&mut self) -> Result<Option<String>, String> {
+        if self.depth > 0 || self.in_string {
+            return Err("unexpected end of input: incomplete form".to_string());
+        }
+        let has_code = self.has_code;
+        let form = self.take();
+        Ok(Some(form).filter(|_| has_code))
+    }
+}
+
+pub struct Stream {
+    pub session: Session,
+}
+
+impl Stream {
+    pub fn new(session_settings: SessionSettings) -> Stream {
+        let session = Session::new(session_settings);
+        Stream { session }
+    }
+
+    fn evaluate(&mut self, form: &str) {
+        let output = self.session.handle_command(form);
+        for line in output {
+            println!("{}", line);
+        }
+        stdout().flush().unwrap();
+    }
+
+    pub fn start(&mut self) {
+        if let Err(e) = self.session.start() {
+            println!("{}", e);
+            std::process::exit(1);
+        }
+
+        let mut forms = FormBuffer::new();
+        let stdin = stdin();
+        for line in stdin.lock().lines() {
+            let mut line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    eprintln!("{}", red!(format!("Error: {}", e)));
+                    std::process::exit(1);
+                }
+            };
+            line.push('\n');
+            match forms.feed(&line) {
+                Ok(complete) => {
+                    for form in complete {
+                        self.evaluate(&form);
+                    }
+                }
+                Err((complete, e)) => {
+                    for form in complete {
+                        self.evaluate(&form);
+                    }
+                    eprintln!("{}", red!(format!("Error: {}", e)));
+                }
+            }
+        }
+
+        match forms.finish() {
+            Ok(Some(form)) => self.evaluate(&form),
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("{}", red!(format!("Error: {}", e)));
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_forms() {
+        let mut forms = FormBuffer::new();
+        assert_eq!(
+            forms.feed("(+ 1 2) (list u1\n").unwrap(),
+            vec!["(+ 1 2)".to_string()]
+        );
+        assert_eq!(
+            forms.feed("  u2) tx-sender\n").unwrap(),
+            vec!["(list u1\n  u2)".to_string(), "tx-sender".to_string()]
+        );
+        assert_eq!(
+            forms.feed("::get_costs (+ 1 2)\n").unwrap(),
+            vec!["::get_costs (+ 1 2)".to_string()]
+        );
+        assert_eq!(forms.finish(), Ok(None));
+    }
+
+    #[test]
+    fn ignore_brackets_in_strings_and_comments() {
+        let mut forms = FormBuffer::new();
+        assert_eq!(
+            forms.feed("(print \"(\\\")\") ;; (\n").unwrap(),
+            vec!["(print \"(\\\")\")".to_string()]
+        );
+        assert_eq!(forms.finish(), Ok(None));
+    }
+
+    #[test]
+    fn incomplete_form() {
+        let mut forms = FormBuffer::new();
+        assert_eq!(forms.feed("(begin (+ 1 2)\n").unwrap().len(), 0);
+        assert!(forms.finish().is_err());
+
+        let mut forms = FormBuffer::new();
+        assert!(forms.feed(")\n").is_err());
+
+        // The forms before the unexpected bracket are still returned
+        let mut forms = FormBuffer::new();
+        assert_eq!(
+            forms.feed("(+ 1 2) u3)\n"),
+            Err((
+                vec!["(+ 1 2)".to_string(), "u3".to_string()],
+                "unexpected closing )".to_string()
+            ))
+        );
+        assert_eq!(forms.finish(), Ok(None));
+    }
+
+    #[test]
+    fn keep_annotations() {
+        let mut forms = FormBuffer::new();
+        assert_eq!(
+            forms.feed(";; #[allow(unchecked_data)]\n").unwrap().len(),
+            0
+        );
+        assert_eq!(
+            forms
+                .feed("(define-public (f (n uint)) ;; n is checked\n  (ok n))\n")
+                .unwrap(),
+            vec![
+                ";; #[allow(unchecked_data)]\n(define-public (f (n uint)) ;; n is checked\n  (ok n))"
+                    .to_string()
+            ]
+        );
+        assert_eq!(forms.finish(), Ok(None));
+    }
+}
@@ -0,0 +1,217 @@
+use crate::clarity::analysis::ContractAnalysis;
+use crate::clarity::types::{FunctionType, SequenceSubtype, StringSubtype, TypeSignature};
+use crate::clarity::ClarityName;
+use serde_json::Value;
+
+// Version of the schema below, bumped on any incompatible change.
+pub const ABI_VERSION: u32 = 1;
+
+// The ABI of a contract, meant for generating client bindings. Unlike the
+// node's contract interface, every type is an object tagged by `kind`, and
+// field names never change between releases of the same `abi_version`:
+//
+// {
+//   "abi_version": 1,
+//   "contract_id": "ST1...contract",
+//   "functions": [{ "name", "access": "public" | "read_only" | "private",
+//                   "args": [{ "name", "type" }], "returns": type }],
+//   "variables": [{ "name", "access": "constant" | "variable", "type" }],
+//   "maps": [{ "name", "key": type, "value": type }],
+//   "fungible_tokens": [{ "name" }],
+//   "non_fungible_tokens": [{ "name", "type" }],
+//   "traits": {
+//     "defined": [{ "name", "functions": [{ "name", "args": [type], "returns": type }] }],
+//     "implemented": ["ST1...contract.trait"]
+//   }
+// }
+//
+// A type is one of:
+//   { "kind": "int" | "uint" | "bool" | "principal" | "none" }
+//   { "kind": "buffer" | "string-ascii" | "string-utf8", "length": n }
+//   { "kind": "list", "max_length": n, "element": type }
+//   { "kind": "tuple", "fields": [{ "name", "type" }] }
+//   { "kind": "optional", "inner": type }
+//   { "kind": "response", "ok": type, "error": type }
+//   { "kind": "trait", "trait_id": "ST1...contract.trait" }
+//
+// Functions are listed by access, then by name; everything else by name.
+pub fn contract_abi(analysis: &ContractAnalysis) -> Value {
+    let mut functions = vec![];
+    for (access, function_types) in [
+        ("public", &analysis.public_function_types),
+        ("read_only", &analysis.read_only_function_types),
+        ("private", &analysis.private_function_types),
+    ] {
+        functions.extend(
+            function_types
+                .iter()
+                .filter_map(|(name, function_type)| function(name, access, function_type)),
+        );
+    }
+
+    let mut variables = vec![];
+    for (access, variable_types) in [
+        ("constant", &analysis.variable_types),
+        ("variable", &analysis.persisted_variable_types),
+    ] {
+        variables.extend(variable_types.iter().map(|(name, type_signature)| {
+            json!({
+                "name": name.as_str(),
+                "access": access,
+                "type": abi_type(type_signature),
+            })
+        }));
+    }
+
+    let maps: Vec<Value> = analysis
+        .map_types
+        .iter()
+        .map(|(name, (key, value))| {
+            json!({
+                "name": name.as_str(),
+                "key": abi_type(key),
+                "value": abi_type(value),
+            })
+        })
+        .collect();
+
+    let fungible_tokens: Vec<Value> = analysis
+        .fungible_tokens
+        .iter()
+        .map(|name| json!({ "name": name.as_str() }))
+        .collect();
+
+    let non_fungible_tokens: Vec<Value> = analysis
+        .non_fungible_tokens
+        .iter()
+        .map(|(name, type_signature)| {
+            json!({
+                "name": name.as_str(),
+                "type": abi_type(type_signature),
+            })
+        })
+        .collect();
+
+    let defined_traits: Vec<Value> = analysis
+        .defined_traits
+        .iter()
+        .map(|(name, signatures)| {
+            let functions: Vec<Value> = signatures
+                .iter()
+                .map(|(name, signature)| {
+                    json!({
+                        "name": name.as_str(),
+                        "args": signature.args.iter().map(abi_type).collect::<Vec<_>>(),
+                        "returns": abi_type(&signature.returns),
+                    })
+                })
+                .collect();
+            json!({
+                "name": name.as_str(),
+                "functions": functions,
+            })
+        })
+        .collect();
+
+    let implemented_traits: Vec<String> = analysis
+        .implemented_traits
+        .iter()
+        .map(|trait_identifier| {
+            format!(
+                "{}.{}",
+                trait_identifier.contract_identifier, trait_identifier.name
+            )
+        })
+        .collect();
+
+    json!({
+        "abi_version": ABI_VERSION,
+        "contract_id": analysis.contract_identifier.to_string(),
+        "functions": functions,
+        "variables": variables,
+        "maps": maps,
+        "fungible_tokens": fungible_tokens,
+        "non_fungible_tokens": non_fungible_tokens,
+        "traits": {
+            "defined": defined_traits,
+            "implemented": implemented_traits,
+        },
+    })
+}
+
+// Functions defined by a contract always have a fixed signature.
+fn function(name: &ClarityName, access: &str, function_type: &FunctionType) -> Option<Value> {
+    let function = match function_type {
+        FunctionType::Fixed(function) => function,
+        _ => return None,
+    };
+    let args: Vec<Value> = function
+        .args
+        .iter()
+        .map(|arg| {
+            json!({
+                "name": arg.name.as_str(),
+                "type": abi_type(&arg.signature),
+            })
+        })
+        .collect();
+    Some(json!({
+        "name": name.as_str(),
+        "access": access,
+        "args": args,
+        "returns": abi_type(&function.returns),
+    }))
+}
+
+pub fn abi_type(type_signature: &TypeSignature) -> Value {
+    match type_signature {
+        TypeSignature::NoType => json!({ "kind": "none" }),
+        TypeSignature::IntType => json!({ "kind": "int" }),
+        TypeSignature::UIntType => json!({ "kind": "uint" }),
+        TypeSignature::BoolType => json!({ "kind": "bool" }),
+        TypeSignature::PrincipalType => json!({ "kind": "principal" }),
+        TypeSignature::SequenceType(SequenceSubtype::BufferType(length)) => {
+            json!({ "kind": "buffer", "length": u32::from(length) })
+        }
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(length))) => {
+            json!({ "kind": "string-ascii", "length": u32::from(length) })
+        }
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(length))) => {
+            json!({ "kind": "string-utf8", "length": u32::from(length) })
+        }
+        TypeSignature::SequenceType(SequenceSubtype::ListType(list_type)) => {
+            json!({
+                "kind": "list",
+                "max_length": list_type.get_max_len(),
+                "element": abi_type(list_type.get_list_item_type()),
+            })
+        }
+        TypeSignature::TupleType(tuple_type) => {
+            let fields: Vec<Value> = tuple_type
+                .get_type_map()
+                .iter()
+                .map(|(name, field_type)| {
+                    json!({
+                        "name": name.as_str(),
+                        "type": abi_type(field_type),
+                    })
+                })
+                .collect();
+            json!({ "kind": "tuple", "fields": fields })
+        }
+        TypeSignature::OptionalType(inner) => {
+            json!({ "kind": "optional", "inner": abi_type(inner) })
+        }
+        TypeSignature::ResponseType(types) => {
+            json!({
+                "kind": "response",
+                "ok": abi_type(&types.0),
+                "error": abi_type(&types.1),
+            })
+        }
+        TypeSignature::TraitReferenceType(trait_identifier) => json!({
+            "kind": "trait",
+            "trait_id": format!("{}.{}", trait_identifier.contract_identifier, trait_identifier.name),
+        }),
+    }
+}
@@ -0,0 +1,157 @@
+use crate::analysis::ast_visitor::match_native_call;
+use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::{Span, SymbolicExpression};
+use crate::clarity::types::Value;
+use std::collections::HashMap;
+
+// A branch of a conditional, like the `else` of an `if`, and whether the call
+// reached it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Branch {
+    pub label: &'static str,
+    pub span: Span,
+    pub taken: bool,
+}
+
+// An `if`, `match` or `asserts!` with its branches. For `asserts!`, the
+// branch `pass` is its condition and `fail` the value thrown.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BranchPoint {
+    pub kind: &'static str,
+    pub span: Span,
+    pub branches: Vec<Branch>,
+}
+
+// The branches of a function taken by a call with given arguments, in the
+// order of the source, with the lines of the function.
+#[derive(Clone, Debug)]
+pub struct BranchReport {
+    pub contract_id: String,
+    pub function: String,
+    pub args: Vec<Value>,
+    pub result: Result<Value, String>,
+    pub points: Vec<BranchPoint>,
+    pub first_line: u32,
+    pub source: Vec<String>,
+}
+
+impl BranchReport {
+    // The source of the function, with the branches taken in green and those
+    // not taken in red. A branch nested in another one is colored by itself.
+    pub fn highlighted_source(&self) -> Vec<String> {
+        let mut colors: Vec<Vec<Option<bool>>> = self
+            .source
+            .iter()
+            .map(|line| vec![None; line.chars().count()])
+            .collect();
+        // Outer branches come first, so the nested ones are painted over them
+        for branch in self.points.iter().flat_map(|point| point.branches.iter()) {
+            for line in branch.span.start_line..=branch.span.end_line {
+                let row = match line
+                    .checked_sub(self.first_line)
+                    .and_then(|row| colors.get_mut(row as usize))
+                {
+                    Some(row) => row,
+                    None => continue,
+                };
+                let start = if line == branch.span.start_line {
+                    branch.span.start_column as usize - 1
+                } else {
+                    0
+                };
+                let end = if line == branch.span.end_line {
+                    (branch.span.end_column as usize).min(row.len())
+                } else {
+                    row.len()
+                };
+                for color in row.iter_mut().take(end).skip(start) {
+                    *color = Some(branch.taken);
+                }
+            }
+        }
+
+        self.source
+            .iter()
+            .zip(colors.iter())
+            .map(|(line, colors)| {
+                let mut highlighted = String::new();
+                let mut run = String::new();
+                let mut run_color = None;
+                for (c, color) in line.chars().zip(colors.iter()) {
+                    if *color != run_color {
+                        highlighted.push_str(&paint(&run, run_color));
+                        run.clear();
+                        run_color = *color;
+                    }
+                    run.push(c);
+                }
+                highlighted.push_str(&paint(&run, run_color));
+                highlighted
+            })
+            .collect()
+    }
+}
+
+fn paint(text: &str, taken: Option<bool>) -> String {
+    match taken {
+        _ if text.is_empty() => String::new(),
+        Some(true) => green!(text),
+        Some(false) => red!(text),
+        None => text.to_string(),
+    }
+}
+
+// Collect the branch points of an expression, in the order of the source,
+// given the number of evaluations of each expression by id.
+pub fn branch_points(
+    expr: &SymbolicExpression,
+    evaluations: &HashMap<u64, u64>,
+    points: &mut Vec<BranchPoint>,
+) {
+    let evaluated = |expr: &SymbolicExpression| evaluations.get(&expr.id).copied().unwrap_or(0);
+    let branch = |label, expr: &SymbolicExpression| Branch {
+        label,
+        span: expr.span.clone(),
+        taken: evaluated(expr) > 0,
+    };
+    let point = match match_native_call(expr) {
+        Some((NativeFunctions::If, [_, then_branch, else_branch])) => Some(BranchPoint {
+            kind: "if",
+            span: expr.span.clone(),
+            branches: vec![branch("then", then_branch), branch("else", else_branch)],
+        }),
+        Some((NativeFunctions::Match, [_, _, some_branch, none_branch])) => Some(BranchPoint {
+            kind: "match",
+            span: expr.span.clone(),
+            branches: vec![branch("some", some_branch), branch("none", none_branch)],
+        }),
+        Some((NativeFunctions::Match, [_, _, ok_branch, _, err_branch])) => Some(BranchPoint {
+            kind: "match",
+            span: expr.span.clone(),
+            branches: vec![branch("ok", ok_branch), branch("err", err_branch)],
+        }),
+        Some((NativeFunctions::Asserts, [condition, thrown])) => Some(BranchPoint {
+            kind: "asserts!",
+            span: expr.span.clone(),
+            // The condition is evaluated each time, the value thrown only when
+            // it is false
+            branches: vec![
+                Branch {
+                    label: "pass",
+                    span: condition.span.clone(),
+                    taken: evaluated(condition) > evaluated(thrown),
+                },
+                branch("fail", thrown),
+            ],
+        }),
+        _ => None,
+    };
+    if let Some(point) = point {
+        points.push(point);
+    }
+    if let Some(list) = expr.match_list() {
+        for element in list.iter() {
+            branch_points(element, evaluations, points);
+        }
+    }
+}
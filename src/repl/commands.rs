@@ -0,0 +1,160 @@
+// An argument of a REPL command. Expressions are a single argument, even if
+// they contain spaces.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArgumentInfo {
+    pub name: &'static str,
+    pub optional: bool,
+}
+
+// A REPL command, without its `::` prefix.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandInfo {
+    pub name: &'static str,
+    pub arguments: Vec<ArgumentInfo>,
+    pub description: &'static str,
+}
+
+// The output of a command, or why it could not be run.
+pub type CommandResult = Result<Vec<String>, String>;
+
+impl CommandInfo {
+    fn new(
+        name: &'static str,
+        arguments: &[(&'static str, bool)],
+        description: &'static str,
+    ) -> CommandInfo {
+        CommandInfo {
+            name,
+            arguments: arguments
+                .iter()
+                .map(|(name, optional)| ArgumentInfo {
+                    name,
+                    optional: *optional,
+                })
+                .collect(),
+            description,
+        }
+    }
+
+    // The command with its arguments, like `::natives [page]`.
+    pub fn usage(&self) -> String {
+        let mut usage = format!("::{}", self.name);
+        for argument in self.arguments.iter() {
+            if argument.optional {
+                usage.push_str(&format!(" [{}]", argument.name));
+            } else {
+                usage.push_str(&format!(" <{}>", argument.name));
+            }
+        }
+        usage
+    }
+
+    pub fn required_arguments(&self) -> usize {
+        self.arguments
+            .iter()
+            .filter(|argument| !argument.optional)
+            .count()
+    }
+}
+
+// All the REPL commands, in the order they are listed by `::help`. A command
+// handled by `Session::handle_command` is added here.
+pub fn commands() -> Vec<CommandInfo> {
+    vec![
+        CommandInfo::new("help", &[], "Display help"),
+        CommandInfo::new(
+            "list_functions",
+            &[],
+            "Display all the native functions available in clarity",
+        ),
+        CommandInfo::new(
+            "describe_function",
+            &[("function", false)],
+            "Display documentation for a given native function fn-name",
+        ),
+        CommandInfo::new(
+            "natives",
+            &[("page", true)],
+            "Display the native functions by category, with examples",
+        ),
+        CommandInfo::new(
+            "mint_stx",
+            &[("principal", false), ("amount", false)],
+            "Mint STX balance for a given principal",
+        ),
+        CommandInfo::new(
+            "set_tx_sender",
+            &[("principal", false)],
+            "Set tx-sender variable to principal",
+        ),
+        CommandInfo::new(
+            "set_caller",
+            &[("principal", true)],
+            "Set contract-caller to principal, or reset it to tx-sender",
+        ),
+        CommandInfo::new(
+            "get_assets_maps",
+            &[],
+            "Get assets maps for active accounts",
+        ),
+        CommandInfo::new("get_costs", &[("expr", false)], "Display the cost analysis"),
+        CommandInfo::new(
+            "cost_compare",
+            &[("expr1", false), ("expr2", false)],
+            "Compare the costs of two expressions",
+        ),
+        CommandInfo::new(
+            "cost_explain",
+            &[("native-fn", false)],
+            "Describe how the cost of a native function is computed",
+        ),
+        CommandInfo::new("get_contracts", &[], "Get contracts"),
+        CommandInfo::new(
+            "freeze",
+            &[("contract", false)],
+            "Prevent a contract from being redeployed or modified",
+        ),
+        CommandInfo::new("limits", &[], "Display the block cost limits in effect"),
+        CommandInfo::new("get_block_height", &[], "Get current block height"),
+        CommandInfo::new(
+            "watch_state",
+            &[("expr", false)],
+            "Evaluate an expression and display the state it changed",
+        ),
+        CommandInfo::new(
+            "mock_block_info",
+            &[("height", false), ("property", false), ("value", false)],
+            "Make get-block-info? return a value for a block",
+        ),
+        CommandInfo::new(
+            "advance_chain_tip",
+            &[("count", false)],
+            "Simulate mining of <count> blocks",
+        ),
+        CommandInfo::new(
+            "toggle_costs",
+            &[],
+            "Display cost analysis after every expression",
+        ),
+        CommandInfo::new(
+            "numformat",
+            &[("hex|dec", false)],
+            "Display integers in hex or decimal",
+        ),
+        CommandInfo::new(
+            "encode",
+            &[("expr", false)],
+            "Display the consensus serialization of a value",
+        ),
+        CommandInfo::new(
+            "decode",
+            &[("hex-bytes", false)],
+            "Display the value of a consensus serialization",
+        ),
+        CommandInfo::new(
+            "coverage_merge",
+            &[("output", false), ("files", false)],
+            "Merge the coverage exported by other sessions into an LCOV file",
+        ),
+    ]
+}
@@ -0,0 +1,68 @@
+use crate::clarity::diagnostic::{Diagnostic, Level};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SeverityCounts {
+    pub errors: usize,
+    pub warnings: usize,
+    pub notes: usize,
+}
+
+// An `impl-trait` of the contract, and whether the contract has the methods
+// of the trait, if it is defined in the session.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TraitConformance {
+    pub trait_identifier: String,
+    pub conforms: Option<bool>,
+}
+
+// The result of the analysis of a contract, for a CI pipeline to decide
+// whether it is ready to be deployed.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ConformanceReport {
+    pub contract_id: String,
+    // No error was reported, and the contract conforms to its traits
+    pub passed: bool,
+    pub error_free: bool,
+    pub counts: SeverityCounts,
+    pub warnings: Vec<Diagnostic>,
+    pub traits: Vec<TraitConformance>,
+    // The catalog codes of the diagnostics reported, sorted
+    pub codes: Vec<String>,
+}
+
+impl ConformanceReport {
+    pub fn new(
+        contract_id: String,
+        diagnostics: &[Diagnostic],
+        traits: Vec<TraitConformance>,
+    ) -> ConformanceReport {
+        let mut counts = SeverityCounts::default();
+        for diagnostic in diagnostics.iter() {
+            match diagnostic.level {
+                Level::Error => counts.errors += 1,
+                Level::Warning => counts.warnings += 1,
+                Level::Note => counts.notes += 1,
+            }
+        }
+        let mut codes: Vec<String> = diagnostics
+            .iter()
+            .filter_map(|diagnostic| diagnostic.code.clone())
+            .collect();
+        codes.sort();
+        codes.dedup();
+        let error_free = counts.errors == 0;
+        ConformanceReport {
+            contract_id,
+            passed: error_free && traits.iter().all(|t| t.conforms == Some(true)),
+            error_free,
+            counts,
+            warnings: diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.level == Level::Warning)
+                .cloned()
+                .collect(),
+            traits,
+            codes,
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::clarity::ast::parser;
+use crate::clarity::callables::CallableType;
+use crate::clarity::costs::cost_functions::ClarityCostFunction;
+use crate::clarity::functions::{lookup_reserved_functions, NativeFunctions};
+use crate::clarity::representations::PreSymbolicExpression;
+use crate::clarity::representations::PreSymbolicExpressionType::{AtomValue, List, Tuple};
+use crate::clarity::types::Value;
+use std::fmt;
+
+// A cost, as defined by the cost contracts, in terms of `n`, the size of the
+// input of the cost function.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CostFormula {
+    Constant(u128),
+    Linear(u128, u128),
+    LogN(u128, u128),
+    NLogN(u128, u128),
+}
+
+impl CostFormula {
+    pub fn is_constant(&self) -> bool {
+        matches!(self, CostFormula::Constant(_))
+    }
+}
+
+impl fmt::Display for CostFormula {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CostFormula::Constant(cost) => write!(f, "{}", cost),
+            CostFormula::Linear(a, b) => write!(f, "{} * n + {}", a, b),
+            CostFormula::LogN(a, b) => write!(f, "{} * log2(n) + {}", a, b),
+            CostFormula::NLogN(a, b) => write!(f, "{} * n * log2(n) + {}", a, b),
+        }
+    }
+}
+
+// The cost function assessed when calling a native function, if any.
+pub fn cost_function(function: NativeFunctions) -> Option<ClarityCostFunction> {
+    use crate::clarity::functions::NativeFunctions::*;
+    if let Some(CallableType::NativeFunction(_, _, cost_function)) =
+        lookup_reserved_functions(function.get_name().as_str())
+    {
+        return Some(cost_function);
+    }
+    let cost_function = match function {
+        And => ClarityCostFunction::And,
+        Or => ClarityCostFunction::Or,
+        If => ClarityCostFunction::If,
+        Let => ClarityCostFunction::Let,
+        FetchVar => ClarityCostFunction::FetchVar,
+        SetVar => ClarityCostFunction::SetVar,
+        Map => ClarityCostFunction::Map,
+        Filter => ClarityCostFunction::Filter,
+        Fold => ClarityCostFunction::Fold,
+        Concat => ClarityCostFunction::Concat,
+        AsMaxLen => ClarityCostFunction::AsMaxLen,
+        Append => ClarityCostFunction::Append,
+        ListCons => ClarityCostFunction::ListCons,
+        FetchEntry => ClarityCostFunction::FetchEntry,
+        SetEntry | InsertEntry | DeleteEntry => ClarityCostFunction::SetEntry,
+        TupleCons => ClarityCostFunction::TupleCons,
+        TupleGet => ClarityCostFunction::TupleGet,
+        Secp256k1Recover => ClarityCostFunction::Secp256k1recover,
+        Secp256k1Verify => ClarityCostFunction::Secp256k1verify,
+        Print => ClarityCostFunction::Print,
+        ContractCall => ClarityCostFunction::ContractCall,
+        ContractOf => ClarityCostFunction::ContractOf,
+        PrincipalOf => ClarityCostFunction::PrincipalOf,
+        GetBlockInfo => ClarityCostFunction::BlockInfo,
+        Asserts => ClarityCostFunction::Asserts,
+        Match => ClarityCostFunction::Match,
+        MintAsset => ClarityCostFunction::NftMint,
+        MintToken => ClarityCostFunction::FtMint,
+        TransferAsset => ClarityCostFunction::NftTransfer,
+        TransferToken => ClarityCostFunction::FtTransfer,
+        GetTokenBalance => ClarityCostFunction::FtBalance,
+        GetAssetOwner => ClarityCostFunction::NftOwner,
+        BurnAsset => ClarityCostFunction::NftBurn,
+        BurnToken => ClarityCostFunction::FtBurn,
+        GetTokenSupply => ClarityCostFunction::FtSupply,
+        AtBlock => ClarityCostFunction::AtBlock,
+        GetStxBalance => ClarityCostFunction::StxBalance,
+        StxTransfer | StxBurn => ClarityCostFunction::StxTransfer,
+        _ => return None,
+    };
+    Some(cost_function)
+}
+
+// Read the definition of a cost function in the source of a cost contract,
+// as the formula of each of its non-zero dimensions (runtime, read_count...).
+pub fn cost_formula(
+    costs_contract: &str,
+    cost_function: ClarityCostFunction,
+) -> Option<Vec<(String, CostFormula)>> {
+    let expressions = parser::parse(costs_contract).ok()?;
+    let name = cost_function.get_name();
+    let body = expressions
+        .iter()
+        .find_map(|expr| match expr.match_list()? {
+            [define, signature, body]
+                if define.match_atom()?.as_str() == "define-read-only"
+                    && signature.match_list()?.first()?.match_atom()?.as_str() == name =>
+            {
+                Some(body)
+            }
+            _ => None,
+        })?;
+
+    match &body.pre_expr {
+        // `(runtime cost)` only has a runtime cost
+        List(list) => match &list[..] {
+            [function, cost] if function.match_atom()?.as_str() == "runtime" => {
+                Some(vec![("runtime".to_string(), formula(cost)?)])
+            }
+            _ => None,
+        },
+        Tuple(fields) => {
+            let mut dimensions = vec![];
+            for field in fields.chunks(2) {
+                let (dimension, cost) = match field {
+                    [dimension, cost] => (dimension.match_atom()?, formula(cost)?),
+                    _ => return None,
+                };
+                if cost != CostFormula::Constant(0) {
+                    dimensions.push((dimension.to_string(), cost));
+                }
+            }
+            Some(dimensions)
+        }
+        _ => None,
+    }
+}
+
+fn formula(expr: &PreSymbolicExpression) -> Option<CostFormula> {
+    match &expr.pre_expr {
+        AtomValue(Value::UInt(cost)) => Some(CostFormula::Constant(*cost)),
+        List(list) => {
+            let (function, a, b) = match &list[..] {
+                [function, n, a, b] if n.match_atom()?.as_str() == "n" => {
+                    (function.match_atom()?, coefficient(a)?, coefficient(b)?)
+                }
+                _ => return None,
+            };
+            match function.as_str() {
+                "linear" => Some(CostFormula::Linear(a, b)),
+                "logn" => Some(CostFormula::LogN(a, b)),
+                "nlogn" => Some(CostFormula::NLogN(a, b)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn coefficient(expr: &PreSymbolicExpression) -> Option<u128> {
+    match expr.match_atom_value()? {
+        Value::UInt(value) => Some(*value),
+        _ => None,
+    }
+}
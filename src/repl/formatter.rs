@@ -0,0 +1,121 @@
+use crate::clarity::diagnostic::Diagnostic;
+use crate::clarity::errors::Error;
+use crate::clarity::types::{OptionalData, SequenceData, Value};
+use crate::repl::settings::IntDisplay;
+use crate::repl::ExecutionResult;
+use std::fmt;
+use std::sync::Arc;
+
+// What a formatter needs to know about the snippet it renders the result of.
+pub struct FormatContext<'a> {
+    // The name of the contract deployed by the snippet, or `<stdin>`
+    pub contract_name: &'a String,
+    // The lines of the snippet, to show the source of the diagnostics
+    pub lines: &'a Vec<String>,
+    pub int_display: &'a IntDisplay,
+}
+
+// Renders the outcome of evaluating a snippet into the lines of output of the
+// session. Install one with `Session::with_result_formatter` to replace the
+// built-in formatting, implemented by `DefaultFormatter`.
+pub trait ResultFormatter: Send + Sync {
+    fn format_result(&self, result: &ExecutionResult, context: &FormatContext) -> Vec<String>;
+
+    // The evaluation failed, with a diagnostic when it failed at analysis,
+    // or a runtime error.
+    fn format_error(
+        &self,
+        diagnostic: Option<&Diagnostic>,
+        error: Option<&Error>,
+        context: &FormatContext,
+    ) -> Vec<String>;
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DefaultFormatter;
+
+impl ResultFormatter for DefaultFormatter {
+    fn format_result(&self, result: &ExecutionResult, context: &FormatContext) -> Vec<String> {
+        let mut output = Vec::<String>::new();
+        for diagnostic in &result.diagnostics {
+            output.append(&mut diagnostic.output(context.contract_name, context.lines));
+        }
+        if !result.events.is_empty() {
+            output.push(black!("Events emitted"));
+            for event in result.events.iter() {
+                output.push(black!(format!("{}", event)));
+            }
+        }
+        if let Some(ref result) = result.result {
+            output.push(green!(format_value(result, context.int_display)));
+        }
+        output
+    }
+
+    fn format_error(
+        &self,
+        diagnostic: Option<&Diagnostic>,
+        error: Option<&Error>,
+        context: &FormatContext,
+    ) -> Vec<String> {
+        let mut output = Vec::<String>::new();
+        if let Some(diagnostic) = diagnostic {
+            output.append(&mut diagnostic.output(context.contract_name, context.lines));
+        } else if let Some(error) = error {
+            output.push(red!(format!("Runtime error: {}", error)));
+        }
+        output
+    }
+}
+
+// The formatter installed on a session, which can be cloned along with it.
+#[derive(Clone)]
+pub struct InstalledFormatter(pub Arc<dyn ResultFormatter>);
+
+impl Default for InstalledFormatter {
+    fn default() -> Self {
+        InstalledFormatter(Arc::new(DefaultFormatter))
+    }
+}
+
+impl fmt::Debug for InstalledFormatter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ResultFormatter")
+    }
+}
+
+// Display a value as its `Display` implementation does, with integers shown
+// as configured.
+pub fn format_value(value: &Value, int_display: &IntDisplay) -> String {
+    match (value, int_display) {
+        (Value::Int(int), IntDisplay::Hex) if *int < 0 => format!("-{:#x}", int.unsigned_abs()),
+        (Value::Int(int), IntDisplay::Hex) => format!("{:#x}", int),
+        (Value::UInt(int), IntDisplay::Hex) => format!("u{:#x}", int),
+        (_, IntDisplay::Decimal) => value.to_string(),
+        (Value::Tuple(tuple), _) => {
+            let fields: Vec<String> = tuple
+                .data_map
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, format_value(value, int_display)))
+                .collect();
+            format!("{{{}}}", fields.join(", "))
+        }
+        (Value::Optional(OptionalData { data: Some(inner) }), _) => {
+            format!("(some {})", format_value(inner, int_display))
+        }
+        (Value::Response(response), _) => format!(
+            "({} {})",
+            if response.committed { "ok" } else { "err" },
+            format_value(&response.data, int_display)
+        ),
+        (Value::Sequence(SequenceData::List(list)), _) => {
+            let elements: Vec<String> = list
+                .data
+                .iter()
+                .map(|element| format_value(element, int_display))
+                .collect();
+            format!("[{}]", elements.join(", "))
+        }
+        _ => value.to_string(),
+    }
+}
@@ -1,32 +1,52 @@
-use std::collections::{btree_map::Entry, BTreeMap, BTreeSet};
+use std::collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap};
+use std::time::{Duration, Instant};
 
 use crate::analysis::annotation::{Annotation, AnnotationKind};
+use crate::analysis::branch_type_checker::BranchTypeChecker;
 use crate::analysis::contract_call_detector::ContractCallDetector;
-use crate::analysis::{self, AnalysisPass as REPLAnalysisPass};
+use crate::analysis::define_trait_checker::DefineTraitChecker;
+use crate::analysis::len_result_checker::LenResultChecker;
+use crate::analysis::map_get_comparison_checker::MapGetComparisonChecker;
+use crate::analysis::public_return_checker::PublicReturnChecker;
+use crate::analysis::sequence_comparison_checker::SequenceComparisonChecker;
+use crate::analysis::trait_signature_checker::TraitSignatureChecker;
+use crate::analysis::unwrap_argument_checker::UnwrapArgumentChecker;
+use crate::analysis::version_syntax_checker::VersionSyntaxChecker;
+use crate::analysis::{self, AnalysisPass as REPLAnalysisPass, AnalysisResult};
 use crate::clarity;
-use crate::clarity::analysis::{types::AnalysisPass, ContractAnalysis};
+use crate::clarity::analysis::{types::AnalysisPass, CheckErrors, ContractAnalysis};
 use crate::clarity::ast;
 use crate::clarity::ast::ContractAST;
+use crate::clarity::branch_recorder::BranchRecorder;
+use crate::clarity::call_recorder::{CallRecorder, RecordedCall};
 use crate::clarity::contexts::{
-    CallStack, ContractContext, Environment, GlobalContext, LocalContext,
+    CallStack, ContractContext, Environment, GlobalContext, IterationLimits, LocalContext,
 };
 use crate::clarity::contracts::Contract;
+use crate::clarity::cost_tree::{call_name, CostTreeNode, CostTreeRecorder};
 use crate::clarity::costs::{ExecutionCost, LimitedCostTracker};
 use crate::clarity::coverage::TestCoverageReport;
-use crate::clarity::database::{Datastore, NULL_HEADER_DB};
+use crate::clarity::database::{ClarityDatabase, Datastore, StoreType, NULL_HEADER_DB};
 use crate::clarity::diagnostic::{Diagnostic, Level};
 use crate::clarity::errors::Error;
 use crate::clarity::events::*;
+use crate::clarity::functions::define::DefineFunctions;
+use crate::clarity::guard_failures::GuardFailureRecorder;
+use crate::clarity::let_bindings::LetBindingsCapture;
+use crate::clarity::native_hooks::NativeCallHooks;
 use crate::clarity::representations::SymbolicExpressionType::{Atom, List};
 use crate::clarity::representations::{Span, SymbolicExpression};
 use crate::clarity::types::{
-    self, PrincipalData, QualifiedContractIdentifier, StandardPrincipalData, Value,
+    self, AssetIdentifier, BlockInfoProperty, PrincipalData, QualifiedContractIdentifier,
+    StandardPrincipalData, TypeSignature, Value,
 };
 use crate::clarity::util::StacksAddress;
 use crate::clarity::{analysis::AnalysisDatabase, database::ClarityBackingStore};
-use crate::clarity::{eval, eval_all};
-use crate::repl::{CostSynthesis, ExecutionResult};
+use crate::clarity::{eval, eval_all, ClarityName, StacksBlockId};
+use crate::repl::state_diff::StateSnapshot;
+use crate::repl::{CostSynthesis, ExecutionResult, StacksEvent};
 
+// Block limits in effect in Stacks 2.0, with costs-v1
 pub const BLOCK_LIMIT_MAINNET: ExecutionCost = ExecutionCost {
     write_length: 15_000_000,
     write_count: 7_750,
@@ -35,14 +55,66 @@ pub const BLOCK_LIMIT_MAINNET: ExecutionCost = ExecutionCost {
     runtime: 5_000_000_000,
 };
 
+// Block limits in effect since Stacks 2.05, with costs-v2
+pub const BLOCK_LIMIT_MAINNET_205: ExecutionCost = ExecutionCost {
+    write_length: 15_000_000,
+    write_count: 15_000,
+    read_length: 100_000_000,
+    read_count: 15_000,
+    runtime: 5_000_000_000,
+};
+
+pub fn block_limit(costs_version: u32) -> ExecutionCost {
+    match costs_version {
+        1 => BLOCK_LIMIT_MAINNET,
+        _ => BLOCK_LIMIT_MAINNET_205,
+    }
+}
+
+// The state of a contract: the entries of its data-vars, maps and tokens in
+// the datastore, with the balances of its tokens shown by `::get_assets_maps`.
+#[derive(Clone, Debug)]
+pub struct ContractSnapshot {
+    pub contract_identifier: QualifiedContractIdentifier,
+    entries: BTreeMap<String, String>,
+    tokens: BTreeMap<String, BTreeMap<String, u128>>,
+}
+
+// The whole state of the chain: the datastore, holding the contracts, their
+// data and the blocks, with the balances shown by `::get_assets_maps`.
+#[derive(Clone, Debug)]
+pub struct StateCheckpoint {
+    datastore: Datastore,
+    accounts: BTreeSet<String>,
+    tokens: BTreeMap<String, BTreeMap<String, u128>>,
+}
+
 #[derive(Clone, Debug)]
 pub struct ClarityInterpreter {
     pub datastore: Datastore,
     tx_sender: StandardPrincipalData,
+    contract_caller: Option<PrincipalData>,
+    as_contract: Option<QualifiedContractIdentifier>,
     accounts: BTreeSet<String>,
     tokens: BTreeMap<String, BTreeMap<String, u128>>,
     costs_version: u32,
     analysis: Vec<String>,
+    analysis_settings: analysis::Settings,
+    diagnostic_callback: Option<analysis::DiagnosticCallback>,
+    analysis_timeout: Option<Duration>,
+    call_recorder: Option<CallRecorder>,
+    branch_recorder: Option<BranchRecorder>,
+    max_value_allocation: Option<u64>,
+    iteration_limits: IterationLimits,
+    capture_let_bindings: bool,
+    record_guard_failures: bool,
+    native_call_hooks: NativeCallHooks,
+    test_mode: bool,
+    block_info_mocks: HashMap<(u32, BlockInfoProperty), Value>,
+    // Heights of the blocks whose state the snippets read, innermost last
+    block_contexts: Vec<u32>,
+    // The calls of the last execution with cost tracking, with their costs
+    cost_tree: Vec<CostTreeNode>,
 }
 
 impl ClarityInterpreter {
@@ -57,13 +129,257 @@ impl ClarityInterpreter {
         ClarityInterpreter {
             datastore,
             tx_sender,
+            contract_caller: None,
+            as_contract: None,
             accounts,
             tokens,
             costs_version,
             analysis,
+            analysis_settings: analysis::Settings::default(),
+            diagnostic_callback: None,
+            analysis_timeout: None,
+            call_recorder: None,
+            branch_recorder: None,
+            max_value_allocation: None,
+            iteration_limits: IterationLimits::default(),
+            capture_let_bindings: false,
+            record_guard_failures: false,
+            native_call_hooks: NativeCallHooks::default(),
+            test_mode: false,
+            block_info_mocks: HashMap::new(),
+            block_contexts: vec![],
+            cost_tree: vec![],
         }
     }
 
+    pub fn set_analysis_settings(&mut self, analysis_settings: analysis::Settings) {
+        self.analysis_settings = analysis_settings;
+    }
+
+    pub fn get_analysis_settings(&self) -> &analysis::Settings {
+        &self.analysis_settings
+    }
+
+    pub fn set_diagnostic_callback(&mut self, callback: Option<analysis::DiagnosticCallback>) {
+        self.diagnostic_callback = callback;
+    }
+
+    pub fn set_max_value_allocation(&mut self, max_value_allocation: Option<u64>) {
+        self.max_value_allocation = max_value_allocation;
+    }
+
+    pub fn set_iteration_limits(&mut self, iteration_limits: IterationLimits) {
+        self.iteration_limits = iteration_limits;
+    }
+
+    pub fn set_capture_let_bindings(&mut self, capture_let_bindings: bool) {
+        self.capture_let_bindings = capture_let_bindings;
+    }
+
+    pub fn set_record_guard_failures(&mut self, record_guard_failures: bool) {
+        self.record_guard_failures = record_guard_failures;
+    }
+
+    pub fn set_analysis_timeout(&mut self, analysis_timeout: Option<Duration>) {
+        self.analysis_timeout = analysis_timeout;
+    }
+
+    // Make `get-block-info?` return a value for a height and property,
+    // whatever the state of the chain.
+    pub fn mock_block_info(&mut self, height: u32, property: BlockInfoProperty, value: Value) {
+        self.block_info_mocks.insert((height, property), value);
+    }
+
+    pub fn set_native_call_hooks(&mut self, native_call_hooks: NativeCallHooks) {
+        self.native_call_hooks = native_call_hooks;
+    }
+
+    // Let the snippets and contracts call the test natives.
+    pub fn set_test_mode(&mut self, test_mode: bool) {
+        self.test_mode = test_mode;
+    }
+
+    pub fn record_call(
+        &mut self,
+        contract_identifier: QualifiedContractIdentifier,
+        function_name: ClarityName,
+    ) {
+        self.call_recorder = Some(CallRecorder::new(contract_identifier, function_name));
+    }
+
+    pub fn recorded_call(&self) -> Option<&RecordedCall> {
+        self.call_recorder
+            .as_ref()
+            .and_then(|recorder| recorder.recorded_call.as_ref())
+    }
+
+    pub fn cost_tree(&self) -> &[CostTreeNode] {
+        &self.cost_tree
+    }
+
+    // Re-execute a recorded call on top of the current state, after restoring
+    // the values it read, then roll back all of its changes.
+    pub fn replay_call(&mut self, call: &RecordedCall) -> Result<Value, Error> {
+        self.call_isolated(
+            &call.contract_identifier,
+            &call.function_name,
+            &call.args,
+            call.sender.clone(),
+            call.caller.clone(),
+            &call.reads,
+        )
+    }
+
+    // Call a public function as the tx-sender, then roll back all of its changes.
+    pub fn call_public_function_isolated(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+        function_name: &ClarityName,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        let tx_sender: PrincipalData = self.tx_sender.clone().into();
+        self.call_isolated(
+            contract_identifier,
+            function_name,
+            args,
+            Some(tx_sender.clone()),
+            Some(tx_sender),
+            &[],
+        )
+    }
+
+    // Call a public function as the tx-sender like
+    // `call_public_function_isolated`, counting the evaluations of the
+    // expressions of its contract, by id.
+    pub fn call_counting_evaluations(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+        function_name: &ClarityName,
+        args: &[Value],
+    ) -> (Result<Value, Error>, HashMap<u64, u64>) {
+        self.branch_recorder = Some(BranchRecorder::new(contract_identifier.clone()));
+        let result = self.call_public_function_isolated(contract_identifier, function_name, args);
+        let evaluations = self
+            .branch_recorder
+            .take()
+            .map(|recorder| recorder.evaluations)
+            .unwrap_or_default();
+        (result, evaluations)
+    }
+
+    fn call_isolated(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+        function_name: &ClarityName,
+        args: &[Value],
+        sender: Option<PrincipalData>,
+        caller: Option<PrincipalData>,
+        reads: &[(String, Option<String>)],
+    ) -> Result<Value, Error> {
+        // The keys which were absent are removed for the call, and put back
+        // after it
+        let removed: Vec<(String, String)> = reads
+            .iter()
+            .filter(|(_, value)| value.is_none())
+            .filter_map(|(key, _)| Some((key.clone(), self.datastore.remove(key)?)))
+            .collect();
+        let conn = self.datastore.as_clarity_db(&NULL_HEADER_DB);
+        let mut global_context = GlobalContext::new(false, conn, LimitedCostTracker::new_free());
+        global_context.begin();
+        global_context.branch_recorder = self.branch_recorder.take();
+        for (key, value) in reads.iter() {
+            if let Some(value) = value {
+                global_context.database.store.put(key, value);
+            }
+        }
+
+        let result = global_context.execute(|g| {
+            if !g.database.has_contract(contract_identifier) {
+                return Err(CheckErrors::NoSuchContract(contract_identifier.to_string()).into());
+            }
+            let contract = g.database.get_contract(contract_identifier)?;
+            let function = contract
+                .contract_context
+                .lookup_function(function_name)
+                .ok_or_else(|| CheckErrors::UndefinedFunction(function_name.to_string()))?;
+            if !function.is_public() {
+                return Err(CheckErrors::NoSuchPublicFunction(
+                    contract_identifier.to_string(),
+                    function_name.to_string(),
+                )
+                .into());
+            }
+            let mut call_stack = CallStack::new();
+            let mut env = Environment::new(
+                g,
+                &contract.contract_context,
+                &mut call_stack,
+                sender,
+                caller,
+            );
+            env.execute_function_as_transaction(&function, args, None)
+        });
+        self.branch_recorder = global_context.branch_recorder.take();
+        global_context.roll_back();
+        for (key, value) in removed.iter() {
+            self.datastore.put(key, value);
+        }
+        result
+    }
+
+    // The source of a deployed contract, as stored at deployment.
+    pub fn get_contract_source(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+    ) -> Option<String> {
+        let conn = self.datastore.as_clarity_db(&NULL_HEADER_DB);
+        let mut global_context = GlobalContext::new(false, conn, LimitedCostTracker::new_free());
+        global_context.begin();
+        let source = global_context
+            .database
+            .get_contract_src(contract_identifier);
+        global_context.roll_back();
+        source
+    }
+
+    // List the public and read-only functions of a contract with their
+    // argument types, sorted by name.
+    pub fn get_public_functions(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+    ) -> Result<Vec<(ClarityName, Vec<TypeSignature>)>, Error> {
+        let conn = self.datastore.as_clarity_db(&NULL_HEADER_DB);
+        let mut global_context = GlobalContext::new(false, conn, LimitedCostTracker::new_free());
+        global_context.begin();
+        let contract = global_context.execute(|g| {
+            if !g.database.has_contract(contract_identifier) {
+                return Err(CheckErrors::NoSuchContract(contract_identifier.to_string()).into());
+            }
+            g.database.get_contract(contract_identifier)
+        });
+        global_context.roll_back();
+        let mut functions: Vec<_> = contract?
+            .contract_context
+            .functions
+            .values()
+            .filter(|function| function.is_public())
+            .map(|function| (function.name.clone(), function.arg_types.clone()))
+            .collect();
+        functions.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(functions)
+    }
+
+    pub fn get_contract_analysis(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+    ) -> Option<ContractAnalysis> {
+        let mut analysis_db = AnalysisDatabase::new(&mut self.datastore);
+        analysis_db.begin();
+        let analysis = analysis_db.load_contract(contract_identifier);
+        analysis_db.roll_back();
+        analysis
+    }
+
     pub fn run(
         &mut self,
         snippet: String,
@@ -71,30 +387,98 @@ impl ClarityInterpreter {
         cost_track: bool,
         coverage_reporter: Option<TestCoverageReport>,
     ) -> Result<ExecutionResult, (String, Option<Diagnostic>, Option<Error>)> {
-        let mut ast = self.build_ast(contract_identifier.clone(), snippet.clone())?;
+        self.run_with_diagnostics(snippet, contract_identifier, cost_track, coverage_reporter)
+            .map_err(|(kind, mut diagnostics, error)| (kind, diagnostics.pop(), error))
+    }
+
+    // Like `run`, but failing with all the diagnostics found, ending with the
+    // error, so that the warnings and notes reported before an analysis
+    // error are kept.
+    pub fn run_with_diagnostics(
+        &mut self,
+        snippet: String,
+        contract_identifier: QualifiedContractIdentifier,
+        cost_track: bool,
+        coverage_reporter: Option<TestCoverageReport>,
+    ) -> Result<ExecutionResult, (String, Vec<Diagnostic>, Option<Error>)> {
+        let mut ast = self
+            .build_ast(contract_identifier.clone(), snippet.clone())
+            .map_err(|(kind, diagnostic, error)| (kind, diagnostic.into_iter().collect(), error))?;
         let (annotations, mut diagnostics) = self.collect_annotations(&ast, &snippet);
-        let (analysis, mut analysis_diagnostics) =
-            match self.run_analysis(contract_identifier.clone(), &mut ast, &annotations) {
-                Ok((analysis, diagnostics)) => (analysis, diagnostics),
-                Err(e) => return Err(e),
-            };
-        diagnostics.append(&mut analysis_diagnostics);
-        let mut result = self.execute(
-            contract_identifier,
-            &mut ast,
-            snippet,
-            analysis,
-            cost_track,
-            coverage_reporter,
-        )?;
+        match self.run_analysis(contract_identifier.clone(), &mut ast, &annotations) {
+            Ok((analysis, mut analysis_diagnostics)) => {
+                diagnostics.append(&mut analysis_diagnostics);
+                let mut result = self
+                    .execute(
+                        contract_identifier,
+                        &mut ast,
+                        snippet,
+                        analysis,
+                        cost_track,
+                        coverage_reporter,
+                    )
+                    .map_err(|(kind, diagnostic, error)| {
+                        (kind, diagnostic.into_iter().collect(), error)
+                    })?;
+                result.diagnostics = diagnostics;
 
-        result.diagnostics = diagnostics;
+                // todo: instead of just returning the value, we should be returning:
+                // - value
+                // - execution cost
+                // - events emitted
+                Ok(result)
+            }
+            Err(mut analysis_diagnostics) => {
+                diagnostics.append(&mut analysis_diagnostics);
+                Err(("Analysis".to_string(), diagnostics, None))
+            }
+        }
+    }
 
-        // todo: instead of just returning the value, we should be returning:
-        // - value
-        // - execution cost
-        // - events emitted
-        Ok(result)
+    // Parse and analyze a snippet without executing it, returning the analysis
+    // if it succeeded, and all of the diagnostics, including the error which
+    // stopped the analysis, if any.
+    pub fn check(
+        &mut self,
+        snippet: String,
+        contract_identifier: QualifiedContractIdentifier,
+    ) -> (Option<ContractAnalysis>, Vec<Diagnostic>) {
+        match self.build_ast(contract_identifier.clone(), snippet.clone()) {
+            Ok(ast) => self.check_ast(snippet, contract_identifier, ast),
+            Err((_, diagnostic, _)) => (None, diagnostic.into_iter().collect()),
+        }
+    }
+
+    pub fn check_ast(
+        &mut self,
+        snippet: String,
+        contract_identifier: QualifiedContractIdentifier,
+        mut ast: ContractAST,
+    ) -> (Option<ContractAnalysis>, Vec<Diagnostic>) {
+        let (annotations, mut diagnostics) = self.collect_annotations(&ast, &snippet);
+        match self.run_analysis(contract_identifier, &mut ast, &annotations) {
+            Ok((analysis, mut analysis_diagnostics)) => {
+                diagnostics.append(&mut analysis_diagnostics);
+                (Some(analysis), diagnostics)
+            }
+            Err(mut analysis_diagnostics) => {
+                diagnostics.append(&mut analysis_diagnostics);
+                (None, diagnostics)
+            }
+        }
+    }
+
+    pub fn save_contract_analysis(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+        contract_analysis: &ContractAnalysis,
+    ) {
+        let mut analysis_db = AnalysisDatabase::new(&mut self.datastore);
+        analysis_db.begin();
+        analysis_db
+            .insert_contract(contract_identifier, contract_analysis)
+            .unwrap();
+        analysis_db.commit();
     }
 
     pub fn detect_dependencies(
@@ -114,7 +498,12 @@ impl ClarityInterpreter {
             LimitedCostTracker::new_free(),
         );
         let mut analysis_db = AnalysisDatabase::new(&mut self.datastore);
-        match ContractCallDetector::run_pass(&mut contract_analysis, &mut analysis_db, &vec![]) {
+        match ContractCallDetector::run_pass(
+            &mut contract_analysis,
+            &mut analysis_db,
+            &vec![],
+            &self.analysis_settings,
+        ) {
             Ok(_) => Ok(contract_analysis.dependencies),
             Err(e) => Err(format!("{:?}", e)),
         }
@@ -157,6 +546,7 @@ impl ClarityInterpreter {
                             Err(e) => {
                                 diagnostics.push(Diagnostic {
                                     level: Level::Warning,
+                                    code: None,
                                     message: format!("{}", e),
                                     spans: vec![span.clone()],
                                     suggestion: None,
@@ -168,6 +558,7 @@ impl ClarityInterpreter {
                     } else {
                         diagnostics.push(Diagnostic {
                             level: Level::Warning,
+                            code: None,
                             message: "malformed annotation".to_string(),
                             spans: vec![span],
                             suggestion: None,
@@ -184,8 +575,64 @@ impl ClarityInterpreter {
         contract_identifier: QualifiedContractIdentifier,
         contract_ast: &mut ContractAST,
         annotations: &Vec<Annotation>,
-    ) -> Result<(ContractAnalysis, Vec<Diagnostic>), (String, Option<Diagnostic>, Option<Error>)>
-    {
+    ) -> Result<(ContractAnalysis, Vec<Diagnostic>), Vec<Diagnostic>> {
+        let deadline = self
+            .analysis_timeout
+            .map(|timeout| (Instant::now() + timeout, timeout));
+
+        let mut traits = HashMap::new();
+        for trait_identifier in contract_ast.implemented_traits.iter() {
+            let methods = self
+                .get_contract_analysis(&trait_identifier.contract_identifier)
+                .and_then(|analysis| analysis.get_defined_trait(&trait_identifier.name).cloned());
+            if let Some(methods) = methods {
+                traits.insert(trait_identifier.clone(), methods);
+            }
+        }
+
+        // Checks run on the AST before type-checking, which would report
+        // these errors as a mismatch or an unknown name, without pointing at
+        // their cause
+        let expressions = &contract_ast.expressions;
+        let settings = &self.analysis_settings;
+        let pre_checks: [&dyn Fn() -> AnalysisResult; 9] = [
+            // Unknown types in trait definitions
+            &|| DefineTraitChecker::run(expressions),
+            // Sequences which cannot be ordered in the version
+            &|| SequenceComparisonChecker::run(expressions, settings),
+            // Functions and keywords unavailable in the version
+            &|| VersionSyntaxChecker::run(expressions, settings),
+            // Values which cannot be unwrapped
+            &|| UnwrapArgumentChecker::run(expressions),
+            // Public functions not returning a response
+            &|| PublicReturnChecker::run(expressions),
+            // Lengths used as ints or sequences
+            &|| LenResultChecker::run(expressions),
+            // Map values compared with values of another type
+            &|| MapGetComparisonChecker::run(expressions),
+            // Branches returning values of different types
+            &|| BranchTypeChecker::run(expressions),
+            // Methods not matching the signature of their trait
+            &|| TraitSignatureChecker::run(expressions, &traits),
+        ];
+        let mut errors: Vec<Diagnostic> = pre_checks
+            .iter()
+            .filter_map(|check| check().err())
+            .flatten()
+            .collect();
+        // Report the first error in the source
+        errors.sort_by_key(|error| {
+            error.spans.first().map_or((u32::MAX, u32::MAX), |span| {
+                (span.start_line, span.start_column)
+            })
+        });
+        if let Some(error) = errors.into_iter().next() {
+            if let Some(ref callback) = self.diagnostic_callback {
+                callback.notify(std::slice::from_ref(&error));
+            }
+            return Err(vec![error]);
+        }
+
         let mut analysis_db = AnalysisDatabase::new(&mut self.datastore);
 
         // Run standard clarity analyses
@@ -195,26 +642,30 @@ impl ClarityInterpreter {
             &mut analysis_db,
             false,
             LimitedCostTracker::new_free(),
+            self.test_mode,
         ) {
             Ok(res) => res,
             Err((error, cost_tracker)) => {
-                return Err(("Analysis".to_string(), Some(error.diagnostic), None));
+                if let Some(ref callback) = self.diagnostic_callback {
+                    callback.notify(std::slice::from_ref(&error.diagnostic));
+                }
+                return Err(vec![error.diagnostic]);
             }
         };
 
-        // Run REPL-only analyses
+        // Run REPL-only analyses, which report to the callback themselves
         match analysis::run_analysis(
             &mut contract_analysis,
             &mut analysis_db,
             &self.analysis,
             annotations,
+            &self.analysis_settings,
+            self.diagnostic_callback.as_ref(),
+            deadline,
         ) {
+            // The last diagnostic of an error is the error
             Ok(diagnostics) => Ok((contract_analysis, diagnostics)),
-            Err(mut diagnostics) => {
-                // The last diagnostic should be the error
-                let error = diagnostics.pop().unwrap();
-                Err(("Analysis".to_string(), Some(error), None))
-            }
+            Err(diagnostics) => Err(diagnostics),
         }
     }
 
@@ -231,17 +682,44 @@ impl ClarityInterpreter {
         let mut execution_result = ExecutionResult::default();
         let mut contract_saved = false;
         let mut serialized_events = vec![];
+        let mut contract_events = vec![];
         let mut accounts_to_debit = vec![];
         let mut accounts_to_credit = vec![];
+        // A snippet defining anything deploys a contract
+        if !self.block_contexts.is_empty()
+            && contract_ast
+                .expressions
+                .iter()
+                .any(|expr| DefineFunctions::try_parse(expr).is_some())
+        {
+            return Err((
+                "Contracts cannot be deployed within a block context".to_string(),
+                None,
+                None,
+            ));
+        }
         let mut contract_context = ContractContext::new(contract_identifier.clone());
         let value = {
-            let tx_sender: PrincipalData = self.tx_sender.clone().into();
+            let (tx_sender, contract_caller) = match self.as_contract {
+                Some(ref contract_identifier) => {
+                    let principal = PrincipalData::Contract(contract_identifier.clone());
+                    (principal.clone(), principal)
+                }
+                None => {
+                    let tx_sender: PrincipalData = self.tx_sender.clone().into();
+                    let contract_caller = self
+                        .contract_caller
+                        .clone()
+                        .unwrap_or_else(|| tx_sender.clone());
+                    (tx_sender, contract_caller)
+                }
+            };
 
             let mut conn = self.datastore.as_clarity_db(&NULL_HEADER_DB);
             let cost_tracker = if cost_track {
                 LimitedCostTracker::new(
                     false,
-                    BLOCK_LIMIT_MAINNET.clone(),
+                    block_limit(self.costs_version),
                     &mut conn,
                     self.costs_version,
                 )
@@ -251,7 +729,39 @@ impl ClarityInterpreter {
             };
             let mut global_context = GlobalContext::new(false, conn, cost_tracker);
             global_context.coverage_reporting = coverage_reporter;
-            global_context.begin();
+            global_context.call_recorder = self.call_recorder.take();
+            global_context.max_value_allocation = self.max_value_allocation;
+            global_context.iteration_limits = self.iteration_limits.clone();
+            if self.capture_let_bindings {
+                global_context.let_bindings = Some(LetBindingsCapture::new());
+            }
+            if self.record_guard_failures {
+                global_context.guard_failures = Some(GuardFailureRecorder::new());
+            }
+            global_context.native_call_hooks = self.native_call_hooks.clone();
+            global_context.test_mode = self.test_mode;
+            global_context.block_info_mocks = self.block_info_mocks.clone();
+            if cost_track {
+                global_context.cost_tree = Some(CostTreeRecorder::new());
+            }
+            // Within a block context, the snippet reads the state at the end
+            // of that block and cannot write, as within `at-block`
+            let prior_tip = match self.block_contexts.last() {
+                Some(height) => {
+                    global_context.begin_read_only();
+                    let block = global_context.database.get_index_block_header_hash(*height);
+                    Some(
+                        global_context
+                            .database
+                            .set_block_hash(block, false)
+                            .unwrap(),
+                    )
+                }
+                None => {
+                    global_context.begin();
+                    None
+                }
+            };
 
             let result = global_context.execute(|g| {
                 // If we have more than one instruction
@@ -263,7 +773,7 @@ impl ClarityInterpreter {
                         &mut contract_context,
                         &mut call_stack,
                         Some(tx_sender.clone()),
-                        Some(tx_sender.clone()),
+                        Some(contract_caller.clone()),
                     );
 
                     let result = match contract_ast.expressions[0].expr {
@@ -281,30 +791,62 @@ impl ClarityInterpreter {
                                     _ => unreachable!(),
                                 };
                                 let method = expression[2].match_atom().unwrap().to_string();
-                                let mut args = vec![];
-                                for arg in expression[3..].iter() {
-                                    let evaluated_arg = eval(arg, &mut env, &context)?;
-                                    args.push(SymbolicExpression::atom_value(evaluated_arg));
+                                // Evaluated here rather than by `eval`, so
+                                // recorded in the cost tree here
+                                let total = env.global_context.cost_track.get_total();
+                                if let Some(ref mut recorder) = env.global_context.cost_tree {
+                                    recorder.enter(
+                                        call_name("contract-call?", &expression[1..]),
+                                        contract_ast.expressions[0].span.clone(),
+                                        total,
+                                    );
+                                }
+                                let res = (|| {
+                                    let mut args = vec![];
+                                    for arg in expression[3..].iter() {
+                                        let evaluated_arg = eval(arg, &mut env, &context)?;
+                                        args.push(SymbolicExpression::atom_value(evaluated_arg));
+                                    }
+                                    env.execute_contract(
+                                        &contract_identifier,
+                                        &method,
+                                        &args,
+                                        false,
+                                    )
+                                })();
+                                let total = env.global_context.cost_track.get_total();
+                                if let Some(ref mut recorder) = env.global_context.cost_tree {
+                                    recorder.exit(total);
                                 }
-                                let res = env.execute_contract(
-                                    &contract_identifier,
-                                    &method,
-                                    &args,
-                                    false,
-                                )?;
-                                res
+                                res?
                             }
-                            _ => eval(&contract_ast.expressions[0], &mut env, &context).unwrap(),
+                            _ => eval(&contract_ast.expressions[0], &mut env, &context)?,
                         },
-                        _ => eval(&contract_ast.expressions[0], &mut env, &context).unwrap(),
+                        _ => eval(&contract_ast.expressions[0], &mut env, &context)?,
                     };
                     Ok(Some(result))
                 } else {
                     eval_all(&contract_ast.expressions, &mut contract_context, g)
                 }
             });
+            if let Some(prior_tip) = prior_tip {
+                global_context
+                    .database
+                    .set_block_hash(prior_tip, true)
+                    .unwrap();
+            }
 
             execution_result.coverage = global_context.coverage_reporting.take();
+            self.call_recorder = global_context.call_recorder.take();
+            if let Some(recorder) = global_context.cost_tree.take() {
+                self.cost_tree = recorder.roots;
+            }
+            if let Some(capture) = global_context.let_bindings.take() {
+                execution_result.let_bindings = capture.values;
+            }
+            if let Some(recorder) = global_context.guard_failures.take() {
+                execution_result.guard_failures = recorder.failures;
+            }
 
             let value = match result {
                 Ok(Some(value)) => value,
@@ -324,6 +866,11 @@ impl ClarityInterpreter {
                 .iter()
                 .flat_map(|b| b.events.clone())
                 .collect::<Vec<_>>();
+            let emitted_by = global_context
+                .event_batches
+                .iter()
+                .flat_map(|b| b.contracts.clone())
+                .collect::<Vec<_>>();
 
             for event in emitted_events.drain(..) {
                 match event {
@@ -364,26 +911,26 @@ impl ClarityInterpreter {
                     )) => {
                         accounts_to_credit.push((
                             event_data.recipient.to_string(),
-                            event_data.asset_identifier.sugared(),
+                            token_key(&event_data.asset_identifier),
                             event_data.amount.clone(),
                         ));
                         accounts_to_debit.push((
                             event_data.sender.to_string(),
-                            event_data.asset_identifier.sugared(),
+                            token_key(&event_data.asset_identifier),
                             event_data.amount.clone(),
                         ));
                     }
                     StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(ref event_data)) => {
                         accounts_to_credit.push((
                             event_data.recipient.to_string(),
-                            event_data.asset_identifier.sugared(),
+                            token_key(&event_data.asset_identifier),
                             event_data.amount.clone(),
                         ));
                     }
                     StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(ref event_data)) => {
                         accounts_to_debit.push((
                             event_data.sender.to_string(),
-                            event_data.asset_identifier.sugared(),
+                            token_key(&event_data.asset_identifier),
                             event_data.amount.clone(),
                         ));
                     }
@@ -392,12 +939,12 @@ impl ClarityInterpreter {
                     )) => {
                         accounts_to_credit.push((
                             event_data.recipient.to_string(),
-                            event_data.asset_identifier.sugared(),
+                            token_key(&event_data.asset_identifier),
                             1,
                         ));
                         accounts_to_debit.push((
                             event_data.sender.to_string(),
-                            event_data.asset_identifier.sugared(),
+                            token_key(&event_data.asset_identifier),
                             1,
                         ));
                     }
@@ -406,7 +953,7 @@ impl ClarityInterpreter {
                     )) => {
                         accounts_to_credit.push((
                             event_data.recipient.to_string(),
-                            event_data.asset_identifier.sugared(),
+                            token_key(&event_data.asset_identifier),
                             1,
                         ));
                     }
@@ -415,7 +962,7 @@ impl ClarityInterpreter {
                     )) => {
                         accounts_to_debit.push((
                             event_data.sender.to_string(),
-                            event_data.asset_identifier.sugared(),
+                            token_key(&event_data.asset_identifier),
                             1,
                         ));
                     }
@@ -426,6 +973,12 @@ impl ClarityInterpreter {
 
                 serialized_events.push(event.json_serialize());
             }
+            for (contract_identifier, event) in emitted_by.into_iter().zip(&serialized_events) {
+                contract_events.push(StacksEvent {
+                    contract_identifier,
+                    event: event.clone(),
+                });
+            }
 
             contract_saved =
                 contract_context.functions.len() > 0 || contract_context.defined_traits.len() > 0;
@@ -474,6 +1027,7 @@ impl ClarityInterpreter {
         };
 
         execution_result.events = serialized_events;
+        execution_result.contract_events = contract_events;
 
         for (account, token, value) in accounts_to_credit.drain(..) {
             self.credit_token(account, token, value);
@@ -488,12 +1042,7 @@ impl ClarityInterpreter {
             return Ok(execution_result);
         }
 
-        let mut analysis_db = AnalysisDatabase::new(&mut self.datastore);
-        analysis_db.begin();
-        analysis_db
-            .insert_contract(&contract_identifier, &contract_analysis)
-            .unwrap();
-        analysis_db.commit();
+        self.save_contract_analysis(&contract_identifier, &contract_analysis);
 
         Ok(execution_result)
     }
@@ -531,14 +1080,170 @@ impl ClarityInterpreter {
         self.tx_sender.clone()
     }
 
+    // When unset, contract-caller is the tx-sender.
+    pub fn set_contract_caller(&mut self, contract_caller: Option<PrincipalData>) {
+        self.contract_caller = contract_caller;
+    }
+
+    pub fn get_contract_caller(&self) -> Option<PrincipalData> {
+        self.contract_caller.clone()
+    }
+
+    // When set, both tx-sender and contract-caller are the contract principal,
+    // as within `as-contract`.
+    pub fn set_as_contract(&mut self, contract_identifier: Option<QualifiedContractIdentifier>) {
+        self.as_contract = contract_identifier;
+    }
+
     pub fn advance_chain_tip(&mut self, count: u32) -> u32 {
         self.datastore.advance_chain_tip(count)
     }
 
+    pub fn get_block_id(&self) -> StacksBlockId {
+        *self.datastore.get_chain_tip()
+    }
+
     pub fn get_block_height(&mut self) -> u32 {
         self.datastore.get_current_block_height()
     }
 
+    // Evaluate the next snippets against the state at the end of a past
+    // block, until the context is popped. Contexts can be nested.
+    pub fn push_block_context(&mut self, height: u32) -> Result<(), String> {
+        let tip_height = self.datastore.get_open_chain_tip_height();
+        if height > tip_height {
+            return Err(format!(
+                "Block {} is past the chain tip, at height {}",
+                height, tip_height
+            ));
+        }
+        self.block_contexts.push(height);
+        Ok(())
+    }
+
+    // Returns the height of the context popped.
+    pub fn pop_block_context(&mut self) -> Result<u32, String> {
+        self.block_contexts
+            .pop()
+            .ok_or_else(|| "No block context to pop".to_string())
+    }
+
+    // The value of a data-var at the end of a past block, as read by
+    // `at-block`. Returns `None` if the block is not part of the chain yet,
+    // or if the data-var was not set at that height.
+    pub fn get_data_var_at(
+        &mut self,
+        contract_identifier: &QualifiedContractIdentifier,
+        var_name: &str,
+        height: u32,
+    ) -> Option<Value> {
+        if height > self.datastore.get_open_chain_tip_height() {
+            return None;
+        }
+        let conn = self.datastore.as_clarity_db(&NULL_HEADER_DB);
+        let mut global_context = GlobalContext::new(false, conn, LimitedCostTracker::new_free());
+        global_context.begin();
+        let block = global_context.database.get_index_block_header_hash(height);
+        let value = match global_context.database.set_block_hash(block, false) {
+            Ok(prior_tip) => {
+                let value = global_context
+                    .database
+                    .load_variable(contract_identifier, var_name)
+                    .ok()
+                    .and_then(|descriptor| {
+                        let key = ClarityDatabase::make_key_for_trip(
+                            contract_identifier,
+                            StoreType::Variable,
+                            var_name,
+                        );
+                        global_context
+                            .database
+                            .get_value(&key, &descriptor.value_type)
+                    });
+                let _ = global_context.database.set_block_hash(prior_tip, true);
+                value
+            }
+            Err(_) => None,
+        };
+        global_context.roll_back();
+        value
+    }
+
+    pub fn snapshot_contract(
+        &self,
+        contract_identifier: &QualifiedContractIdentifier,
+    ) -> ContractSnapshot {
+        let (prefix, token_prefix) = contract_prefixes(contract_identifier);
+        ContractSnapshot {
+            contract_identifier: contract_identifier.clone(),
+            entries: self.datastore.get_entries_with_prefix(&prefix),
+            tokens: self
+                .tokens
+                .iter()
+                .filter(|(token, _)| token.starts_with(&token_prefix))
+                .map(|(token, balances)| (token.clone(), balances.clone()))
+                .collect(),
+        }
+    }
+
+    // The state of all the contracts and balances, to be compared with
+    // `state_diff::diff`.
+    pub fn snapshot_state(&self) -> StateSnapshot {
+        StateSnapshot {
+            entries: self.datastore.get_entries_with_prefix("vm::"),
+            tokens: self.tokens.clone(),
+        }
+    }
+
+    // Restore the state of a contract, leaving the other contracts and the
+    // chain as they are.
+    pub fn restore_contract(&mut self, snapshot: &ContractSnapshot) {
+        let (prefix, token_prefix) = contract_prefixes(&snapshot.contract_identifier);
+        self.datastore
+            .set_entries_with_prefix(&prefix, &snapshot.entries);
+        self.tokens
+            .retain(|token, _| !token.starts_with(&token_prefix));
+        for (token, balances) in snapshot.tokens.iter() {
+            self.tokens.insert(token.clone(), balances.clone());
+        }
+    }
+
+    pub fn checkpoint(&self) -> StateCheckpoint {
+        StateCheckpoint {
+            datastore: self.datastore.clone(),
+            accounts: self.accounts.clone(),
+            tokens: self.tokens.clone(),
+        }
+    }
+
+    // Whether the state of a contract differs from its state at the
+    // checkpoint, so that restoring the checkpoint would modify it.
+    pub fn contract_changed_since(
+        &self,
+        checkpoint: &StateCheckpoint,
+        contract_identifier: &QualifiedContractIdentifier,
+    ) -> bool {
+        let (prefix, token_prefix) = contract_prefixes(contract_identifier);
+        let tokens = |tokens: &BTreeMap<String, BTreeMap<String, u128>>| {
+            tokens
+                .iter()
+                .filter(|(token, _)| token.starts_with(&token_prefix))
+                .map(|(token, balances)| (token.clone(), balances.clone()))
+                .collect::<BTreeMap<_, _>>()
+        };
+        self.datastore.get_entries_with_prefix(&prefix)
+            != checkpoint.datastore.get_entries_with_prefix(&prefix)
+            || tokens(&self.tokens) != tokens(&checkpoint.tokens)
+    }
+
+    // Roll back all the changes made since the checkpoint, including the
+    // deployments and the blocks mined.
+    pub fn restore_checkpoint(&mut self, checkpoint: StateCheckpoint) {
+        self.datastore = checkpoint.datastore;
+        self.accounts = checkpoint.accounts;
+        self.tokens = checkpoint.tokens;
+    }
+
     fn credit_token(&mut self, account: String, token: String, value: u128) {
         self.accounts.insert(account.clone());
         match self.tokens.entry(token) {
@@ -575,12 +1280,26 @@ impl ClarityInterpreter {
         };
     }
 
+    // The balances by token, named `.contract.token` like in Clarity. The
+    // tokens of same-named contracts of other deployers are summed up.
     pub fn get_assets_maps(&self) -> BTreeMap<String, BTreeMap<String, u128>> {
-        self.tokens.clone()
+        let mut assets_maps: BTreeMap<String, BTreeMap<String, u128>> = BTreeMap::new();
+        for (token, balances) in self.tokens.iter() {
+            let sugared = assets_maps.entry(sugared_token(token)).or_default();
+            for (account, balance) in balances.iter() {
+                *sugared.entry(account.clone()).or_default() += balance;
+            }
+        }
+        assets_maps
     }
 
     pub fn get_tokens(&self) -> Vec<String> {
-        self.tokens.keys().cloned().collect()
+        let tokens: BTreeSet<String> = self
+            .tokens
+            .keys()
+            .map(|token| sugared_token(token))
+            .collect();
+        tokens.into_iter().collect()
     }
 
     pub fn get_accounts(&self) -> Vec<String> {
@@ -588,12 +1307,37 @@ impl ClarityInterpreter {
     }
 
     pub fn get_balance_for_account(&self, account: &str, token: &str) -> u128 {
-        match self.tokens.get(token) {
-            Some(balances) => match balances.get(account) {
-                Some(value) => value.clone(),
-                _ => 0,
-            },
-            _ => 0,
-        }
+        self.tokens
+            .iter()
+            .filter(|(key, _)| sugared_token(key) == token)
+            .filter_map(|(_, balances)| balances.get(account))
+            .sum()
     }
 }
+
+// The key of a token in the assets maps, qualified by the deployer of its
+// contract so that the tokens of same-named contracts are kept apart.
+fn token_key(asset_identifier: &AssetIdentifier) -> String {
+    format!(
+        "{}.{}",
+        asset_identifier.contract_identifier, asset_identifier.asset_name
+    )
+}
+
+// The name of a token without the deployer of its contract, as shown by
+// `::get_assets_maps`. STX is shown as it is.
+fn sugared_token(token: &str) -> String {
+    match token.split_once('.') {
+        Some((_, sugared)) => format!(".{}", sugared),
+        None => token.to_string(),
+    }
+}
+
+// The prefixes of the keys of a contract's state in the datastore, and of its
+// tokens in the assets maps.
+fn contract_prefixes(contract_identifier: &QualifiedContractIdentifier) -> (String, String) {
+    (
+        format!("vm::{}::", contract_identifier),
+        format!("{}.", contract_identifier),
+    )
+}
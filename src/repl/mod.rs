@@ -4,14 +4,27 @@ use crate::clarity::ast::ContractAST;
 use crate::clarity::costs::{ExecutionCost, LimitedCostTracker};
 use crate::clarity::coverage::TestCoverageReport;
 use crate::clarity::diagnostic::Diagnostic;
+use crate::clarity::guard_failures::GuardFailure;
 use crate::clarity::types;
 use serde_json::Value;
 use std::collections::BTreeMap;
 
+pub mod abi;
+pub mod branches;
+pub mod commands;
+pub mod conformance;
+pub mod cost_formula;
+pub mod formatter;
 pub mod interpreter;
+pub mod natives;
+pub mod sarif;
 pub mod session;
 pub mod settings;
+pub mod smoke;
+pub mod state_diff;
+pub mod trace_event;
 
+pub use formatter::ResultFormatter;
 pub use interpreter::ClarityInterpreter;
 pub use session::Session;
 pub use settings::SessionSettings;
@@ -27,9 +40,27 @@ pub struct ExecutionResult {
     )>,
     pub result: Option<types::Value>,
     pub events: Vec<Value>,
+    pub contract_events: Vec<StacksEvent>,
     pub cost: Option<CostSynthesis>,
     pub coverage: Option<TestCoverageReport>,
     pub diagnostics: Vec<Diagnostic>,
+    // Set when a post-condition failure was simulated, so that none of the
+    // changes made by the execution were kept
+    pub aborted_by_post_condition: bool,
+    // The value of each `let` binding evaluated, when
+    // `SessionSettings::capture_let_bindings` is enabled
+    pub let_bindings: BTreeMap<String, types::Value>,
+    // The guards which failed, when `SessionSettings::record_guard_failures`
+    // is enabled
+    pub guard_failures: Vec<GuardFailure>,
+}
+
+// A serialized event, along with the contract which was executing when it
+// was emitted.
+#[derive(Clone, Debug)]
+pub struct StacksEvent {
+    pub contract_identifier: types::QualifiedContractIdentifier,
+    pub event: Value,
 }
 
 #[derive(Clone, Debug)]
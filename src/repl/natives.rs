@@ -0,0 +1,175 @@
+use crate::clarity::docs::make_api_reference;
+use crate::clarity::functions::NativeFunctions;
+
+// The number of functions displayed per page by `::natives`.
+pub const PAGE_SIZE: usize = 20;
+
+// The categories of the catalog, in the order they are displayed.
+pub const CATEGORIES: [&str; 12] = [
+    "Arithmetic",
+    "Comparison and logic",
+    "Control flow",
+    "Optionals and responses",
+    "Sequences",
+    "Tuples",
+    "Maps and data-vars",
+    "Hashing and signatures",
+    "Tokens",
+    "STX",
+    "Principals and contracts",
+    "Blocks",
+];
+
+// A native function, with its signature from the API reference and a short
+// usage example.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NativeEntry {
+    pub name: String,
+    pub category: &'static str,
+    pub signature: String,
+    pub example: &'static str,
+}
+
+// All the native functions, grouped by category. The interpreter implements
+// Clarity 1, whose functions are all available in the later versions.
+pub fn catalog() -> Vec<NativeEntry> {
+    let mut entries: Vec<NativeEntry> = NativeFunctions::ALL
+        .iter()
+        .map(|function| {
+            let (category, example) = describe(function);
+            NativeEntry {
+                name: function.get_name(),
+                category,
+                signature: make_api_reference(function).signature,
+                example,
+            }
+        })
+        .collect();
+    entries.sort_by_key(|entry| {
+        CATEGORIES
+            .iter()
+            .position(|category| *category == entry.category)
+    });
+    entries
+}
+
+// The category and example of a native function. The match is exhaustive, so
+// that new functions are added to the catalog.
+fn describe(function: &NativeFunctions) -> (&'static str, &'static str) {
+    use crate::clarity::functions::NativeFunctions::*;
+    match function {
+        Add => ("Arithmetic", "(+ 1 2 3)"),
+        Subtract => ("Arithmetic", "(- u10 u3)"),
+        Multiply => ("Arithmetic", "(* 2 3)"),
+        Divide => ("Arithmetic", "(/ u10 u3)"),
+        Modulo => ("Arithmetic", "(mod u10 u3)"),
+        Power => ("Arithmetic", "(pow 2 8)"),
+        Sqrti => ("Arithmetic", "(sqrti u16)"),
+        Log2 => ("Arithmetic", "(log2 u8)"),
+        BitwiseXOR => ("Arithmetic", "(xor 5 3)"),
+        ToInt => ("Arithmetic", "(to-int u42)"),
+        ToUInt => ("Arithmetic", "(to-uint 42)"),
+        CmpGeq => ("Comparison and logic", "(>= u2 u1)"),
+        CmpLeq => ("Comparison and logic", "(<= 1 2)"),
+        CmpLess => ("Comparison and logic", "(< 1 2)"),
+        CmpGreater => ("Comparison and logic", "(> u2 u1)"),
+        Equals => ("Comparison and logic", "(is-eq u1 u1)"),
+        And => ("Comparison and logic", "(and true false)"),
+        Or => ("Comparison and logic", "(or true false)"),
+        Not => ("Comparison and logic", "(not true)"),
+        If => ("Control flow", "(if (> u2 u1) \"yes\" \"no\")"),
+        Let => ("Control flow", "(let ((a u1) (b u2)) (+ a b))"),
+        Begin => ("Control flow", "(begin (print \"hello\") (ok u1))"),
+        Asserts => ("Control flow", "(asserts! (> amount u0) (err u1))"),
+        UnwrapRet => ("Control flow", "(unwrap! (some u1) (err u2))"),
+        UnwrapErrRet => ("Control flow", "(unwrap-err! (err u1) (ok u2))"),
+        Unwrap => ("Control flow", "(unwrap-panic (some u1))"),
+        UnwrapErr => ("Control flow", "(unwrap-err-panic (err u1))"),
+        Match => ("Control flow", "(match (some u1) value (+ value u1) u0)"),
+        TryRet => ("Control flow", "(try! (ok u1))"),
+        Print => ("Control flow", "(print \"hello\")"),
+        ConsOkay => ("Optionals and responses", "(ok true)"),
+        ConsError => ("Optionals and responses", "(err u1)"),
+        ConsSome => ("Optionals and responses", "(some u1)"),
+        DefaultTo => ("Optionals and responses", "(default-to u0 (some u1))"),
+        IsOkay => ("Optionals and responses", "(is-ok (ok u1))"),
+        IsErr => ("Optionals and responses", "(is-err (err u1))"),
+        IsSome => ("Optionals and responses", "(is-some none)"),
+        IsNone => ("Optionals and responses", "(is-none (some u1))"),
+        ListCons => ("Sequences", "(list u1 u2 u3)"),
+        Map => ("Sequences", "(map not (list true false))"),
+        Filter => ("Sequences", "(filter not (list true false))"),
+        Fold => ("Sequences", "(fold + (list 1 2 3) 0)"),
+        Append => ("Sequences", "(append (list u1 u2) u3)"),
+        Concat => ("Sequences", "(concat \"hello \" \"world\")"),
+        AsMaxLen => ("Sequences", "(as-max-len? (list u1 u2) u4)"),
+        Len => ("Sequences", "(len \"clarity\")"),
+        ElementAt => ("Sequences", "(element-at (list u1 u2) u0)"),
+        IndexOf => ("Sequences", "(index-of (list u1 u2) u2)"),
+        TupleCons => ("Tuples", "(tuple (id u1) (name \"alice\"))"),
+        TupleGet => ("Tuples", "(get id (tuple (id u1)))"),
+        TupleMerge => ("Tuples", "(merge {a: u1} {b: u2})"),
+        FetchVar => ("Maps and data-vars", "(var-get counter)"),
+        SetVar => ("Maps and data-vars", "(var-set counter u1)"),
+        FetchEntry => ("Maps and data-vars", "(map-get? balances tx-sender)"),
+        SetEntry => ("Maps and data-vars", "(map-set balances tx-sender u100)"),
+        InsertEntry => ("Maps and data-vars", "(map-insert balances tx-sender u100)"),
+        DeleteEntry => ("Maps and data-vars", "(map-delete balances tx-sender)"),
+        Hash160 => ("Hashing and signatures", "(hash160 0x00)"),
+        Sha256 => ("Hashing and signatures", "(sha256 u1)"),
+        Sha512 => ("Hashing and signatures", "(sha512 0x00)"),
+        Sha512Trunc256 => ("Hashing and signatures", "(sha512/256 0x00)"),
+        Keccak256 => ("Hashing and signatures", "(keccak256 0x00)"),
+        Secp256k1Recover => (
+            "Hashing and signatures",
+            "(secp256k1-recover? message-hash signature)",
+        ),
+        Secp256k1Verify => (
+            "Hashing and signatures",
+            "(secp256k1-verify message-hash signature public-key)",
+        ),
+        MintToken => ("Tokens", "(ft-mint? token u100 tx-sender)"),
+        TransferToken => ("Tokens", "(ft-transfer? token u100 tx-sender recipient)"),
+        BurnToken => ("Tokens", "(ft-burn? token u100 tx-sender)"),
+        GetTokenBalance => ("Tokens", "(ft-get-balance token tx-sender)"),
+        GetTokenSupply => ("Tokens", "(ft-get-supply token)"),
+        MintAsset => ("Tokens", "(nft-mint? ticket u1 tx-sender)"),
+        TransferAsset => ("Tokens", "(nft-transfer? ticket u1 tx-sender recipient)"),
+        BurnAsset => ("Tokens", "(nft-burn? ticket u1 tx-sender)"),
+        GetAssetOwner => ("Tokens", "(nft-get-owner? ticket u1)"),
+        GetStxBalance => ("STX", "(stx-get-balance tx-sender)"),
+        StxTransfer => ("STX", "(stx-transfer? u100 tx-sender recipient)"),
+        StxBurn => ("STX", "(stx-burn? u100 tx-sender)"),
+        ContractCall => (
+            "Principals and contracts",
+            "(contract-call? .token transfer u100 tx-sender)",
+        ),
+        AsContract => ("Principals and contracts", "(as-contract tx-sender)"),
+        ContractOf => ("Principals and contracts", "(contract-of token)"),
+        PrincipalOf => ("Principals and contracts", "(principal-of? public-key)"),
+        AtBlock => ("Blocks", "(at-block block-hash (var-get counter))"),
+        GetBlockInfo => ("Blocks", "(get-block-info? time u0)"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clarity::ast::parser;
+
+    #[test]
+    fn examples_parse() {
+        let entries = catalog();
+        assert_eq!(entries.len(), NativeFunctions::ALL.len());
+        for entry in entries.iter() {
+            assert!(
+                parser::parse(entry.example).is_ok(),
+                "example of {} does not parse",
+                entry.name
+            );
+            assert!(entry.example.starts_with(&format!("({} ", entry.name)));
+        }
+        assert_eq!(entries[0].category, "Arithmetic");
+        assert_eq!(entries[entries.len() - 1].category, "Blocks");
+    }
+}
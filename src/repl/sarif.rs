@@ -0,0 +1,169 @@
+use crate::analysis::catalog::diagnostic_catalog;
+use crate::clarity::diagnostic::{Diagnostic, Level};
+use serde_json::Value;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+// The rule of the diagnostics without a code, like the errors of the
+// interpreter, for each level.
+fn level_rule_id(level: &Level) -> &'static str {
+    match level {
+        Level::Error => "clarity-error",
+        Level::Warning => "clarity-warning",
+        Level::Note => "clarity-note",
+    }
+}
+
+fn rule_id(diagnostic: &Diagnostic) -> &str {
+    match diagnostic.code {
+        Some(ref code) => code,
+        None => level_rule_id(&diagnostic.level),
+    }
+}
+
+fn sarif_level(level: &Level) -> &'static str {
+    match level {
+        Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Note => "note",
+    }
+}
+
+fn rule(id: &str, level: &Level, description: &str) -> Value {
+    json!({
+        "id": id,
+        "shortDescription": { "text": description },
+        "defaultConfiguration": { "level": sarif_level(level) },
+    })
+}
+
+// A rule for each entry of the catalog, then one for each level.
+fn rules() -> Vec<Value> {
+    let mut rules: Vec<Value> = diagnostic_catalog()
+        .iter()
+        .map(|entry| rule(entry.code, &entry.level, entry.title))
+        .collect();
+    for (level, description) in [
+        (Level::Error, "Clarity analysis error"),
+        (Level::Warning, "Clarity analysis warning"),
+        (Level::Note, "Clarity analysis note"),
+    ] {
+        rules.push(rule(level_rule_id(&level), &level, description));
+    }
+    rules
+}
+
+fn result(diagnostic: &Diagnostic, uri: &str) -> Value {
+    // SARIF columns are 1-based like ours, but the end column is exclusive.
+    let locations: Vec<Value> = diagnostic
+        .spans
+        .iter()
+        .map(|span| {
+            json!({
+                "physicalLocation": {
+                    "artifactLocation": { "uri": uri },
+                    "region": {
+                        "startLine": span.start_line,
+                        "startColumn": span.start_column,
+                        "endLine": span.end_line,
+                        "endColumn": span.end_column + 1,
+                    },
+                },
+            })
+        })
+        .collect();
+    let text = match diagnostic.suggestion {
+        Some(ref suggestion) => format!("{}\n{}", diagnostic.message, suggestion),
+        None => diagnostic.message.clone(),
+    };
+    json!({
+        "ruleId": rule_id(diagnostic),
+        "level": sarif_level(&diagnostic.level),
+        "message": { "text": text },
+        "locations": locations,
+    })
+}
+
+// Build a SARIF 2.1.0 log with a single run, reporting the diagnostics of
+// the contract found at `uri`.
+pub fn diagnostics_to_sarif(diagnostics: &[Diagnostic], uri: &str) -> Value {
+    let results: Vec<Value> = diagnostics.iter().map(|d| result(d, uri)).collect();
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "rules": rules(),
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clarity::representations::Span;
+
+    #[test]
+    fn sarif_log() {
+        let diagnostics = vec![
+            Diagnostic {
+                level: Level::Warning,
+                code: Some("unchecked-data".to_string()),
+                message: "use of potentially unchecked data".to_string(),
+                spans: vec![Span {
+                    start_line: 3,
+                    start_column: 5,
+                    end_line: 3,
+                    end_column: 12,
+                }],
+                suggestion: None,
+            },
+            Diagnostic {
+                level: Level::Error,
+                code: None,
+                message: "use of unresolved function 'test'".to_string(),
+                spans: vec![],
+                suggestion: None,
+            },
+        ];
+        let log = diagnostics_to_sarif(&diagnostics, "contracts/counter.clar");
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "clarity-repl");
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), diagnostic_catalog().len() + 3);
+        let rule = rules
+            .iter()
+            .find(|rule| rule["id"] == "unchecked-data")
+            .unwrap();
+        assert_eq!(
+            rule["shortDescription"]["text"],
+            "Use of potentially unchecked data"
+        );
+        assert_eq!(rule["defaultConfiguration"]["level"], "warning");
+
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "unchecked-data");
+        assert_eq!(result["level"], "warning");
+        assert_eq!(
+            result["message"]["text"],
+            "use of potentially unchecked data"
+        );
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(
+            location["artifactLocation"]["uri"],
+            "contracts/counter.clar"
+        );
+        assert_eq!(location["region"]["startColumn"], 5);
+        assert_eq!(location["region"]["endColumn"], 13);
+
+        assert_eq!(run["results"][1]["ruleId"], "clarity-error");
+    }
+}
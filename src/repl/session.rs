@@ -1,22 +1,50 @@
 use super::{ClarityInterpreter, ExecutionResult};
+use crate::analysis::catalog::{self, DiagnosticCatalogEntry};
+use crate::analysis::{contract_call_detector, DiagnosticCallback};
 use crate::clarity::analysis::ContractAnalysis;
-use crate::clarity::ast::ContractAST;
+use crate::clarity::ast::{parser, ContractAST};
+use crate::clarity::call_recorder::RecordedCall;
 use crate::clarity::codec::StacksMessageCodec;
+use crate::clarity::contexts::IterationLimits;
+use crate::clarity::cost_tree::CostTreeNode;
+use crate::clarity::costs::cost_functions::ClarityCostFunction;
+use crate::clarity::costs::ExecutionCost;
 use crate::clarity::coverage::{CoverageReporter, TestCoverageReport};
 use crate::clarity::docs::{make_api_reference, make_define_reference, make_keyword_reference};
 use crate::clarity::errors::Error;
 use crate::clarity::functions::define::DefineFunctions;
 use crate::clarity::functions::NativeFunctions;
+use crate::clarity::representations::{
+    Span, SymbolicExpression, SymbolicExpressionType, TraitDefinition,
+};
 use crate::clarity::types::{
-    PrincipalData, QualifiedContractIdentifier, StandardPrincipalData, Value,
+    BlockInfoProperty, CharType, FunctionType, OptionalData, PrincipalData,
+    QualifiedContractIdentifier, SequenceData, StandardPrincipalData, TraitIdentifier, Value,
 };
-use crate::clarity::util::StacksAddress;
 use crate::clarity::variables::NativeVariables;
+use crate::clarity::version::ClarityVersion;
+use crate::clarity::{ClarityName, StacksBlockId};
 use crate::contracts::{BNS_CONTRACT, COSTS_V1_CONTRACT, COSTS_V2_CONTRACT, POX_CONTRACT};
-use crate::repl::CostSynthesis;
-use crate::{clarity::diagnostic::Diagnostic, repl::settings::InitialContract};
+use crate::repl::abi;
+use crate::repl::branches::{self, BranchReport};
+use crate::repl::commands::{self, CommandInfo, CommandResult};
+use crate::repl::conformance::{ConformanceReport, TraitConformance};
+use crate::repl::cost_formula::{self, CostFormula};
+use crate::repl::formatter::{format_value, FormatContext, InstalledFormatter, ResultFormatter};
+use crate::repl::interpreter::{block_limit, ContractSnapshot, StateCheckpoint};
+use crate::repl::natives;
+use crate::repl::sarif;
+use crate::repl::smoke::{self, SmokeOutcome, SmokeReport, SmokeResult};
+use crate::repl::state_diff::{self, StateKind};
+use crate::repl::trace_event;
+use crate::repl::{CostSynthesis, StacksEvent};
+use crate::{
+    clarity::diagnostic::{Diagnostic, Level},
+    repl::settings::InitialContract,
+};
 use ansi_term::{Colour, Style};
 use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::convert::TryFrom;
 use std::fmt;
 use std::num::ParseIntError;
 use std::sync::{Arc, Mutex};
@@ -24,11 +52,18 @@ use std::sync::{Arc, Mutex};
 #[cfg(feature = "cli")]
 use prettytable::{Cell, Row, Table};
 
-use super::settings::InitialLink;
+use super::settings::{BlockModel, InitialLink, IntDisplay, PrincipalVersions};
 use super::SessionSettings;
 
 #[cfg(feature = "wasm")]
 use reqwest_wasm as reqwest;
+
+// Helpers only available when `SessionSettings::test_mode` is enabled. As the
+// top-level forms of a script or of a test block, they report their results;
+// nested in other expressions, they are the natives of `test_natives`, and a
+// failed assertion aborts the evaluation.
+const TEST_HELPERS: [&str; 4] = ["assert", "assert-eq", "print-and-continue", "test"];
+
 enum Command {
     LoadLocalContract(String),
     LoadDeployContract(String),
@@ -38,6 +73,13 @@ enum Command {
     CloseSession,
 }
 
+enum TestStep {
+    Snippet(String),
+    Helper(String, Vec<String>),
+    // A named test block, evaluated in isolation
+    Test(String, Vec<TestStep>),
+}
+
 #[derive(Clone, Debug)]
 pub struct CostsReport {
     pub test_name: String,
@@ -47,6 +89,35 @@ pub struct CostsReport {
     pub cost_result: CostSynthesis,
 }
 
+// A `use-trait` or `impl-trait` of a deployed contract.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraitUsage {
+    // The alias of a `use-trait`, `None` for an `impl-trait`
+    pub alias: Option<ClarityName>,
+    pub trait_identifier: TraitIdentifier,
+    pub span: Span,
+    // The trait is defined by a contract of the session
+    pub resolved: bool,
+    // For an `impl-trait` of a resolved trait, whether the contract has the
+    // methods of the trait, with matching signatures
+    pub conforms: Option<bool>,
+}
+
+// The state of the session, saved to roll back a transaction or an
+// evaluation which must leave the session as it was.
+#[derive(Clone, Debug)]
+struct SessionSnapshot {
+    interpreter: ClarityInterpreter,
+    contracts: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+    asts: BTreeMap<QualifiedContractIdentifier, ContractAST>,
+    coverage_reports: Vec<TestCoverageReport>,
+    costs_reports: Vec<CostsReport>,
+    diagnostics: BTreeMap<String, Vec<Diagnostic>>,
+    events: Vec<StacksEvent>,
+    frozen_contracts: BTreeSet<String>,
+    contract_snapshots: BTreeMap<String, ContractSnapshot>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Session {
     session_id: u32,
@@ -60,40 +131,86 @@ pub struct Session {
     pub coverage_reports: Vec<TestCoverageReport>,
     pub costs_reports: Vec<CostsReport>,
     pub initial_contracts_analysis: Vec<(ContractAnalysis, String, String)>,
+    pub diagnostics: BTreeMap<String, Vec<Diagnostic>>,
+    pub events: Vec<StacksEvent>,
     pub show_costs: bool,
+    // Contracts which can no longer be redeployed, nor modified by the helpers
+    pub frozen_contracts: BTreeSet<String>,
+    // The snapshots of single contracts, by name
+    contract_snapshots: BTreeMap<String, ContractSnapshot>,
+    // The transaction open, whose changes are rolled back if it is aborted
+    transaction: Option<SessionSnapshot>,
+    result_formatter: InstalledFormatter,
 }
 
 impl Session {
     pub fn new(settings: SessionSettings) -> Session {
-        let tx_sender = {
-            let address = match settings.initial_deployer {
-                Some(ref entry) => entry.address.clone(),
-                None => format!("{}", StacksAddress::burn_address(false)),
-            };
-            PrincipalData::parse_standard_principal(&address)
-                .expect("Unable to parse deployer's address")
+        let tx_sender = match settings.initial_deployer {
+            Some(ref entry) => PrincipalData::parse_standard_principal(&entry.address)
+                .expect("Unable to parse deployer's address"),
+            None => settings
+                .analysis_settings
+                .principal_versions
+                .burn_principal(false),
         };
 
+        let mut analysis = settings.analysis.clone();
+        if settings.strict {
+            analysis.push("strict".to_string());
+        }
+        let mut interpreter = ClarityInterpreter::new(tx_sender, settings.costs_version, analysis);
+        interpreter.set_analysis_settings(settings.analysis_settings.clone());
+        interpreter.set_max_value_allocation(settings.max_value_allocation);
+        interpreter.set_iteration_limits(settings.iteration_limits.clone());
+        interpreter.set_capture_let_bindings(settings.capture_let_bindings);
+        interpreter.set_record_guard_failures(settings.record_guard_failures);
+        interpreter.set_native_call_hooks(settings.native_call_hooks.clone());
+        interpreter.set_test_mode(settings.test_mode);
+        interpreter.set_analysis_timeout(settings.analysis_timeout);
+
         Session {
             session_id: 0,
             started_at: 0,
             is_interactive: false,
-            interpreter: ClarityInterpreter::new(
-                tx_sender,
-                settings.costs_version,
-                settings.analysis.clone(),
-            ),
+            interpreter,
             asts: BTreeMap::new(),
             contracts: BTreeMap::new(),
             api_reference: build_api_reference(),
             coverage_reports: vec![],
             costs_reports: vec![],
             initial_contracts_analysis: vec![],
+            diagnostics: BTreeMap::new(),
+            events: vec![],
             show_costs: false,
+            frozen_contracts: BTreeSet::new(),
+            contract_snapshots: BTreeMap::new(),
+            transaction: None,
+            result_formatter: InstalledFormatter::default(),
             settings,
         }
     }
 
+    // Render the results of the evaluations with a custom formatter, instead
+    // of the built-in `DefaultFormatter`.
+    pub fn with_result_formatter<F>(mut self, formatter: F) -> Session
+    where
+        F: ResultFormatter + 'static,
+    {
+        self.result_formatter = InstalledFormatter(Arc::new(formatter));
+        self
+    }
+
+    // Report each diagnostic as soon as the analysis pass producing it
+    // completes, rather than once the whole contract has been analyzed.
+    pub fn with_diagnostic_callback<F>(mut self, callback: F) -> Session
+    where
+        F: FnMut(&Diagnostic) + Send + 'static,
+    {
+        self.interpreter
+            .set_diagnostic_callback(Some(DiagnosticCallback::new(callback)));
+        self
+    }
+
     async fn retrieve_contract(
         &mut self,
         link: &InitialLink,
@@ -191,6 +308,8 @@ impl Session {
         let mut output = vec![];
         let mut contracts = vec![];
 
+        self.validate_settings()?;
+
         if !self.settings.include_boot_contracts.is_empty() {
             let default_tx_sender = self.interpreter.get_tx_sender();
 
@@ -278,14 +397,8 @@ impl Session {
             let mut initial_contracts = self.settings.initial_contracts.clone();
             let default_tx_sender = self.interpreter.get_tx_sender();
             for contract in initial_contracts.drain(..) {
-                let deployer = {
-                    let address = match contract.deployer {
-                        Some(ref entry) => entry.clone(),
-                        None => format!("{}", StacksAddress::burn_address(false)),
-                    };
-                    PrincipalData::parse_standard_principal(&address)
-                        .expect("Unable to parse deployer's address")
-                };
+                let deployer =
+                    contract.get_deployer_principal_with_versions(self.principal_versions(), false);
 
                 self.interpreter.set_tx_sender(deployer);
                 match self.formatted_interpretation(
@@ -411,6 +524,10 @@ impl Session {
     pub async fn start_wasm(&mut self) -> String {
         let mut output = Vec::<String>::new();
 
+        if let Err(error) = self.validate_settings() {
+            return error;
+        }
+
         if !self.settings.include_boot_contracts.is_empty() {
             let default_tx_sender = self.interpreter.get_tx_sender();
 
@@ -504,21 +621,38 @@ impl Session {
             "::help" => self.display_help(&mut output),
             cmd if cmd.starts_with("::list_functions") => self.display_functions(&mut output),
             cmd if cmd.starts_with("::describe_function") => self.display_doc(&mut output, cmd),
+            cmd if cmd.starts_with("::natives") => self.display_natives(&mut output, cmd),
             cmd if cmd.starts_with("::mint_stx") => self.mint_stx(&mut output, cmd),
             cmd if cmd.starts_with("::set_tx_sender") => {
                 self.parse_and_set_tx_sender(&mut output, cmd)
             }
+            cmd if cmd.starts_with("::set_caller") => {
+                self.parse_and_set_contract_caller(&mut output, cmd)
+            }
             cmd if cmd.starts_with("::get_assets_maps") => self.get_accounts(&mut output),
             cmd if cmd.starts_with("::get_costs") => self.get_costs(&mut output, cmd),
+            cmd if cmd.starts_with("::cost_compare") => self.cost_compare(&mut output, cmd),
+            cmd if cmd.starts_with("::cost_explain") => self.cost_explain(&mut output, cmd),
+            cmd if cmd.starts_with("::limits") => self.display_limits(&mut output),
             cmd if cmd.starts_with("::get_contracts") => self.get_contracts(&mut output),
+            cmd if cmd.starts_with("::freeze") => self.parse_and_freeze_contract(&mut output, cmd),
             cmd if cmd.starts_with("::get_block_height") => self.get_block_height(&mut output),
+            cmd if cmd.starts_with("::watch_state") => self.watch_state(&mut output, cmd),
+            cmd if cmd.starts_with("::mock_block_info") => {
+                self.parse_and_mock_block_info(&mut output, cmd)
+            }
             cmd if cmd.starts_with("::advance_chain_tip") => {
                 self.parse_and_advance_chain_tip(&mut output, cmd)
             }
             cmd if cmd.starts_with("::toggle_costs") => self.toggle_costs(&mut output),
+            cmd if cmd.starts_with("::numformat") => self.set_int_display(&mut output, cmd),
             cmd if cmd.starts_with("::encode") => self.encode(&mut output, cmd),
             cmd if cmd.starts_with("::decode") => self.decode(&mut output, cmd),
+            cmd if cmd.starts_with("::coverage_merge") => self.coverage_merge(&mut output, cmd),
 
+            snippet if self.settings.test_mode && self.parse_test_script(snippet).is_some() => {
+                self.run_test_script(&mut output, snippet)
+            }
             snippet => {
                 if self.show_costs {
                     self.get_costs(&mut output, &format!("::get_costs {}", snippet))
@@ -545,6 +679,303 @@ impl Session {
         output
     }
 
+    // Returns `None` if the script does not use any of the test helpers.
+    fn parse_test_script(&self, snippet: &str) -> Option<Vec<TestStep>> {
+        let steps = self.parse_test_steps(snippet)?;
+        if steps
+            .iter()
+            .any(|step| !matches!(step, TestStep::Snippet(_)))
+        {
+            Some(steps)
+        } else {
+            None
+        }
+    }
+
+    // Split a test script into its top-level forms. Consecutive regular forms
+    // are kept together so that definitions end up in the same contract.
+    fn parse_test_steps(&self, snippet: &str) -> Option<Vec<TestStep>> {
+        let expressions = parser::parse(snippet).ok()?;
+        let lines: Vec<String> = snippet.lines().map(|l| l.to_string()).collect();
+        let mut steps = vec![];
+        let mut pending = vec![];
+        for (i, expr) in expressions.iter().enumerate() {
+            let start = (expr.span.start_line, expr.span.start_column);
+            let end = expressions
+                .get(i + 1)
+                .map(|next| (next.span.start_line, next.span.start_column));
+            let helper = match expr.match_list().and_then(|list| list.split_first()) {
+                Some((name, args)) => match name.match_atom() {
+                    Some(name) if TEST_HELPERS.contains(&name.as_str()) => {
+                        let mut sources = vec![];
+                        for (j, arg) in args.iter().enumerate() {
+                            let arg_end = match args.get(j + 1) {
+                                Some(next) => (next.span.start_line, next.span.start_column),
+                                None => (expr.span.end_line, expr.span.end_column),
+                            };
+                            sources.push(extract_source(
+                                &lines,
+                                (arg.span.start_line, arg.span.start_column),
+                                Some(arg_end),
+                            ));
+                        }
+                        if name.as_str() == "test" && !sources.is_empty() {
+                            let test_name = match args[0].match_atom_value() {
+                                Some(Value::Sequence(SequenceData::String(CharType::ASCII(
+                                    name,
+                                )))) => String::from_utf8_lossy(&name.data).to_string(),
+                                _ => sources[0].clone(),
+                            };
+                            let body = self.parse_test_steps(&sources[1..].join("\n"))?;
+                            Some(TestStep::Test(test_name, body))
+                        } else {
+                            Some(TestStep::Helper(name.to_string(), sources))
+                        }
+                    }
+                    _ => None,
+                },
+                None => None,
+            };
+            match helper {
+                Some(step) => {
+                    if !pending.is_empty() {
+                        steps.push(TestStep::Snippet(pending.join("\n")));
+                        pending.clear();
+                    }
+                    steps.push(step);
+                }
+                None => pending.push(extract_source(&lines, start, end)),
+            }
+        }
+        if !pending.is_empty() {
+            steps.push(TestStep::Snippet(pending.join("\n")));
+        }
+        Some(steps)
+    }
+
+    // Evaluate a test script step by step, aborting on the first failure.
+    // Test blocks do not abort the script, their results are summarized at
+    // the end instead.
+    fn run_test_script(&mut self, output: &mut Vec<String>, snippet: &str) {
+        let steps = match self.parse_test_script(snippet) {
+            Some(steps) => steps,
+            None => return,
+        };
+        let mut summary = (0, 0);
+        if self.run_test_steps(output, steps, &mut summary).is_err() {
+            output.push(red!("Test script aborted"));
+            return;
+        }
+        let (passed, failed) = summary;
+        if passed + failed > 0 {
+            let message = format!("test result: {} passed, {} failed", passed, failed);
+            output.push(if failed == 0 {
+                green!(message)
+            } else {
+                red!(message)
+            });
+        }
+    }
+
+    // Counts the passed and failed test blocks in `summary`.
+    fn run_test_steps(
+        &mut self,
+        output: &mut Vec<String>,
+        steps: Vec<TestStep>,
+        summary: &mut (usize, usize),
+    ) -> Result<(), ()> {
+        for step in steps {
+            let result = match step {
+                TestStep::Snippet(snippet) => {
+                    match self.formatted_interpretation(snippet, None, true, None) {
+                        Ok((mut res_output, result)) => {
+                            output.append(&mut res_output);
+                            if let Some((ref contract_name, _, _, _, _)) = result.contract {
+                                output.push(green!(format!(
+                                    "→ .{} contract successfully stored.",
+                                    contract_name
+                                )));
+                            }
+                            Ok(())
+                        }
+                        Err(mut res_output) => {
+                            output.append(&mut res_output);
+                            Err(())
+                        }
+                    }
+                }
+                TestStep::Helper(name, args) => self.run_test_helper(output, &name, &args),
+                TestStep::Test(name, steps) => {
+                    self.run_test_block(output, &name, steps, summary);
+                    Ok(())
+                }
+            };
+            result?;
+        }
+        Ok(())
+    }
+
+    // Run the body of a test block, then roll back every change it made, so
+    // that test blocks cannot affect each other.
+    fn run_test_block(
+        &mut self,
+        output: &mut Vec<String>,
+        name: &str,
+        steps: Vec<TestStep>,
+        summary: &mut (usize, usize),
+    ) {
+        let snapshot = self.snapshot();
+        let result = self.run_test_steps(output, steps, summary);
+        self.restore(snapshot);
+        match result {
+            Ok(()) => {
+                output.push(green!(format!("test {} passed", name)));
+                summary.0 += 1;
+            }
+            Err(()) => {
+                output.push(red!(format!("test {} failed", name)));
+                summary.1 += 1;
+            }
+        }
+    }
+
+    fn run_test_helper(
+        &mut self,
+        output: &mut Vec<String>,
+        name: &str,
+        args: &[String],
+    ) -> Result<(), ()> {
+        match name {
+            "assert" => {
+                if args.len() != 1 {
+                    output.push(red!("Usage: (assert <condition>)"));
+                    return Err(());
+                }
+                match self.formatted_interpretation(args[0].clone(), None, true, None) {
+                    Ok((_, result)) => match result.result {
+                        Some(Value::Bool(true)) => {
+                            output.push(green!(format!("assertion passed: {}", args[0])));
+                            Ok(())
+                        }
+                        Some(Value::Bool(false)) => {
+                            output.push(red!(format!("assertion failed: {}", args[0])));
+                            Err(())
+                        }
+                        _ => {
+                            output
+                                .push(red!(format!("assertion failed: {} is not a bool", args[0])));
+                            Err(())
+                        }
+                    },
+                    Err(mut res_output) => {
+                        output.append(&mut res_output);
+                        Err(())
+                    }
+                }
+            }
+            "assert-eq" => {
+                if args.len() != 2 {
+                    output.push(red!("Usage: (assert-eq <expected> <actual>)"));
+                    return Err(());
+                }
+                // The operands are evaluated apart, so that values of different
+                // types fail the assertion rather than the evaluation
+                let mut values = vec![];
+                for arg in args {
+                    match self.formatted_interpretation(arg.clone(), None, true, None) {
+                        Ok((_, result)) => match result.result {
+                            Some(value) => values.push(value),
+                            None => {
+                                output
+                                    .push(red!(format!("assertion failed: {} has no value", arg)));
+                                return Err(());
+                            }
+                        },
+                        Err(mut res_output) => {
+                            output.append(&mut res_output);
+                            return Err(());
+                        }
+                    }
+                }
+                if values[0] == values[1] {
+                    output.push(green!(format!("assertion passed: {}", values[0])));
+                    Ok(())
+                } else {
+                    output.push(red!(format!(
+                        "assertion failed: expected {}, got {}",
+                        values[0], values[1]
+                    )));
+                    Err(())
+                }
+            }
+            "print-and-continue" => {
+                if args.len() != 1 {
+                    output.push(red!("Usage: (print-and-continue <expr>)"));
+                    return Err(());
+                }
+                match self.formatted_interpretation(args[0].clone(), None, true, None) {
+                    Ok((mut res_output, _)) => {
+                        output.append(&mut res_output);
+                        Ok(())
+                    }
+                    Err(mut res_output) => {
+                        output.append(&mut res_output);
+                        Err(())
+                    }
+                }
+            }
+            // Test blocks with a body are parsed as `TestStep::Test`
+            "test" => {
+                output.push(red!("Usage: (test \"name\" <body>...)"));
+                Err(())
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    // Interpret a snippet, returning its errors, warnings and notes apart.
+    // Warnings and notes do not prevent the execution, so they come with its
+    // result, or with the error of the analysis. An error without a diagnostic, such as a runtime error, is
+    // returned as one without spans.
+    pub fn interpret_split(
+        &mut self,
+        snippet: String,
+        name: Option<String>,
+    ) -> (
+        Option<ExecutionResult>,
+        Vec<Diagnostic>,
+        Vec<Diagnostic>,
+        Vec<Diagnostic>,
+    ) {
+        let (result, diagnostics) =
+            match self.interpret_with_diagnostics(snippet, name, false, None) {
+                Ok(mut result) => {
+                    let diagnostics = std::mem::take(&mut result.diagnostics);
+                    (Some(result), diagnostics)
+                }
+                Err((message, diagnostics, _)) if diagnostics.is_empty() => (
+                    None,
+                    vec![Diagnostic {
+                        level: Level::Error,
+                        code: None,
+                        message,
+                        spans: vec![],
+                        suggestion: None,
+                    }],
+                ),
+                Err((_, diagnostics, _)) => (None, diagnostics),
+            };
+        let (mut errors, mut warnings, mut notes) = (vec![], vec![], vec![]);
+        for diagnostic in diagnostics {
+            match diagnostic.level {
+                Level::Error => errors.push(diagnostic),
+                Level::Warning => warnings.push(diagnostic),
+                Level::Note => notes.push(diagnostic),
+            }
+        }
+        (result, errors, warnings, notes)
+    }
+
     pub fn formatted_interpretation(
         &mut self,
         snippet: String,
@@ -552,35 +983,24 @@ impl Session {
         cost_track: bool,
         test_name: Option<String>,
     ) -> Result<(Vec<String>, ExecutionResult), Vec<String>> {
-        let light_red = Colour::Red.bold();
-
         let result = self.interpret(snippet.to_string(), name.clone(), cost_track, test_name);
-        let mut output = Vec::<String>::new();
         let lines = snippet.lines();
         let formatted_lines: Vec<String> = lines.map(|l| l.to_string()).collect();
         let contract_name = name.unwrap_or("<stdin>".to_string());
+        let context = FormatContext {
+            contract_name: &contract_name,
+            lines: &formatted_lines,
+            int_display: &self.settings.int_display,
+        };
+        let formatter = &self.result_formatter.0;
 
         match result {
             Ok(result) => {
-                for diagnostic in &result.diagnostics {
-                    output.append(&mut diagnostic.output(&contract_name, &formatted_lines));
-                }
-                if result.events.len() > 0 {
-                    output.push(black!("Events emitted"));
-                    for event in result.events.iter() {
-                        output.push(black!(format!("{}", event)));
-                    }
-                }
-                if let Some(ref result) = result.result {
-                    output.push(green!(format!("{}", result)));
-                }
+                let output = formatter.format_result(&result, &context);
                 Ok((output, result))
             }
-            Err((_, diagnostic, _)) => {
-                if let Some(diagnostic) = diagnostic {
-                    output.append(&mut diagnostic.output(&contract_name, &formatted_lines));
-                }
-                Err(output)
+            Err((_, diagnostic, error)) => {
+                Err(formatter.format_error(diagnostic.as_ref(), error.as_ref(), &context))
             }
         }
     }
@@ -624,6 +1044,86 @@ impl Session {
         Ok(result)
     }
 
+    // Interpret a snippet with contract-caller and tx-sender set independently,
+    // as when the code is reached through a `contract-call?` or `as-contract`.
+    // Both are restored afterwards.
+    pub fn eval_as_caller(
+        &mut self,
+        contract_caller: &str,
+        tx_sender: &str,
+        snippet: String,
+    ) -> Result<ExecutionResult, (String, Option<Diagnostic>, Option<Error>)> {
+        let contract_caller = PrincipalData::parse(contract_caller).map_err(|e| {
+            (
+                format!("Unable to parse contract-caller: {}", e),
+                None,
+                Some(e),
+            )
+        })?;
+        let tx_sender = PrincipalData::parse_standard_principal(tx_sender)
+            .map_err(|e| (format!("Unable to parse tx-sender: {}", e), None, Some(e)))?;
+        for principal in [&contract_caller, &tx_sender.clone().into()] {
+            self.check_principal_version(principal)
+                .map_err(|error| (error, None, None))?;
+        }
+
+        let initial_tx_sender = self.interpreter.get_tx_sender();
+        let initial_contract_caller = self.interpreter.get_contract_caller();
+        self.interpreter.set_tx_sender(tx_sender);
+        self.interpreter.set_contract_caller(Some(contract_caller));
+        let result = self.interpret(snippet, None, false, None);
+        self.interpreter.set_tx_sender(initial_tx_sender);
+        self.interpreter
+            .set_contract_caller(initial_contract_caller);
+        result
+    }
+
+    // Evaluate a snippet with tx-sender and contract-caller set to the
+    // principal of a deployed contract, as if wrapped in `as-contract`
+    // within that contract.
+    pub fn eval_as_contract(
+        &mut self,
+        contract_id: &str,
+        snippet: String,
+    ) -> Result<ExecutionResult, (String, Option<Diagnostic>, Option<Error>)> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        let contract_identifier = QualifiedContractIdentifier::parse(&contract_id)
+            .map_err(|e| (format!("Unable to parse contract: {}", e), None, Some(e)))?;
+        if !self.contracts.contains_key(&contract_id) {
+            return Err((
+                format!("Contract {} is not deployed", contract_id),
+                None,
+                None,
+            ));
+        }
+        if self.frozen_contracts.contains(&contract_id) {
+            return Err((
+                format!("Contract {} is frozen and cannot be modified", contract_id),
+                None,
+                None,
+            ));
+        }
+
+        self.interpreter.set_as_contract(Some(contract_identifier));
+        let result = self.interpret(snippet, None, false, None);
+        self.interpreter.set_as_contract(None);
+        result
+    }
+
+    // Evaluate a snippet, then simulate a failing post-condition: as for a
+    // transaction aborted by a post-condition, every change made by the
+    // evaluation, including its events, is rolled back. Post-conditions are
+    // not actually evaluated; this only models the abort, to test that the
+    // state is left intact.
+    pub fn eval_with_failing_postcondition(
+        &mut self,
+        snippet: String,
+    ) -> Result<ExecutionResult, (String, Option<Diagnostic>, Option<Error>)> {
+        let mut result = self.interpret_isolated(snippet, false)?;
+        result.aborted_by_post_condition = true;
+        Ok(result)
+    }
+
     pub fn interpret(
         &mut self,
         snippet: String,
@@ -631,6 +1131,19 @@ impl Session {
         cost_track: bool,
         test_name: Option<String>,
     ) -> Result<ExecutionResult, (String, Option<Diagnostic>, Option<Error>)> {
+        self.interpret_with_diagnostics(snippet, name, cost_track, test_name)
+            .map_err(|(kind, mut diagnostics, error)| (kind, diagnostics.pop(), error))
+    }
+
+    // Like `interpret`, but failing with all the diagnostics found, ending
+    // with the error.
+    fn interpret_with_diagnostics(
+        &mut self,
+        snippet: String,
+        name: Option<String>,
+        cost_track: bool,
+        test_name: Option<String>,
+    ) -> Result<ExecutionResult, (String, Vec<Diagnostic>, Option<Error>)> {
         let (contract_name, is_tx) = match name {
             Some(name) => (name, false),
             None => (format!("contract-{}", self.contracts.len()), true),
@@ -652,15 +1165,32 @@ impl Session {
             let id = format!("{}.{}", tx_sender, contract_name);
             QualifiedContractIdentifier::parse(&id).unwrap()
         };
-
-        match self
-            .interpreter
-            .run(snippet, contract_identifier.clone(), cost_track, report)
+        if !is_tx
+            && self
+                .frozen_contracts
+                .contains(&contract_identifier.to_string())
         {
+            return Err((
+                format!(
+                    "Contract {} is frozen and cannot be redeployed",
+                    contract_identifier
+                ),
+                vec![],
+                None,
+            ));
+        }
+
+        match self.interpreter.run_with_diagnostics(
+            snippet,
+            contract_identifier.clone(),
+            cost_track,
+            report,
+        ) {
             Ok(result) => {
                 if let Some(ref coverage) = result.coverage {
                     self.coverage_reports.push(coverage.clone());
                 }
+                self.events.extend(result.contract_events.iter().cloned());
                 if let Some((
                     ref contract_identifier_str,
                     ref source,
@@ -672,656 +1202,4751 @@ impl Session {
                     self.asts.insert(contract_identifier.clone(), ast.clone());
                     self.contracts
                         .insert(contract_identifier_str.clone(), contract.clone());
+                    self.diagnostics
+                        .insert(contract_identifier_str.clone(), result.diagnostics.clone());
+                }
+                if is_tx && self.settings.block_model == BlockModel::BlockPerCall {
+                    self.advance_chain_tip(1);
                 }
                 Ok(result)
             }
-            Err(res) => Err(res),
+            Err((kind, diagnostics, error)) => {
+                // Keep the diagnostics of a contract which could not be deployed
+                if !is_tx && !diagnostics.is_empty() {
+                    self.diagnostics
+                        .insert(contract_identifier.to_string(), diagnostics.clone());
+                }
+                Err((kind, diagnostics, error))
+            }
         }
     }
 
-    pub fn lookup_api_reference(&self, keyword: &str) -> Option<&String> {
-        self.api_reference.get(keyword)
-    }
-
-    pub fn get_api_reference_index(&self) -> Vec<String> {
-        let mut keys = self
-            .api_reference
-            .iter()
-            .map(|(k, _)| k.to_string())
-            .collect::<Vec<String>>();
-        keys.sort();
-        keys
-    }
-
-    fn display_help(&self, output: &mut Vec<String>) {
-        let help_colour = Colour::Yellow;
-        let coming_soon_colour = Colour::Black.bold();
-        output.push(format!(
-            "{}",
-            help_colour.paint("::help\t\t\t\t\tDisplay help")
-        ));
-        output.push(format!(
-            "{}",
-            help_colour.paint(
-                "::list_functions\t\t\tDisplay all the native functions available in clarity"
-            )
-        ));
-        output.push(format!(
-            "{}",
-            help_colour.paint(
-                "::describe_function <function>\t\tDisplay documentation for a given native function fn-name"
-            )
-        ));
-        output.push(format!(
-            "{}",
-            help_colour
-                .paint("::mint_stx <principal> <amount>\t\tMint STX balance for a given principal")
-        ));
-        output.push(format!(
-            "{}",
-            help_colour.paint("::set_tx_sender <principal>\t\tSet tx-sender variable to principal")
-        ));
-        output.push(format!(
-            "{}",
-            help_colour.paint("::get_assets_maps\t\t\tGet assets maps for active accounts")
-        ));
-        output.push(format!(
-            "{}",
-            help_colour.paint("::get_costs <expr>\t\t\tDisplay the cost analysis")
-        ));
-        output.push(format!(
-            "{}",
-            help_colour.paint("::get_contracts\t\t\t\tGet contracts")
-        ));
-        output.push(format!(
-            "{}",
-            help_colour.paint("::get_block_height\t\t\tGet current block height")
-        ));
-        output.push(format!(
-            "{}",
-            help_colour.paint("::advance_chain_tip <count>\t\tSimulate mining of <count> blocks")
-        ));
-        output.push(format!(
-            "{}",
-            help_colour.paint("::toggle_costs\t\t\t\tDisplay cost analysis after every expression")
-        ))
+    // Prevent a deployed contract from being redeployed, or its state from
+    // being modified outside of its own functions, as for a boot contract.
+    // The contract can still be called.
+    pub fn freeze_contract(&mut self, contract_id: &str) -> Result<(), String> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        if !self.contracts.contains_key(&contract_id) {
+            return Err(format!("Contract {} is not deployed", contract_id));
+        }
+        self.frozen_contracts.insert(contract_id);
+        Ok(())
     }
 
-    fn parse_and_advance_chain_tip(&mut self, output: &mut Vec<String>, command: &str) {
+    fn parse_and_freeze_contract(&mut self, output: &mut Vec<String>, command: &str) {
         let args: Vec<_> = command.split(' ').collect();
 
         if args.len() != 2 {
-            output.push(red!("Usage: ::advance_chain_tip <count>"));
+            output.push(red!("Usage: ::freeze <contract>"));
             return;
         }
 
-        let count = match args[1].parse::<u32>() {
-            Ok(count) => count,
-            _ => {
-                output.push(red!("Unable to parse count"));
-                return;
-            }
+        // A bare name is a contract deployed by the tx-sender
+        let contract_id = if args[1].contains('.') {
+            args[1].to_string()
+        } else {
+            format!(".{}", args[1])
         };
-
-        let new_height = self.advance_chain_tip(count);
-        output.push(green!(format!(
-            "{} blocks simulated, new height: {}",
-            count, new_height
-        )));
-    }
-
-    pub fn advance_chain_tip(&mut self, count: u32) -> u32 {
-        self.interpreter.advance_chain_tip(count)
+        match self.freeze_contract(&contract_id) {
+            Ok(()) => output.push(green!(format!(
+                "{} is frozen",
+                self.resolve_contract_id(&contract_id)
+            ))),
+            Err(err) => output.push(red!(err)),
+        }
     }
 
-    fn parse_and_set_tx_sender(&mut self, output: &mut Vec<String>, command: &str) {
-        let args: Vec<_> = command.split(' ').collect();
+    fn coverage_merge(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<_> = command.split_whitespace().collect();
 
-        if args.len() != 2 {
-            output.push(red!("Usage: ::set_tx_sender <address>"));
+        if args.len() < 3 {
+            output.push(red!("Usage: ::coverage_merge <output> <files>"));
             return;
         }
 
-        let tx_sender = match PrincipalData::parse_standard_principal(&args[1]) {
-            Ok(address) => address,
-            _ => {
-                output.push(red!("Unable to parse the address"));
-                return;
-            }
-        };
+        match self.merge_coverage(&args[2..], args[1]) {
+            Ok(()) => output.push(green!(format!(
+                "Coverage of {} sessions written to {}",
+                args.len() - 1,
+                args[1]
+            ))),
+            Err(err) => output.push(red!(err)),
+        }
+    }
 
-        self.set_tx_sender(tx_sender.to_address());
-        output.push(green!(format!("tx-sender switched to {}", tx_sender)));
+    // Expand the `.name` shorthand into a contract deployed by the tx-sender.
+    fn resolve_contract_id(&self, contract_id: &str) -> String {
+        match contract_id.strip_prefix('.') {
+            Some(name) => format!("{}.{}", self.interpreter.get_tx_sender().to_address(), name),
+            None => contract_id.to_string(),
+        }
     }
 
-    pub fn set_tx_sender(&mut self, address: String) {
-        let tx_sender =
-            PrincipalData::parse_standard_principal(&address).expect("Unable to parse address");
-        self.interpreter.set_tx_sender(tx_sender)
+    // Checkpoint the data-vars, maps and tokens of a contract, to restore
+    // them with `restore_contract`.
+    pub fn snapshot_contract(&mut self, contract_id: &str, name: &str) -> Result<(), String> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        if !self.contracts.contains_key(&contract_id) {
+            return Err(format!("Contract {} is not deployed", contract_id));
+        }
+        let contract_identifier =
+            QualifiedContractIdentifier::parse(&contract_id).map_err(|e| e.to_string())?;
+        let snapshot = self.interpreter.snapshot_contract(&contract_identifier);
+        self.contract_snapshots.insert(name.to_string(), snapshot);
+        Ok(())
     }
 
-    pub fn get_tx_sender(&self) -> String {
-        self.interpreter.get_tx_sender().to_address()
+    // Restore a contract to a snapshot, without affecting the other contracts
+    // or the block height. The snapshot must be of the same contract.
+    pub fn restore_contract(&mut self, contract_id: &str, name: &str) -> Result<(), String> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        let snapshot = self
+            .contract_snapshots
+            .get(name)
+            .ok_or_else(|| format!("No snapshot named {}", name))?;
+        if snapshot.contract_identifier.to_string() != contract_id {
+            return Err(format!(
+                "Snapshot {} is of contract {}, not {}",
+                name, snapshot.contract_identifier, contract_id
+            ));
+        }
+        if self.frozen_contracts.contains(&contract_id) {
+            return Err(format!(
+                "Contract {} is frozen and cannot be modified",
+                contract_id
+            ));
+        }
+        self.interpreter.restore_contract(snapshot);
+        Ok(())
     }
 
-    fn get_block_height(&mut self, output: &mut Vec<String>) {
-        let height = self.interpreter.get_block_height();
-        output.push(green!(format!("Current height: {}", height)));
+    // The state of the chain, to be restored by `restore_checkpoint`.
+    pub fn checkpoint(&self) -> StateCheckpoint {
+        self.interpreter.checkpoint()
     }
 
-    fn get_account_name(&self, address: &String) -> Option<&String> {
-        for account in self.settings.initial_accounts.iter() {
-            if &account.address == address {
-                return Some(&account.name);
+    // Roll back the changes made to the chain since the checkpoint, unless
+    // this would modify a frozen contract.
+    pub fn restore_checkpoint(&mut self, checkpoint: StateCheckpoint) -> Result<(), String> {
+        self.check_frozen_since(&checkpoint)?;
+        self.interpreter.restore_checkpoint(checkpoint);
+        Ok(())
+    }
+
+    // Fail if a frozen contract changed since the checkpoint, so that
+    // restoring it would modify the contract.
+    fn check_frozen_since(&self, checkpoint: &StateCheckpoint) -> Result<(), String> {
+        for contract_id in self.frozen_contracts.iter() {
+            let contract_identifier = match QualifiedContractIdentifier::parse(contract_id) {
+                Ok(contract_identifier) => contract_identifier,
+                Err(_) => continue,
+            };
+            if self
+                .interpreter
+                .contract_changed_since(checkpoint, &contract_identifier)
+            {
+                return Err(format!(
+                    "Contract {} is frozen and cannot be modified",
+                    contract_id
+                ));
             }
         }
-        None
+        Ok(())
     }
 
-    pub fn get_assets_maps(&self) -> BTreeMap<String, BTreeMap<String, u128>> {
-        self.interpreter.get_assets_maps()
+    // Group the next evaluations into a transaction: their changes persist
+    // once it is committed, or are all rolled back if it is aborted.
+    // Transactions cannot be nested, so a transaction must be committed or
+    // aborted before the next one begins.
+    pub fn begin_transaction(&mut self) -> Result<(), String> {
+        if self.transaction.is_some() {
+            return Err("A transaction is already open, commit or abort it first".to_string());
+        }
+        self.transaction = Some(self.snapshot());
+        Ok(())
     }
 
-    pub fn toggle_costs(&mut self, output: &mut Vec<String>) {
-        self.show_costs = !self.show_costs;
-        output.push(green!(format!("Always show costs: {}", self.show_costs)))
+    // Keep the changes made since the transaction began, discarding the state
+    // saved to roll them back.
+    pub fn commit_transaction(&mut self) -> Result<(), String> {
+        match self.transaction.take() {
+            Some(_) => Ok(()),
+            None => Err("No transaction is open".to_string()),
+        }
     }
 
-    pub fn encode(&mut self, output: &mut Vec<String>, cmd: &str) {
-        let snippet = match cmd.split_once(" ") {
-            Some((_, snippet)) => snippet,
-            _ => return output.push(red!("Usage: ::encode <expr>")),
-        };
-
-        let result = self.interpret(snippet.to_string(), None, false, None);
-        let value = match result {
-            Ok(result) => {
-                let mut tx_bytes = vec![];
-                let value = match result.result {
-                    Some(value) => value,
-                    None => return output.push("No value".to_string()),
-                };
-                match value.consensus_serialize(&mut tx_bytes) {
-                    Err(e) => return output.push(red!(format!("{}", e))),
-                    _ => (),
-                };
-                let mut s = String::with_capacity(2 * tx_bytes.len());
-                for byte in tx_bytes {
-                    s = format!("{}{:02x}", s, byte);
-                }
-                green!(s)
-            }
-            Err((_, Some(diagnostic), Some(e))) => red!(format!("{}: {}", diagnostic.message, e)),
-            Err((_, Some(diagnostic), None)) => red!(format!("{}", diagnostic.message)),
-            Err((_, None, Some(e))) => red!(format!("{}", e)),
-            _ => panic!("Result of interpret has neither diagnostic or error"),
-        };
-        output.push(value);
+    // Roll back the contracts deployed, the state changed and the blocks
+    // mined since the transaction began, with the events, diagnostics and
+    // reports of the session.
+    pub fn abort_transaction(&mut self) -> Result<(), String> {
+        let transaction = self
+            .transaction
+            .as_ref()
+            .ok_or_else(|| "No transaction is open".to_string())?;
+        self.check_frozen_since(&transaction.interpreter.checkpoint())?;
+        let transaction = self.transaction.take().unwrap();
+        self.restore(transaction);
+        Ok(())
     }
 
-    pub fn decode(&mut self, output: &mut Vec<String>, cmd: &str) {
-        let byteString = match cmd.split_once(" ") {
-            Some((_, bytes)) => bytes,
-            _ => return output.push(red!("Usage: ::decode <hex-bytes>")),
-        };
-        let tx_bytes = match decode_hex(byteString) {
-            Ok(tx_bytes) => tx_bytes,
-            Err(e) => return output.push(red!(format!("Parsing error: {}", e))),
-        };
-
-        let value = match Value::consensus_deserialize(&mut &tx_bytes[..]) {
-            Ok(value) => value,
-            Err(e) => return output.push(red!(format!("{}", e))),
-        };
-        output.push(green!(format!("{}", value)));
+    fn snapshot(&self) -> SessionSnapshot {
+        SessionSnapshot {
+            interpreter: self.interpreter.clone(),
+            contracts: self.contracts.clone(),
+            asts: self.asts.clone(),
+            coverage_reports: self.coverage_reports.clone(),
+            costs_reports: self.costs_reports.clone(),
+            diagnostics: self.diagnostics.clone(),
+            events: self.events.clone(),
+            frozen_contracts: self.frozen_contracts.clone(),
+            contract_snapshots: self.contract_snapshots.clone(),
+        }
     }
 
-    #[cfg(feature = "cli")]
-    pub fn get_costs(&mut self, output: &mut Vec<String>, cmd: &str) {
-        let snippet = cmd.to_string().split_off("::get_costs ".len());
-        let (mut result, cost) = match self.formatted_interpretation(snippet, None, true, None) {
-            Ok((output, result)) => (output, result.cost.clone()),
-            Err(output) => (output, None),
-        };
+    fn restore(&mut self, snapshot: SessionSnapshot) {
+        self.interpreter = snapshot.interpreter;
+        self.contracts = snapshot.contracts;
+        self.asts = snapshot.asts;
+        self.coverage_reports = snapshot.coverage_reports;
+        self.costs_reports = snapshot.costs_reports;
+        self.diagnostics = snapshot.diagnostics;
+        self.events = snapshot.events;
+        self.frozen_contracts = snapshot.frozen_contracts;
+        self.contract_snapshots = snapshot.contract_snapshots;
+    }
 
-        if let Some(cost) = cost {
-            let headers = vec!["".to_string(), "Consumed".to_string(), "Limit".to_string()];
-            let mut headers_cells = vec![];
-            for header in headers.iter() {
-                headers_cells.push(Cell::new(&header));
+    // The source of each deployed contract, sorted by contract id, to share
+    // the code of a session without its state.
+    pub fn export_contracts(&mut self) -> Vec<(QualifiedContractIdentifier, String)> {
+        let mut contracts = vec![];
+        for contract_id in self.contracts.keys() {
+            let contract_identifier = match QualifiedContractIdentifier::parse(contract_id) {
+                Ok(contract_identifier) => contract_identifier,
+                Err(_) => continue,
+            };
+            if let Some(source) = self.interpreter.get_contract_source(&contract_identifier) {
+                contracts.push((contract_identifier, source));
             }
-            let mut table = Table::new();
-            table.add_row(Row::new(headers_cells));
-            table.add_row(Row::new(vec![
-                Cell::new("Runtime"),
-                Cell::new(&cost.total.runtime.to_string()),
-                Cell::new(&cost.limit.runtime.to_string()),
-            ]));
-            table.add_row(Row::new(vec![
-                Cell::new("Read count"),
-                Cell::new(&cost.total.read_count.to_string()),
-                Cell::new(&cost.limit.read_count.to_string()),
-            ]));
-            table.add_row(Row::new(vec![
-                Cell::new("Read length (bytes)"),
-                Cell::new(&cost.total.read_length.to_string()),
-                Cell::new(&cost.limit.read_length.to_string()),
-            ]));
-            table.add_row(Row::new(vec![
-                Cell::new("Write count"),
-                Cell::new(&cost.total.write_count.to_string()),
-                Cell::new(&cost.limit.write_count.to_string()),
-            ]));
-            table.add_row(Row::new(vec![
-                Cell::new("Write length (bytes)"),
-                Cell::new(&cost.total.write_length.to_string()),
-                Cell::new(&cost.limit.write_length.to_string()),
-            ]));
-            output.push(format!("{}", table));
         }
-        output.append(&mut result);
+        contracts
     }
 
-    #[cfg(feature = "cli")]
-    fn get_accounts(&mut self, output: &mut Vec<String>) {
-        let accounts = self.interpreter.get_accounts();
-        if accounts.len() > 0 {
-            let tokens = self.interpreter.get_tokens();
-            let mut headers = vec!["Address".to_string()];
-            headers.append(&mut tokens.clone());
-            let mut headers_cells = vec![];
-            for header in headers.iter() {
-                headers_cells.push(Cell::new(&header));
-            }
-            let mut table = Table::new();
-            table.add_row(Row::new(headers_cells));
-            for account in accounts.iter() {
-                let mut cells = vec![];
+    // Every diagnostic the built-in analysis passes can produce, with its code,
+    // level and title.
+    pub fn diagnostic_catalog(&self) -> Vec<DiagnosticCatalogEntry> {
+        catalog::diagnostic_catalog()
+    }
 
-                if let Some(name) = self.get_account_name(account) {
-                    cells.push(Cell::new(&format!("{} ({})", account, name)));
-                } else {
-                    cells.push(Cell::new(account));
-                }
+    // Render the AST of a snippet, as the analysis passes visit it: one node
+    // per line, indented by depth, with its id, kind and span.
+    pub fn dump_ast(&self, snippet: &str) -> Result<String, String> {
+        let ast = self
+            .interpreter
+            .build_ast(
+                QualifiedContractIdentifier::transient(),
+                snippet.to_string(),
+            )
+            .map_err(|(kind, diagnostic, _)| match diagnostic {
+                Some(diagnostic) => diagnostic.message,
+                None => kind,
+            })?;
+        let mut lines = vec![];
+        for expr in ast.expressions.iter() {
+            dump_expression(expr, 0, &mut lines);
+        }
+        Ok(lines.join("\n"))
+    }
 
-                for token in tokens.iter() {
-                    let balance = self.interpreter.get_balance_for_account(account, token);
-                    cells.push(Cell::new(&format!("{}", balance)));
-                }
-                table.add_row(Row::new(cells));
+    // Deploy contracts exported by `export_contracts`, analyzing them again.
+    // A contract is deployed after the contracts of the set it depends on,
+    // whatever the order given.
+    pub fn import_contracts(
+        &mut self,
+        contracts: Vec<(QualifiedContractIdentifier, String)>,
+    ) -> Result<Vec<ExecutionResult>, String> {
+        let mut pending = vec![];
+        for (contract_identifier, source) in contracts {
+            let ast = self
+                .interpreter
+                .build_ast(contract_identifier.clone(), source.clone())
+                .map_err(|(kind, diagnostic, _)| match diagnostic {
+                    Some(diagnostic) => format!("{}: {}", contract_identifier, diagnostic.message),
+                    None => format!("{}: {}", contract_identifier, kind),
+                })?;
+            let mut dependencies = BTreeSet::new();
+            contract_call_detector::traverse(&ast.expressions, &mut dependencies);
+            pending.push((contract_identifier, source, dependencies));
+        }
+        let imported: BTreeSet<QualifiedContractIdentifier> =
+            pending.iter().map(|(id, _, _)| id.clone()).collect();
+
+        let mut deployed = BTreeSet::new();
+        let mut results = vec![];
+        while !pending.is_empty() {
+            let (ready, waiting): (Vec<_>, Vec<_>) =
+                pending.into_iter().partition(|(_, _, dependencies)| {
+                    dependencies.iter().all(|dependency| {
+                        !imported.contains(dependency) || deployed.contains(dependency)
+                    })
+                });
+            if ready.is_empty() {
+                let cycle: Vec<String> = waiting.iter().map(|(id, _, _)| id.to_string()).collect();
+                return Err(format!(
+                    "Circular dependency between contracts: {}",
+                    cycle.join(", ")
+                ));
             }
-            output.push(format!("{}", table));
+            for (contract_identifier, source, _) in ready {
+                let result = self
+                    .interpret(source, Some(contract_identifier.to_string()), false, None)
+                    .map_err(|(kind, diagnostic, error)| match (diagnostic, error) {
+                        (Some(diagnostic), _) => {
+                            format!("{}: {}", contract_identifier, diagnostic.message)
+                        }
+                        (None, Some(error)) => format!("{}: {}", contract_identifier, error),
+                        (None, None) => format!("{}: {}", contract_identifier, kind),
+                    })?;
+                results.push(result);
+                deployed.insert(contract_identifier);
+            }
+            pending = waiting;
         }
+        Ok(results)
     }
 
-    #[cfg(feature = "cli")]
-    fn get_contracts(&mut self, output: &mut Vec<String>) {
-        if self.contracts.len() > 0 {
-            let mut table = Table::new();
-            table.add_row(row!["Contract identifier", "Public functions"]);
-            let contracts = self.contracts.clone();
-            for (contract_id, methods) in contracts.iter() {
-                if !contract_id.starts_with("ST000000000000000000002AMW42H")
-                    && !contract_id.starts_with("SP000000000000000000002Q6VF78")
-                {
-                    let mut formatted_methods = vec![];
-                    for (method_name, method_args) in methods.iter() {
-                        let formatted_args = if method_args.len() == 0 {
-                            format!("")
-                        } else if method_args.len() == 1 {
-                            format!(" {}", method_args.join(" "))
-                        } else {
-                            format!("\n    {}", method_args.join("\n    "))
-                        };
-                        formatted_methods.push(format!("({}{})", method_name, formatted_args));
-                    }
-                    let formatted_spec = format!("{}", formatted_methods.join("\n"));
-                    table.add_row(Row::new(vec![
-                        Cell::new(&contract_id),
-                        Cell::new(&formatted_spec),
-                    ]));
-                }
-            }
-            output.push(format!("{}", table));
+    // Analyze a snippet under each of the given Clarity versions, without
+    // deploying it, to show which diagnostics change across versions.
+    pub fn check_across_versions(
+        &mut self,
+        snippet: String,
+        versions: &[ClarityVersion],
+    ) -> HashMap<ClarityVersion, Vec<Diagnostic>> {
+        let contract_id = format!(
+            "{}.contract-{}",
+            self.interpreter.get_tx_sender().to_address(),
+            self.contracts.len()
+        );
+        let contract_identifier = QualifiedContractIdentifier::parse(&contract_id).unwrap();
+        let initial_settings = self.interpreter.get_analysis_settings().clone();
+
+        let mut diagnostics = HashMap::new();
+        for version in versions {
+            let mut settings = initial_settings.clone();
+            settings.clarity_version = *version;
+            self.interpreter.set_analysis_settings(settings);
+            let (_, version_diagnostics) = self
+                .interpreter
+                .check(snippet.clone(), contract_identifier.clone());
+            diagnostics.insert(*version, version_diagnostics);
         }
+        self.interpreter.set_analysis_settings(initial_settings);
+        diagnostics
     }
 
-    #[cfg(not(feature = "cli"))]
-    pub fn get_costs(&mut self, output: &mut Vec<String>, cmd: &str) {
-        let snippet = cmd.to_string().split_off("::get_costs ".len());
-        let (mut result, cost) = match self.formatted_interpretation(snippet, None, true, None) {
-            Ok((output, result)) => (output, result.cost.clone()),
-            Err(output) => (output, None),
+    // Analyze a snippet being typed, which may end with an incomplete form:
+    // the complete forms before it are analyzed, and the incomplete one is
+    // only reported by a note, so that it does not cascade into errors.
+    pub fn check_partial(&mut self, prefix: &str) -> Vec<Diagnostic> {
+        let contract_id = format!(
+            "{}.contract-{}",
+            self.interpreter.get_tx_sender().to_address(),
+            self.contracts.len()
+        );
+        let contract_identifier = QualifiedContractIdentifier::parse(&contract_id).unwrap();
+        let (complete, dangling) = match dangling_form(prefix) {
+            Some((offset, span)) => (&prefix[..offset], Some(span)),
+            None => (prefix, None),
         };
-
-        if let Some(cost) = cost {
-            output.push(format!(
-                "Execution: {:?}\nLimit: {:?}",
-                cost.total, cost.limit
-            ));
+        let mut diagnostics = if complete.trim().is_empty() {
+            vec![]
+        } else {
+            let (_, diagnostics) = self
+                .interpreter
+                .check(complete.to_string(), contract_identifier);
+            diagnostics
+        };
+        if let Some(span) = dangling {
+            diagnostics.push(Diagnostic {
+                level: Level::Note,
+                code: None,
+                message: "this expression is incomplete".to_string(),
+                spans: vec![span],
+                suggestion: None,
+            });
         }
-        output.append(&mut result);
+        diagnostics
     }
 
-    #[cfg(not(feature = "cli"))]
-    fn get_accounts(&mut self, output: &mut Vec<String>) {
-        if self.settings.initial_accounts.len() > 0 {
-            let mut initial_accounts = self.settings.initial_accounts.clone();
-            for account in initial_accounts.drain(..) {
-                output.push(format!(
-                    "{}: {} ({})",
-                    account.address, account.balance, account.name
-                ));
+    // Analyze the contracts of a project without deploying them. Contracts
+    // whose dependencies within the project have all been analyzed are
+    // analyzed together, each on its own thread with its own copy of the
+    // datastore. Diagnostics are keyed by contract id, so that the result does
+    // not depend on thread scheduling. Threads are not available in wasm.
+    // `examples/check_project_parallel.rs` measures the speedup.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn check_project_parallel(
+        &self,
+        contracts: &[InitialContract],
+    ) -> Result<BTreeMap<String, Vec<Diagnostic>>, String> {
+        let mut interpreter = self.interpreter.clone();
+        let mut pending = vec![];
+        let mut parse_errors = BTreeMap::new();
+        for contract in contracts {
+            let contract_identifier = contract
+                .get_contract_identifier_with_versions(self.principal_versions(), false)
+                .ok_or_else(|| format!("Contract {} has no name", contract.path))?;
+            let code = contract.code.clone();
+            match interpreter.build_ast(contract_identifier.clone(), code.clone()) {
+                Ok(ast) => {
+                    let mut dependencies = BTreeSet::new();
+                    contract_call_detector::traverse(&ast.expressions, &mut dependencies);
+                    pending.push((contract_identifier, (code, ast), dependencies));
+                }
+                Err((_, diagnostic, _)) => {
+                    parse_errors.insert(
+                        contract_identifier.to_string(),
+                        diagnostic.into_iter().collect(),
+                    );
+                }
             }
         }
-    }
+        let project: BTreeSet<QualifiedContractIdentifier> =
+            pending.iter().map(|(id, _, _)| id.clone()).collect();
+
+        let mut diagnostics = parse_errors;
+        let mut analyzed = BTreeSet::new();
+        while !pending.is_empty() {
+            let (ready, waiting): (Vec<_>, Vec<_>) =
+                pending.into_iter().partition(|(_, _, dependencies)| {
+                    dependencies.iter().all(|dependency| {
+                        !project.contains(dependency) || analyzed.contains(dependency)
+                    })
+                });
+            if ready.is_empty() {
+                let cycle: Vec<String> = waiting.iter().map(|(id, _, _)| id.to_string()).collect();
+                return Err(format!(
+                    "Circular dependency between contracts: {}",
+                    cycle.join(", ")
+                ));
+            }
 
-    #[cfg(not(feature = "cli"))]
-    fn get_contracts(&mut self, output: &mut Vec<String>) {
-        for (contract_id, methods) in self.contracts.iter() {
-            if !contract_id.ends_with(".pox")
-                && !contract_id.ends_with(".bns")
-                && !contract_id.ends_with(".costs")
-            {
-                output.push(format!("{}", contract_id));
+            // Each worker analyzes its share of the contracts with its own copy
+            // of the interpreter, since copying the datastore is not free
+            let workers = std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+                .min(ready.len());
+            let mut shares = vec![vec![]; workers];
+            for (i, (contract_identifier, source, _)) in ready.into_iter().enumerate() {
+                shares[i % workers].push((contract_identifier, source));
+            }
+            let handles: Vec<_> = shares
+                .into_iter()
+                .map(|share| {
+                    let mut interpreter = interpreter.clone();
+                    std::thread::spawn(move || {
+                        share
+                            .into_iter()
+                            .map(|(contract_identifier, (code, ast))| {
+                                let (analysis, diagnostics) =
+                                    interpreter.check_ast(code, contract_identifier.clone(), ast);
+                                (contract_identifier, analysis, diagnostics)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            let mut results = vec![];
+            for handle in handles {
+                let mut share = handle
+                    .join()
+                    .map_err(|_| "Unable to analyze contracts".to_string())?;
+                results.append(&mut share);
             }
+            // Dependent contracts are type-checked against these analyses
+            results.sort_by_key(|(contract_identifier, _, _)| contract_identifier.to_string());
+            for (contract_identifier, analysis, contract_diagnostics) in results {
+                if let Some(analysis) = analysis {
+                    interpreter.save_contract_analysis(&contract_identifier, &analysis);
+                }
+                diagnostics.insert(contract_identifier.to_string(), contract_diagnostics);
+                analyzed.insert(contract_identifier);
+            }
+            pending = waiting;
         }
+        Ok(diagnostics)
     }
 
-    fn mint_stx(&mut self, output: &mut Vec<String>, command: &str) {
-        let args: Vec<_> = command.split(' ').collect();
-
-        if args.len() != 3 {
-            output.push(red!("Usage: ::mint_stx <recipient address> <amount>"));
-            return;
+    // Describe the ABI of a deployed contract, as defined in `repl::abi`.
+    pub fn contract_abi(&mut self, contract_id: &str) -> Result<serde_json::Value, String> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        let contract_identifier =
+            QualifiedContractIdentifier::parse(&contract_id).map_err(|e| e.to_string())?;
+        match self.interpreter.get_contract_analysis(&contract_identifier) {
+            Some(analysis) => Ok(abi::contract_abi(&analysis)),
+            None => Err(format!("Contract {} is not deployed", contract_id)),
         }
+    }
 
-        let recipient = match PrincipalData::parse(&args[1]) {
-            Ok(address) => address,
-            _ => {
-                output.push(red!("Unable to parse the address"));
-                return;
+    // Locate the name of a function, map, variable, constant, token or trait
+    // in the source of a deployed contract. Native functions, which have no
+    // source, and unknown names return `None`.
+    pub fn definition_span(&self, contract_id: &str, name: &str) -> Option<Span> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        let contract_identifier = QualifiedContractIdentifier::parse(&contract_id).ok()?;
+        let ast = self.asts.get(&contract_identifier)?;
+        ast.expressions.iter().find_map(|expr| {
+            let name_expr = match DefineFunctions::try_parse(expr)? {
+                (DefineFunctions::ImplTrait, _) => return None,
+                (
+                    DefineFunctions::PublicFunction
+                    | DefineFunctions::ReadOnlyFunction
+                    | DefineFunctions::PrivateFunction,
+                    [signature, ..],
+                ) => signature.match_list()?.first()?,
+                (_, [name_expr, ..]) => name_expr,
+                _ => return None,
+            };
+            match name_expr.match_atom() {
+                Some(defined) if defined.as_str() == name => Some(name_expr.span.clone()),
+                _ => None,
             }
-        };
+        })
+    }
 
-        let amount: u64 = match args[2].parse() {
-            Ok(recipient) => recipient,
-            _ => {
-                output.push(red!("Unable to parse the balance"));
-                return;
-            }
+    // List the traits used and implemented by a deployed contract, in the
+    // order of its source. Unknown contracts have no traits.
+    pub fn contract_traits(&mut self, contract_id: &str) -> Vec<TraitUsage> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        let contract_identifier = match QualifiedContractIdentifier::parse(&contract_id) {
+            Ok(contract_identifier) => contract_identifier,
+            Err(_) => return vec![],
         };
-
-        match self.interpreter.mint_stx_balance(recipient, amount) {
-            Ok(msg) => output.push(green!(msg)),
-            Err(err) => output.push(red!(err)),
+        let (ast, analysis) = match (
+            self.asts.get(&contract_identifier),
+            self.interpreter.get_contract_analysis(&contract_identifier),
+        ) {
+            (Some(ast), Some(analysis)) => (ast, analysis),
+            _ => return vec![],
         };
+        let mut usages = vec![];
+        for expr in ast.expressions.iter() {
+            let (alias, trait_identifier) = match DefineFunctions::try_parse(expr) {
+                Some((DefineFunctions::UseTrait, [name, trait_expr])) => {
+                    match (name.match_atom(), trait_expr.match_field()) {
+                        (Some(name), Some(trait_identifier)) => (Some(name), trait_identifier),
+                        _ => continue,
+                    }
+                }
+                Some((DefineFunctions::ImplTrait, [trait_expr])) => {
+                    match trait_expr.match_field() {
+                        Some(trait_identifier) => (None, trait_identifier),
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            };
+            let definition = self
+                .interpreter
+                .get_contract_analysis(&trait_identifier.contract_identifier)
+                .and_then(|defining| defining.get_defined_trait(&trait_identifier.name).cloned());
+            let conforms = match (&alias, &definition) {
+                (None, Some(definition)) => Some(
+                    analysis
+                        .check_trait_compliance(trait_identifier, definition)
+                        .is_ok(),
+                ),
+                _ => None,
+            };
+            usages.push(TraitUsage {
+                alias: alias.cloned(),
+                trait_identifier: trait_identifier.clone(),
+                span: expr.span.clone(),
+                resolved: definition.is_some(),
+                conforms,
+            });
+        }
+        usages
     }
 
-    fn display_functions(&self, output: &mut Vec<String>) {
-        let help_colour = Colour::Yellow;
-        let api_reference_index = self.get_api_reference_index();
-        output.push(format!(
-            "{}",
-            help_colour.paint(api_reference_index.join("\n"))
-        ));
-    }
-
-    fn display_doc(&self, output: &mut Vec<String>, command: &str) {
-        let help_colour = Colour::Yellow;
-        let help_accent_colour = Colour::Yellow.bold();
-        let keyword = {
-            let mut s = command.to_string();
-            s = s.replace("::describe_function", "");
-            s = s.replace(" ", "");
-            s
+    // Analyze a deployed contract again, with the passes of the session, to
+    // report whether it is ready to be deployed.
+    pub fn conformance_report(&mut self, contract_id: &str) -> Result<ConformanceReport, String> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        let contract_identifier =
+            QualifiedContractIdentifier::parse(&contract_id).map_err(|e| e.to_string())?;
+        let source = self
+            .interpreter
+            .get_contract_source(&contract_identifier)
+            .ok_or_else(|| format!("Contract {} is not deployed", contract_id))?;
+        let (_, diagnostics) = self.interpreter.check(source, contract_identifier);
+        let traits = self
+            .contract_traits(&contract_id)
+            .into_iter()
+            .filter(|usage| usage.alias.is_none())
+            .map(|usage| TraitConformance {
+                trait_identifier: usage.trait_identifier.to_string(),
+                conforms: usage.conforms,
+            })
+            .collect();
+        Ok(ConformanceReport::new(contract_id, &diagnostics, traits))
+    }
+
+    // Return the events emitted while executing code of the given contract.
+    pub fn events_for(&self, contract_id: &str) -> Vec<StacksEvent> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        self.events
+            .iter()
+            .filter(|event| event.contract_identifier.to_string() == contract_id)
+            .cloned()
+            .collect()
+    }
+
+    // Arm the recorder to capture the next invocation of the given function.
+    pub fn record_call(&mut self, contract_id: &str, fn_name: &str) -> Result<(), String> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        let contract_identifier =
+            QualifiedContractIdentifier::parse(&contract_id).map_err(|e| e.to_string())?;
+        let function_name =
+            ClarityName::try_from(fn_name.to_string()).map_err(|e| e.to_string())?;
+        self.interpreter
+            .record_call(contract_identifier, function_name);
+        Ok(())
+    }
+
+    pub fn recorded_call(&self) -> Option<&RecordedCall> {
+        self.interpreter.recorded_call()
+    }
+
+    pub fn replay_recorded_call(&mut self) -> Result<Value, String> {
+        let call = match self.interpreter.recorded_call() {
+            Some(call) => call.clone(),
+            None => return Err("no call has been recorded".to_string()),
         };
-        let result = match self.lookup_api_reference(&keyword) {
-            Some(doc) => format!("{}", help_colour.paint(doc)),
-            None => format!("{}", help_colour.paint("Function unknown")),
+        self.interpreter
+            .replay_call(&call)
+            .map_err(|e| e.to_string())
+    }
+
+    // Export the diagnostics reported while deploying a contract as a SARIF
+    // log. Contracts loaded from the settings are located by their path.
+    pub fn diagnostics_to_sarif(&self, contract_id: &str) -> serde_json::Value {
+        let contract_id = self.resolve_contract_id(contract_id);
+        let diagnostics = match self.diagnostics.get(&contract_id) {
+            Some(diagnostics) => &diagnostics[..],
+            None => &[],
         };
-        output.push(result);
+        let uri = self
+            .settings
+            .initial_contracts
+            .iter()
+            .find(|contract| {
+                contract
+                    .get_contract_identifier_with_versions(self.principal_versions(), false)
+                    .map(|id| id.to_string())
+                    .as_ref()
+                    == Some(&contract_id)
+            })
+            .map_or(contract_id.clone(), |contract| contract.path.clone());
+        sarif::diagnostics_to_sarif(diagnostics, &uri)
     }
-}
 
-#[derive(Debug, PartialEq)]
-enum DecodeHexError {
-    ParseError(ParseIntError),
-    OddLength,
-}
+    // Call every public and read-only function of a contract with minimal
+    // arguments, rolling back the changes of each call.
+    pub fn smoke_test(&mut self, contract_id: &str) -> Result<SmokeReport, String> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        let contract_identifier =
+            QualifiedContractIdentifier::parse(&contract_id).map_err(|e| e.to_string())?;
+        let functions = self
+            .interpreter
+            .get_public_functions(&contract_identifier)
+            .map_err(|e| e.to_string())?;
+        let sender: PrincipalData = self.interpreter.get_tx_sender().into();
 
-impl fmt::Display for DecodeHexError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            DecodeHexError::ParseError(e) => write!(f, "{}", e),
-            DecodeHexError::OddLength => write!(f, "odd number of hex digits"),
+        let mut report = SmokeReport {
+            contract_id,
+            results: vec![],
+        };
+        for (function_name, arg_types) in functions {
+            let args: Option<Vec<Value>> = arg_types
+                .iter()
+                .map(|arg_type| smoke::minimal_value(arg_type, &sender))
+                .collect();
+            let (args, outcome) = match args {
+                Some(args) => {
+                    let outcome = match self.interpreter.call_public_function_isolated(
+                        &contract_identifier,
+                        &function_name,
+                        &args,
+                    ) {
+                        Ok(Value::Response(response)) if !response.committed => {
+                            SmokeOutcome::Errored(*response.data)
+                        }
+                        Ok(value) => SmokeOutcome::Succeeded(value),
+                        Err(e) => SmokeOutcome::Aborted(e.to_string()),
+                    };
+                    (args, outcome)
+                }
+                None => (
+                    vec![],
+                    SmokeOutcome::Skipped("arguments of trait types cannot be generated".into()),
+                ),
+            };
+            report.results.push(SmokeResult {
+                function: function_name.to_string(),
+                args,
+                outcome,
+            });
         }
+        Ok(report)
     }
-}
 
-impl From<ParseIntError> for DecodeHexError {
-    fn from(err: ParseIntError) -> Self {
-        DecodeHexError::ParseError(err)
+    // The minimal arguments of a function of a contract, as generated for
+    // the smoke test, with principals set to the tx-sender.
+    pub fn default_args(&mut self, contract_id: &str, fn_name: &str) -> Result<Vec<Value>, String> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        let contract_identifier =
+            QualifiedContractIdentifier::parse(&contract_id).map_err(|e| e.to_string())?;
+        let analysis = self
+            .interpreter
+            .get_contract_analysis(&contract_identifier)
+            .ok_or_else(|| format!("Contract {} is not deployed", contract_id))?;
+        let function_type = analysis
+            .get_public_function_type(fn_name)
+            .or_else(|| analysis.get_read_only_function_type(fn_name))
+            .or_else(|| analysis.get_private_function(fn_name))
+            .ok_or_else(|| format!("Function {} is not defined by {}", fn_name, contract_id))?;
+        let args = match function_type {
+            FunctionType::Fixed(function) => &function.args,
+            _ => return Err(format!("Function {} has no fixed arguments", fn_name)),
+        };
+        let sender: PrincipalData = self.interpreter.get_tx_sender().into();
+        args.iter()
+            .map(|arg| {
+                smoke::minimal_value(&arg.signature, &sender).ok_or_else(|| {
+                    format!(
+                        "Argument {} of {} is a trait reference, which cannot be generated",
+                        arg.name, fn_name
+                    )
+                })
+            })
+            .collect()
     }
-}
 
-fn decode_hex(byteString: &str) -> Result<Vec<u8>, DecodeHexError> {
-    let byteStringFiltered: String = byteString
-        .strip_prefix("0x")
-        .unwrap_or(byteString)
-        .chars()
-        .filter(|c| !c.is_whitespace())
-        .collect();
-    if byteStringFiltered.len() % 2 != 0 {
-        return Err(DecodeHexError::OddLength);
+    // Call a public or read-only function with the given arguments, rolling
+    // back its changes, and report which branches of its `if`, `match` and
+    // `asserts!` expressions were taken.
+    pub fn reachable_branches(
+        &mut self,
+        contract_id: &str,
+        fn_name: &str,
+        args: &[Value],
+    ) -> Result<BranchReport, String> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        let contract_identifier =
+            QualifiedContractIdentifier::parse(&contract_id).map_err(|e| e.to_string())?;
+        let ast = self
+            .asts
+            .get(&contract_identifier)
+            .ok_or_else(|| format!("Contract {} is not deployed", contract_id))?;
+        let (definition, body) = ast
+            .expressions
+            .iter()
+            .find_map(|expr| match DefineFunctions::try_parse(expr)? {
+                (
+                    DefineFunctions::PublicFunction | DefineFunctions::ReadOnlyFunction,
+                    [signature, body],
+                ) if signature.match_list()?.first()?.match_atom()?.as_str() == fn_name => {
+                    Some((expr, body))
+                }
+                _ => None,
+            })
+            .ok_or_else(|| {
+                format!(
+                    "Function {} is not a public or read-only function of {}",
+                    fn_name, contract_id
+                )
+            })?;
+        // The AST is borrowed from the session, which the call needs
+        let (definition, body) = (definition.clone(), body.clone());
+        let function_name =
+            ClarityName::try_from(fn_name.to_string()).map_err(|e| e.to_string())?;
+
+        let (result, evaluations) =
+            self.interpreter
+                .call_counting_evaluations(&contract_identifier, &function_name, args);
+        let mut points = vec![];
+        branches::branch_points(&body, &evaluations, &mut points);
+        let source = self
+            .interpreter
+            .get_contract_source(&contract_identifier)
+            .unwrap_or_default();
+        let source = source
+            .lines()
+            .skip(definition.span.start_line.saturating_sub(1) as usize)
+            .take(
+                (definition.span.end_line + 1).saturating_sub(definition.span.start_line) as usize,
+            )
+            .map(|line| line.to_string())
+            .collect();
+        Ok(BranchReport {
+            contract_id,
+            function: fn_name.to_string(),
+            args: args.to_vec(),
+            result: result.map_err(|e| e.to_string()),
+            points,
+            first_line: definition.span.start_line,
+            source,
+        })
     }
-    let result: Result<Vec<u8>, ParseIntError> = (0..byteStringFiltered.len())
-        .step_by(2)
-        .map(|i| u8::from_str_radix(&byteStringFiltered[i..i + 2], 16))
-        .collect();
-    match result {
-        Ok(result) => Ok(result),
-        Err(e) => Err(DecodeHexError::ParseError(e)),
+
+    // The calls to user-defined functions and other contracts made by the
+    // last snippet interpreted with cost tracking, each with its cost.
+    pub fn cost_tree(&self) -> &[CostTreeNode] {
+        self.interpreter.cost_tree()
     }
-}
 
-#[derive(Deserialize, Debug, Default, Clone)]
-struct Contract {
-    source: String,
-    publish_height: u32,
-}
+    // Write the cost tree of the last snippet interpreted with cost tracking
+    // as a Chrome trace, to visualize its calls in chrome://tracing or
+    // Perfetto.
+    pub fn export_trace_json(&self, path: &str) -> Result<(), String> {
+        if self.cost_tree().is_empty() {
+            return Err("No calls were traced, interpret a snippet with costs first".to_string());
+        }
+        let trace = trace_event::cost_tree_to_trace(self.cost_tree());
+        let json = serde_json::to_string_pretty(&trace).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| format!("Unable to write {}: {}", path, e))
+    }
 
-async fn fetch_contract(request_url: String) -> Contract {
-    let response: Contract = reqwest::get(&request_url)
-        .await
-        .expect("Unable to retrieve contract")
-        .json()
-        .await
-        .expect("Unable to parse contract");
-    return response;
-}
+    // The coverage recorded by the tests run in the session, with the
+    // contracts it covers. Only the initial contracts have a path, and are
+    // written to an LCOV file.
+    pub fn coverage_reporter(&self) -> CoverageReporter {
+        let mut reporter = CoverageReporter::new();
+        reporter.add_reports(&self.coverage_reports);
+        reporter.add_asts(&self.asts);
+        for contract in self.settings.initial_contracts.iter() {
+            if let Some(ref name) = contract.name {
+                reporter.register_contract(name.clone(), contract.path.clone());
+            }
+        }
+        reporter
+    }
 
-fn build_api_reference() -> HashMap<String, String> {
-    let mut api_reference = HashMap::new();
-    for func in NativeFunctions::ALL.iter() {
-        let api = make_api_reference(&func);
-        let description = {
-            let mut s = api.description.to_string();
-            s = s.replace("\n", " ");
-            s
+    // Write the coverage of the session, to be merged with that of other
+    // sessions by `merge_coverage`.
+    pub fn export_coverage(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string(&self.coverage_reporter()).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| format!("Unable to write {}: {}", path, e))
+    }
+
+    // Merge the coverage exported by other sessions with that of this one,
+    // and write the result as an LCOV file.
+    pub fn merge_coverage(&self, partials: &[&str], lcov_path: &str) -> Result<(), String> {
+        let mut reporter = self.coverage_reporter();
+        for partial in partials {
+            let json = std::fs::read_to_string(partial)
+                .map_err(|e| format!("Unable to read {}: {}", partial, e))?;
+            let other: CoverageReporter = serde_json::from_str(&json)
+                .map_err(|e| format!("Unable to parse {}: {}", partial, e))?;
+            reporter.merge(other);
+        }
+        reporter
+            .write_lcov_file(lcov_path)
+            .map_err(|e| format!("Unable to write {}: {}", lcov_path, e))
+    }
+
+    // The limits on the iterations and the depth of the calls in effect.
+    pub fn iteration_limits(&self) -> &IterationLimits {
+        &self.settings.iteration_limits
+    }
+
+    // The most `contract-call?` which can be nested, by default the depth of
+    // the call stack allowed by consensus.
+    pub fn call_depth_limit(&self) -> usize {
+        self.settings.iteration_limits.max_contract_call_depth
+    }
+
+    pub fn cost_limits(&self) -> ExecutionCost {
+        block_limit(self.settings.costs_version)
+    }
+
+    // The cost function of a native function, with the formula of each
+    // dimension it costs, as defined by the cost contract of the session.
+    pub fn cost_formula(
+        &self,
+        fn_name: &str,
+    ) -> Result<(ClarityCostFunction, Vec<(String, CostFormula)>), String> {
+        let function = NativeFunctions::lookup_by_name(fn_name)
+            .ok_or_else(|| format!("'{}' is not a native function", fn_name))?;
+        let cost_function = cost_formula::cost_function(function)
+            .ok_or_else(|| format!("'{}' has no cost of its own", fn_name))?;
+        let costs_contract = match self.settings.costs_version {
+            1 => COSTS_V1_CONTRACT,
+            _ => COSTS_V2_CONTRACT,
         };
-        let doc = format!(
-            "Usage\n{}\n\nDescription\n{}\n\nExamples\n{}",
-            api.signature, description, api.example
-        );
-        api_reference.insert(api.name, doc);
+        let dimensions = cost_formula::cost_formula(costs_contract, cost_function)
+            .ok_or_else(|| format!("unable to read '{}'", cost_function.get_name()))?;
+        Ok((cost_function, dimensions))
     }
 
-    for func in DefineFunctions::ALL.iter() {
-        let api = make_define_reference(&func);
-        let description = {
-            let mut s = api.description.to_string();
-            s = s.replace("\n", " ");
-            s
+    pub fn cost_explain(&self, output: &mut Vec<String>, cmd: &str) {
+        let fn_name = match cmd.split_once(' ') {
+            Some((_, fn_name)) if !fn_name.trim().is_empty() => fn_name.trim(),
+            _ => {
+                output.push(red!("Usage: ::cost_explain <native-fn>"));
+                return;
+            }
         };
-        let doc = format!(
-            "Usage\n{}\n\nDescription\n{}\n\nExamples\n{}",
-            api.signature, description, api.example
-        );
-        api_reference.insert(api.name, doc);
+        let (cost_function, dimensions) = match self.cost_formula(fn_name) {
+            Ok(formula) => formula,
+            Err(message) => {
+                output.push(red!(message));
+                return;
+            }
+        };
+        output.push(format!(
+            "{} is charged by {}",
+            fn_name,
+            cost_function.get_name()
+        ));
+        for (dimension, formula) in dimensions.iter() {
+            output.push(format!("  {}: {}", dimension, formula));
+        }
+        if dimensions.iter().all(|(_, formula)| formula.is_constant()) {
+            output.push(green!("Constant cost"));
+        } else {
+            output.push(green!(
+                "Scales with n, the size of its input (length or count)"
+            ));
+        }
     }
 
-    for func in NativeVariables::ALL.iter() {
-        let api = make_keyword_reference(&func);
-        let description = {
-            let mut s = api.description.to_string();
-            s = s.replace("\n", " ");
-            s
+    // Interpret a snippet, then roll back any change it made to the session
+    // state, so that the next evaluation starts from the same state.
+    pub fn interpret_isolated(
+        &mut self,
+        snippet: String,
+        cost_track: bool,
+    ) -> Result<ExecutionResult, (String, Option<Diagnostic>, Option<Error>)> {
+        let snapshot = self.snapshot();
+        let result = self.interpret(snippet, None, cost_track, None);
+        self.restore(snapshot);
+        result
+    }
+
+    pub fn lookup_api_reference(&self, keyword: &str) -> Option<&String> {
+        self.api_reference.get(keyword)
+    }
+
+    pub fn get_api_reference_index(&self) -> Vec<String> {
+        let mut keys = self
+            .api_reference
+            .iter()
+            .map(|(k, _)| k.to_string())
+            .collect::<Vec<String>>();
+        keys.sort();
+        keys
+    }
+
+    fn display_help(&self, output: &mut Vec<String>) {
+        let help_colour = Colour::Yellow;
+        for command in commands::commands() {
+            // Align the descriptions on the fifth tab stop
+            let usage = command.usage();
+            let mut column = usage.len();
+            let mut line = usage;
+            loop {
+                line.push('\t');
+                column = (column / 8 + 1) * 8;
+                if column >= 40 {
+                    break;
+                }
+            }
+            line.push_str(command.description);
+            output.push(format!("{}", help_colour.paint(line)));
+        }
+    }
+
+    // The commands which can be run with `run_command`, or typed in the REPL
+    // prefixed with `::`.
+    pub fn commands(&self) -> Vec<CommandInfo> {
+        commands::commands()
+    }
+
+    // Run a command with its arguments, as if `::name args` was typed in the
+    // REPL. An expression is a single argument.
+    pub fn run_command(&mut self, name: &str, args: &[&str]) -> CommandResult {
+        let command = commands::commands()
+            .into_iter()
+            .find(|command| command.name == name)
+            .ok_or_else(|| format!("Unknown command {}", name))?;
+        if args.len() < command.required_arguments() {
+            return Err(format!("Usage: {}", command.usage()));
+        }
+        let mut line = format!("::{}", name);
+        for arg in args {
+            line.push(' ');
+            line.push_str(arg);
+        }
+        Ok(self.handle_command(&line))
+    }
+
+    fn parse_and_advance_chain_tip(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<_> = command.split(' ').collect();
+
+        if args.len() != 2 {
+            output.push(red!("Usage: ::advance_chain_tip <count>"));
+            return;
+        }
+
+        let count = match args[1].parse::<u32>() {
+            Ok(count) => count,
+            _ => {
+                output.push(red!("Unable to parse count"));
+                return;
+            }
         };
-        let doc = format!("Description\n{}\n\nExamples\n{}", description, api.example);
-        api_reference.insert(api.name.to_string(), doc);
+
+        let new_height = self.advance_chain_tip(count);
+        output.push(green!(format!(
+            "{} blocks simulated, new height: {}",
+            count, new_height
+        )));
+    }
+
+    // The value of a data-var as of a past block height, or `None` if that
+    // height has not been reached, or the data-var was not set yet.
+    pub fn data_var_at(&mut self, contract_id: &str, var_name: &str, height: u32) -> Option<Value> {
+        let contract_id = self.resolve_contract_id(contract_id);
+        let contract_identifier = QualifiedContractIdentifier::parse(&contract_id).ok()?;
+        self.interpreter
+            .get_data_var_at(&contract_identifier, var_name, height)
+    }
+
+    // Evaluate the next snippets against the state at the end of a past
+    // block, read-only, as within `at-block`, until the context is popped.
+    pub fn push_block_context(&mut self, height: u32) -> Result<(), String> {
+        self.interpreter.push_block_context(height)
+    }
+
+    pub fn pop_block_context(&mut self) -> Result<u32, String> {
+        self.interpreter.pop_block_context()
+    }
+
+    pub fn advance_chain_tip(&mut self, count: u32) -> u32 {
+        self.interpreter.advance_chain_tip(count)
+    }
+
+    // Make `get-block-info?` return `(some value)` for a height and property,
+    // even for blocks not mined yet. The other lookups are unchanged.
+    pub fn mock_block_info(
+        &mut self,
+        height: u32,
+        property: &str,
+        value: Value,
+    ) -> Result<(), String> {
+        let property = BlockInfoProperty::lookup_by_name(property).ok_or_else(|| {
+            format!(
+                "Unknown block property {}, expected one of {}",
+                property,
+                BlockInfoProperty::ALL_NAMES.join(", ")
+            )
+        })?;
+        let expected = property.type_result();
+        if !expected.admits(&value) {
+            return Err(format!(
+                "The {} of a block is a {}, not {}",
+                property.get_name(),
+                expected,
+                value
+            ));
+        }
+        self.interpreter.mock_block_info(height, property, value);
+        Ok(())
+    }
+
+    fn parse_and_mock_block_info(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<&str> = command.splitn(4, ' ').collect();
+        let (height, property, value) = match args[..] {
+            [_, height, property, value] => (height, property, value),
+            _ => return output.push(red!("Usage: ::mock_block_info <height> <property> <value>")),
+        };
+        let height = match height.parse::<u32>() {
+            Ok(height) => height,
+            Err(_) => return output.push(red!("Unable to parse height")),
+        };
+        let value = match self.interpret(value.to_string(), None, false, None) {
+            Ok(ExecutionResult {
+                result: Some(value),
+                ..
+            }) => value,
+            _ => return output.push(red!(format!("Unable to evaluate {}", value))),
+        };
+        match self.mock_block_info(height, property, value.clone()) {
+            Ok(()) => output.push(green!(format!(
+                "get-block-info? {} at height {} returns (some {})",
+                property, height, value
+            ))),
+            Err(error) => output.push(red!(error)),
+        }
+    }
+
+    // The id of the block the next snippet is evaluated in.
+    pub fn current_block_id(&self) -> StacksBlockId {
+        self.interpreter.get_block_id()
+    }
+
+    fn parse_and_set_tx_sender(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<_> = command.split(' ').collect();
+
+        if args.len() != 2 {
+            output.push(red!("Usage: ::set_tx_sender <address>"));
+            return;
+        }
+
+        let tx_sender = match PrincipalData::parse_standard_principal(&args[1]) {
+            Ok(address) => address,
+            _ => {
+                output.push(red!("Unable to parse the address"));
+                return;
+            }
+        };
+        if let Err(error) = self.check_principal_version(&tx_sender.clone().into()) {
+            output.push(red!(error));
+            return;
+        }
+
+        self.set_tx_sender(tx_sender.to_address());
+        output.push(green!(format!("tx-sender switched to {}", tx_sender)));
+    }
+
+    fn parse_and_set_contract_caller(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<_> = command.split(' ').collect();
+
+        if args.len() == 1 {
+            self.interpreter.set_contract_caller(None);
+            output.push(green!("contract-caller reset to tx-sender"));
+            return;
+        } else if args.len() != 2 {
+            output.push(red!("Usage: ::set_caller <principal>"));
+            return;
+        }
+
+        let contract_caller = match PrincipalData::parse(args[1]) {
+            Ok(principal) => principal,
+            _ => {
+                output.push(red!("Unable to parse the principal"));
+                return;
+            }
+        };
+        if let Err(error) = self.check_principal_version(&contract_caller) {
+            output.push(red!(error));
+            return;
+        }
+
+        output.push(green!(format!(
+            "contract-caller switched to {}",
+            contract_caller
+        )));
+        self.interpreter.set_contract_caller(Some(contract_caller));
+    }
+
+    fn principal_versions(&self) -> &PrincipalVersions {
+        &self.settings.analysis_settings.principal_versions
+    }
+
+    // The principal versions must be valid, and the principals of the
+    // settings must have one of them. The addresses which do not parse are
+    // reported while starting.
+    fn validate_settings(&self) -> Result<(), String> {
+        self.principal_versions().validate()?;
+        let addresses = self
+            .settings
+            .initial_deployer
+            .iter()
+            .map(|entry| &entry.address)
+            .chain(
+                self.settings
+                    .initial_accounts
+                    .iter()
+                    .map(|account| &account.address),
+            )
+            .chain(
+                self.settings
+                    .initial_contracts
+                    .iter()
+                    .filter_map(|contract| contract.deployer.as_ref()),
+            );
+        for address in addresses {
+            if let Ok(principal) = PrincipalData::parse(address) {
+                self.check_principal_version(&principal)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Principals of other chains parse, as any c32 character is a version,
+    // but they are rejected by the commands.
+    fn check_principal_version(&self, principal: &PrincipalData) -> Result<(), String> {
+        if self.principal_versions().is_known(principal.version()) {
+            Ok(())
+        } else {
+            Err(format!(
+                "The version byte {} of {} is not one of the configured principal versions",
+                principal.version(),
+                principal
+            ))
+        }
+    }
+
+    pub fn set_tx_sender(&mut self, address: String) {
+        let tx_sender =
+            PrincipalData::parse_standard_principal(&address).expect("Unable to parse address");
+        if let Err(error) = self.check_principal_version(&tx_sender.clone().into()) {
+            panic!("{}", error);
+        }
+        self.interpreter.set_tx_sender(tx_sender)
+    }
+
+    pub fn get_tx_sender(&self) -> String {
+        self.interpreter.get_tx_sender().to_address()
+    }
+
+    fn get_block_height(&mut self, output: &mut Vec<String>) {
+        let height = self.interpreter.get_block_height();
+        output.push(green!(format!("Current height: {}", height)));
+    }
+
+    fn get_account_name(&self, address: &String) -> Option<&String> {
+        for account in self.settings.initial_accounts.iter() {
+            if &account.address == address {
+                return Some(&account.name);
+            }
+        }
+        None
+    }
+
+    pub fn get_assets_maps(&self) -> BTreeMap<String, BTreeMap<String, u128>> {
+        self.interpreter.get_assets_maps()
+    }
+
+    pub fn toggle_costs(&mut self, output: &mut Vec<String>) {
+        self.show_costs = !self.show_costs;
+        output.push(green!(format!("Always show costs: {}", self.show_costs)))
+    }
+
+    fn set_int_display(&mut self, output: &mut Vec<String>, cmd: &str) {
+        let (int_display, name) = match cmd.split_whitespace().nth(1) {
+            Some("hex") => (IntDisplay::Hex, "hex"),
+            Some("dec") => (IntDisplay::Decimal, "decimal"),
+            _ => return output.push(red!("Usage: ::numformat hex|dec")),
+        };
+        self.settings.int_display = int_display;
+        output.push(green!(format!("Integers displayed in {}", name)));
+    }
+
+    pub fn encode(&mut self, output: &mut Vec<String>, cmd: &str) {
+        let snippet = match cmd.split_once(" ") {
+            Some((_, snippet)) => snippet,
+            _ => return output.push(red!("Usage: ::encode <expr>")),
+        };
+
+        let result = self.interpret(snippet.to_string(), None, false, None);
+        let value = match result {
+            Ok(result) => {
+                let mut tx_bytes = vec![];
+                let value = match result.result {
+                    Some(value) => value,
+                    None => return output.push("No value".to_string()),
+                };
+                match value.consensus_serialize(&mut tx_bytes) {
+                    Err(e) => return output.push(red!(format!("{}", e))),
+                    _ => (),
+                };
+                let mut s = String::with_capacity(2 * tx_bytes.len());
+                for byte in tx_bytes {
+                    s = format!("{}{:02x}", s, byte);
+                }
+                green!(s)
+            }
+            Err((_, Some(diagnostic), Some(e))) => red!(format!("{}: {}", diagnostic.message, e)),
+            Err((_, Some(diagnostic), None)) => red!(format!("{}", diagnostic.message)),
+            Err((_, None, Some(e))) => red!(format!("{}", e)),
+            _ => panic!("Result of interpret has neither diagnostic or error"),
+        };
+        output.push(value);
+    }
+
+    pub fn decode(&mut self, output: &mut Vec<String>, cmd: &str) {
+        let byteString = match cmd.split_once(" ") {
+            Some((_, bytes)) => bytes,
+            _ => return output.push(red!("Usage: ::decode <hex-bytes>")),
+        };
+        let tx_bytes = match decode_hex(byteString) {
+            Ok(tx_bytes) => tx_bytes,
+            Err(e) => return output.push(red!(format!("Parsing error: {}", e))),
+        };
+
+        let value = match Value::consensus_deserialize(&mut &tx_bytes[..]) {
+            Ok(value) => value,
+            Err(e) => return output.push(red!(format!("{}", e))),
+        };
+        output.push(green!(format!("{}", value)));
+    }
+
+    #[cfg(feature = "cli")]
+    pub fn get_costs(&mut self, output: &mut Vec<String>, cmd: &str) {
+        let snippet = cmd.to_string().split_off("::get_costs ".len());
+        let (mut result, cost) = match self.formatted_interpretation(snippet, None, true, None) {
+            Ok((output, result)) => (output, result.cost.clone()),
+            Err(output) => (output, None),
+        };
+
+        if let Some(cost) = cost {
+            let headers = vec!["".to_string(), "Consumed".to_string(), "Limit".to_string()];
+            let mut headers_cells = vec![];
+            for header in headers.iter() {
+                headers_cells.push(Cell::new(&header));
+            }
+            let mut table = Table::new();
+            table.add_row(Row::new(headers_cells));
+            table.add_row(Row::new(vec![
+                Cell::new("Runtime"),
+                Cell::new(&cost.total.runtime.to_string()),
+                Cell::new(&cost.limit.runtime.to_string()),
+            ]));
+            table.add_row(Row::new(vec![
+                Cell::new("Read count"),
+                Cell::new(&cost.total.read_count.to_string()),
+                Cell::new(&cost.limit.read_count.to_string()),
+            ]));
+            table.add_row(Row::new(vec![
+                Cell::new("Read length (bytes)"),
+                Cell::new(&cost.total.read_length.to_string()),
+                Cell::new(&cost.limit.read_length.to_string()),
+            ]));
+            table.add_row(Row::new(vec![
+                Cell::new("Write count"),
+                Cell::new(&cost.total.write_count.to_string()),
+                Cell::new(&cost.limit.write_count.to_string()),
+            ]));
+            table.add_row(Row::new(vec![
+                Cell::new("Write length (bytes)"),
+                Cell::new(&cost.total.write_length.to_string()),
+                Cell::new(&cost.limit.write_length.to_string()),
+            ]));
+            output.push(format!("{}", table));
+            output.push(format!(
+                "Block fullness: {:.2}%",
+                cost.total.to_scalar(&cost.limit) * 100.0
+            ));
+        }
+        output.append(&mut result);
+    }
+
+    #[cfg(feature = "cli")]
+    pub fn display_limits(&mut self, output: &mut Vec<String>) {
+        let limits = self.cost_limits();
+        let mut table = Table::new();
+        table.add_row(row!["", "Limit"]);
+        table.add_row(row!["Runtime", limits.runtime]);
+        table.add_row(row!["Read count", limits.read_count]);
+        table.add_row(row!["Read length (bytes)", limits.read_length]);
+        table.add_row(row!["Write count", limits.write_count]);
+        table.add_row(row!["Write length (bytes)", limits.write_length]);
+        output.push(format!("{}", table));
+    }
+
+    // Evaluate both expressions of a `::cost_compare` command from the same
+    // initial state and return their costs.
+    fn compare_costs(
+        &mut self,
+        output: &mut Vec<String>,
+        cmd: &str,
+    ) -> Option<(CostSynthesis, CostSynthesis)> {
+        let snippet = cmd.trim_start_matches("::cost_compare").trim();
+        let lines: Vec<String> = snippet.lines().map(|l| l.to_string()).collect();
+        let expressions = match parser::parse(snippet) {
+            Ok(expressions) if expressions.len() == 2 => expressions,
+            _ => {
+                output.push(red!("Usage: ::cost_compare <expr1> <expr2>"));
+                return None;
+            }
+        };
+        let first = extract_source(
+            &lines,
+            (
+                expressions[0].span.start_line,
+                expressions[0].span.start_column,
+            ),
+            Some((
+                expressions[1].span.start_line,
+                expressions[1].span.start_column,
+            )),
+        );
+        let second = extract_source(
+            &lines,
+            (
+                expressions[1].span.start_line,
+                expressions[1].span.start_column,
+            ),
+            None,
+        );
+
+        let mut costs = vec![];
+        for expr in [first, second].iter() {
+            match self.interpret_isolated(expr.to_string(), true) {
+                Ok(result) => match result.cost {
+                    Some(cost) => costs.push(cost),
+                    None => {
+                        output.push(red!(format!("No cost available for {}", expr)));
+                        return None;
+                    }
+                },
+                Err((_, diagnostic, error)) => {
+                    let lines: Vec<String> = expr.lines().map(|l| l.to_string()).collect();
+                    if let Some(diagnostic) = diagnostic {
+                        output.append(&mut diagnostic.output(&"<stdin>".to_string(), &lines));
+                    } else if let Some(error) = error {
+                        output.push(red!(format!("{}", error)));
+                    }
+                    return None;
+                }
+            }
+        }
+        let second = costs.pop().unwrap();
+        let first = costs.pop().unwrap();
+        Some((first, second))
+    }
+
+    #[cfg(feature = "cli")]
+    pub fn cost_compare(&mut self, output: &mut Vec<String>, cmd: &str) {
+        let (first, second) = match self.compare_costs(output, cmd) {
+            Some(costs) => costs,
+            None => return,
+        };
+
+        let mut table = Table::new();
+        table.add_row(row!["", "Expr 1", "Expr 2", "Delta"]);
+        for (label, a, b) in cost_dimensions(&first, &second).iter() {
+            let delta = *b as i128 - *a as i128;
+            let style = if delta > 0 {
+                "Fr"
+            } else if delta < 0 {
+                "Fg"
+            } else {
+                ""
+            };
+            table.add_row(Row::new(vec![
+                Cell::new(label),
+                Cell::new(&a.to_string()),
+                Cell::new(&b.to_string()),
+                Cell::new(&format_delta(delta)).style_spec(style),
+            ]));
+        }
+        output.push(format!("{}", table));
+    }
+
+    #[cfg(not(feature = "cli"))]
+    pub fn cost_compare(&mut self, output: &mut Vec<String>, cmd: &str) {
+        let (first, second) = match self.compare_costs(output, cmd) {
+            Some(costs) => costs,
+            None => return,
+        };
+
+        for (label, a, b) in cost_dimensions(&first, &second).iter() {
+            let delta = *b as i128 - *a as i128;
+            output.push(format!(
+                "{}: {} / {} ({})",
+                label,
+                a,
+                b,
+                format_delta(delta)
+            ));
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    fn get_accounts(&mut self, output: &mut Vec<String>) {
+        let accounts = self.interpreter.get_accounts();
+        if accounts.len() > 0 {
+            let tokens = self.interpreter.get_tokens();
+            let mut headers = vec!["Address".to_string()];
+            headers.append(&mut tokens.clone());
+            let mut headers_cells = vec![];
+            for header in headers.iter() {
+                headers_cells.push(Cell::new(&header));
+            }
+            let mut table = Table::new();
+            table.add_row(Row::new(headers_cells));
+            for account in accounts.iter() {
+                let mut cells = vec![];
+
+                if let Some(name) = self.get_account_name(account) {
+                    cells.push(Cell::new(&format!("{} ({})", account, name)));
+                } else {
+                    cells.push(Cell::new(account));
+                }
+
+                for token in tokens.iter() {
+                    let balance = self.interpreter.get_balance_for_account(account, token);
+                    cells.push(Cell::new(&format!("{}", balance)));
+                }
+                table.add_row(Row::new(cells));
+            }
+            output.push(format!("{}", table));
+        }
+    }
+
+    #[cfg(feature = "cli")]
+    fn get_contracts(&mut self, output: &mut Vec<String>) {
+        if self.contracts.len() > 0 {
+            let mut table = Table::new();
+            table.add_row(row!["Contract identifier", "Public functions"]);
+            let contracts = self.contracts.clone();
+            for (contract_id, methods) in contracts.iter() {
+                if !contract_id.starts_with("ST000000000000000000002AMW42H")
+                    && !contract_id.starts_with("SP000000000000000000002Q6VF78")
+                {
+                    let mut formatted_methods = vec![];
+                    for (method_name, method_args) in methods.iter() {
+                        let formatted_args = if method_args.len() == 0 {
+                            format!("")
+                        } else if method_args.len() == 1 {
+                            format!(" {}", method_args.join(" "))
+                        } else {
+                            format!("\n    {}", method_args.join("\n    "))
+                        };
+                        formatted_methods.push(format!("({}{})", method_name, formatted_args));
+                    }
+                    let formatted_spec = format!("{}", formatted_methods.join("\n"));
+                    table.add_row(Row::new(vec![
+                        Cell::new(&contract_id),
+                        Cell::new(&formatted_spec),
+                    ]));
+                }
+            }
+            output.push(format!("{}", table));
+        }
+    }
+
+    #[cfg(not(feature = "cli"))]
+    pub fn display_limits(&mut self, output: &mut Vec<String>) {
+        output.push(format!("Limit: {:?}", self.cost_limits()));
+    }
+
+    #[cfg(not(feature = "cli"))]
+    pub fn get_costs(&mut self, output: &mut Vec<String>, cmd: &str) {
+        let snippet = cmd.to_string().split_off("::get_costs ".len());
+        let (mut result, cost) = match self.formatted_interpretation(snippet, None, true, None) {
+            Ok((output, result)) => (output, result.cost.clone()),
+            Err(output) => (output, None),
+        };
+
+        if let Some(cost) = cost {
+            output.push(format!(
+                "Execution: {:?}\nLimit: {:?}\nBlock fullness: {:.2}%",
+                cost.total,
+                cost.limit,
+                cost.total.to_scalar(&cost.limit) * 100.0
+            ));
+        }
+        output.append(&mut result);
+    }
+
+    #[cfg(not(feature = "cli"))]
+    fn get_accounts(&mut self, output: &mut Vec<String>) {
+        if self.settings.initial_accounts.len() > 0 {
+            let mut initial_accounts = self.settings.initial_accounts.clone();
+            for account in initial_accounts.drain(..) {
+                output.push(format!(
+                    "{}: {} ({})",
+                    account.address, account.balance, account.name
+                ));
+            }
+        }
+    }
+
+    #[cfg(not(feature = "cli"))]
+    fn get_contracts(&mut self, output: &mut Vec<String>) {
+        for (contract_id, methods) in self.contracts.iter() {
+            if !contract_id.ends_with(".pox")
+                && !contract_id.ends_with(".bns")
+                && !contract_id.ends_with(".costs")
+            {
+                output.push(format!("{}", contract_id));
+            }
+        }
+    }
+
+    fn mint_stx(&mut self, output: &mut Vec<String>, command: &str) {
+        let args: Vec<_> = command.split(' ').collect();
+
+        if args.len() != 3 {
+            output.push(red!("Usage: ::mint_stx <recipient address> <amount>"));
+            return;
+        }
+
+        let recipient = match PrincipalData::parse(&args[1]) {
+            Ok(address) => address,
+            _ => {
+                output.push(red!("Unable to parse the address"));
+                return;
+            }
+        };
+        if let Err(error) = self.check_principal_version(&recipient) {
+            output.push(red!(error));
+            return;
+        }
+
+        let amount: u64 = match args[2].parse() {
+            Ok(recipient) => recipient,
+            _ => {
+                output.push(red!("Unable to parse the balance"));
+                return;
+            }
+        };
+
+        if let PrincipalData::Contract(ref contract_identifier) = recipient {
+            if self
+                .frozen_contracts
+                .contains(&contract_identifier.to_string())
+            {
+                output.push(red!(format!(
+                    "Contract {} is frozen and cannot be modified",
+                    contract_identifier
+                )));
+                return;
+            }
+        }
+
+        match self.interpreter.mint_stx_balance(recipient, amount) {
+            Ok(msg) => output.push(green!(msg)),
+            Err(err) => output.push(red!(err)),
+        };
+    }
+
+    fn display_functions(&self, output: &mut Vec<String>) {
+        let help_colour = Colour::Yellow;
+        let api_reference_index = self.get_api_reference_index();
+        output.push(format!(
+            "{}",
+            help_colour.paint(api_reference_index.join("\n"))
+        ));
+    }
+
+    // Evaluate an expression, then display the data-vars, map entries and
+    // balances it changed, grouped by contract and by kind of state.
+    fn watch_state(&mut self, output: &mut Vec<String>, command: &str) {
+        let snippet = command.trim_start_matches("::watch_state").trim();
+        if snippet.is_empty() {
+            output.push(red!("Usage: ::watch_state <expr>"));
+            return;
+        }
+        let before = self.interpreter.snapshot_state();
+        match self.formatted_interpretation(snippet.to_string(), None, true, None) {
+            Ok((mut result, _)) => output.append(&mut result),
+            Err(mut result) => {
+                output.append(&mut result);
+                return;
+            }
+        }
+        let after = self.interpreter.snapshot_state();
+        let changes = state_diff::diff(&before, &after);
+        if changes.is_empty() {
+            output.push(blue!("No state was changed"));
+            return;
+        }
+        output.push(blue!("State changes:"));
+        let accent_colour = Colour::Yellow.bold();
+        let mut group: Option<(&String, StateKind)> = None;
+        for change in changes.iter() {
+            if group.map(|(contract, _)| contract) != Some(&change.contract) {
+                output.push(format!("{}", accent_colour.paint(&change.contract)));
+                group = None;
+            }
+            if group.map(|(_, kind)| kind) != Some(change.kind) {
+                output.push(format!("  {}", change.kind.label()));
+            }
+            group = Some((&change.contract, change.kind));
+            let label = match (change.kind, &change.key) {
+                (StateKind::Balance, Some(account)) => format!("{} of {}", change.name, account),
+                (_, Some(key)) => format!("{} {}", change.name, key),
+                (_, None) => change.name.clone(),
+            };
+            let absent = "(absent)".to_string();
+            output.push(format!(
+                "    {}: {} → {}",
+                label,
+                change.old.as_ref().unwrap_or(&absent),
+                change.new.as_ref().unwrap_or(&absent)
+            ));
+        }
+    }
+
+    fn display_natives(&self, output: &mut Vec<String>, command: &str) {
+        let help_colour = Colour::Yellow;
+        let help_accent_colour = Colour::Yellow.bold();
+        let page = match command.split_whitespace().nth(1) {
+            Some(page) => match page.parse::<usize>() {
+                Ok(page) if page > 0 => page,
+                _ => {
+                    output.push(red!("Usage: ::natives [page]"));
+                    return;
+                }
+            },
+            None => 1,
+        };
+        let entries = natives::catalog();
+        let pages = entries.len().div_ceil(natives::PAGE_SIZE);
+        if page > pages {
+            output.push(red!(format!(
+                "Page {} does not exist, there are {} pages",
+                page, pages
+            )));
+            return;
+        }
+        output.push(blue!(format!(
+            "Native functions of {}, page {} of {}",
+            self.interpreter.get_analysis_settings().clarity_version,
+            page,
+            pages
+        )));
+        let mut category = None;
+        for entry in entries
+            .iter()
+            .skip((page - 1) * natives::PAGE_SIZE)
+            .take(natives::PAGE_SIZE)
+        {
+            if category != Some(entry.category) {
+                category = Some(entry.category);
+                output.push(format!("{}", help_accent_colour.paint(entry.category)));
+            }
+            output.push(format!(
+                "{}",
+                help_colour.paint(format!("  {}", entry.signature))
+            ));
+            output.push(format!("    {}", entry.example));
+        }
+        if page < pages {
+            output.push(format!("Use ::natives {} for the next page", page + 1));
+        }
+    }
+
+    fn display_doc(&self, output: &mut Vec<String>, command: &str) {
+        let help_colour = Colour::Yellow;
+        let help_accent_colour = Colour::Yellow.bold();
+        let keyword = {
+            let mut s = command.to_string();
+            s = s.replace("::describe_function", "");
+            s = s.replace(" ", "");
+            s
+        };
+        let result = match self.lookup_api_reference(&keyword) {
+            Some(doc) => format!("{}", help_colour.paint(doc)),
+            None => format!("{}", help_colour.paint("Function unknown")),
+        };
+        output.push(result);
+    }
+}
+
+// Retrieve the source code from the `start` position up to (but excluding) the
+// `end` position, or up to the end of the source if there is no `end`.
+// Positions are 1-based (line, column) pairs, matching the AST spans.
+fn extract_source(lines: &[String], start: (u32, u32), end: Option<(u32, u32)>) -> String {
+    let first_line = start.0.saturating_sub(1) as usize;
+    let last_line = match end {
+        Some((line, _)) => line.saturating_sub(1) as usize,
+        None => lines.len().saturating_sub(1),
+    };
+    let mut source = vec![];
+    for (i, line) in lines
+        .iter()
+        .enumerate()
+        .take(last_line + 1)
+        .skip(first_line)
+    {
+        let from = if i == first_line {
+            start.1.saturating_sub(1) as usize
+        } else {
+            0
+        };
+        let to = match end {
+            Some((_, column)) if i == last_line => {
+                (column.saturating_sub(1) as usize).min(line.len())
+            }
+            _ => line.len(),
+        };
+        source.push(line.get(from..to).unwrap_or(""));
+    }
+    source.join("\n").trim().to_string()
+}
+
+// Find the top-level form left open at the end of the source, by an unclosed
+// parenthesis or string, and return its byte offset and span.
+fn dangling_form(source: &str) -> Option<(usize, Span)> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut in_comment = false;
+    let mut start = None;
+    let mut end = (1, 1);
+    let (mut line, mut column) = (1, 0);
+    for (offset, c) in source.char_indices() {
+        if c == '\n' {
+            line += 1;
+            column = 0;
+            in_comment = false;
+            continue;
+        }
+        column += 1;
+        if in_comment || (c == ';' && !in_string) {
+            in_comment = true;
+            continue;
+        }
+        if !c.is_whitespace() {
+            end = (line, column);
+        }
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '(' || c == '"' {
+            if depth == 0 {
+                start = Some((offset, line, column));
+            }
+            if c == '(' {
+                depth += 1;
+            } else {
+                in_string = true;
+            }
+        } else if c == ')' && depth > 0 {
+            // An unbalanced closing parenthesis is left to the parser
+            depth -= 1;
+        }
+    }
+    if depth == 0 && !in_string {
+        return None;
+    }
+    start.map(|(offset, start_line, start_column)| {
+        (
+            offset,
+            Span {
+                start_line,
+                start_column,
+                end_line: end.0,
+                end_column: end.1,
+            },
+        )
+    })
+}
+
+fn dump_expression(expr: &SymbolicExpression, depth: usize, lines: &mut Vec<String>) {
+    let (kind, content) = match &expr.expr {
+        SymbolicExpressionType::AtomValue(value) => ("atom-value", value.to_string()),
+        SymbolicExpressionType::Atom(name) => ("atom", name.to_string()),
+        SymbolicExpressionType::List(list) => ("list", format!("({} elements)", list.len())),
+        SymbolicExpressionType::LiteralValue(value) => ("literal", value.to_string()),
+        SymbolicExpressionType::Field(field) => ("field", field.to_string()),
+        SymbolicExpressionType::TraitReference(name, definition) => {
+            let trait_identifier = match definition {
+                TraitDefinition::Defined(identifier) => identifier,
+                TraitDefinition::Imported(identifier) => identifier,
+            };
+            ("trait-reference", format!("{} {}", name, trait_identifier))
+        }
+    };
+    lines.push(format!(
+        "{}#{} {} {}:{}-{}:{} {}",
+        "  ".repeat(depth),
+        expr.id,
+        kind,
+        expr.span.start_line,
+        expr.span.start_column,
+        expr.span.end_line,
+        expr.span.end_column,
+        content
+    ));
+    for child in expr.match_list().unwrap_or_default() {
+        dump_expression(child, depth + 1, lines);
+    }
+}
+
+fn cost_dimensions(first: &CostSynthesis, second: &CostSynthesis) -> [(&'static str, u64, u64); 5] {
+    [
+        ("Runtime", first.total.runtime, second.total.runtime),
+        (
+            "Read count",
+            first.total.read_count,
+            second.total.read_count,
+        ),
+        (
+            "Read length (bytes)",
+            first.total.read_length,
+            second.total.read_length,
+        ),
+        (
+            "Write count",
+            first.total.write_count,
+            second.total.write_count,
+        ),
+        (
+            "Write length (bytes)",
+            first.total.write_length,
+            second.total.write_length,
+        ),
+    ]
+}
+
+fn format_delta(delta: i128) -> String {
+    if delta > 0 {
+        format!("+{}", delta)
+    } else {
+        format!("{}", delta)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum DecodeHexError {
+    ParseError(ParseIntError),
+    OddLength,
+}
+
+impl fmt::Display for DecodeHexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeHexError::ParseError(e) => write!(f, "{}", e),
+            DecodeHexError::OddLength => write!(f, "odd number of hex digits"),
+        }
+    }
+}
+
+impl From<ParseIntError> for DecodeHexError {
+    fn from(err: ParseIntError) -> Self {
+        DecodeHexError::ParseError(err)
+    }
+}
+
+fn decode_hex(byteString: &str) -> Result<Vec<u8>, DecodeHexError> {
+    let byteStringFiltered: String = byteString
+        .strip_prefix("0x")
+        .unwrap_or(byteString)
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    if byteStringFiltered.len() % 2 != 0 {
+        return Err(DecodeHexError::OddLength);
+    }
+    let result: Result<Vec<u8>, ParseIntError> = (0..byteStringFiltered.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&byteStringFiltered[i..i + 2], 16))
+        .collect();
+    match result {
+        Ok(result) => Ok(result),
+        Err(e) => Err(DecodeHexError::ParseError(e)),
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+struct Contract {
+    source: String,
+    publish_height: u32,
+}
+
+async fn fetch_contract(request_url: String) -> Contract {
+    let response: Contract = reqwest::get(&request_url)
+        .await
+        .expect("Unable to retrieve contract")
+        .json()
+        .await
+        .expect("Unable to parse contract");
+    return response;
+}
+
+fn build_api_reference() -> HashMap<String, String> {
+    let mut api_reference = HashMap::new();
+    for func in NativeFunctions::ALL.iter() {
+        let api = make_api_reference(&func);
+        let description = {
+            let mut s = api.description.to_string();
+            s = s.replace("\n", " ");
+            s
+        };
+        let doc = format!(
+            "Usage\n{}\n\nDescription\n{}\n\nExamples\n{}",
+            api.signature, description, api.example
+        );
+        api_reference.insert(api.name, doc);
+    }
+
+    for func in DefineFunctions::ALL.iter() {
+        let api = make_define_reference(&func);
+        let description = {
+            let mut s = api.description.to_string();
+            s = s.replace("\n", " ");
+            s
+        };
+        let doc = format!(
+            "Usage\n{}\n\nDescription\n{}\n\nExamples\n{}",
+            api.signature, description, api.example
+        );
+        api_reference.insert(api.name, doc);
+    }
+
+    for func in NativeVariables::ALL.iter() {
+        let api = make_keyword_reference(&func);
+        let description = {
+            let mut s = api.description.to_string();
+            s = s.replace("\n", " ");
+            s
+        };
+        let doc = format!("Description\n{}\n\nExamples\n{}", description, api.example);
+        api_reference.insert(api.name.to_string(), doc);
+    }
+    api_reference
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repl::interpreter::BLOCK_LIMIT_MAINNET;
+    use crate::repl::settings::Account;
+    use std::time::Duration;
+
+    #[test]
+    fn encode_simple() {
+        let mut session = Session::new(SessionSettings::default());
+        let mut output: Vec<String> = Vec::new();
+        session.encode(&mut output, "::encode 42");
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0], green!("000000000000000000000000000000002a"));
+    }
+
+    #[test]
+    fn encode_map() {
+        let mut session = Session::new(SessionSettings::default());
+        let mut output: Vec<String> = Vec::new();
+        session.encode(&mut output, "::encode { foo: \"hello\", bar: false }");
+        assert_eq!(output.len(), 1);
+        assert_eq!(
+            output[0],
+            green!("0c00000002036261720403666f6f0d0000000568656c6c6f")
+        );
+    }
+
+    #[test]
+    fn encode_error() {
+        let mut session = Session::new(SessionSettings::default());
+        let mut output: Vec<String> = Vec::new();
+        session.encode(&mut output, "::encode { foo false }");
+        assert_eq!(output.len(), 1);
+        assert_eq!(
+            output[0],
+            red!("Tuple literal construction expects a colon at index 1")
+        );
+
+        session.encode(&mut output, "::encode (foo 1)");
+        assert_eq!(output.len(), 2);
+        assert_eq!(output[1], red!("use of unresolved function 'foo'"));
+    }
+
+    #[test]
+    fn decode_simple() {
+        let mut session = Session::new(SessionSettings::default());
+        let mut output: Vec<String> = Vec::new();
+        session.decode(&mut output, "::decode 0000000000000000 0000000000000000 2a");
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0], green!("42"));
+    }
+
+    #[test]
+    fn decode_map() {
+        let mut session = Session::new(SessionSettings::default());
+        let mut output: Vec<String> = Vec::new();
+        session.decode(
+            &mut output,
+            "::decode 0x0c00000002036261720403666f6f0d0000000568656c6c6f",
+        );
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0], green!("{bar: false, foo: \"hello\"}"));
+    }
+
+    #[test]
+    fn decode_error() {
+        let mut session = Session::new(SessionSettings::default());
+        let mut output: Vec<String> = Vec::new();
+        session.decode(&mut output, "::decode 42");
+        assert_eq!(output.len(), 1);
+        assert_eq!(
+            output[0],
+            red!("Failed to decode clarity value: Deserialization error: Bad type prefix")
+        );
+
+        session.decode(&mut output, "::decode 4g");
+        assert_eq!(output.len(), 2);
+        assert_eq!(
+            output[1],
+            red!("Parsing error: invalid digit found in string")
+        );
+    }
+
+    #[test]
+    fn test_mode_assert_eq() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        settings.test_mode = true;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+
+        let output = session.handle_command("(assert-eq (+ u1 u2) u3)");
+        assert_eq!(output, vec![green!("assertion passed: u3")]);
+
+        let output = session.handle_command("(assert-eq u1 u2)\n(assert-eq u3 u3)");
+        assert_eq!(
+            output,
+            vec![
+                red!("assertion failed: expected u1, got u2"),
+                red!("Test script aborted")
+            ]
+        );
+
+        // Values of different types are not equal
+        let output = session.handle_command("(assert-eq u1 1)");
+        assert_eq!(
+            output,
+            vec![
+                red!("assertion failed: expected u1, got 1"),
+                red!("Test script aborted")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mode_script() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        settings.test_mode = true;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+
+        let output = session.handle_command(
+            "(define-read-only (double (n uint)) (* n u2))
+(print-and-continue (contract-call? .contract-2 double u2))
+(assert-eq (contract-call? .contract-2 double u3) u6)",
+        );
+        assert_eq!(output.len(), 3);
+        assert_eq!(output[1], green!("u4"));
+        assert_eq!(output[2], green!("assertion passed: u6"));
+    }
+
+    #[test]
+    fn test_mode_blocks() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        settings.test_mode = true;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+
+        let output = session.handle_command(
+            "(define-data-var count uint u0)
+(define-public (increment)
+    (begin (var-set count (+ (var-get count) u1)) (ok (var-get count))))
+(define-read-only (get-count) (var-get count))
+(test \"increment\"
+    (contract-call? .contract-2 increment)
+    (assert (is-eq (contract-call? .contract-2 get-count) u1)))
+(test \"isolated\"
+    (assert-eq u1 (contract-call? .contract-2 get-count)))
+(test \"reset\"
+    (assert (is-eq (contract-call? .contract-2 get-count) u0)))",
+        );
+        assert_eq!(
+            output[2..],
+            [
+                green!("assertion passed: (is-eq (contract-call? .contract-2 get-count) u1)"),
+                green!("test increment passed"),
+                red!("assertion failed: expected u1, got u0"),
+                red!("test isolated failed"),
+                green!("assertion passed: (is-eq (contract-call? .contract-2 get-count) u0)"),
+                green!("test reset passed"),
+                red!("test result: 2 passed, 1 failed"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_helpers_require_test_mode() {
+        let mut session = Session::new(SessionSettings::default());
+        let output = session.handle_command("(assert-eq u1 u1)");
+        assert_eq!(
+            output[0],
+            format!(
+                "<stdin>:1:1: {}: use of unresolved function 'assert-eq'",
+                red!("error")
+            )
+        );
+    }
+
+    #[test]
+    fn test_mode_nested_helpers() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        settings.test_mode = true;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+
+        let output = session.handle_command("(begin (assert-eq (+ u1 u2) u3) (assert true) u4)");
+        assert_eq!(output, vec![green!("u4")]);
+
+        let output = session.handle_command("(begin (print-and-continue u1) (assert-eq u1 u2) u3)");
+        assert!(output[0].starts_with("Runtime error: assertion failed: expected u1, got u2"));
+    }
+
+    #[test]
+    fn test_mode_helpers_in_functions() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        settings.test_mode = true;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+
+        let output = session.handle_command(
+            "(define-read-only (check-double (n uint) (expected uint))
+    (assert-eq (* n u2) expected))",
+        );
+        assert_eq!(output.len(), 1);
+        let output = session.handle_command("(contract-call? .contract-2 check-double u2 u4)");
+        assert_eq!(output, vec![green!("true")]);
+        let output = session.handle_command("(contract-call? .contract-2 check-double u2 u5)");
+        assert!(output[0].contains("expected u4, got u5"));
+    }
+
+    #[test]
+    fn nested_helpers_require_test_mode() {
+        let mut session = Session::new(SessionSettings::default());
+        let output = session.handle_command("(begin (assert-eq u1 u1) u2)");
+        assert_eq!(
+            output[0],
+            format!(
+                "<stdin>:1:2: {}: use of unresolved function 'assert-eq'",
+                red!("error")
+            )
+        );
+    }
+
+    #[test]
+    fn test_blocks_require_test_mode() {
+        let mut session = Session::new(SessionSettings::default());
+        let output = session.handle_command("(test \"name\" (assert true))");
+        assert_eq!(
+            output[0],
+            format!(
+                "<stdin>:1:1: {}: use of unresolved function 'test'",
+                red!("error")
+            )
+        );
+    }
+
+    #[test]
+    fn record_and_replay_call() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+        let contract = "(define-data-var count uint u0)
+(define-public (incr (n uint))
+    (begin
+        (var-set count (+ (var-get count) n))
+        (ok (var-get count))))"
+            .to_string();
+        session
+            .formatted_interpretation(contract, Some("counter".to_string()), false, None)
+            .unwrap();
+
+        assert!(session.replay_recorded_call().is_err());
+        session.record_call(".counter", "incr").unwrap();
+        session.handle_command("(contract-call? .counter incr u5)");
+        assert_eq!(
+            session.handle_command("(contract-call? .counter incr u5)")[0],
+            green!("(ok u10)")
+        );
+
+        let call = session.recorded_call().unwrap();
+        assert_eq!(call.args, vec![Value::UInt(5)]);
+        assert_eq!(
+            call.sender,
+            Some(session.interpreter.get_tx_sender().into())
+        );
+
+        // The replay reads the state seen by the first call, not the current one
+        assert_eq!(
+            session.replay_recorded_call(),
+            Ok(Value::okay(Value::UInt(5)).unwrap())
+        );
+        assert_eq!(
+            session.handle_command("(contract-call? .counter incr u1)")[0],
+            green!("(ok u11)")
+        );
+    }
+
+    #[test]
+    fn replay_absent_reads() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+        let contract = "(define-data-var last uint u0)
+(define-map seen uint bool)
+(define-public (mark (n uint))
+    (begin
+        (asserts! (is-none (map-get? seen n)) (err u1))
+        (map-set seen n true)
+        (var-set last n)
+        (ok (var-get last))))"
+            .to_string();
+        session
+            .formatted_interpretation(contract, Some("marker".to_string()), false, None)
+            .unwrap();
+
+        session.record_call(".marker", "mark").unwrap();
+        assert_eq!(
+            session.handle_command("(contract-call? .marker mark u1)")[0],
+            green!("(ok u1)")
+        );
+
+        let call = session.recorded_call().unwrap();
+        assert!(call.reads.iter().any(|(_, value)| value.is_none()));
+        // `last` is written before being read
+        assert!(!call.reads.iter().any(|(key, _)| key.ends_with("::last")));
+
+        // The entry set by the first call is absent again for the replay
+        assert_eq!(
+            session.replay_recorded_call(),
+            Ok(Value::okay(Value::UInt(1)).unwrap())
+        );
+        assert_eq!(
+            session.handle_command("(contract-call? .marker mark u1)")[0],
+            green!("(err u1)")
+        );
+    }
+
+    #[test]
+    fn eval_as_caller() {
+        let mut session = Session::new(SessionSettings::default());
+        let sender = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5";
+        let caller = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5.proxy";
+        let result = session
+            .eval_as_caller(
+                caller,
+                sender,
+                "{ caller: contract-caller, sender: tx-sender }".to_string(),
+            )
+            .unwrap();
+        assert_eq!(
+            result.result.unwrap().to_string(),
+            format!("{{caller: {}, sender: {}}}", caller, sender)
+        );
+
+        // Both are restored afterwards
+        let result = session
+            .interpret("contract-caller".to_string(), None, false, None)
+            .unwrap();
+        assert_eq!(result.result.unwrap().to_string(), session.get_tx_sender());
+
+        assert!(session
+            .eval_as_caller("not-a-principal", sender, "tx-sender".to_string())
+            .is_err());
+        assert!(session
+            .eval_as_caller(caller, caller, "tx-sender".to_string())
+            .is_err());
+    }
+
+    #[test]
+    fn diagnostic_callback() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["check_checker".to_string()];
+        let reported = Arc::new(Mutex::new(vec![]));
+        let callback_reported = reported.clone();
+        let mut session = Session::new(settings).with_diagnostic_callback(move |diagnostic| {
+            callback_reported
+                .lock()
+                .unwrap()
+                .push(diagnostic.message.clone());
+        });
+        let snippet = "(define-data-var x uint u0)
+(define-public (set-x (value uint))
+    (ok (var-set x value)))"
+            .to_string();
+        let result = session.interpret(snippet, None, false, None).unwrap();
+        assert_eq!(result.diagnostics.len(), 2);
+        assert_eq!(
+            *reported.lock().unwrap(),
+            vec![
+                "use of potentially unchecked data".to_string(),
+                "source of untrusted input here".to_string()
+            ]
+        );
+
+        // A panicking callback does not abort the analysis
+        let mut session = Session::new(SessionSettings::default())
+            .with_diagnostic_callback(|_| panic!("callback failed"));
+        let snippet = "(define-read-only (before) (< \"a\" \"b\"))".to_string();
+        match session.interpret(snippet, None, false, None) {
+            Err((_, Some(diagnostic), _)) => {
+                assert!(diagnostic.message.contains("cannot compare"))
+            }
+            _ => panic!("Expected failed interpretation"),
+        }
+    }
+
+    #[test]
+    fn check_project_parallel() {
+        let mut session = Session::new(SessionSettings::default());
+        let deployer = session.get_tx_sender();
+        let contract = |name: &str, code: &str| InitialContract {
+            code: code.to_string(),
+            name: Some(name.to_string()),
+            path: format!("contracts/{}.clar", name),
+            deployer: Some(deployer.clone()),
+        };
+        let contracts = vec![
+            contract(
+                "exchange",
+                &format!(
+                    "(define-public (swap (amount uint)) (contract-call? '{}.token transfer amount))",
+                    deployer
+                ),
+            ),
+            contract("token", "(define-public (transfer (amount uint)) (ok amount))"),
+            contract("counter", "(define-read-only (get-count) (+ 1 u1))"),
+        ];
+        let diagnostics = session.check_project_parallel(&contracts).unwrap();
+        assert_eq!(
+            diagnostics.keys().cloned().collect::<Vec<String>>(),
+            vec![
+                format!("{}.counter", deployer),
+                format!("{}.exchange", deployer),
+                format!("{}.token", deployer),
+            ]
+        );
+        // The token is analyzed before the exchange which calls it
+        assert!(diagnostics[&format!("{}.exchange", deployer)].is_empty());
+        assert_eq!(diagnostics[&format!("{}.counter", deployer)].len(), 1);
+        // Nothing was deployed
+        assert!(session.contracts.is_empty());
+
+        let contracts = vec![
+            contract(
+                "ping",
+                &format!(
+                    "(define-public (ping) (contract-call? '{}.pong pong))",
+                    deployer
+                ),
+            ),
+            contract(
+                "pong",
+                &format!(
+                    "(define-public (pong) (contract-call? '{}.ping ping))",
+                    deployer
+                ),
+            ),
+        ];
+        assert_eq!(
+            session.check_project_parallel(&contracts),
+            Err(format!(
+                "Circular dependency between contracts: {}.ping, {}.pong",
+                deployer, deployer
+            ))
+        );
+    }
+
+    #[test]
+    fn check_across_versions() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "(define-read-only (newer (a (buff 4)) (b (buff 4))) (> a b))".to_string();
+        let versions = [ClarityVersion::Clarity1, ClarityVersion::Clarity2];
+        let diagnostics = session.check_across_versions(snippet, &versions);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics[&ClarityVersion::Clarity1][0].message,
+            "'>' cannot compare values of type 'buff' in Clarity 1, only int and uint can be ordered"
+        );
+        // Clarity 2 orders buffers, but the interpreter implements Clarity 1
+        assert!(!diagnostics[&ClarityVersion::Clarity2][0]
+            .message
+            .contains("cannot compare"));
+
+        // Nothing was deployed, and the version is restored
+        assert!(session.contracts.is_empty());
+        assert_eq!(
+            session.interpreter.get_analysis_settings().clarity_version,
+            ClarityVersion::Clarity1
+        );
+    }
+
+    #[test]
+    fn check_partial() {
+        let mut session = Session::new(SessionSettings::default());
+        let prefix = "(define-data-var count uint u0)
+(define-read-only (broken) (+ 1 u1))
+(define-public (bump)
+    (ok (var-set count \"(\" ; )
+";
+        let diagnostics = session.check_partial(prefix);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].level, Level::Error);
+        assert_eq!(diagnostics[0].spans[0].start_line, 2);
+        assert_eq!(diagnostics[1].level, Level::Note);
+        assert_eq!(diagnostics[1].message, "this expression is incomplete");
+        assert_eq!(
+            diagnostics[1].spans[0],
+            Span {
+                start_line: 3,
+                start_column: 1,
+                end_line: 4,
+                end_column: 26,
+            }
+        );
+
+        // Complete snippets are checked as a whole, and nothing is deployed
+        assert!(session
+            .check_partial("(define-constant total u1) ;; (")
+            .is_empty());
+        assert_eq!(session.check_partial("(define-constant \"").len(), 1);
+        assert!(session.contracts.is_empty());
+    }
+
+    #[test]
+    fn contract_abi() {
+        let mut session = Session::new(SessionSettings::default());
+        let contract = "(define-fungible-token token)
+(define-map balances principal { amount: uint, memo: (optional (buff 34)) })
+(define-data-var owner principal tx-sender)
+(define-public (transfer (amount uint) (to principal))
+    (ok amount))
+(define-read-only (get-names) (list \"a\" \"b\"))"
+            .to_string();
+        session
+            .interpret(contract, Some("bank".to_string()), false, None)
+            .unwrap();
+        let abi = session.contract_abi(".bank").unwrap();
+        assert_eq!(abi["abi_version"], 1);
+        assert_eq!(
+            abi["contract_id"],
+            format!("{}.bank", session.get_tx_sender())
+        );
+        assert_eq!(
+            abi["functions"][0],
+            json!({
+                "name": "transfer",
+                "access": "public",
+                "args": [
+                    { "name": "amount", "type": { "kind": "uint" } },
+                    { "name": "to", "type": { "kind": "principal" } },
+                ],
+                "returns": {
+                    "kind": "response",
+                    "ok": { "kind": "uint" },
+                    "error": { "kind": "none" },
+                },
+            })
+        );
+        assert_eq!(
+            abi["functions"][1]["returns"],
+            json!({
+                "kind": "list",
+                "max_length": 2,
+                "element": { "kind": "string-ascii", "length": 1 },
+            })
+        );
+        assert_eq!(
+            abi["maps"][0]["value"]["fields"][1],
+            json!({
+                "name": "memo",
+                "type": { "kind": "optional", "inner": { "kind": "buffer", "length": 34 } },
+            })
+        );
+        assert_eq!(abi["variables"][0]["access"], "variable");
+        assert_eq!(abi["fungible_tokens"][0]["name"], "token");
+
+        assert!(session.contract_abi(".missing").is_err());
+    }
+
+    #[test]
+    fn definition_span() {
+        let mut session = Session::new(SessionSettings::default());
+        let contract = "(define-constant owner tx-sender)
+(define-map balances principal uint)
+(define-public (transfer (amount uint) (to principal))
+    (ok (map-set balances to amount)))"
+            .to_string();
+        session
+            .interpret(contract, Some("bank".to_string()), false, None)
+            .unwrap();
+        assert_eq!(
+            session.definition_span(".bank", "transfer"),
+            Some(Span {
+                start_line: 3,
+                start_column: 17,
+                end_line: 3,
+                end_column: 24,
+            })
+        );
+        assert_eq!(
+            session.definition_span(".bank", "balances"),
+            Some(Span {
+                start_line: 2,
+                start_column: 13,
+                end_line: 2,
+                end_column: 20,
+            })
+        );
+        assert_eq!(
+            session
+                .definition_span(".bank", "owner")
+                .unwrap()
+                .start_line,
+            1
+        );
+        assert_eq!(session.definition_span(".bank", "map-set"), None);
+        assert_eq!(session.definition_span(".bank", "amount"), None);
+        assert_eq!(session.definition_span(".missing", "transfer"), None);
+    }
+
+    #[test]
+    fn eval_with_failing_postcondition() {
+        let mut session = Session::new(SessionSettings::default());
+        let contract = "(define-data-var counter uint u0)
+(define-public (increment)
+    (begin
+        (print \"incremented\")
+        (var-set counter (+ (var-get counter) u1))
+        (ok (var-get counter))))
+(define-read-only (get-counter) (var-get counter))"
+            .to_string();
+        session
+            .interpret(contract, Some("counter".to_string()), false, None)
+            .unwrap();
+
+        let result = session
+            .eval_with_failing_postcondition("(contract-call? .counter increment)".to_string())
+            .unwrap();
+        assert!(result.aborted_by_post_condition);
+        assert_eq!(result.result, Some(Value::okay(Value::UInt(1)).unwrap()));
+        assert_eq!(result.contract_events.len(), 1);
+        assert!(session.events_for(".counter").is_empty());
+
+        let result = session
+            .interpret(
+                "(contract-call? .counter get-counter)".to_string(),
+                None,
+                false,
+                None,
+            )
+            .unwrap();
+        assert!(!result.aborted_by_post_condition);
+        assert_eq!(result.result, Some(Value::UInt(0)));
+    }
+
+    #[test]
+    fn int_display() {
+        let mut settings = SessionSettings::default();
+        settings.int_display = IntDisplay::Hex;
+        let mut session = Session::new(settings);
+        let snippet = "{a: u255, b: (list -16 16), c: (some (ok 0x01))}".to_string();
+        let (output, _) = session
+            .formatted_interpretation(snippet.clone(), None, false, None)
+            .unwrap();
+        assert_eq!(
+            output[0],
+            green!("{a: u0xff, b: [-0x10, 0x10], c: (some (ok 0x01))}")
+        );
+
+        let output = session.handle_command("::numformat dec");
+        assert_eq!(output[0], green!("Integers displayed in decimal"));
+        let (output, _) = session
+            .formatted_interpretation(snippet, None, false, None)
+            .unwrap();
+        assert_eq!(
+            output[0],
+            green!("{a: u255, b: [-16, 16], c: (some (ok 0x01))}")
+        );
+
+        let output = session.handle_command("::numformat oct");
+        assert_eq!(output[0], red!("Usage: ::numformat hex|dec"));
+    }
+
+    #[test]
+    fn eval_as_contract() {
+        let mut session = Session::new(SessionSettings::default());
+        session
+            .interpret(
+                "(define-read-only (caller) contract-caller)".to_string(),
+                Some("vault".to_string()),
+                false,
+                None,
+            )
+            .unwrap();
+        let vault = format!("{}.vault", session.get_tx_sender());
+        let result = session
+            .eval_as_contract(
+                ".vault",
+                "{ caller: contract-caller, sender: tx-sender }".to_string(),
+            )
+            .unwrap();
+        assert_eq!(
+            result.result.unwrap().to_string(),
+            format!("{{caller: {}, sender: {}}}", vault, vault)
+        );
+
+        // Both are restored afterwards
+        let result = session
+            .interpret("tx-sender".to_string(), None, false, None)
+            .unwrap();
+        assert_eq!(result.result.unwrap().to_string(), session.get_tx_sender());
+
+        match session.eval_as_contract(".missing", "tx-sender".to_string()) {
+            Err((message, _, _)) => assert_eq!(
+                message,
+                format!(
+                    "Contract {}.missing is not deployed",
+                    session.get_tx_sender()
+                )
+            ),
+            _ => panic!("Expected an undeployed contract to be rejected"),
+        }
+    }
+
+    #[test]
+    fn set_caller() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+        let caller = "ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5.proxy";
+        assert_eq!(
+            session.handle_command(&format!("::set_caller {}", caller)),
+            vec![green!(format!("contract-caller switched to {}", caller))]
+        );
+        assert_eq!(
+            session.handle_command("contract-caller"),
+            vec![green!(caller)]
+        );
+        assert_eq!(
+            session.handle_command("::set_caller foo"),
+            vec![red!("Unable to parse the principal")]
+        );
+        session.handle_command("::set_caller");
+        assert_eq!(
+            session.handle_command("contract-caller"),
+            vec![green!(session.get_tx_sender())]
+        );
+    }
+
+    #[test]
+    fn diagnostics_to_sarif() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["is_eq_checker".to_string()];
+        let mut session = Session::new(settings);
+        let snippet = "(define-read-only (same (a uint) (b uint) (c uint)) (is-eq a b c))";
+        session
+            .formatted_interpretation(snippet.to_string(), Some("same".to_string()), false, None)
+            .unwrap();
+
+        let log = session.diagnostics_to_sarif(".same");
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["level"], "note");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(
+            location["artifactLocation"]["uri"],
+            format!("{}.same", session.get_tx_sender())
+        );
+        assert_eq!(location["region"]["startColumn"], 53);
+
+        let log = session.diagnostics_to_sarif(".unknown");
+        assert_eq!(log["runs"][0]["results"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn events_for() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+        let callee = "(define-public (ping) (begin (print \"pong\") (ok true)))";
+        session
+            .formatted_interpretation(callee.to_string(), Some("callee".to_string()), false, None)
+            .unwrap();
+        let caller = "(define-public (call)
+    (begin
+        (print \"calling\")
+        (contract-call? .callee ping)))";
+        session
+            .formatted_interpretation(caller.to_string(), Some("caller".to_string()), false, None)
+            .unwrap();
+        session.handle_command("(contract-call? .caller call)");
+
+        let events = session.events_for(".callee");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event["contract_event"]["value"], "\"pong\"");
+        assert_eq!(session.events_for(".caller").len(), 1);
+        assert_eq!(session.events_for(".unknown").len(), 0);
+    }
+
+    #[test]
+    fn smoke_test() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+        let contract = "(define-data-var total uint u0)
+(define-public (deposit (amount uint) (memo (optional (buff 34))))
+    (begin
+        (asserts! (> amount u0) (err u1))
+        (ok (var-set total (+ (var-get total) amount)))))
+(define-public (withdraw (amount uint))
+    (ok (var-set total (- (var-get total) amount u1))))
+(define-read-only (get-total) (var-get total))
+(define-private (helper) true)"
+            .to_string();
+        session
+            .formatted_interpretation(contract, Some("vault".to_string()), false, None)
+            .unwrap();
+
+        let report = session.smoke_test(".vault").unwrap();
+        let outcomes: Vec<_> = report
+            .results
+            .iter()
+            .map(|result| (result.function.as_str(), &result.outcome))
+            .collect();
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(
+            outcomes[0],
+            ("deposit", &SmokeOutcome::Errored(Value::UInt(1)))
+        );
+        assert_eq!(
+            outcomes[1],
+            ("get-total", &SmokeOutcome::Succeeded(Value::UInt(0)))
+        );
+        assert_eq!(outcomes[2].0, "withdraw");
+        assert!(matches!(outcomes[2].1, SmokeOutcome::Aborted(_)));
+        assert_eq!(report.results[0].args, vec![Value::UInt(0), Value::none()]);
+        assert!(!report.succeeded());
+        assert!(session.smoke_test(".unknown").is_err());
+    }
+
+    #[test]
+    fn reachable_branches() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+        let contract = "(define-map limits principal uint)
+(define-public (withdraw (amount uint))
+    (begin
+        (asserts! (> amount u0) (err u1))
+        (match (map-get? limits tx-sender)
+            limit (ok (if (> amount limit) limit amount))
+            (ok u0))))"
+            .to_string();
+        session
+            .formatted_interpretation(contract, Some("vault".to_string()), false, None)
+            .unwrap();
+
+        let report = session
+            .reachable_branches(".vault", "withdraw", &[Value::UInt(5)])
+            .unwrap();
+        assert_eq!(report.result, Ok(Value::okay(Value::UInt(0)).unwrap()));
+        let points: Vec<_> = report
+            .points
+            .iter()
+            .map(|point| {
+                let taken: Vec<_> = point
+                    .branches
+                    .iter()
+                    .map(|branch| (branch.label, branch.taken))
+                    .collect();
+                (point.kind, point.span.start_line, taken)
+            })
+            .collect();
+        assert_eq!(
+            points,
+            vec![
+                ("asserts!", 4, vec![("pass", true), ("fail", false)]),
+                ("match", 5, vec![("some", false), ("none", true)]),
+                ("if", 6, vec![("then", false), ("else", false)]),
+            ]
+        );
+        let source = report.highlighted_source();
+        assert_eq!(source.len(), 6);
+        assert_eq!(source[0], "(define-public (withdraw (amount uint))");
+        assert_eq!(
+            source[2],
+            format!(
+                "        (asserts! {} {})",
+                green!("(> amount u0)"),
+                red!("(err u1)")
+            )
+        );
+
+        let report = session
+            .reachable_branches(".vault", "withdraw", &[Value::UInt(0)])
+            .unwrap();
+        assert_eq!(report.result, Ok(Value::error(Value::UInt(1)).unwrap()));
+        assert!(!report.points[0].branches[0].taken);
+        assert!(report.points[0].branches[1].taken);
+        assert!(report.points[1].branches.iter().all(|branch| !branch.taken));
+        assert!(session
+            .reachable_branches(".vault", "deposit", &[])
+            .is_err());
+    }
+
+    #[test]
+    fn max_value_allocation() {
+        let mut settings = SessionSettings::default();
+        settings.max_value_allocation = Some(250);
+        let mut session = Session::new(settings);
+
+        // The final result is small, but the intermediate list is not
+        let snippet = "(len (concat (list u1 u2 u3 u4 u5 u6) (list u7 u8 u9 u10 u11 u12)))";
+        match session.formatted_interpretation(snippet.to_string(), None, false, None) {
+            Err(output) => assert!(output[0]
+                .contains("values allocated exceeded the cap of 250 bytes (402 bytes) in concat")),
+            _ => panic!("Expected failed interpretation"),
+        };
+
+        let mut settings = SessionSettings::default();
+        settings.max_value_allocation = Some(1000);
+        let mut session = Session::new(settings);
+        match session.formatted_interpretation(snippet.to_string(), None, false, None) {
+            Ok((output, _)) => assert_eq!(output[0], green!("u12")),
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn iteration_limits() {
+        let session = Session::new(SessionSettings::default());
+        assert_eq!(session.iteration_limits().max_iterations, 1024 * 1024);
+        assert_eq!(session.iteration_limits().max_call_stack_depth, 64);
+
+        let mut settings = SessionSettings::default();
+        settings.iteration_limits.max_iterations = 3;
+        settings.iteration_limits.max_call_stack_depth = 2;
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-private (double (value int)) (* value 2))
+(define-read-only (quadruple (value int)) (double (double value)))"
+            .to_string();
+        session
+            .formatted_interpretation(snippet, Some("math".to_string()), false, None)
+            .unwrap();
+        match session.formatted_interpretation(
+            "(fold + (list 1 2 3 4) 0)".to_string(),
+            None,
+            false,
+            None,
+        ) {
+            Err(output) => {
+                assert!(output[0].contains("fold over 4 elements exceeds the iteration limit of 3"))
+            }
+            _ => panic!("Expected failed interpretation"),
+        };
+        match session.formatted_interpretation(
+            "(map - (list 1 2 3 4) (list 1 2 3))".to_string(),
+            None,
+            false,
+            None,
+        ) {
+            Ok((output, _)) => assert_eq!(output[0], green!("[0, 0, 0]")),
+            _ => panic!("Expected successful interpretation"),
+        };
+        match session.formatted_interpretation(
+            "(filter not (list true false true false))".to_string(),
+            None,
+            false,
+            None,
+        ) {
+            Err(output) => assert!(
+                output[0].contains("filter over 4 elements exceeds the iteration limit of 3")
+            ),
+            _ => panic!("Expected failed interpretation"),
+        };
+        match session.formatted_interpretation(
+            "(contract-call? .math quadruple 1)".to_string(),
+            None,
+            false,
+            None,
+        ) {
+            Err(output) => assert_eq!(
+                output[0],
+                "Runtime error: call to double exceeds the call stack depth limit of 2"
+            ),
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn call_depth_limit() {
+        let session = Session::new(SessionSettings::default());
+        assert_eq!(session.call_depth_limit(), 64);
+
+        let mut settings = SessionSettings::default();
+        settings.iteration_limits.max_contract_call_depth = 2;
+        let mut session = Session::new(settings);
+        assert_eq!(session.call_depth_limit(), 2);
+        for (name, snippet) in [
+            ("c", "(define-read-only (ping) (ok u3))"),
+            ("b", "(define-read-only (ping) (contract-call? .c ping))"),
+            ("a", "(define-read-only (ping) (contract-call? .b ping))"),
+        ] {
+            session
+                .formatted_interpretation(snippet.to_string(), Some(name.to_string()), false, None)
+                .unwrap();
+        }
+        match session.formatted_interpretation(
+            "(contract-call? .b ping)".to_string(),
+            None,
+            false,
+            None,
+        ) {
+            Ok((output, _)) => assert_eq!(output[0], green!("(ok u3)")),
+            _ => panic!("Expected successful interpretation"),
+        };
+        let sender = session.get_tx_sender();
+        match session.formatted_interpretation(
+            "(contract-call? .a ping)".to_string(),
+            None,
+            false,
+            None,
+        ) {
+            Err(output) => assert!(output[0].starts_with(&format!(
+                "Runtime error: contract-call? chain exceeds the depth limit of 2: {0}.a -> {0}.b -> {0}.c",
+                sender
+            ))),
+            _ => panic!("Expected failed interpretation"),
+        };
+    }
+
+    #[test]
+    fn freeze_contract() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+        let snippet = "(define-data-var count uint u0)
+(define-public (increment) (ok (var-set count (+ (var-get count) u1))))"
+            .to_string();
+        session
+            .interpret(snippet.clone(), Some("counter".to_string()), false, None)
+            .unwrap();
+        let counter = format!("{}.counter", session.get_tx_sender());
+
+        assert_eq!(
+            session.freeze_contract(".missing"),
+            Err(format!(
+                "Contract {}.missing is not deployed",
+                session.get_tx_sender()
+            ))
+        );
+        let output = session.handle_command("::freeze counter");
+        assert_eq!(output[0], green!(format!("{} is frozen", counter)));
+
+        match session.interpret(snippet, Some("counter".to_string()), false, None) {
+            Err((message, _, _)) => assert_eq!(
+                message,
+                format!("Contract {} is frozen and cannot be redeployed", counter)
+            ),
+            _ => panic!("Expected a frozen contract not to be redeployed"),
+        }
+        match session.eval_as_contract(".counter", "(stx-burn? u1 tx-sender)".to_string()) {
+            Err((message, _, _)) => assert_eq!(
+                message,
+                format!("Contract {} is frozen and cannot be modified", counter)
+            ),
+            _ => panic!("Expected a frozen contract not to be modified"),
+        }
+        let output = session.handle_command(&format!("::mint_stx {} 1000", counter));
+        assert_eq!(
+            output[0],
+            red!(format!(
+                "Contract {} is frozen and cannot be modified",
+                counter
+            ))
+        );
+
+        // It can still be called
+        let checkpoint = session.checkpoint();
+        session.begin_transaction().unwrap();
+        let result = session
+            .interpret(
+                "(contract-call? .counter increment)".to_string(),
+                None,
+                false,
+                None,
+            )
+            .unwrap();
+        assert_eq!(result.result.unwrap().to_string(), "(ok true)");
+
+        // But not rolled back to an earlier state
+        let frozen = Err(format!(
+            "Contract {} is frozen and cannot be modified",
+            counter
+        ));
+        assert_eq!(session.abort_transaction(), frozen);
+        assert_eq!(session.restore_checkpoint(checkpoint), frozen);
+        assert_eq!(session.commit_transaction(), Ok(()));
+    }
+
+    #[test]
+    fn export_import_contracts() {
+        let mut session = Session::new(SessionSettings::default());
+        session
+            .interpret(
+                "(define-data-var count uint u0)
+(define-public (increment) (begin (var-set count (+ (var-get count) u1)) (ok (var-get count))))"
+                    .to_string(),
+                Some("counter".to_string()),
+                false,
+                None,
+            )
+            .unwrap();
+        session
+            .interpret(
+                "(define-public (bump) (contract-call? .counter increment))".to_string(),
+                Some("caller".to_string()),
+                false,
+                None,
+            )
+            .unwrap();
+        session
+            .interpret(
+                "(contract-call? .caller bump)".to_string(),
+                None,
+                false,
+                None,
+            )
+            .unwrap();
+
+        let mut contracts = session.export_contracts();
+        let ids: Vec<String> = contracts.iter().map(|(id, _)| id.to_string()).collect();
+        let deployer = session.get_tx_sender();
+        assert_eq!(
+            ids,
+            vec![
+                format!("{}.caller", deployer),
+                format!("{}.counter", deployer)
+            ]
+        );
+
+        // The dependency is deployed first, and the state is not carried over
+        contracts.reverse();
+        let mut imported = Session::new(SessionSettings::default());
+        let results = imported.import_contracts(contracts).unwrap();
+        assert_eq!(results.len(), 2);
+        let result = imported
+            .interpret(
+                "(contract-call? .caller bump)".to_string(),
+                None,
+                false,
+                None,
+            )
+            .unwrap();
+        assert_eq!(result.result.unwrap().to_string(), "(ok u1)");
+
+        let cycle = vec![
+            (
+                QualifiedContractIdentifier::parse(&format!("{}.a", deployer)).unwrap(),
+                "(define-public (f) (contract-call? .b f))".to_string(),
+            ),
+            (
+                QualifiedContractIdentifier::parse(&format!("{}.b", deployer)).unwrap(),
+                "(define-public (f) (contract-call? .a f))".to_string(),
+            ),
+        ];
+        assert_eq!(
+            Session::new(SessionSettings::default())
+                .import_contracts(cycle)
+                .err(),
+            Some(format!(
+                "Circular dependency between contracts: {}.a, {}.b",
+                deployer, deployer
+            ))
+        );
+    }
+
+    #[test]
+    fn capture_let_bindings() {
+        let snippet = "(define-private (double (n int)) (let ((twice (* n 2))) twice))
+(let ((a 1) (b (let ((c (+ a 1))) (double c)))) (+ a b))";
+
+        let mut session = Session::new(SessionSettings::default());
+        let result = session
+            .interpret(snippet.to_string(), None, false, None)
+            .unwrap();
+        assert!(result.let_bindings.is_empty());
+
+        let mut settings = SessionSettings::default();
+        settings.capture_let_bindings = true;
+        let mut session = Session::new(settings);
+        let result = session
+            .interpret(snippet.to_string(), None, false, None)
+            .unwrap();
+        let bindings: Vec<(&str, Value)> = result
+            .let_bindings
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone()))
+            .collect();
+        assert_eq!(
+            bindings,
+            vec![
+                ("a", Value::Int(1)),
+                ("b", Value::Int(4)),
+                ("b.c", Value::Int(2)),
+                ("b.twice", Value::Int(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn native_call_hooks() {
+        use crate::clarity::errors::InterpreterResult;
+        use crate::clarity::native_hooks::{NativeCall, NativeCallHandler, NativeCallHooks};
+
+        struct Tracer(Arc<Mutex<Vec<String>>>);
+
+        impl NativeCallHandler for Tracer {
+            fn before(&self, call: &NativeCall) {
+                // Special functions receive the expressions of their arguments
+                let args: Vec<String> = match call.values {
+                    Some(values) => values.iter().map(|v| v.to_string()).collect(),
+                    None => vec![format!("({} expressions)", call.args.len())],
+                };
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} {}", call.name, args.join(" ")));
+            }
+
+            fn after(&self, call: &NativeCall, result: &InterpreterResult<Value>) {
+                let result = match result {
+                    Ok(value) => value.to_string(),
+                    Err(e) => e.to_string(),
+                };
+                self.0
+                    .lock()
+                    .unwrap()
+                    .push(format!("{} -> {}", call.name, result));
+            }
+
+            fn inject(&self, call: &NativeCall) -> Option<InterpreterResult<Value>> {
+                Some(Ok(Value::error(Value::UInt(42)).unwrap()))
+            }
+        }
+
+        let trace = Arc::new(Mutex::new(vec![]));
+        let mut hooks = NativeCallHooks::new();
+        assert_eq!(
+            hooks.register("transfer", Tracer(trace.clone())),
+            Err("'transfer' is not a native function".to_string())
+        );
+        hooks.register("+", Tracer(trace.clone())).unwrap();
+        hooks
+            .register("stx-transfer?", Tracer(trace.clone()))
+            .unwrap();
+        let mut settings = SessionSettings::default();
+        settings.native_call_hooks = hooks.clone();
+        let mut session = Session::new(settings);
+        let snippet =
+            "(stx-transfer? (+ u1 u2) tx-sender 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM)";
+        let result = session
+            .interpret(snippet.to_string(), None, false, None)
+            .unwrap();
+        // Hooks only observe the calls
+        assert_eq!(result.result.unwrap().to_string(), "(err u1)");
+        assert_eq!(
+            *trace.lock().unwrap(),
+            vec![
+                "stx-transfer? (3 expressions)".to_string(),
+                "+ u1 u2".to_string(),
+                "+ -> u3".to_string(),
+                "stx-transfer? -> (err u1)".to_string(),
+            ]
+        );
+
+        hooks.fault_injection = true;
+        let mut settings = SessionSettings::default();
+        settings.native_call_hooks = hooks;
+        let mut session = Session::new(settings);
+        let result = session
+            .interpret("(+ 1 2)".to_string(), None, false, None)
+            .unwrap();
+        assert_eq!(result.result.unwrap().to_string(), "(err u42)");
+    }
+
+    #[test]
+    fn result_formatter() {
+        struct PlainFormatter;
+        impl ResultFormatter for PlainFormatter {
+            fn format_result(
+                &self,
+                result: &ExecutionResult,
+                context: &FormatContext,
+            ) -> Vec<String> {
+                vec![format!(
+                    "{}: {}",
+                    context.contract_name,
+                    result.result.as_ref().unwrap()
+                )]
+            }
+
+            fn format_error(
+                &self,
+                diagnostic: Option<&Diagnostic>,
+                error: Option<&Error>,
+                context: &FormatContext,
+            ) -> Vec<String> {
+                vec![format!("{} failed", context.contract_name)]
+            }
+        }
+
+        let mut session =
+            Session::new(SessionSettings::default()).with_result_formatter(PlainFormatter);
+        let (output, _) = session
+            .formatted_interpretation("(+ 1 2)".to_string(), None, false, None)
+            .unwrap();
+        assert_eq!(output, vec!["<stdin>: 3".to_string()]);
+        match session.formatted_interpretation("(unwrap-panic none)".to_string(), None, false, None)
+        {
+            Err(output) => assert_eq!(output, vec!["<stdin> failed".to_string()]),
+            _ => panic!("Expected the evaluation to fail"),
+        }
+
+        // The default formatter is kept otherwise
+        let mut session = Session::new(SessionSettings::default());
+        let (output, _) = session
+            .formatted_interpretation("(+ 1 2)".to_string(), None, false, None)
+            .unwrap();
+        assert_eq!(output, vec![green!("3")]);
+    }
+
+    #[test]
+    fn cost_limits() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+        assert_eq!(session.cost_limits(), BLOCK_LIMIT_MAINNET);
+
+        let result = session.interpret("(+ 1 2)".to_string(), None, true, None);
+        assert_eq!(result.unwrap().cost.unwrap().limit, BLOCK_LIMIT_MAINNET);
+
+        let mut settings = SessionSettings::default();
+        settings.costs_version = 2;
+        let session = Session::new(settings);
+        assert_eq!(session.cost_limits().read_count, 15_000);
+        assert_eq!(session.cost_limits().write_count, 15_000);
+    }
+
+    #[test]
+    fn cost_formula() {
+        let mut settings = SessionSettings::default();
+        settings.costs_version = 2;
+        let session = Session::new(settings);
+        assert_eq!(
+            session.cost_formula("+"),
+            Ok((
+                ClarityCostFunction::Add,
+                vec![("runtime".to_string(), CostFormula::Linear(14, 157))]
+            ))
+        );
+        assert_eq!(
+            session.cost_formula("len"),
+            Ok((
+                ClarityCostFunction::Len,
+                vec![("runtime".to_string(), CostFormula::Constant(486))]
+            ))
+        );
+        assert_eq!(
+            session.cost_formula("map-delete"),
+            Ok((
+                ClarityCostFunction::SetEntry,
+                vec![
+                    ("runtime".to_string(), CostFormula::Linear(4, 2204)),
+                    ("write_length".to_string(), CostFormula::Linear(1, 1)),
+                    ("write_count".to_string(), CostFormula::Constant(1)),
+                    ("read_count".to_string(), CostFormula::Constant(1)),
+                ]
+            ))
+        );
+        assert_eq!(
+            session.cost_formula("as-contract"),
+            Err("'as-contract' has no cost of its own".to_string())
+        );
+        assert_eq!(
+            session.cost_formula("foo"),
+            Err("'foo' is not a native function".to_string())
+        );
+
+        let mut output = vec![];
+        session.cost_explain(&mut output, "::cost_explain append");
+        assert_eq!(output[0], "append is charged by cost_append");
+        assert_eq!(output[1], "  runtime: 71 * n + 176");
+        assert_eq!(
+            output[2],
+            green!("Scales with n, the size of its input (length or count)")
+        );
+
+        let mut settings = SessionSettings::default();
+        settings.costs_version = 1;
+        let session = Session::new(settings);
+        assert_eq!(
+            session.cost_formula("+").unwrap().1,
+            vec![("runtime".to_string(), CostFormula::Linear(1000, 1000))]
+        );
+    }
+
+    #[test]
+    fn cost_compare() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+
+        let mut output: Vec<String> = Vec::new();
+        let (first, second) = session
+            .compare_costs(&mut output, "::cost_compare (+ 1 2) (+ 1 2 3 4)")
+            .unwrap();
+        assert!(first.total.runtime < second.total.runtime);
+        assert_eq!(format_delta(-3), "-3");
+        assert_eq!(format_delta(3), "+3");
+
+        session.handle_command(
+            "(define-data-var x uint u0)
+            (define-public (incr)
+                (begin
+                    (var-set x (+ (var-get x) u1))
+                    (ok (var-get x))))",
+        );
+
+        // Both calls start from the same state, so the costs are equal
+        let (first, second) = session
+            .compare_costs(
+                &mut output,
+                "::cost_compare (contract-call? .contract-2 incr) (contract-call? .contract-2 incr)",
+            )
+            .unwrap();
+        assert_eq!(first.total, second.total);
+
+        // Nor do their events remain in the session
+        let events = session.events.len();
+        session
+            .compare_costs(&mut output, "::cost_compare (print u1) (print u2)")
+            .unwrap();
+        assert_eq!(session.events.len(), events);
+
+        // And the state was rolled back afterwards
+        assert_eq!(
+            session.handle_command("(contract-call? .contract-2 incr)")[0],
+            green!("(ok u1)")
+        );
+
+        let mut output: Vec<String> = Vec::new();
+        assert!(session
+            .compare_costs(&mut output, "::cost_compare (+ 1 2)")
+            .is_none());
+        assert_eq!(output, vec![red!("Usage: ::cost_compare <expr1> <expr2>")]);
+    }
+
+    #[test]
+    fn data_var_at() {
+        let mut session = Session::new(SessionSettings::default());
+        session
+            .interpret(
+                "(define-data-var count uint u1)
+(define-public (increment) (ok (var-set count (+ (var-get count) u1))))"
+                    .to_string(),
+                Some("counter".to_string()),
+                false,
+                None,
+            )
+            .unwrap();
+        let increment = "(contract-call? .counter increment)".to_string();
+        session.advance_chain_tip(5);
+        session
+            .interpret(increment.clone(), None, false, None)
+            .unwrap();
+        session.advance_chain_tip(5);
+        session.interpret(increment, None, false, None).unwrap();
+
+        assert_eq!(
+            session.data_var_at(".counter", "count", 0),
+            Some(Value::UInt(1))
+        );
+        assert_eq!(
+            session.data_var_at(".counter", "count", 4),
+            Some(Value::UInt(1))
+        );
+        assert_eq!(
+            session.data_var_at(".counter", "count", 5),
+            Some(Value::UInt(2))
+        );
+        assert_eq!(
+            session.data_var_at(".counter", "count", 9),
+            Some(Value::UInt(2))
+        );
+        assert_eq!(
+            session.data_var_at(".counter", "count", 10),
+            Some(Value::UInt(3))
+        );
+        assert_eq!(session.data_var_at(".counter", "count", 11), None);
+        assert_eq!(session.data_var_at(".counter", "missing", 10), None);
+        assert_eq!(session.data_var_at(".missing", "count", 10), None);
+    }
+
+    #[test]
+    fn block_contexts() {
+        let mut session = Session::new(SessionSettings::default());
+        session
+            .interpret(
+                "(define-data-var count uint u1)
+(define-read-only (get-count) (var-get count))
+(define-public (increment) (ok (var-set count (+ (var-get count) u1))))"
+                    .to_string(),
+                Some("counter".to_string()),
+                false,
+                None,
+            )
+            .unwrap();
+        let increment = "(contract-call? .counter increment)".to_string();
+        session.advance_chain_tip(5);
+        session
+            .interpret(increment.clone(), None, false, None)
+            .unwrap();
+        session.advance_chain_tip(5);
+        session
+            .interpret(increment.clone(), None, false, None)
+            .unwrap();
+
+        let evaluate = |session: &mut Session, snippet: &str| -> String {
+            match session.interpret(snippet.to_string(), None, false, None) {
+                Ok(result) => result.result.unwrap().to_string(),
+                Err((message, _, _)) => message,
+            }
+        };
+        assert_eq!(
+            session.push_block_context(11),
+            Err("Block 11 is past the chain tip, at height 10".to_string())
+        );
+        session.push_block_context(5).unwrap();
+        assert_eq!(
+            evaluate(&mut session, "(contract-call? .counter get-count)"),
+            "u2"
+        );
+        // A nested `at-block` returns to the pushed block
+        assert_eq!(
+            evaluate(
+                &mut session,
+                "(list (at-block (unwrap-panic (get-block-info? id-header-hash u0)) (contract-call? .counter get-count)) (contract-call? .counter get-count))"
+            ),
+            "[u1, u2]"
+        );
+        session.push_block_context(0).unwrap();
+        assert_eq!(
+            evaluate(&mut session, "(contract-call? .counter get-count)"),
+            "u1"
+        );
+        assert_eq!(session.pop_block_context(), Ok(0));
+        assert_eq!(
+            evaluate(&mut session, "(contract-call? .counter get-count)"),
+            "u2"
+        );
+
+        // Writes and deployments are not allowed
+        assert!(session.interpret(increment, None, false, None).is_err());
+        assert_eq!(
+            evaluate(&mut session, "(define-data-var x int 0)"),
+            "Contracts cannot be deployed within a block context"
+        );
+        assert_eq!(evaluate(&mut session, "(len \"(define-\")"), "u8");
+
+        assert_eq!(session.pop_block_context(), Ok(5));
+        assert_eq!(
+            evaluate(&mut session, "(contract-call? .counter get-count)"),
+            "u3"
+        );
+        assert_eq!(
+            session.pop_block_context(),
+            Err("No block context to pop".to_string())
+        );
+
+        // Not set before deployment
+        session
+            .interpret(
+                "(define-data-var late bool true)".to_string(),
+                Some("late".to_string()),
+                false,
+                None,
+            )
+            .unwrap();
+        assert_eq!(session.data_var_at(".late", "late", 5), None);
+        assert_eq!(
+            session.data_var_at(".late", "late", 10),
+            Some(Value::Bool(true))
+        );
+    }
+
+    #[test]
+    fn evaluate_at_block() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+
+        // setup contract state
+        session.handle_command(
+            "
+            (define-data-var x uint u0)
+
+            (define-read-only (get-x)
+                (var-get x))
+            
+            (define-public (incr)
+                (begin
+                    (var-set x (+ (var-get x) u1))
+                    (ok (var-get x))))",
+        );
+
+        // assert data-var is set to 0
+        assert_eq!(
+            session.handle_command("(contract-call? .contract-2 get-x)")[0],
+            green!("u0")
+        );
+
+        // advance chain tip and test at-block
+        session.advance_chain_tip(10000);
+        assert_eq!(
+            session.handle_command("(contract-call? .contract-2 get-x)")[0],
+            green!("u0")
+        );
+        session.handle_command("(contract-call? .contract-2 incr)");
+        assert_eq!(
+            session.handle_command("(contract-call? .contract-2 get-x)")[0],
+            green!("u1")
+        );
+        assert_eq!(session.handle_command("(at-block (unwrap-panic (get-block-info? id-header-hash u0)) (contract-call? .contract-2 get-x))")[0], green!("u0"));
+        assert_eq!(session.handle_command("(at-block (unwrap-panic (get-block-info? id-header-hash u5000)) (contract-call? .contract-2 get-x))")[0], green!("u0"));
+
+        // advance chain tip again and test at-block
+        // do this twice to make sure that the lookup table is being updated properly
+        session.advance_chain_tip(10);
+        session.advance_chain_tip(10);
+
+        assert_eq!(
+            session.handle_command("(contract-call? .contract-2 get-x)")[0],
+            green!("u1")
+        );
+        session.handle_command("(contract-call? .contract-2 incr)");
+        assert_eq!(
+            session.handle_command("(contract-call? .contract-2 get-x)")[0],
+            green!("u2")
+        );
+        assert_eq!(session.handle_command("(at-block (unwrap-panic (get-block-info? id-header-hash u10000)) (contract-call? .contract-2 get-x))")[0], green!("u1"));
+    }
+
+    #[test]
+    fn strict_analysis() {
+        let mut settings = SessionSettings::default();
+        settings.strict = true;
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-data-var last (optional { id: uint, amount: uint }) none)
+(define-public (place (id uint) (amount uint))
+    (begin
+        (var-set last (some { amount: amount, id: id }))
+        (ok (is-eq id amount u1))
+    )
+)
+";
+        match session.formatted_interpretation(
+            snippet.to_string(),
+            Some("strict".to_string()),
+            false,
+            None,
+        ) {
+            Ok((_, result)) => {
+                let mut messages: Vec<(Level, String)> = result
+                    .diagnostics
+                    .into_iter()
+                    .map(|diagnostic| (diagnostic.level, diagnostic.message))
+                    .collect();
+                messages.sort_by(|a, b| a.1.cmp(&b.1));
+                assert_eq!(
+                    messages,
+                    vec![
+                        (
+                            Level::Warning,
+                            "is-eq with 3 arguments is true only if all of them are equal"
+                                .to_string()
+                        ),
+                        (
+                            Level::Warning,
+                            "the fields of this tuple are not in the order of its type".to_string()
+                        ),
+                    ]
+                );
+            }
+            _ => panic!("Expected successful interpretation"),
+        };
+
+        // Passes also enabled by name run once
+        let mut settings = SessionSettings::default();
+        settings.strict = true;
+        settings.analysis = vec!["all".to_string(), "tuple_order_checker".to_string()];
+        let mut session = Session::new(settings);
+        match session.formatted_interpretation(
+            snippet.to_string(),
+            Some("strict".to_string()),
+            false,
+            None,
+        ) {
+            Ok((_, result)) => assert_eq!(result.diagnostics.len(), 2),
+            _ => panic!("Expected successful interpretation"),
+        };
+    }
+
+    #[test]
+    fn contract_traits() {
+        let mut session = Session::new(SessionSettings::default());
+        let sender = session.get_tx_sender();
+        let snippets = [
+            (
+                "token-trait",
+                "(define-trait token ((get-balance (principal) (response uint uint))))",
+            ),
+            (
+                "token",
+                "(impl-trait .token-trait.token)
+(define-read-only (get-balance (who principal)) (ok u0))",
+            ),
+            (
+                "wallet",
+                "(use-trait token .token-trait.token)
+(define-public (balance (t <token>)) (contract-call? t get-balance tx-sender))",
+            ),
+        ];
+        for (name, snippet) in snippets.iter() {
+            session
+                .formatted_interpretation(snippet.to_string(), Some(name.to_string()), false, None)
+                .unwrap();
+        }
+        let trait_identifier = TraitIdentifier {
+            name: ClarityName::try_from("token".to_string()).unwrap(),
+            contract_identifier: QualifiedContractIdentifier::parse(&format!(
+                "{}.token-trait",
+                sender
+            ))
+            .unwrap(),
+        };
+
+        let usages = session.contract_traits(".token");
+        assert_eq!(usages.len(), 1);
+        assert_eq!(usages[0].alias, None);
+        assert_eq!(usages[0].trait_identifier, trait_identifier);
+        assert_eq!(usages[0].span.start_line, 1);
+        assert!(usages[0].resolved);
+        assert_eq!(usages[0].conforms, Some(true));
+
+        let usages = session.contract_traits(".wallet");
+        assert_eq!(usages.len(), 1);
+        assert_eq!(usages[0].alias.as_deref(), Some("token"));
+        assert_eq!(usages[0].trait_identifier, trait_identifier);
+        assert!(usages[0].resolved);
+        assert_eq!(usages[0].conforms, None);
+
+        assert!(session.contract_traits(".token-trait").is_empty());
+        assert!(session.contract_traits(".unknown").is_empty());
+    }
+
+    #[test]
+    fn interpret_split() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec![
+            "is_eq_checker".to_string(),
+            "sequence_length_checker".to_string(),
+        ];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-read-only (same (a (buff 32)) (b (buff 32)) (c (buff 32)))
+    (and (is-eq a b c) (is-some (as-max-len? a u64)))
+)
+";
+        let (result, errors, warnings, notes) =
+            session.interpret_split(snippet.to_string(), Some("split".to_string()));
+        assert!(result.is_some());
+        assert!(errors.is_empty());
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].level, Level::Warning);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(
+            notes[0].message,
+            "is-eq with 3 arguments is true only if all of them are equal"
+        );
+
+        let (result, errors, warnings, notes) =
+            session.interpret_split("(+ 1 u1)".to_string(), None);
+        assert!(result.is_none());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].level, Level::Error);
+        assert!(warnings.is_empty() && notes.is_empty());
+
+        let (result, errors, _, _) = session.interpret_split("(/ 1 0)".to_string(), None);
+        assert!(result.is_none());
+        assert_eq!(errors[0].spans, vec![]);
+
+        // The notes found before an analysis error are kept
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec![
+            "is_eq_checker".to_string(),
+            "pow_overflow_checker".to_string(),
+        ];
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-read-only (same (a uint) (b uint) (c uint)) (is-eq a b c))
+(define-read-only (too-big) (pow u2 u128))
+";
+        let (result, errors, warnings, notes) =
+            session.interpret_split(snippet.to_string(), Some("failed".to_string()));
+        assert!(result.is_none());
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.starts_with("pow overflows"));
+        assert!(warnings.is_empty());
+        assert_eq!(notes.len(), 1);
+        assert_eq!(
+            session.diagnostics[&format!("{}.failed", session.get_tx_sender())].len(),
+            2
+        );
+    }
+
+    #[test]
+    fn default_args() {
+        let mut session = Session::new(SessionSettings::default());
+        let snippet = "
+(define-trait token ((get-balance (principal) (response uint uint))))
+(define-public (deposit (who principal) (amount uint) (memo (optional (buff 34))) (ids (list 5 int)))
+    (ok true))
+(define-private (label (name (string-ascii 8)) (flags { active: bool, rank: int }))
+    name)
+(define-read-only (balance (t <token>)) u0)
+";
+        session
+            .formatted_interpretation(
+                snippet.to_string(),
+                Some("defaults".to_string()),
+                false,
+                None,
+            )
+            .unwrap();
+        let args: Vec<String> = session
+            .default_args(".defaults", "deposit")
+            .unwrap()
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(
+            args,
+            vec![
+                session.get_tx_sender(),
+                "u0".to_string(),
+                "none".to_string(),
+                "[]".to_string()
+            ]
+        );
+        let args: Vec<String> = session
+            .default_args(".defaults", "label")
+            .unwrap()
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(args, vec!["\"\"", "{active: false, rank: 0}"]);
+        assert_eq!(
+            session.default_args(".defaults", "balance"),
+            Err(
+                "Argument t of balance is a trait reference, which cannot be generated".to_string()
+            )
+        );
+        assert_eq!(
+            session.default_args(".defaults", "withdraw"),
+            Err(format!(
+                "Function withdraw is not defined by {}.defaults",
+                session.get_tx_sender()
+            ))
+        );
+    }
+
+    #[test]
+    fn cost_tree() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+        let callee = "
+(define-map values uint uint)
+(define-private (store (key uint)) (map-set values key key))
+(define-public (fill) (begin (store u1) (store u2) (ok true)))";
+        session
+            .formatted_interpretation(callee.to_string(), Some("callee".to_string()), false, None)
+            .unwrap();
+        let caller = "(define-public (call) (contract-call? .callee fill))";
+        session
+            .formatted_interpretation(caller.to_string(), Some("caller".to_string()), false, None)
+            .unwrap();
+        session
+            .interpret(
+                "(contract-call? .caller call)".to_string(),
+                None,
+                true,
+                None,
+            )
+            .unwrap();
+
+        let tree = session.cost_tree();
+        assert_eq!(tree.len(), 1);
+        let caller = &tree[0];
+        assert_eq!(
+            caller.function,
+            format!("contract-call? {}.caller call", session.get_tx_sender())
+        );
+        assert_eq!(caller.span.start_column, 1);
+        assert_eq!(caller.children.len(), 1);
+        let callee = &caller.children[0];
+        assert_eq!(
+            callee.function,
+            format!("contract-call? {}.callee fill", session.get_tx_sender())
+        );
+        let stores: Vec<&str> = callee
+            .children
+            .iter()
+            .map(|node| node.function.as_str())
+            .collect();
+        assert_eq!(stores, vec!["store", "store"]);
+        assert_eq!(callee.children[0].cost.write_count, 1);
+        assert_eq!(callee.cost.write_count, 2);
+        assert!(caller.cost.runtime > callee.cost.runtime);
+        assert!(callee.cost.runtime > callee.children[0].cost.runtime);
+
+        let path = std::env::temp_dir().join(format!("cost-tree-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        session.export_trace_json(path).unwrap();
+        let trace: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        std::fs::remove_file(path).unwrap();
+        let events = trace["traceEvents"].as_array().unwrap();
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[1]["name"], callee.function.as_str());
+        assert_eq!(events[1]["ts"], 0);
+        assert_eq!(events[3]["ts"], callee.children[0].cost.runtime);
+        assert_eq!(events[1]["args"]["write_count"], 2);
+
+        session
+            .interpret("(+ 1 2)".to_string(), None, false, None)
+            .unwrap();
+        assert_eq!(session.cost_tree().len(), 1);
+    }
+
+    #[test]
+    fn coverage_merge() {
+        let run_test = |test_name: &str, snippet: &str| {
+            let mut settings = SessionSettings::default();
+            settings.include_boot_contracts = vec!["costs-v1".into()];
+            settings.costs_version = 1;
+            settings.initial_contracts.push(InitialContract {
+                code: "(define-data-var count int 0)
+(define-public (incr)
+    (ok (var-set count (+ (var-get count) 1))))
+(define-public (decr)
+    (ok (var-set count (- (var-get count) 1))))"
+                    .to_string(),
+                name: Some("counter".to_string()),
+                path: "contracts/counter.clar".to_string(),
+                deployer: None,
+            });
+            let mut session = Session::new(settings);
+            session.start().expect("session could not start");
+            session
+                .interpret(snippet.to_string(), None, false, Some(test_name.into()))
+                .unwrap();
+            session
+        };
+        let dir = std::env::temp_dir();
+        let partial = dir.join(format!("coverage-{}.json", std::process::id()));
+        let partial = partial.to_str().unwrap();
+        let lcov = dir.join(format!("coverage-{}.lcov", std::process::id()));
+        let lcov = lcov.to_str().unwrap();
+
+        let first = run_test("test-incr", "(contract-call? .counter incr)");
+        first.export_coverage(partial).unwrap();
+        let mut second = run_test("test-incr", "(contract-call? .counter incr)");
+        second
+            .interpret(
+                "(contract-call? .counter decr)".to_string(),
+                None,
+                false,
+                Some("test-decr".into()),
+            )
+            .unwrap();
+        let output = second.handle_command(&format!("::coverage_merge {} {}", lcov, partial));
+        assert_eq!(
+            output[0],
+            green!(format!("Coverage of 2 sessions written to {}", lcov))
+        );
+        let report = std::fs::read_to_string(lcov).unwrap();
+        std::fs::remove_file(partial).unwrap();
+        std::fs::remove_file(lcov).unwrap();
+
+        let records: Vec<&str> = report.split("end_of_record\n").collect();
+        assert_eq!(records.len(), 4);
+        // Both sessions deployed the contract, and incremented the counter
+        assert!(records[0].starts_with("TN:Deployment\nSF:contracts/counter.clar\n"));
+        assert!(records[0].contains("DA:1,2\n"));
+        assert!(records[2].starts_with("TN:test-incr\n"));
+        assert!(records[2].contains("FNDA:1,incr\n"));
+        assert!(records[2].contains("DA:3,10\n"));
+        assert!(records[2].contains("DA:5,0\n"));
+        // Only the second decremented it
+        assert!(records[1].starts_with("TN:test-decr\n"));
+        assert!(records[1].contains("DA:5,5\n"));
+
+        assert_eq!(
+            second.handle_command("::coverage_merge out.lcov"),
+            vec![red!("Usage: ::coverage_merge <output> <files>")]
+        );
+    }
+
+    #[test]
+    fn block_model() {
+        let mut session = Session::new(SessionSettings::default());
+        let genesis = session.current_block_id();
+        session
+            .interpret("(+ 1 2)".to_string(), None, false, None)
+            .unwrap();
+        assert_eq!(session.current_block_id(), genesis);
+
+        let mut settings = SessionSettings::default();
+        settings.block_model = BlockModel::BlockPerCall;
+        let mut session = Session::new(settings);
+        session
+            .formatted_interpretation(
+                "(define-data-var count uint u0)
+(define-public (incr) (ok (var-set count (+ (var-get count) u1))))"
+                    .to_string(),
+                Some("counter".to_string()),
+                false,
+                None,
+            )
+            .unwrap();
+        assert_eq!(session.current_block_id(), genesis);
+        let (_, result) = session
+            .formatted_interpretation(
+                "(contract-call? .counter incr)".to_string(),
+                None,
+                false,
+                None,
+            )
+            .unwrap();
+        assert_eq!(result.result, Some(Value::okay_true()));
+        let first = session.current_block_id();
+        assert_ne!(first, genesis);
+        let (_, result) = session
+            .formatted_interpretation("block-height".to_string(), None, false, None)
+            .unwrap();
+        assert_eq!(result.result, Some(Value::UInt(1)));
+        assert_ne!(session.current_block_id(), first);
     }
-    api_reference
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn encode_simple() {
+    fn natives() {
         let mut session = Session::new(SessionSettings::default());
-        let mut output: Vec<String> = Vec::new();
-        session.encode(&mut output, "::encode 42");
-        assert_eq!(output.len(), 1);
-        assert_eq!(output[0], green!("000000000000000000000000000000002a"));
+        let output = session.handle_command("::natives");
+        assert_eq!(
+            output[0],
+            blue!("Native functions of Clarity 1, page 1 of 5")
+        );
+        assert_eq!(
+            output[1],
+            format!("{}", Colour::Yellow.bold().paint("Arithmetic"))
+        );
+        assert_eq!(output[3], "    (+ 1 2 3)");
+        assert_eq!(output.last().unwrap(), "Use ::natives 2 for the next page");
+
+        let output = session.handle_command("::natives 5");
+        assert!(output.contains(&format!("{}", Colour::Yellow.bold().paint("Blocks"))));
+        assert!(!output.last().unwrap().starts_with("Use ::natives"));
+
+        assert_eq!(
+            session.handle_command("::natives 6")[0],
+            red!("Page 6 does not exist, there are 5 pages")
+        );
     }
 
     #[test]
-    fn encode_map() {
+    fn snapshot_contract() {
         let mut session = Session::new(SessionSettings::default());
-        let mut output: Vec<String> = Vec::new();
-        session.encode(&mut output, "::encode { foo: \"hello\", bar: false }");
-        assert_eq!(output.len(), 1);
+        let contract = "(define-data-var count uint u0)
+(define-map seen uint bool)
+(define-fungible-token points)
+(define-public (bump (id uint))
+    (begin
+        (var-set count (+ (var-get count) u1))
+        (map-set seen id true)
+        (ft-mint? points u10 tx-sender)
+    )
+)
+(define-read-only (get-count) (var-get count))
+(define-read-only (was-seen (id uint)) (map-get? seen id))";
+        for name in ["first", "second"] {
+            session
+                .formatted_interpretation(contract.to_string(), Some(name.to_string()), false, None)
+                .unwrap();
+        }
+        let bump = |session: &mut Session, name: &str, id: u32| {
+            session
+                .formatted_interpretation(
+                    format!("(contract-call? .{} bump u{})", name, id),
+                    None,
+                    false,
+                    None,
+                )
+                .unwrap();
+        };
+        let eval = |session: &mut Session, snippet: &str| {
+            session
+                .formatted_interpretation(snippet.to_string(), None, false, None)
+                .unwrap()
+                .1
+                .result
+                .unwrap()
+        };
+        bump(&mut session, "first", 1);
+        session.snapshot_contract(".first", "start").unwrap();
+        bump(&mut session, "first", 2);
+        bump(&mut session, "second", 2);
+        // A contract of the same name, from another deployer, is left as it is
+        let sender = session.get_tx_sender();
+        let other = "ST2CY5V39NHDPWSXMW9QDT3HC3GD6Q6XX4CFRK9AG";
+        session.set_tx_sender(other.to_string());
+        session
+            .formatted_interpretation(contract.to_string(), Some("first".to_string()), false, None)
+            .unwrap();
+        bump(&mut session, "first", 1);
+        session.set_tx_sender(sender.clone());
+        let height = session.interpreter.get_block_height();
+
+        session.restore_contract(".first", "start").unwrap();
         assert_eq!(
-            output[0],
-            green!("0c00000002036261720403666f6f0d0000000568656c6c6f")
+            eval(&mut session, "(contract-call? .first get-count)"),
+            Value::UInt(1)
+        );
+        assert_eq!(
+            eval(&mut session, "(contract-call? .second get-count)"),
+            Value::UInt(1)
+        );
+        // The map entry added after the snapshot is removed
+        assert_eq!(
+            eval(&mut session, "(contract-call? .first was-seen u2)"),
+            Value::none()
+        );
+        assert_eq!(
+            eval(&mut session, "(contract-call? .second was-seen u2)"),
+            Value::some(Value::Bool(true)).unwrap()
+        );
+        assert_eq!(
+            session
+                .interpreter
+                .get_balance_for_account(&sender, ".first.points"),
+            10
+        );
+        assert_eq!(
+            session
+                .interpreter
+                .get_balance_for_account(&sender, ".second.points"),
+            10
+        );
+        assert_eq!(
+            session
+                .interpreter
+                .get_balance_for_account(other, ".first.points"),
+            10
+        );
+        // The tokens are named without the deployer of their contract
+        let assets_maps = session.get_assets_maps();
+        assert_eq!(
+            assets_maps.keys().collect::<Vec<_>>(),
+            vec![".first.points", ".second.points"]
+        );
+        assert_eq!(assets_maps[".first.points"].get(other), Some(&10));
+        assert_eq!(session.interpreter.get_block_height(), height);
+
+        assert_eq!(
+            session.restore_contract(".second", "start"),
+            Err(format!(
+                "Snapshot start is of contract {}.first, not {}.second",
+                sender, sender
+            ))
+        );
+        assert_eq!(
+            session.restore_contract(".first", "none"),
+            Err("No snapshot named none".to_string())
         );
     }
 
     #[test]
-    fn encode_error() {
-        let mut session = Session::new(SessionSettings::default());
-        let mut output: Vec<String> = Vec::new();
-        session.encode(&mut output, "::encode { foo false }");
-        assert_eq!(output.len(), 1);
+    fn conformance_report() {
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["contract_call_checker".to_string()];
+        let mut session = Session::new(settings);
+        let sender = session.get_tx_sender();
+        let snippets = [
+            (
+                "ping-trait",
+                "(define-trait ping-trait ((ping () (response bool uint))))",
+            ),
+            (
+                "callee",
+                "(define-public (ping) (if true (ok true) (err u1)))",
+            ),
+            (
+                "pinger",
+                "(impl-trait .ping-trait.ping-trait)
+(define-public (ping) (ok true))
+(define-public (call)
+    (begin
+        (is-ok (contract-call? .callee ping))
+        (ok true)
+    )
+)",
+            ),
+        ];
+        for (name, snippet) in snippets {
+            session
+                .formatted_interpretation(snippet.to_string(), Some(name.to_string()), false, None)
+                .unwrap();
+        }
+
+        let report = session.conformance_report(".pinger").unwrap();
+        assert_eq!(report.contract_id, format!("{}.pinger", sender));
+        assert!(report.passed);
+        assert!(report.error_free);
+        assert_eq!(report.counts.warnings, 1);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(report.codes, vec!["discarded-contract-call".to_string()]);
         assert_eq!(
-            output[0],
-            red!("Tuple literal construction expects a colon at index 1")
+            report.traits,
+            vec![TraitConformance {
+                trait_identifier: format!("{}.ping-trait.ping-trait", sender),
+                conforms: Some(true),
+            }]
         );
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["counts"]["errors"], 0);
 
-        session.encode(&mut output, "::encode (foo 1)");
-        assert_eq!(output.len(), 2);
-        assert_eq!(output[1], red!("use of unresolved function 'foo'"));
+        assert_eq!(
+            session.conformance_report(".unknown"),
+            Err(format!("Contract {}.unknown is not deployed", sender))
+        );
     }
 
     #[test]
-    fn decode_simple() {
-        let mut session = Session::new(SessionSettings::default());
-        let mut output: Vec<String> = Vec::new();
-        session.decode(&mut output, "::decode 0000000000000000 0000000000000000 2a");
-        assert_eq!(output.len(), 1);
-        assert_eq!(output[0], green!("42"));
+    fn watch_state() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        let mut session = Session::new(settings);
+        session.start().expect("session could not start");
+        let sender = session.get_tx_sender();
+
+        session.handle_command(
+            "(define-data-var count uint u0)
+            (define-map seen uint bool)
+            (define-fungible-token points)
+            (define-public (bump)
+                (begin
+                    (var-set count (+ (var-get count) u1))
+                    (map-set seen (var-get count) true)
+                    (ft-mint? points u10 tx-sender)))",
+        );
+        let contract_id = format!("{}.contract-2", sender);
+        let output = session.handle_command("::watch_state (contract-call? .contract-2 bump)");
+        assert!(output.contains(&green!("(ok true)")));
+        assert_eq!(output[output.len() - 8], blue!("State changes:"));
+        assert_eq!(
+            output[output.len() - 7],
+            format!("{}", Colour::Yellow.bold().paint(&contract_id))
+        );
+        assert_eq!(output[output.len() - 6], "  data-vars");
+        assert_eq!(output[output.len() - 5], "    count: u0 → u1");
+        assert_eq!(output[output.len() - 4], "  map entries");
+        assert_eq!(output[output.len() - 3], "    seen u1: (absent) → true");
+        assert_eq!(output[output.len() - 2], "  balances");
+        assert_eq!(
+            output[output.len() - 1],
+            format!("    points of {}: 0 → 10", sender)
+        );
+
+        let output = session.handle_command("::watch_state (+ 1 2)");
+        assert_eq!(output.last().unwrap(), &blue!("No state was changed"));
     }
 
     #[test]
-    fn decode_map() {
-        let mut session = Session::new(SessionSettings::default());
-        let mut output: Vec<String> = Vec::new();
-        session.decode(
-            &mut output,
-            "::decode 0x0c00000002036261720403666f6f0d0000000568656c6c6f",
-        );
-        assert_eq!(output.len(), 1);
-        assert_eq!(output[0], green!("{bar: false, foo: \"hello\"}"));
+    fn analysis_timeout() {
+        let snippet = "
+(define-data-var count uint u0)
+(define-public (set-count (value uint))
+    (ok (var-set count value))
+)
+"
+        .to_string();
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["all".to_string()];
+        settings.analysis_timeout = Some(Duration::ZERO);
+        let mut session = Session::new(settings);
+        let result = session
+            .interpret(snippet.clone(), None, false, None)
+            .unwrap();
+        let timeout = result.diagnostics.last().unwrap();
+        assert_eq!(timeout.level, Level::Warning);
+        assert!(timeout
+            .message
+            .starts_with("the analysis timed out after 0ms, so"));
+
+        let mut settings = SessionSettings::default();
+        settings.analysis = vec!["all".to_string()];
+        settings.analysis_timeout = Some(Duration::from_secs(60));
+        let mut session = Session::new(settings);
+        let result = session.interpret(snippet, None, false, None).unwrap();
+        assert!(result
+            .diagnostics
+            .iter()
+            .all(|diagnostic| !diagnostic.message.contains("timed out")));
     }
 
     #[test]
-    fn decode_error() {
+    fn run_command() {
         let mut session = Session::new(SessionSettings::default());
-        let mut output: Vec<String> = Vec::new();
-        session.decode(&mut output, "::decode 42");
-        assert_eq!(output.len(), 1);
+        let commands = session.commands();
+        assert_eq!(commands[0].name, "help");
+        let natives = commands
+            .iter()
+            .find(|command| command.name == "natives")
+            .unwrap();
+        assert_eq!(natives.usage(), "::natives [page]");
+        assert_eq!(natives.required_arguments(), 0);
+
+        let output = session.run_command("help", &[]).unwrap();
+        assert_eq!(output.len(), commands.len());
         assert_eq!(
             output[0],
-            red!("Failed to decode clarity value: Deserialization error: Bad type prefix")
+            format!("{}", Colour::Yellow.paint("::help\t\t\t\t\tDisplay help"))
+        );
+        assert_eq!(
+            output[3],
+            format!(
+                "{}",
+                Colour::Yellow.paint(
+                    "::natives [page]\t\t\tDisplay the native functions by category, with examples"
+                )
+            )
         );
 
-        session.decode(&mut output, "::decode 4g");
-        assert_eq!(output.len(), 2);
+        let output = session.run_command("numformat", &["hex"]).unwrap();
+        assert_eq!(output, vec![green!("Integers displayed in hex")]);
+        let output = session.run_command("encode", &["(some u1)"]).unwrap();
+        assert_eq!(output, vec![green!("0a0100000000000000000000000000000001")]);
         assert_eq!(
-            output[1],
-            red!("Parsing error: invalid digit found in string")
+            session.run_command("freeze", &[]),
+            Err("Usage: ::freeze <contract>".to_string())
+        );
+        assert_eq!(
+            session.run_command("deploy", &[]),
+            Err("Unknown command deploy".to_string())
         );
     }
 
     #[test]
-    fn evaluate_at_block() {
+    fn mock_block_info() {
         let mut settings = SessionSettings::default();
         settings.include_boot_contracts = vec!["costs-v1".into()];
         settings.costs_version = 1;
-
         let mut session = Session::new(settings);
         session.start().expect("session could not start");
 
-        // setup contract state
-        session.handle_command(
-            "
-            (define-data-var x uint u0)
+        session
+            .mock_block_info(5, "time", Value::UInt(1650000000))
+            .unwrap();
+        let output = session.handle_command("(get-block-info? time u5)");
+        assert_eq!(output.last().unwrap(), &green!("(some u1650000000)"));
+        let output = session.handle_command("(get-block-info? time u6)");
+        assert_eq!(output.last().unwrap(), &green!("none"));
 
-            (define-read-only (get-x)
-                (var-get x))
-            
-            (define-public (incr)
-                (begin
-                    (var-set x (+ (var-get x) u1))
-                    (ok (var-get x))))",
+        let output = session.handle_command("::mock_block_info 6 time u1650000600");
+        assert_eq!(
+            output,
+            vec![green!(
+                "get-block-info? time at height 6 returns (some u1650000600)"
+            )]
         );
+        let output = session.handle_command("(get-block-info? time u6)");
+        assert_eq!(output.last().unwrap(), &green!("(some u1650000600)"));
 
-        // assert data-var is set to 0
         assert_eq!(
-            session.handle_command("(contract-call? .contract-2 get-x)")[0],
-            green!("u0")
+            session.mock_block_info(5, "time", Value::Int(1)),
+            Err("The time of a block is a uint, not 1".to_string())
         );
+        assert!(session
+            .mock_block_info(5, "size", Value::UInt(1))
+            .unwrap_err()
+            .starts_with("Unknown block property size, expected one of "));
+    }
 
-        // advance chain tip and test at-block
-        session.advance_chain_tip(10000);
+    #[test]
+    fn principal_versions() {
+        let mut settings = SessionSettings::default();
+        settings.include_boot_contracts = vec!["costs-v1".into()];
+        settings.costs_version = 1;
+        settings.analysis_settings.principal_versions = PrincipalVersions {
+            mainnet_singlesig: 1,
+            mainnet_multisig: 2,
+            testnet_singlesig: 3,
+            testnet_multisig: 4,
+        };
+        let mut session = Session::new(settings.clone());
+        session.start().expect("session could not start");
+        let sender = session.get_tx_sender();
+        assert!(sender.starts_with("S3"));
         assert_eq!(
-            session.handle_command("(contract-call? .contract-2 get-x)")[0],
-            green!("u0")
+            PrincipalData::parse_standard_principal(&sender)
+                .unwrap()
+                .to_address(),
+            sender
         );
-        session.handle_command("(contract-call? .contract-2 incr)");
+        let output = session.handle_command("tx-sender");
+        assert_eq!(output.last().unwrap(), &green!(format!("{}", sender)));
+
+        let output =
+            session.handle_command("::set_tx_sender S1G2081040G2081040G2081040G208105NK8PE5");
         assert_eq!(
-            session.handle_command("(contract-call? .contract-2 get-x)")[0],
-            green!("u1")
+            output,
+            vec![green!(
+                "tx-sender switched to S1G2081040G2081040G2081040G208105NK8PE5"
+            )]
+        );
+        let output =
+            session.handle_command("::set_tx_sender ST1HTBVD3JG9C05J7HBJTHGR0GGW7KXW28M5JS8QE");
+        assert_eq!(
+            output,
+            vec![red!("The version byte 26 of ST1HTBVD3JG9C05J7HBJTHGR0GGW7KXW28M5JS8QE is not one of the configured principal versions")]
         );
-        assert_eq!(session.handle_command("(at-block (unwrap-panic (get-block-info? id-header-hash u0)) (contract-call? .contract-2 get-x))")[0], green!("u0"));
-        assert_eq!(session.handle_command("(at-block (unwrap-panic (get-block-info? id-header-hash u5000)) (contract-call? .contract-2 get-x))")[0], green!("u0"));
 
-        // advance chain tip again and test at-block
-        // do this twice to make sure that the lookup table is being updated properly
-        session.advance_chain_tip(10);
-        session.advance_chain_tip(10);
+        assert_eq!(
+            session
+                .eval_as_caller(
+                    &sender,
+                    "ST1HTBVD3JG9C05J7HBJTHGR0GGW7KXW28M5JS8QE",
+                    "tx-sender".to_string()
+                )
+                .err()
+                .map(|(message, _, _)| message),
+            Some("The version byte 26 of ST1HTBVD3JG9C05J7HBJTHGR0GGW7KXW28M5JS8QE is not one of the configured principal versions".to_string())
+        );
 
+        // The principals of the settings are checked when starting
+        let mut unknown = settings.clone();
+        unknown.initial_accounts.push(Account {
+            address: "ST1HTBVD3JG9C05J7HBJTHGR0GGW7KXW28M5JS8QE".to_string(),
+            balance: 1000,
+            name: "wallet_1".to_string(),
+            mnemonic: String::new(),
+            derivation: String::new(),
+        });
+        let mut session = Session::new(unknown);
         assert_eq!(
-            session.handle_command("(contract-call? .contract-2 get-x)")[0],
-            green!("u1")
+            session.start().err(),
+            Some("The version byte 26 of ST1HTBVD3JG9C05J7HBJTHGR0GGW7KXW28M5JS8QE is not one of the configured principal versions".to_string())
         );
-        session.handle_command("(contract-call? .contract-2 incr)");
+
+        // Contracts without a deployer are deployed by the burn address of
+        // their network
+        let contract = InitialContract {
+            code: String::new(),
+            name: Some("token".to_string()),
+            path: String::new(),
+            deployer: None,
+        };
         assert_eq!(
-            session.handle_command("(contract-call? .contract-2 get-x)")[0],
-            green!("u2")
+            contract.get_deployer_principal(true).to_address(),
+            "SP000000000000000000002Q6VF78"
+        );
+        assert_eq!(
+            contract
+                .get_deployer_principal_with_versions(
+                    &settings.analysis_settings.principal_versions,
+                    true
+                )
+                .to_address(),
+            StandardPrincipalData(1, [0; 20]).to_address()
+        );
+
+        settings
+            .analysis_settings
+            .principal_versions
+            .testnet_multisig = 1;
+        let mut session = Session::new(settings);
+        assert_eq!(
+            session.start().err(),
+            Some("The mainnet_singlesig and testnet_multisig version bytes are both 1".to_string())
+        );
+    }
+
+    #[test]
+    fn dump_ast() {
+        let session = Session::new(SessionSettings::default());
+        let dump = session
+            .dump_ast("(use-trait token .token.token-trait)\n(define-read-only (f (t <token>)) (ok true))")
+            .unwrap();
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(lines.len(), 14);
+        assert_eq!(lines[0], "#1 list 1:1-1:36 (3 elements)");
+        assert_eq!(lines[2], "  #3 atom 1:12-1:16 token");
+        assert_eq!(
+            lines[3],
+            "  #4 field 1:18-1:35 S1G2081040G2081040G2081040G208105NK8PE5.token.token-trait"
+        );
+        assert_eq!(
+            lines[10],
+            "      #11 trait-reference 2:25-2:29 token S1G2081040G2081040G2081040G208105NK8PE5.token.token-trait"
+        );
+        assert_eq!(lines[13], "    #14 atom 2:39-2:42 true");
+
+        let dump = session.dump_ast("(+ 1 u2 \"a\")").unwrap();
+        assert_eq!(dump.lines().nth(4), Some("  #5 literal 1:9-1:9 \"a\""));
+        assert!(session.dump_ast("(+ 1").is_err());
+    }
+
+    #[test]
+    fn record_guard_failures() {
+        let mut settings = SessionSettings::default();
+        settings.record_guard_failures = true;
+        let mut session = Session::new(settings);
+        let snippet = "
+(define-private (check (amount uint))
+    (begin
+        (asserts! (> amount u0) (err u1))
+        (ok amount)))
+(define-public (deposit (amount uint))
+    (begin
+        (unwrap! (check amount) (err u2))
+        (ok true)))";
+        session
+            .interpret(snippet.to_string(), Some("vault".into()), false, None)
+            .unwrap();
+
+        let result = session
+            .interpret(
+                "(contract-call? .vault deposit u0)".to_string(),
+                None,
+                false,
+                None,
+            )
+            .unwrap();
+        let failures: Vec<(&str, u32, Value)> = result
+            .guard_failures
+            .iter()
+            .map(|failure| {
+                (
+                    failure.guard.as_str(),
+                    failure.span.start_line,
+                    failure.value.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            failures,
+            vec![
+                ("asserts!", 4, Value::error(Value::UInt(1)).unwrap()),
+                ("unwrap!", 8, Value::error(Value::UInt(2)).unwrap()),
+            ]
+        );
+        assert_eq!(
+            result.guard_failures[0]
+                .contract_identifier
+                .name
+                .to_string(),
+            "vault"
+        );
+
+        let result = session
+            .interpret(
+                "(contract-call? .vault deposit u1)".to_string(),
+                None,
+                false,
+                None,
+            )
+            .unwrap();
+        assert!(result.guard_failures.is_empty());
+    }
+
+    #[test]
+    fn diagnostic_catalog() {
+        let session = Session::new(SessionSettings::default());
+        let catalog = session.diagnostic_catalog();
+        let entry = catalog
+            .iter()
+            .find(|entry| entry.code == "unbounded-mint")
+            .unwrap();
+        assert_eq!(entry.level, Level::Warning);
+        assert_eq!(entry.pass, "unbounded_mint_checker");
+
+        // Each pass listed can be enabled by name, apart from the checks run
+        // before the type-checker
+        let pre_checks = [
+            "analysis",
+            "branch_type_checker",
+            "call_checker",
+            "define_trait_checker",
+            "len_result_checker",
+            "public_return_checker",
+            "sequence_comparison_checker",
+            "trait_signature_checker",
+            "unwrap_argument_checker",
+            "version_syntax_checker",
+        ];
+        let mut passes: Vec<&str> = catalog.iter().map(|entry| entry.pass).collect();
+        passes.dedup();
+        for pass in passes {
+            if pre_checks.contains(&pass) {
+                continue;
+            }
+            let mut settings = SessionSettings::default();
+            settings.analysis = vec![pass.to_string()];
+            let mut session = Session::new(settings);
+            assert!(session
+                .formatted_interpretation("(+ 1 2)".to_string(), None, false, None)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn transactions() {
+        let mut session = Session::new(SessionSettings::default());
+        session.start().expect("session could not start");
+        let snippet = "
+(define-data-var count uint u0)
+(define-public (bump) (ok (var-set count (+ (var-get count) u1))))
+(define-public (shout) (ok (print \"bump\")))
+(define-read-only (get-count) (var-get count))
+"
+        .to_string();
+        session
+            .formatted_interpretation(snippet, Some("counter".to_string()), false, None)
+            .unwrap();
+        let count = |session: &mut Session| {
+            session
+                .interpret(
+                    "(contract-call? .counter get-count)".to_string(),
+                    None,
+                    false,
+                    None,
+                )
+                .unwrap()
+                .result
+                .unwrap()
+        };
+
+        session.begin_transaction().unwrap();
+        assert_eq!(
+            session.begin_transaction(),
+            Err("A transaction is already open, commit or abort it first".to_string())
+        );
+        for _ in 0..2 {
+            session
+                .interpret(
+                    "(contract-call? .counter bump)".to_string(),
+                    None,
+                    false,
+                    None,
+                )
+                .unwrap();
+        }
+        session
+            .formatted_interpretation(
+                "(define-read-only (ping) true)".to_string(),
+                Some("pinger".to_string()),
+                false,
+                None,
+            )
+            .unwrap();
+        assert!(session
+            .contracts
+            .keys()
+            .any(|contract_id| contract_id.ends_with(".pinger")));
+        session
+            .interpret(
+                "(contract-call? .counter shout)".to_string(),
+                None,
+                false,
+                None,
+            )
+            .unwrap();
+        assert_eq!(session.events_for(".counter").len(), 1);
+        session.abort_transaction().unwrap();
+        assert_eq!(count(&mut session), Value::UInt(0));
+        assert!(session.events_for(".counter").is_empty());
+        assert!(!session
+            .contracts
+            .keys()
+            .any(|contract_id| contract_id.ends_with(".pinger")));
+
+        session.begin_transaction().unwrap();
+        session
+            .interpret(
+                "(contract-call? .counter bump)".to_string(),
+                None,
+                false,
+                None,
+            )
+            .unwrap();
+        session.commit_transaction().unwrap();
+        assert_eq!(count(&mut session), Value::UInt(1));
+        assert_eq!(
+            session.commit_transaction(),
+            Err("No transaction is open".to_string())
+        );
+        assert_eq!(
+            session.abort_transaction(),
+            Err("No transaction is open".to_string())
         );
-        assert_eq!(session.handle_command("(at-block (unwrap-panic (get-block-info? id-header-hash u10000)) (contract-call? .contract-2 get-x))")[0], green!("u1"));
     }
 }
@@ -1,9 +1,17 @@
 use std::convert::TryInto;
+use std::time::Duration;
+
+use crate::analysis;
 
 use crate::clarity::{
+    codec::transaction::{
+        C32_ADDRESS_VERSION_MAINNET_MULTISIG, C32_ADDRESS_VERSION_MAINNET_SINGLESIG,
+        C32_ADDRESS_VERSION_TESTNET_MULTISIG, C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+    },
+    contexts::IterationLimits,
     coverage::CoverageReporter,
+    native_hooks::NativeCallHooks,
     types::{PrincipalData, QualifiedContractIdentifier, StandardPrincipalData},
-    util::StacksAddress,
 };
 
 #[derive(Clone, Debug)]
@@ -16,22 +24,108 @@ pub struct InitialContract {
 
 impl InitialContract {
     pub fn get_contract_identifier(&self, is_mainnet: bool) -> Option<QualifiedContractIdentifier> {
+        self.get_contract_identifier_with_versions(&PrincipalVersions::default(), is_mainnet)
+    }
+
+    pub fn get_deployer_principal(&self, is_mainnet: bool) -> StandardPrincipalData {
+        self.get_deployer_principal_with_versions(&PrincipalVersions::default(), is_mainnet)
+    }
+
+    // The identifier of the contract, deployed by the burn address of the
+    // given versions when it has no deployer.
+    pub fn get_contract_identifier_with_versions(
+        &self,
+        versions: &PrincipalVersions,
+        is_mainnet: bool,
+    ) -> Option<QualifiedContractIdentifier> {
         match self.name {
             Some(ref name) => Some(QualifiedContractIdentifier {
-                issuer: self.get_deployer_principal(is_mainnet).into(),
+                issuer: self.get_deployer_principal_with_versions(versions, is_mainnet),
                 name: name.to_string().try_into().unwrap(),
             }),
             _ => None,
         }
     }
 
-    pub fn get_deployer_principal(&self, is_mainnet: bool) -> StandardPrincipalData {
-        let address = match self.deployer {
-            Some(ref entry) => entry.clone(),
-            None => format!("{}", StacksAddress::burn_address(is_mainnet)),
+    pub fn get_deployer_principal_with_versions(
+        &self,
+        versions: &PrincipalVersions,
+        is_mainnet: bool,
+    ) -> StandardPrincipalData {
+        match self.deployer {
+            Some(ref entry) => PrincipalData::parse_standard_principal(entry)
+                .expect("Unable to parse deployer's address"),
+            None => versions.burn_principal(is_mainnet),
+        }
+    }
+}
+
+// The version bytes of the standard principals, by network and kind of
+// account, for the chains which do not use those of Stacks. Contract
+// principals have the version of their deployer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrincipalVersions {
+    pub mainnet_singlesig: u8,
+    pub mainnet_multisig: u8,
+    pub testnet_singlesig: u8,
+    pub testnet_multisig: u8,
+}
+
+impl Default for PrincipalVersions {
+    fn default() -> Self {
+        PrincipalVersions {
+            mainnet_singlesig: C32_ADDRESS_VERSION_MAINNET_SINGLESIG,
+            mainnet_multisig: C32_ADDRESS_VERSION_MAINNET_MULTISIG,
+            testnet_singlesig: C32_ADDRESS_VERSION_TESTNET_SINGLESIG,
+            testnet_multisig: C32_ADDRESS_VERSION_TESTNET_MULTISIG,
+        }
+    }
+}
+
+impl PrincipalVersions {
+    fn all(&self) -> [(&'static str, u8); 4] {
+        [
+            ("mainnet_singlesig", self.mainnet_singlesig),
+            ("mainnet_multisig", self.mainnet_multisig),
+            ("testnet_singlesig", self.testnet_singlesig),
+            ("testnet_multisig", self.testnet_multisig),
+        ]
+    }
+
+    // The versions must be distinct, and each one a c32 character.
+    pub fn validate(&self) -> Result<(), String> {
+        let versions = self.all();
+        for (index, (name, version)) in versions.iter().enumerate() {
+            if *version >= 32 {
+                return Err(format!(
+                    "The {} version byte {} is not lower than 32",
+                    name, version
+                ));
+            }
+            if let Some((other, _)) = versions[..index].iter().find(|(_, v)| v == version) {
+                return Err(format!(
+                    "The {} and {} version bytes are both {}",
+                    other, name, version
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_known(&self, version: u8) -> bool {
+        self.all().iter().any(|(_, known)| *known == version)
+    }
+
+    // The burn address of the network, with its singlesig version, which
+    // deploys the contracts and sends the transactions when no account is
+    // given.
+    pub fn burn_principal(&self, is_mainnet: bool) -> StandardPrincipalData {
+        let version = if is_mainnet {
+            self.mainnet_singlesig
+        } else {
+            self.testnet_singlesig
         };
-        PrincipalData::parse_standard_principal(&address)
-            .expect("Unable to parse deployer's address")
+        StandardPrincipalData(version, [0; 20])
     }
 }
 
@@ -51,6 +145,26 @@ pub struct Account {
     pub derivation: String,
 }
 
+// How int and uint values are displayed: in hex, uints are shown like `u0xff`
+// and ints like `-0x10`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum IntDisplay {
+    #[default]
+    Decimal,
+    Hex,
+}
+
+// How blocks are mined as snippets are interpreted: all the calls share the
+// current block, which only advances with `::advance_chain_tip`, or each
+// successful call is followed by a new block. Contract deployments do not
+// advance the chain either way.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum BlockModel {
+    #[default]
+    Shared,
+    BlockPerCall,
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct SessionSettings {
     pub node: String,
@@ -63,4 +177,24 @@ pub struct SessionSettings {
     pub initial_deployer: Option<Account>,
     pub scoping_contract: Option<String>,
     pub analysis: Vec<String>,
+    // Enables every analysis pass: those of "all", and the opt-in ones. Their
+    // notes are reported as warnings.
+    pub strict: bool,
+    pub analysis_settings: analysis::Settings,
+    pub test_mode: bool,
+    pub max_value_allocation: Option<u64>,
+    // The limits on the iterations of `fold`, `map` and `filter`, and on the
+    // depth of the calls, for experimenting at their bounds
+    pub iteration_limits: IterationLimits,
+    pub int_display: IntDisplay,
+    pub capture_let_bindings: bool,
+    // Record the `asserts!`, `unwrap!`, `unwrap-err!` and `try!` which fail
+    // in the execution results
+    pub record_guard_failures: bool,
+    // Handlers called around the evaluation of native functions
+    pub native_call_hooks: NativeCallHooks,
+    pub block_model: BlockModel,
+    // Time after which the analysis of a contract skips its remaining passes,
+    // returning the diagnostics found until then, and a warning
+    pub analysis_timeout: Option<Duration>,
 }
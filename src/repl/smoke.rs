@@ -0,0 +1,105 @@
+use crate::clarity::types::{
+    PrincipalData, SequenceSubtype, StringSubtype, TupleData, TypeSignature, Value,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SmokeOutcome {
+    // The function returned `(ok ...)`, or a value for read-only functions
+    Succeeded(Value),
+    // The function returned `(err ...)`
+    Errored(Value),
+    // The call aborted with a runtime error
+    Aborted(String),
+    // No argument could be generated for the function
+    Skipped(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct SmokeResult {
+    pub function: String,
+    pub args: Vec<Value>,
+    pub outcome: SmokeOutcome,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SmokeReport {
+    pub contract_id: String,
+    pub results: Vec<SmokeResult>,
+}
+
+impl SmokeReport {
+    pub fn succeeded(&self) -> bool {
+        self.results
+            .iter()
+            .all(|result| matches!(result.outcome, SmokeOutcome::Succeeded(_)))
+    }
+}
+
+// Generate the smallest value admitted by a type: zero, false, empty
+// sequences, `none` and `(ok ...)`. Principals are set to `sender`. Trait
+// references cannot be generated.
+pub fn minimal_value(type_signature: &TypeSignature, sender: &PrincipalData) -> Option<Value> {
+    match type_signature {
+        TypeSignature::IntType => Some(Value::Int(0)),
+        TypeSignature::UIntType => Some(Value::UInt(0)),
+        TypeSignature::BoolType => Some(Value::Bool(false)),
+        TypeSignature::PrincipalType => Some(Value::Principal(sender.clone())),
+        TypeSignature::NoType | TypeSignature::OptionalType(_) => Some(Value::none()),
+        TypeSignature::ResponseType(types) => Value::okay(minimal_value(&types.0, sender)?).ok(),
+        TypeSignature::SequenceType(SequenceSubtype::BufferType(_)) => {
+            Value::buff_from(vec![]).ok()
+        }
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(_))) => {
+            Value::string_ascii_from_bytes(vec![]).ok()
+        }
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(_))) => {
+            Value::string_utf8_from_bytes(vec![]).ok()
+        }
+        TypeSignature::SequenceType(SequenceSubtype::ListType(list_type)) => {
+            Value::list_with_type(vec![], list_type.clone()).ok()
+        }
+        TypeSignature::TupleType(tuple_type) => {
+            let mut fields = vec![];
+            for (name, field_type) in tuple_type.get_type_map() {
+                fields.push((name.clone(), minimal_value(field_type, sender)?));
+            }
+            TupleData::from_data(fields).ok().map(Value::from)
+        }
+        TypeSignature::TraitReferenceType(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    #[test]
+    fn minimal_values() {
+        let sender = PrincipalData::parse("ST1SJ3DTE5DN7X54YDH5D64R3BCB6A2AG2ZQ8YPD5").unwrap();
+        let sample = Value::from(
+            TupleData::from_data(vec![
+                ("amount".try_into().unwrap(), Value::UInt(5)),
+                (
+                    "memo".try_into().unwrap(),
+                    Value::some(Value::buff_from_byte(1)).unwrap(),
+                ),
+                (
+                    "ids".try_into().unwrap(),
+                    Value::list_from(vec![Value::Int(1), Value::Int(2)]).unwrap(),
+                ),
+                (
+                    "owner".try_into().unwrap(),
+                    Value::Principal(sender.clone()),
+                ),
+            ])
+            .unwrap(),
+        );
+        assert_eq!(
+            minimal_value(&TypeSignature::type_of(&sample), &sender)
+                .unwrap()
+                .to_string(),
+            format!("{{amount: u0, ids: [], memo: none, owner: {}}}", sender)
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use crate::clarity::types::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+// The identifiers used in the keys of the datastore for data-vars and maps,
+// see `StoreType`.
+const DATA_MAP_STORE: &str = "0";
+const VARIABLE_STORE: &str = "1";
+
+// The state of the contracts and of the balances of the accounts, to compare
+// the states before and after an evaluation.
+#[derive(Clone, Debug, Default)]
+pub struct StateSnapshot {
+    pub entries: BTreeMap<String, String>,
+    pub tokens: BTreeMap<String, BTreeMap<String, u128>>,
+}
+
+// The kinds of state, in the order they are displayed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StateKind {
+    DataVar,
+    MapEntry,
+    Balance,
+}
+
+impl StateKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            StateKind::DataVar => "data-vars",
+            StateKind::MapEntry => "map entries",
+            StateKind::Balance => "balances",
+        }
+    }
+}
+
+// A value of the state which was changed, `None` when it did not exist.
+// `name` is the data-var, map or token, and `key` the map key or the account.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateChange {
+    pub contract: String,
+    pub kind: StateKind,
+    pub name: String,
+    pub key: Option<String>,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+// The changes from one state to another, sorted by contract, kind, name and
+// key. STX balances are grouped under "STX".
+pub fn diff(before: &StateSnapshot, after: &StateSnapshot) -> Vec<StateChange> {
+    let mut changes = vec![];
+    let keys: BTreeSet<&String> = before.entries.keys().chain(after.entries.keys()).collect();
+    for key in keys {
+        let old = before.entries.get(key);
+        let new = after.entries.get(key);
+        if old == new {
+            continue;
+        }
+        let parts: Vec<&str> = key.splitn(5, "::").collect();
+        let change = match parts[..] {
+            ["vm", contract, VARIABLE_STORE, name] => StateChange {
+                contract: contract.to_string(),
+                kind: StateKind::DataVar,
+                name: name.to_string(),
+                key: None,
+                old: old.and_then(|value| decode(value)),
+                new: new.and_then(|value| decode(value)),
+            },
+            ["vm", contract, DATA_MAP_STORE, name, map_key] => StateChange {
+                contract: contract.to_string(),
+                kind: StateKind::MapEntry,
+                name: name.to_string(),
+                key: decode(map_key),
+                old: old.and_then(|value| decode_entry(value)),
+                new: new.and_then(|value| decode_entry(value)),
+            },
+            _ => continue,
+        };
+        // A deleted map entry is stored as none
+        if change.old != change.new {
+            changes.push(change);
+        }
+    }
+
+    let tokens: BTreeSet<&String> = before.tokens.keys().chain(after.tokens.keys()).collect();
+    let empty = BTreeMap::new();
+    for token in tokens {
+        let old_balances = before.tokens.get(token).unwrap_or(&empty);
+        let new_balances = after.tokens.get(token).unwrap_or(&empty);
+        // Tokens are named `issuer.contract.token`, and STX by itself
+        let (contract, name) = match token.rsplit_once('.') {
+            Some((contract, name)) => (contract.to_string(), name.to_string()),
+            None => (token.to_string(), token.to_string()),
+        };
+        let accounts: BTreeSet<&String> = old_balances.keys().chain(new_balances.keys()).collect();
+        for account in accounts {
+            let old = old_balances.get(account).copied().unwrap_or(0);
+            let new = new_balances.get(account).copied().unwrap_or(0);
+            if old == new {
+                continue;
+            }
+            changes.push(StateChange {
+                contract: contract.clone(),
+                kind: StateKind::Balance,
+                name: name.clone(),
+                key: Some(account.to_string()),
+                old: Some(old.to_string()),
+                new: Some(new.to_string()),
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| {
+        (&a.contract, a.kind, &a.name, &a.key).cmp(&(&b.contract, b.kind, &b.name, &b.key))
+    });
+    changes
+}
+
+fn decode(hex: &str) -> Option<String> {
+    Value::try_deserialize_hex_untyped(hex)
+        .ok()
+        .map(|value| value.to_string())
+}
+
+// Map values are stored as optionals.
+fn decode_entry(hex: &str) -> Option<String> {
+    match Value::try_deserialize_hex_untyped(hex).ok()? {
+        Value::Optional(optional) => optional.data.map(|value| value.to_string()),
+        value => Some(value.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clarity::database::structures::ClaritySerializable;
+
+    #[test]
+    fn diff_entries() {
+        let contract = "S1G2081040G2081040G2081040G208105NK8PE5.counter";
+        let mut before = StateSnapshot::default();
+        before.entries.insert(
+            format!("vm::{}::1::count", contract),
+            Value::UInt(1).serialize(),
+        );
+        before.entries.insert(
+            format!("vm::{}::0::seen::{}", contract, Value::UInt(1).serialize()),
+            Value::some(Value::Bool(true)).unwrap().serialize(),
+        );
+        before
+            .tokens
+            .insert(format!("{}.points", contract), BTreeMap::new());
+        let mut after = before.clone();
+        after.entries.insert(
+            format!("vm::{}::1::count", contract),
+            Value::UInt(2).serialize(),
+        );
+        after.entries.insert(
+            format!("vm::{}::0::seen::{}", contract, Value::UInt(1).serialize()),
+            Value::none().serialize(),
+        );
+        after
+            .tokens
+            .get_mut(&format!("{}.points", contract))
+            .unwrap()
+            .insert("alice".to_string(), 10);
+
+        let changes = diff(&before, &after);
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].kind, StateKind::DataVar);
+        assert_eq!(changes[0].old, Some("u1".to_string()));
+        assert_eq!(changes[0].new, Some("u2".to_string()));
+        assert_eq!(changes[1].kind, StateKind::MapEntry);
+        assert_eq!(changes[1].key, Some("u1".to_string()));
+        assert_eq!(changes[1].new, None);
+        assert_eq!(changes[2].contract, contract);
+        assert_eq!(changes[2].name, "points");
+        assert_eq!(changes[2].old, Some("0".to_string()));
+        assert!(diff(&after, &after).is_empty());
+    }
+}
@@ -0,0 +1,109 @@
+use crate::clarity::cost_tree::CostTreeNode;
+use serde_json::{json, Value};
+
+// The evaluation is not timed, so the timeline of the trace is the runtime
+// cost: each call lasts its runtime, and starts once the calls made before it
+// by the same caller end. The calls then nest like in the cost tree.
+fn push_events(node: &CostTreeNode, start: u64, events: &mut Vec<Value>) {
+    events.push(json!({
+        "name": node.function,
+        "cat": "call",
+        "ph": "X",
+        "ts": start,
+        "dur": node.cost.runtime,
+        "pid": 1,
+        "tid": 1,
+        "args": {
+            "runtime": node.cost.runtime,
+            "read_count": node.cost.read_count,
+            "read_length": node.cost.read_length,
+            "write_count": node.cost.write_count,
+            "write_length": node.cost.write_length,
+            "span": format!(
+                "{}:{}-{}:{}",
+                node.span.start_line,
+                node.span.start_column,
+                node.span.end_line,
+                node.span.end_column
+            ),
+        },
+    }));
+    let mut child_start = start;
+    for child in node.children.iter() {
+        push_events(child, child_start, events);
+        child_start += child.cost.runtime;
+    }
+}
+
+// Build a trace in the Chrome trace-event format, loadable in
+// chrome://tracing or Perfetto, with a duration event for each call.
+pub fn cost_tree_to_trace(roots: &[CostTreeNode]) -> Value {
+    let mut events = vec![];
+    let mut start = 0;
+    for root in roots {
+        push_events(root, start, &mut events);
+        start += root.cost.runtime;
+    }
+    json!({
+        "traceEvents": events,
+        "displayTimeUnit": "ms",
+        "otherData": {
+            "version": format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            "time_unit": "runtime cost",
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clarity::costs::ExecutionCost;
+    use crate::clarity::representations::Span;
+
+    fn node(function: &str, runtime: u64, children: Vec<CostTreeNode>) -> CostTreeNode {
+        CostTreeNode {
+            function: function.to_string(),
+            span: Span::zero(),
+            cost: ExecutionCost {
+                runtime,
+                ..ExecutionCost::zero()
+            },
+            children,
+        }
+    }
+
+    #[test]
+    fn nested_events() {
+        let tree = vec![
+            node(
+                "fill",
+                100,
+                vec![node("store", 30, vec![]), node("store", 40, vec![])],
+            ),
+            node("drain", 10, vec![]),
+        ];
+        let trace = cost_tree_to_trace(&tree);
+        let events = trace["traceEvents"].as_array().unwrap();
+        let timeline: Vec<(&str, u64, u64)> = events
+            .iter()
+            .map(|event| {
+                (
+                    event["name"].as_str().unwrap(),
+                    event["ts"].as_u64().unwrap(),
+                    event["dur"].as_u64().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            timeline,
+            vec![
+                ("fill", 0, 100),
+                ("store", 0, 30),
+                ("store", 30, 40),
+                ("drain", 100, 10),
+            ]
+        );
+        assert_eq!(events[0]["ph"], "X");
+        assert_eq!(events[0]["args"]["runtime"], 100);
+    }
+}